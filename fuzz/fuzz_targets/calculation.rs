@@ -0,0 +1,88 @@
+#![no_main]
+
+use arbitrary::Arbitrary;
+use chrono::FixedOffset;
+use chrono::NaiveDate;
+use libfuzzer_sys::fuzz_target;
+use miqat::AsrFactor;
+use miqat::Coordinates;
+use miqat::DayBoundary;
+use miqat::DeltaT;
+use miqat::HighLatitudeRule;
+use miqat::Mazhab;
+use miqat::Method;
+use miqat::NextDayFajr;
+use miqat::PrayerTimes;
+use miqat::TimeAdjustment;
+
+#[derive(Debug, Arbitrary)]
+struct Input {
+    days_from_ce: i32,
+    latitude: f64,
+    longitude: f64,
+    method: u8,
+    hanafi: bool,
+    high_latitude_rule: u8,
+    fajr_angle: Option<f64>,
+    asr_factor: Option<f64>,
+    adjustments: [i64; 6],
+    dhuhr_offset_after_transit: i64,
+    delta_t: Option<f64>,
+    utc_offset_secs: Option<i32>,
+    estimated_next_day_fajr: bool,
+    seasonal_twilight: bool,
+    disable_safe_time_clamp: bool,
+}
+
+fuzz_target!(|input: Input| {
+    let Some(date) = NaiveDate::from_num_days_from_ce_opt(input.days_from_ce) else {
+        return;
+    };
+    let method = Method::ALL[input.method as usize % Method::ALL.len()];
+    let mut parameters = method.parameters();
+
+    if input.hanafi {
+        parameters = parameters.mazhab(Mazhab::Hanafi);
+    }
+    parameters.high_latitude_rule = match input.high_latitude_rule % 3 {
+        0 => HighLatitudeRule::MiddleOfTheNight,
+        1 => HighLatitudeRule::SeventhOfTheNight,
+        _ => HighLatitudeRule::TwilightAngle,
+    };
+    if let Some(angle) = input.fajr_angle {
+        parameters.fajr_angle = angle;
+    }
+    if let Some(factor) = input.asr_factor {
+        parameters = parameters.asr_factor(AsrFactor::Custom(factor));
+    }
+    let [fajr, sunrise, dhuhr, asr, maghrib, ishaa] = input.adjustments;
+    parameters.adjustments = TimeAdjustment {
+        fajr,
+        sunrise,
+        dhuhr,
+        asr,
+        maghrib,
+        ishaa,
+    };
+    parameters.dhuhr_offset_after_transit = input.dhuhr_offset_after_transit;
+    if let Some(seconds) = input.delta_t {
+        parameters.delta_t = DeltaT::Fixed(seconds);
+    }
+    if let Some(offset) = input.utc_offset_secs.and_then(FixedOffset::east_opt) {
+        parameters.day_boundary = DayBoundary::LocalCivil(offset);
+    }
+    if input.estimated_next_day_fajr {
+        parameters.next_day_fajr = NextDayFajr::Estimated;
+    }
+    parameters.seasonal_twilight = input.seasonal_twilight;
+    parameters.disable_safe_time_clamp = input.disable_safe_time_clamp;
+
+    let coordinates = Coordinates::new(input.latitude, input.longitude);
+
+    if let Ok(times) = PrayerTimes::try_new(date, coordinates, parameters) {
+        let _ = times.ordering_violations();
+    }
+    if let Ok(times) = PrayerTimes::try_computed(date, coordinates, parameters) {
+        let _ = times.ordering_violations();
+    }
+});
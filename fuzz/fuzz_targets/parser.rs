@@ -0,0 +1,32 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use miqat::PrayerTimes;
+use miqat::parser::Config;
+use miqat::parser::batch;
+
+/// Batches spanning more days are only parsed, long ranges are slow
+/// rather than interesting.
+const MAX_BATCH_DAYS: i64 = 31;
+
+fuzz_target!(|json: &str| {
+    if let Ok(config) = Config::from_json(json) {
+        let _ = config.validate();
+
+        if let Some(date) = config.date {
+            let _ = PrayerTimes::try_new(date, config.coordinates, config.parameters);
+            let _ = PrayerTimes::try_computed(date, config.coordinates, config.parameters);
+        }
+    }
+
+    if let Ok(requests) = batch::parse_batch(json) {
+        let days: i64 = requests
+            .iter()
+            .map(|request| (request.until - request.from).num_days() + 1)
+            .sum();
+
+        if days <= MAX_BATCH_DAYS {
+            let _ = batch::calculate_batch(json);
+        }
+    }
+});
@@ -0,0 +1,173 @@
+//! Throughput of the yearly and multi-location batch APIs.
+//!
+//! Run with `cargo bench -p miqat --bench batch`. Every benchmark runs
+//! once with the solar caches of the thread kept, and once with them
+//! emptied before each schedule, so that nothing carries over from one
+//! schedule to the next and the cost of the astronomy itself stays
+//! visible. The single days go through the whole year rather than
+//! computing the same day over and over.
+//!
+//! Days per second on a single thread of a Linux x86_64 runner:
+//!
+//! | Benchmark          |    Cached | Uncached |
+//! |--------------------|----------:|---------:|
+//! | single day         |   428 000 |  119 000 |
+//! | year               |   382 000 |  122 000 |
+//! | year, 50 locations |   246 000 |  100 000 |
+//! | year, maghrib only | 3 256 000 |  247 000 |
+//!
+//! The positions of the sun only depend on the day, so the locations of
+//! a batch share them, while the solar days of a location are shared by
+//! its consecutive schedules.
+
+use criterion::Criterion;
+use criterion::Throughput;
+use criterion::criterion_group;
+use criterion::criterion_main;
+use miqat::prelude::*;
+use std::hint::black_box;
+
+const CACHES: [(&str, bool); 2] = [("cached", true), ("uncached", false)];
+
+struct Setup {
+    dates: Vec<NaiveDate>,
+    locations: Vec<Coordinates>,
+    params: Parameters,
+}
+
+fn setup() -> Setup {
+    let first = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+    let last = NaiveDate::from_ymd_opt(2024, 12, 31).unwrap();
+
+    Setup {
+        dates: first.iter_days().take_while(|date| *date <= last).collect(),
+        locations: (0..50)
+            .map(|index| Coordinates::new(-50.0 + 2.0 * index as f64, -180.0 + 7.0 * index as f64))
+            .collect(),
+        params: Method::MuslimWorldLeague.parameters(),
+    }
+}
+
+// The schedule of `date`, with the solar caches of the thread emptied
+// first unless `cached`, so that nothing carries over from the schedules
+// computed before it.
+fn schedule(date: NaiveDate, coordinates: Coordinates, params: Parameters, cached: bool) {
+    if !cached {
+        miqat::clear_thread_caches();
+    } else {
+        // Nothing to do.
+    }
+    black_box(PrayerTimes::computed(date, coordinates, params));
+}
+
+fn single_days(c: &mut Criterion) {
+    let setup = setup();
+    let mut group = c.benchmark_group("single day");
+    group.throughput(Throughput::Elements(1));
+
+    for (name, cached) in CACHES {
+        let mut dates = setup.dates.iter().cycle();
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                let date = *dates.next().unwrap();
+                schedule(date, setup.locations[0], setup.params, cached);
+            });
+        });
+    }
+
+    group.finish();
+}
+
+fn years(c: &mut Criterion) {
+    let setup = setup();
+    let mut group = c.benchmark_group("year");
+    group.throughput(Throughput::Elements(setup.dates.len() as u64));
+
+    for (name, cached) in CACHES {
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                for date in &setup.dates {
+                    schedule(*date, setup.locations[0], setup.params, cached);
+                }
+            });
+        });
+    }
+
+    group.finish();
+}
+
+fn locations(c: &mut Criterion) {
+    let setup = setup();
+    let mut group = c.benchmark_group("year, 50 locations");
+    group.throughput(Throughput::Elements(
+        (setup.dates.len() * setup.locations.len()) as u64,
+    ));
+    group.sample_size(20);
+
+    for (name, cached) in CACHES {
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                for coordinates in &setup.locations {
+                    for date in &setup.dates {
+                        schedule(*date, *coordinates, setup.params, cached);
+                    }
+                }
+            });
+        });
+    }
+
+    #[cfg(feature = "parallel")]
+    {
+        let threads = std::thread::available_parallelism().unwrap();
+        group.bench_function(format!("parallel, {threads} threads"), |b| {
+            b.iter(|| {
+                black_box(
+                    miqat::batch::schedules_parallel(
+                        setup.dates[0],
+                        setup.dates[setup.dates.len() - 1],
+                        &setup.locations,
+                        setup.params,
+                        threads,
+                    )
+                    .unwrap(),
+                );
+            });
+        });
+    }
+
+    group.finish();
+}
+
+fn maghrib_only(c: &mut Criterion) {
+    let setup = setup();
+    let mut group = c.benchmark_group("year, maghrib only");
+    group.throughput(Throughput::Elements(setup.dates.len() as u64));
+
+    for (name, cached) in CACHES {
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                for date in &setup.dates {
+                    if !cached {
+                        miqat::clear_thread_caches();
+                    } else {
+                        // Nothing to do.
+                    }
+                    black_box(
+                        PrayerTimes::only(
+                            &[Prayer::Maghrib],
+                            *date,
+                            setup.locations[0],
+                            setup.params,
+                        )
+                        .unwrap(),
+                    );
+                }
+            });
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, single_days, years, locations, maghrib_only);
+criterion_main!(benches);
@@ -35,7 +35,8 @@ fn main() {
     );
 
     // --- Precomputed prayer times (Dar El-Fatwa, Beirut) ---
-    let precomputed = PrayerTimes::precomputed(today, Provider::DarElFatwa(ProviderCity::Beirut));
+    let precomputed =
+        PrayerTimes::precomputed(today, Provider::DarElFatwa(ProviderCity::Beirut)).unwrap();
 
     println!("\n=== Precomputed / Dar El-Fatwa (Beirut) ===");
     println!("Fajr:    {}", precomputed.time(Prayer::Fajr).timestamp());
@@ -12,49 +12,31 @@ fn main() {
     println!(
         "{:?}: {}",
         Prayer::Fajr,
-        prayer_times
-            .time(Prayer::Fajr)
-            .format("%-l:%M %p")
-            .to_string()
+        prayer_times.time(Prayer::Fajr).format("%-l:%M %p")
     );
     println!(
         "{:?}: {}",
         Prayer::Sunrise,
-        prayer_times
-            .time(Prayer::Sunrise)
-            .format("%-l:%M %p")
-            .to_string()
+        prayer_times.time(Prayer::Sunrise).format("%-l:%M %p")
     );
     println!(
         "{:?}: {}",
         Prayer::Dhuhr,
-        prayer_times
-            .time(Prayer::Dhuhr)
-            .format("%-l:%M %p")
-            .to_string()
+        prayer_times.time(Prayer::Dhuhr).format("%-l:%M %p")
     );
     println!(
         "{:?}: {}",
         Prayer::Asr,
-        prayer_times
-            .time(Prayer::Asr)
-            .format("%-l:%M %p")
-            .to_string()
+        prayer_times.time(Prayer::Asr).format("%-l:%M %p")
     );
     println!(
         "{:?}: {}",
         Prayer::Maghrib,
-        prayer_times
-            .time(Prayer::Maghrib)
-            .format("%-l:%M %p")
-            .to_string()
+        prayer_times.time(Prayer::Maghrib).format("%-l:%M %p")
     );
     println!(
         "{:?}: {}",
         Prayer::Ishaa,
-        prayer_times
-            .time(Prayer::Ishaa)
-            .format("%-l:%M %p")
-            .to_string()
+        prayer_times.time(Prayer::Ishaa).format("%-l:%M %p")
     );
 }
@@ -0,0 +1,371 @@
+//! # AlAdhan Compatibility
+//!
+//! Serde types matching the JSON of the AlAdhan API's `timings` endpoint,
+//! so that existing clients of that API can read locally computed prayer
+//! times without any change. Times are strings in `HH:MM` at the offset
+//! they are serialized for, as the API returns them.
+//!
+//! Only available with the `serde` feature.
+//!
+//! ##### Example
+//!
+//! ```
+//! use chrono::FixedOffset;
+//! use miqat::aladhan::{Response, TimingsData};
+//! use miqat::prelude::*;
+//!
+//! let date = NaiveDate::from_ymd_opt(2015, 7, 12).unwrap();
+//! let raleigh = Coordinates::new(35.7750, -78.6336);
+//! let times = PrayerTimes::computed(date, raleigh, Method::NorthAmerica.parameters());
+//! let edt = FixedOffset::west_opt(4 * 3600).unwrap();
+//! let response = Response::ok(TimingsData::new(&times, edt));
+//!
+//! assert_eq!(response.data.timings.fajr, "04:42");
+//! assert_eq!(response.data.meta.method.id, 2);
+//! ```
+
+use crate::hijri::HijriDate;
+use crate::models::high_altitude_rule::HighLatitudeRule;
+use crate::models::ishaa_parameter::IshaaParameter;
+use crate::models::mazhab::Mazhab;
+use crate::models::method::Method;
+use crate::models::prayer::Prayer;
+use crate::prayer_times::Origin;
+use crate::prayer_times::PrayerTimes;
+use crate::sunnah_times::SunnahTimes;
+use chrono::DateTime;
+use chrono::Datelike;
+use chrono::FixedOffset;
+use chrono::NaiveDate;
+use chrono::NaiveTime;
+use chrono::Utc;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// The id AlAdhan uses for a method with custom settings.
+pub const CUSTOM_METHOD_ID: u8 = 99;
+
+/// AlAdhan's mode for the midnight of [`Timings`], halfway from Maghrib
+/// to the next Fajr as in [`SunnahTimes`], rather than to sunrise.
+pub const MIDNIGHT_MODE: &str = "JAFARI";
+
+/// The AlAdhan id of a preset.
+pub fn method_id(method: Method) -> u8 {
+    match method {
+        Method::MuslimWorldLeague => 3,
+        Method::Egyptian => 5,
+        Method::UmmAlQura => 4,
+        Method::MoonsightingCommittee => 15,
+        Method::NorthAmerica => 2,
+        Method::Singapore => 11,
+        Method::Tehran => 7,
+    }
+}
+
+/// The preset with the given AlAdhan id, if there is one.
+pub fn method_for_id(id: u8) -> Option<Method> {
+    Method::ALL
+        .iter()
+        .copied()
+        .find(|method| method_id(*method) == id)
+}
+
+/// The envelope of every AlAdhan response.
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub struct Response<T> {
+    pub code: u16,
+    pub status: String,
+    pub data: T,
+}
+
+impl<T> Response<T> {
+    /// A successful response carrying `data`.
+    pub fn ok(data: T) -> Self {
+        Response {
+            code: 200,
+            status: "OK".to_string(),
+            data,
+        }
+    }
+}
+
+/// The `data` of a `timings` response.
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub struct TimingsData {
+    pub timings: Timings,
+    pub date: DateInfo,
+    pub meta: Meta,
+}
+
+impl TimingsData {
+    /// The prayer times of `times`, with the times shown at `offset`.
+    pub fn new(times: &PrayerTimes, offset: FixedOffset) -> Self {
+        TimingsData {
+            timings: Timings::new(times, offset),
+            date: DateInfo::new(times.date()),
+            meta: Meta::new(times, offset),
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Timings {
+    pub fajr: String,
+    pub sunrise: String,
+    pub dhuhr: String,
+    pub asr: String,
+    pub maghrib: String,
+    pub isha: String,
+    pub midnight: String,
+    pub lastthird: String,
+}
+
+impl Timings {
+    pub fn new(times: &PrayerTimes, offset: FixedOffset) -> Self {
+        let sunnah = SunnahTimes::from_prayer_times(times);
+        let time =
+            |instant: DateTime<Utc>| instant.with_timezone(&offset).format("%H:%M").to_string();
+
+        Timings {
+            fajr: time(times.time(Prayer::Fajr)),
+            sunrise: time(times.time(Prayer::Sunrise)),
+            dhuhr: time(times.time(Prayer::Dhuhr)),
+            asr: time(times.time(Prayer::Asr)),
+            maghrib: time(times.time(Prayer::Maghrib)),
+            isha: time(times.time(Prayer::Ishaa)),
+            midnight: time(sunnah.middle_of_the_night),
+            lastthird: time(sunnah.last_third_of_the_night),
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub struct DateInfo {
+    pub readable: String,
+    pub timestamp: String,
+    pub gregorian: CalendarDate,
+    pub hijri: CalendarDate,
+}
+
+impl DateInfo {
+    pub fn new(date: NaiveDate) -> Self {
+        let midnight = date.and_time(NaiveTime::MIN);
+        let hijri = HijriDate::from_gregorian(date);
+
+        DateInfo {
+            readable: date.format("%d %b %Y").to_string(),
+            timestamp: midnight.and_utc().timestamp().to_string(),
+            gregorian: CalendarDate::new(
+                date.format("%d").to_string(),
+                date.month() as u8,
+                date.format("%Y").to_string(),
+            ),
+            hijri: CalendarDate::new(
+                format!("{:02}", hijri.day),
+                hijri.month,
+                hijri.year.to_string(),
+            ),
+        }
+    }
+}
+
+/// A date in AlAdhan's `DD-MM-YYYY` format, with its parts.
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub struct CalendarDate {
+    pub date: String,
+    pub format: String,
+    pub day: String,
+    pub month: CalendarMonth,
+    pub year: String,
+}
+
+impl CalendarDate {
+    fn new(day: String, month: u8, year: String) -> Self {
+        CalendarDate {
+            date: format!("{day}-{month:02}-{year}"),
+            format: "DD-MM-YYYY".to_string(),
+            month: CalendarMonth { number: month },
+            day,
+            year,
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub struct CalendarMonth {
+    pub number: u8,
+}
+
+/// How the times were calculated.
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Meta {
+    pub latitude: f64,
+    pub longitude: f64,
+    pub timezone: String,
+    pub method: MethodInfo,
+    pub latitude_adjustment_method: String,
+    pub midnight_mode: String,
+    pub school: String,
+}
+
+impl Meta {
+    pub fn new(times: &PrayerTimes, offset: FixedOffset) -> Self {
+        let (coordinates, method, latitude_adjustment_method, school) = match &times.origin {
+            Origin::Computed(coordinates, parameters) => {
+                let preset = Method::ALL.iter().find(|method| {
+                    let preset = method.parameters();
+                    preset.behavior == parameters.behavior
+                        && preset.fajr_angle == parameters.fajr_angle
+                        && preset.maghrib_parameter == parameters.maghrib_parameter
+                        && preset.ishaa_parameter == parameters.ishaa_parameter
+                });
+                let method = MethodInfo {
+                    id: preset.map_or(CUSTOM_METHOD_ID, |method| method_id(*method)),
+                    name: preset.map_or("Custom".to_string(), |method| format!("{method:?}")),
+                    params: MethodParams {
+                        fajr: MethodParam::Angle(parameters.fajr_angle),
+                        isha: match parameters.ishaa_parameter {
+                            IshaaParameter::Angle(angle) => MethodParam::Angle(angle),
+                            IshaaParameter::Interval(minutes) => {
+                                MethodParam::Interval(format!("{minutes} min"))
+                            }
+                        },
+                    },
+                };
+                let rule = match parameters.high_latitude_rule {
+                    HighLatitudeRule::MiddleOfTheNight => "MIDDLE_OF_THE_NIGHT",
+                    HighLatitudeRule::SeventhOfTheNight => "ONE_SEVENTH",
+                    HighLatitudeRule::TwilightAngle => "ANGLE_BASED",
+                };
+                let school = match parameters.mazhab {
+                    Mazhab::Shafi => "STANDARD",
+                    Mazhab::Hanafi => "HANAFI",
+                };
+
+                (*coordinates, method, rule, school)
+            }
+            Origin::Precomputed(provider) => {
+                let method = MethodInfo {
+                    id: CUSTOM_METHOD_ID,
+                    name: format!("{provider:?}"),
+                    params: MethodParams {
+                        fajr: MethodParam::Interval("precomputed".to_string()),
+                        isha: MethodParam::Interval("precomputed".to_string()),
+                    },
+                };
+
+                (provider.coordinates(), method, "NONE", "STANDARD")
+            }
+            Origin::Table(table) => {
+                let method = MethodInfo {
+                    id: CUSTOM_METHOD_ID,
+                    name: "OfficialTable".to_string(),
+                    params: MethodParams {
+                        fajr: MethodParam::Interval("official table".to_string()),
+                        isha: MethodParam::Interval("official table".to_string()),
+                    },
+                };
+
+                (table.coordinates(), method, "NONE", "STANDARD")
+            }
+            Origin::Restored(coordinates) => {
+                let method = MethodInfo {
+                    id: CUSTOM_METHOD_ID,
+                    name: "Restored".to_string(),
+                    params: MethodParams {
+                        fajr: MethodParam::Interval("unknown".to_string()),
+                        isha: MethodParam::Interval("unknown".to_string()),
+                    },
+                };
+
+                (*coordinates, method, "NONE", "STANDARD")
+            }
+        };
+
+        Meta {
+            latitude: coordinates.latitude,
+            longitude: coordinates.longitude,
+            timezone: offset.to_string(),
+            method,
+            latitude_adjustment_method: latitude_adjustment_method.to_string(),
+            midnight_mode: MIDNIGHT_MODE.to_string(),
+            school: school.to_string(),
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub struct MethodInfo {
+    pub id: u8,
+    pub name: String,
+    pub params: MethodParams,
+}
+
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct MethodParams {
+    pub fajr: MethodParam,
+    pub isha: MethodParam,
+}
+
+/// A method setting, which AlAdhan sends as a number for an angle and as
+/// a string such as `"90 min"` for an interval.
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum MethodParam {
+    Angle(f64),
+    Interval(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::astronomy::unit::Coordinates;
+    use crate::precomputed::provider::Provider;
+    use crate::precomputed::provider::ProviderCity;
+
+    #[test]
+    fn serializes_in_the_aladhan_shape() {
+        let date = NaiveDate::from_ymd_opt(2015, 7, 12).unwrap();
+        let coordinates = Coordinates::new(21.4225241, 39.8261818);
+        let times = PrayerTimes::computed(date, coordinates, Method::UmmAlQura.parameters());
+        let ast = FixedOffset::east_opt(3 * 3600).unwrap();
+        let json = serde_json::to_value(Response::ok(TimingsData::new(&times, ast))).unwrap();
+
+        assert_eq!(json["code"], 200);
+        assert_eq!(json["data"]["timings"]["Isha"], "20:37");
+        assert_eq!(json["data"]["date"]["gregorian"]["date"], "12-07-2015");
+        assert_eq!(json["data"]["date"]["hijri"]["month"]["number"], 9);
+        assert_eq!(json["data"]["meta"]["timezone"], "+03:00");
+        assert_eq!(json["data"]["meta"]["method"]["id"], 4);
+        assert_eq!(json["data"]["meta"]["method"]["params"]["Fajr"], 18.5);
+        assert_eq!(json["data"]["meta"]["method"]["params"]["Isha"], "90 min");
+        assert_eq!(
+            json["data"]["meta"]["latitudeAdjustmentMethod"],
+            "MIDDLE_OF_THE_NIGHT"
+        );
+        assert_eq!(json["data"]["meta"]["midnightMode"], "JAFARI");
+    }
+
+    #[test]
+    fn round_trips_through_json() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 13).unwrap();
+        let times =
+            PrayerTimes::precomputed(date, Provider::DarElFatwa(ProviderCity::Beirut)).unwrap();
+        let data = TimingsData::new(&times, FixedOffset::east_opt(0).unwrap());
+        let json = serde_json::to_string(&data).unwrap();
+
+        assert_eq!(serde_json::from_str::<TimingsData>(&json).unwrap(), data);
+        assert_eq!(data.meta.method.id, CUSTOM_METHOD_ID);
+    }
+
+    #[test]
+    fn method_ids() {
+        for method in Method::ALL {
+            assert_eq!(method_for_id(method_id(*method)), Some(*method));
+        }
+        assert_eq!(method_for_id(CUSTOM_METHOD_ID), None);
+    }
+}
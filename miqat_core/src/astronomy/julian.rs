@@ -0,0 +1,164 @@
+//! # Julian Dates
+//!
+//! The Julian day and day-of-year conversions the calculations are built
+//! on, for binding layers and table generators that need to handle dates
+//! exactly as the library does.
+//!
+//! Julian days count days and fractions of days since noon UTC on the
+//! 1st of January 4713 BC in the proleptic Julian calendar; midnight UTC
+//! falls on a half day. They are in Universal Time, without delta T.
+//!
+//! ##### Example
+//!
+//! ```
+//! use miqat::julian;
+//! use miqat::prelude::*;
+//!
+//! let date = NaiveDate::from_ymd_opt(2000, 1, 1).unwrap();
+//! let noon = Utc.with_ymd_and_hms(2000, 1, 1, 12, 0, 0).unwrap();
+//!
+//! assert_eq!(julian::from_date(date), 2451544.5);
+//! assert_eq!(julian::from_instant(noon), julian::J2000);
+//! assert_eq!(julian::to_instant(julian::J2000), Some(noon));
+//! assert_eq!(julian::day_of_year(date), 1);
+//! ```
+
+pub use crate::astronomy::ops::is_leap_year;
+
+use crate::astronomy::ops;
+use chrono::DateTime;
+use chrono::Datelike;
+use chrono::NaiveDate;
+use chrono::Timelike;
+use chrono::Utc;
+
+/// The Julian day of the J2000.0 epoch, noon UTC on the 1st of January
+/// 2000.
+pub const J2000: f64 = 2451545.0;
+
+/// The Julian day of the Unix epoch, midnight UTC on the 1st of January
+/// 1970.
+pub const UNIX_EPOCH: f64 = 2440587.5;
+
+const SECONDS_PER_DAY: f64 = 86400.0;
+
+/// The Julian day at midnight UTC of `date`.
+pub fn from_date(date: NaiveDate) -> f64 {
+    ops::julian_day(date.year(), date.month() as i32, date.day() as i32, 0.0)
+}
+
+/// The Julian day of `instant`, with its time of day as a fraction.
+pub fn from_instant(instant: DateTime<Utc>) -> f64 {
+    let hours = instant.hour() as f64
+        + instant.minute() as f64 / 60.0
+        + (instant.second() as f64 + instant.nanosecond() as f64 / 1e9) / 3600.0;
+
+    ops::julian_day(
+        instant.year(),
+        instant.month() as i32,
+        instant.day() as i32,
+        hours,
+    )
+}
+
+/// The instant of the Julian day `julian_day`, to the millisecond, or
+/// `None` if it is not finite or out of the range of [`DateTime`].
+pub fn to_instant(julian_day: f64) -> Option<DateTime<Utc>> {
+    let millis = ((julian_day - UNIX_EPOCH) * SECONDS_PER_DAY * 1000.0).round();
+
+    if millis.is_finite() && (i64::MIN as f64..i64::MAX as f64).contains(&millis) {
+        DateTime::from_timestamp_millis(millis as i64)
+    } else {
+        None
+    }
+}
+
+/// The UTC date the Julian day `julian_day` falls on, or `None` if it is
+/// not finite or out of the range of [`NaiveDate`].
+pub fn to_date(julian_day: f64) -> Option<NaiveDate> {
+    to_instant(julian_day).map(|instant| instant.date_naive())
+}
+
+/// The number of Julian centuries of 36525 days since
+/// [`J2000`](J2000), the time unit of the solar position formulas.
+pub fn julian_century(julian_day: f64) -> f64 {
+    ops::julian_century(julian_day)
+}
+
+/// The day of the year of `date`, from 1 for the 1st of January.
+pub fn day_of_year(date: NaiveDate) -> u32 {
+    date.ordinal()
+}
+
+/// The date of the given day of `year`, from 1 for the 1st of January,
+/// or `None` if `year` has no such day.
+pub fn from_day_of_year(year: i32, day_of_year: u32) -> Option<NaiveDate> {
+    NaiveDate::from_yo_opt(year, day_of_year)
+}
+
+/// The number of days in `year`, 365 or 366.
+pub fn days_in_year(year: u32) -> u32 {
+    if is_leap_year(year) { 366 } else { 365 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use chrono::TimeZone;
+
+    #[test]
+    fn known_julian_days() {
+        let date = |year, month, day| NaiveDate::from_ymd_opt(year, month, day).unwrap();
+
+        assert_eq!(from_date(date(1992, 10, 13)), 2448908.5);
+        assert_eq!(from_date(date(1970, 1, 1)), UNIX_EPOCH);
+        assert_eq!(from_date(date(1957, 10, 4)), 2436115.5);
+        assert_eq!(from_date(date(2000, 1, 1)) + 0.5, J2000);
+        assert_eq!(julian_century(J2000 + 36525.0), 1.0);
+    }
+
+    #[test]
+    fn dates_round_trip() {
+        let first = NaiveDate::from_ymd_opt(1800, 1, 1).unwrap();
+        let last = NaiveDate::from_ymd_opt(2200, 12, 31).unwrap();
+
+        for date in first.iter_days().take_while(|date| *date <= last) {
+            let julian_day = from_date(date);
+
+            assert_eq!(to_date(julian_day), Some(date));
+            assert_eq!(to_date(julian_day + 0.999), Some(date));
+            assert_eq!(from_day_of_year(date.year(), day_of_year(date)), Some(date));
+        }
+    }
+
+    #[test]
+    fn instants_round_trip() {
+        let start = Utc.with_ymd_and_hms(1900, 2, 28, 23, 59, 59).unwrap();
+
+        for step in 0..5000 {
+            let instant = start + Duration::milliseconds(step * 9_876_543_211);
+
+            assert_eq!(to_instant(from_instant(instant)), Some(instant));
+        }
+        assert_eq!(to_instant(f64::NAN), None);
+        assert_eq!(to_instant(f64::INFINITY), None);
+        assert_eq!(to_date(1e30), None);
+    }
+
+    #[test]
+    fn days_of_the_year() {
+        assert!(is_leap_year(2000));
+        assert!(is_leap_year(2024));
+        assert!(!is_leap_year(1900));
+        assert!(!is_leap_year(2023));
+        assert_eq!(days_in_year(2024), 366);
+        assert_eq!(days_in_year(2100), 365);
+        assert_eq!(
+            from_day_of_year(2024, 60),
+            NaiveDate::from_ymd_opt(2024, 2, 29)
+        );
+        assert_eq!(from_day_of_year(2023, 366), None);
+        assert_eq!(from_day_of_year(2023, 0), None);
+    }
+}
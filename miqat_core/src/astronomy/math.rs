@@ -0,0 +1,128 @@
+//! Deterministic floating point helpers.
+//!
+//! The transcendental functions in `std` defer to the platform's libm, whose
+//! results can differ in the last bits between glibc, Apple's libm, bionic
+//! and wasm runtimes. Those differences are enough to flip a rounded prayer
+//! minute, so every trigonometric and power function used by the astronomical
+//! calculations goes through the pure Rust `libm` port instead. Together with
+//! Rust never contracting `a * b + c` into a fused multiply-add on its own,
+//! this makes the results bit-for-bit identical on every supported target.
+//!
+//! With the `f32-trigonometry` feature, these functions alone are evaluated
+//! in single precision, their arguments narrowed to `f32` and their results
+//! widened back, while the rest of the calculation stays in double
+//! precision. The transcendental functions are the bulk of the work on
+//! targets where double precision is emulated in software. Their arguments
+//! are reduced angles and Julian centuries, never Julian days, so the loss
+//! of precision moves the solar positions by well under an arcsecond and
+//! the prayer times by at most a minute after rounding. The results remain
+//! deterministic across targets, but differ from those of the default
+//! build.
+
+// Each function in double precision, or in single precision with the
+// `f32-trigonometry` feature.
+macro_rules! deterministic {
+    ($($name:ident($($arg:ident),+) => $double:ident, $single:ident;)+) => {
+        $(
+            #[cfg(not(feature = "f32-trigonometry"))]
+            pub fn $name($($arg: f64),+) -> f64 {
+                libm::$double($($arg),+)
+            }
+
+            #[cfg(feature = "f32-trigonometry")]
+            pub fn $name($($arg: f64),+) -> f64 {
+                libm::$single($($arg as f32),+) as f64
+            }
+        )+
+    };
+}
+
+deterministic! {
+    sin(x) => sin, sinf;
+    cos(x) => cos, cosf;
+    tan(x) => tan, tanf;
+    asin(x) => asin, asinf;
+    acos(x) => acos, acosf;
+    atan(x) => atan, atanf;
+    atan2(y, x) => atan2, atan2f;
+    powf(x, y) => pow, powf;
+}
+
+/// Asserts that `actual` is `expected`, or within `tolerance` of it with
+/// the `f32-trigonometry` feature, whose results differ in the last digits.
+#[cfg(test)]
+macro_rules! assert_close {
+    ($actual:expr, $expected:expr, $tolerance:expr $(,)?) => {{
+        let (actual, expected): (f64, f64) = ($actual, $expected);
+
+        if cfg!(feature = "f32-trigonometry") {
+            assert!(
+                (actual - expected).abs() <= $tolerance,
+                "{actual} is not within {} of {expected}",
+                $tolerance
+            );
+        } else {
+            assert_eq!(actual, expected);
+        }
+    }};
+}
+
+#[cfg(test)]
+pub(crate) use assert_close;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Reference bit patterns produced by the `libm` implementation. These must
+    // hold on every target; a mismatch means a platform specific code path
+    // has crept back into the calculations. Single precision only comes
+    // close to them.
+    #[test]
+    fn trigonometry_is_bit_exact() {
+        assert_close!(sin(1.0), f64::from_bits(0x3FEA_ED54_8F09_0CEE), 1e-7);
+        assert_close!(cos(1.0), f64::from_bits(0x3FE1_4A28_0FB5_068C), 1e-7);
+        assert_close!(tan(0.5), f64::from_bits(0x3FE1_7B4F_5BF3_474A), 1e-7);
+        assert_close!(asin(0.5), f64::from_bits(0x3FE0_C152_382D_7366), 1e-7);
+        assert_close!(acos(0.5), f64::from_bits(0x3FF0_C152_382D_7366), 1e-7);
+        assert_close!(atan(1.0), f64::from_bits(0x3FE9_21FB_5444_2D18), 1e-7);
+        assert_close!(
+            atan2(1.0, -1.0),
+            f64::from_bits(0x4002_D97C_7F33_21D2),
+            1e-7
+        );
+    }
+
+    #[test]
+    fn power_is_bit_exact() {
+        let julian_century = -0.072_183_436_002_737_85;
+
+        assert_close!(
+            powf(julian_century, 2.0),
+            f64::from_bits(0x3F75_578D_19E3_02A6),
+            1e-7
+        );
+        assert_close!(
+            powf(julian_century, 3.0),
+            f64::from_bits(0xBF38_A60B_DD63_3B7A),
+            1e-7
+        );
+    }
+
+    // Single precision only has to stay within about a microradian of the
+    // double precision results for the prayer times to round the same.
+    #[cfg(feature = "f32-trigonometry")]
+    #[test]
+    fn single_precision_is_close() {
+        for index in 0..=1000 {
+            let x = -1.0 + index as f64 / 500.0;
+            let angle = x * std::f64::consts::PI;
+
+            assert!((sin(angle) - libm::sin(angle)).abs() < 1e-6);
+            assert!((cos(angle) - libm::cos(angle)).abs() < 1e-6);
+            assert!((asin(x) - libm::asin(x)).abs() < 1e-6);
+            assert!((acos(x) - libm::acos(x)).abs() < 1e-6);
+            assert!((atan2(x, 0.5) - libm::atan2(x, 0.5)).abs() < 1e-6);
+        }
+    }
+}
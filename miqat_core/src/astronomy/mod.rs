@@ -1,4 +1,8 @@
+pub mod julian;
+pub mod math;
 pub mod ops;
 pub mod qiblah;
+pub mod seasonal;
 pub mod solar;
+pub mod twilight;
 pub mod unit;
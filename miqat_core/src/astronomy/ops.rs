@@ -1,19 +1,14 @@
+use crate::astronomy::math;
 use crate::astronomy::unit::Angle;
 use crate::astronomy::unit::Coordinates;
 use crate::astronomy::unit::Normalize;
-use crate::astronomy::unit::Stride;
-use crate::models::rounding::Rounding;
-use crate::models::twilight::Twilight;
-use chrono::DateTime;
-use chrono::Duration;
-use chrono::Utc;
 
 // The geometric mean longitude of the sun.
 pub fn mean_solar_longitude(julian_century: f64) -> Angle {
     // Equation from Astronomical Algorithms page 163
     let term1 = 280.4664567;
     let term2 = 36000.76983 * julian_century;
-    let term3 = 0.0003032 * julian_century.powf(2.0);
+    let term3 = 0.0003032 * math::powf(julian_century, 2.0);
     let degrees = term1 + term2 + term3;
 
     Angle::new(degrees).unwound()
@@ -33,8 +28,8 @@ pub fn ascending_lunar_node_longitude(julian_century: f64) -> Angle {
     // Equation from Astronomical Algorithms page 144
     let term1 = 125.04452;
     let term2 = 1934.136261 * julian_century;
-    let term3 = 0.0020708 * julian_century.powf(2.0);
-    let term4 = julian_century.powf(3.0) / 450000.0;
+    let term3 = 0.0020708 * math::powf(julian_century, 2.0);
+    let term4 = math::powf(julian_century, 3.0) / 450000.0;
     let degrees = term1 - term2 + term3 + term4;
 
     Angle::new(degrees).unwound()
@@ -45,7 +40,7 @@ pub fn mean_solar_anomaly(julian_century: f64) -> Angle {
     // Equation from Astronomical Algorithms page 163
     let term1 = 357.52911;
     let term2 = 35999.05029 * julian_century;
-    let term3 = 0.0001537 * julian_century.powf(2.0);
+    let term3 = 0.0001537 * math::powf(julian_century, 2.0);
     let degrees = term1 + term2 - term3;
 
     Angle::new(degrees).unwound()
@@ -55,10 +50,11 @@ pub fn mean_solar_anomaly(julian_century: f64) -> Angle {
 pub fn solar_equation_of_the_center(julian_century: f64, mean_anomaly: Angle) -> Angle {
     // Equation from Astronomical Algorithms page 164
     let mean_radians = mean_anomaly.radians();
-    let term1 = (1.914602 - (0.004817 * julian_century) - (0.000014 * julian_century.powf(2.0)))
-        * mean_radians.sin();
-    let term2 = (0.019993 - (0.000101 * julian_century)) * (2.0 * mean_radians).sin();
-    let term3 = 0.000289 * (3.0 * mean_radians).sin();
+    let term1 =
+        (1.914602 - (0.004817 * julian_century) - (0.000014 * math::powf(julian_century, 2.0)))
+            * math::sin(mean_radians);
+    let term2 = (0.019993 - (0.000101 * julian_century)) * math::sin(2.0 * mean_radians);
+    let term3 = 0.000289 * math::sin(3.0 * mean_radians);
 
     Angle::new(term1 + term2 + term3)
 }
@@ -70,7 +66,7 @@ pub fn apparent_solar_longitude(julian_century: f64, mean_longitude: Angle) -> A
     let longitude = mean_longitude
         + solar_equation_of_the_center(julian_century, mean_solar_anomaly(julian_century));
     let omega = Angle::new(125.04 - (1934.136 * julian_century));
-    let lambda = Angle::new(longitude.degrees - 0.00569 - (0.00478 * omega.radians().sin()));
+    let lambda = Angle::new(longitude.degrees - 0.00569 - (0.00478 * math::sin(omega.radians())));
 
     lambda.unwound()
 }
@@ -81,8 +77,8 @@ pub fn mean_obliquity_of_the_ecliptic(julian_century: f64) -> Angle {
     // Equation from Astronomical Algorithms page 147
     let term1 = 23.439291;
     let term2 = 0.013004167 * julian_century;
-    let term3 = 0.0000001639 * julian_century.powf(2.0);
-    let term4 = 0.0000005036 * julian_century.powf(3.0);
+    let term3 = 0.0000001639 * math::powf(julian_century, 2.0);
+    let term4 = 0.0000005036 * math::powf(julian_century, 3.0);
 
     Angle::new(term1 - term2 - term3 + term4)
 }
@@ -97,7 +93,8 @@ pub fn apparent_obliquity_of_the_ecliptic(
     let degrees: f64 = 125.04 - (1934.136 * julian_century);
 
     Angle::new(
-        mean_obliquity_of_the_ecliptic.degrees + (0.00256 * Angle::new(degrees).radians().cos()),
+        mean_obliquity_of_the_ecliptic.degrees
+            + (0.00256 * math::cos(Angle::new(degrees).radians())),
     )
 }
 
@@ -107,8 +104,8 @@ pub fn mean_sidereal_time(julian_century: f64) -> Angle {
     let julian_day = (julian_century * 36525.0) + 2451545.0;
     let term1 = 280.46061837;
     let term2 = 360.98564736629 * (julian_day - 2451545.0);
-    let term3 = 0.000387933 * julian_century.powf(2.0);
-    let term4 = julian_century.powf(3.0) / 38710000.0;
+    let term3 = 0.000387933 * math::powf(julian_century, 2.0);
+    let term4 = math::powf(julian_century, 3.0) / 38710000.0;
     let degrees = term1 + term2 + term3 - term4;
 
     Angle::new(degrees).unwound()
@@ -120,10 +117,10 @@ pub fn nutation_in_longitude(
     ascending_node: Angle,
 ) -> f64 {
     // Equation from Astronomical Algorithms page 144
-    let term1 = (-17.2 / 3600.0) * ascending_node.radians().sin();
-    let term2 = (1.32 / 3600.0) * (2.0 * solar_longitude.radians()).sin();
-    let term3 = (0.23 / 3600.0) * (2.0 * lunar_longitude.radians()).sin();
-    let term4 = (0.21 / 3600.0) * (2.0 * ascending_node.radians()).sin();
+    let term1 = (-17.2 / 3600.0) * math::sin(ascending_node.radians());
+    let term2 = (1.32 / 3600.0) * math::sin(2.0 * solar_longitude.radians());
+    let term3 = (0.23 / 3600.0) * math::sin(2.0 * lunar_longitude.radians());
+    let term4 = (0.21 / 3600.0) * math::sin(2.0 * ascending_node.radians());
 
     term1 - term2 - term3 + term4
 }
@@ -134,10 +131,10 @@ pub fn nutation_in_obliquity(
     ascending_node: Angle,
 ) -> f64 {
     // Equation from Astronomical Algorithms page 144
-    let term1 = (9.2 / 3600.0) * ascending_node.radians().cos();
-    let term2 = (0.57 / 3600.0) * (2.0 * solar_longitude.radians()).cos();
-    let term3 = (0.10 / 3600.0) * (2.0 * lunar_longitude.radians()).cos();
-    let term4 = (0.09 / 3600.0) * (2.0 * ascending_node.radians()).cos();
+    let term1 = (9.2 / 3600.0) * math::cos(ascending_node.radians());
+    let term2 = (0.57 / 3600.0) * math::cos(2.0 * solar_longitude.radians());
+    let term3 = (0.10 / 3600.0) * math::cos(2.0 * lunar_longitude.radians());
+    let term4 = (0.09 / 3600.0) * math::cos(2.0 * ascending_node.radians());
 
     term1 + term2 + term3 - term4
 }
@@ -148,12 +145,12 @@ pub fn altitude_of_celestial_body(
     local_hour_angle: Angle,
 ) -> Angle {
     // Equation from Astronomical Algorithms page 93
-    let term1 = observer_latitude.radians().sin() * declination.radians().sin();
-    let term2 = observer_latitude.radians().cos()
-        * declination.radians().cos()
-        * local_hour_angle.radians().cos();
+    let term1 = math::sin(observer_latitude.radians()) * math::sin(declination.radians());
+    let term2 = math::cos(observer_latitude.radians())
+        * math::cos(declination.radians())
+        * math::cos(local_hour_angle.radians());
 
-    Angle::from_radians((term1 + term2).asin())
+    Angle::from_radians(math::asin(term1 + term2))
 }
 
 pub fn approximate_transit(longitude: Angle, sidereal_time: Angle, right_ascension: Angle) -> f64 {
@@ -191,10 +188,39 @@ pub fn corrected_transit(
     (approximate_transit + angle_delta.degrees) * 24.0
 }
 
+// An observer on the ground, with the sine and cosine of its latitude
+// that the hour angles of every altitude sought from it share.
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct Observer {
+    pub coordinates: Coordinates,
+    sin_latitude: f64,
+    cos_latitude: f64,
+}
+
+impl Observer {
+    pub fn new(coordinates: Coordinates) -> Self {
+        Observer {
+            coordinates,
+            sin_latitude: math::sin(coordinates.latitude_angle().radians()),
+            cos_latitude: math::cos(coordinates.latitude_angle().radians()),
+        }
+    }
+
+    // The observer on the same latitude at `longitude`, without computing
+    // the sine and cosine of the latitude again.
+    pub fn at_longitude(self, longitude: f64) -> Self {
+        Observer {
+            coordinates: Coordinates::new(self.coordinates.latitude, longitude),
+            ..self
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn corrected_hour_angle(
+    observer: Observer,
     approximate_transit: f64,
     angle: Angle,
-    coordinates: Coordinates,
     after_transit: bool,
     sidereal_time: Angle,
     right_ascension: Angle,
@@ -205,11 +231,15 @@ pub fn corrected_hour_angle(
     next_declination: Angle,
 ) -> f64 {
     // Equation from page Astronomical Algorithms 102
-    let longitude_angle = coordinates.longitude_angle() * Angle::new(-1.0);
-    let term1 = angle.radians().sin()
-        - (coordinates.latitude_angle().radians().sin() * declination.radians().sin());
-    let term2 = coordinates.latitude_angle().radians().cos() * declination.radians().cos();
-    let term_angle = Angle::from_radians((term1 / term2).acos());
+    let longitude_angle = observer.coordinates.longitude_angle() * Angle::new(-1.0);
+    let Observer {
+        sin_latitude,
+        cos_latitude,
+        ..
+    } = observer;
+    let term1 = math::sin(angle.radians()) - (sin_latitude * math::sin(declination.radians()));
+    let term2 = cos_latitude * math::cos(declination.radians());
+    let term_angle = Angle::from_radians(math::acos(term1 / term2));
 
     let adjusted_approx_transit = if after_transit {
         approximate_transit + (term_angle.degrees / 360.0)
@@ -233,16 +263,15 @@ pub fn corrected_hour_angle(
         adjusted_approx_transit,
     ));
     let adjusted_angles = plane_angle - longitude_angle - interpolated_angles;
-    let celestial_body_altitude = altitude_of_celestial_body(
-        coordinates.latitude_angle(),
-        declination_angle,
-        adjusted_angles,
-    );
+    // The altitude as in `altitude_of_celestial_body`, reusing the sines
+    // and cosines already computed.
+    let cos_declination = math::cos(declination_angle.radians());
+    let celestial_body_altitude = Angle::from_radians(math::asin(
+        sin_latitude * math::sin(declination_angle.radians())
+            + cos_latitude * cos_declination * math::cos(adjusted_angles.radians()),
+    ));
     let term3 = (celestial_body_altitude - angle).degrees;
-    let term4 = 360.0
-        * declination_angle.radians().cos()
-        * coordinates.latitude_angle().radians().cos()
-        * adjusted_angles.radians().sin();
+    let term4 = 360.0 * cos_declination * cos_latitude * math::sin(adjusted_angles.radians());
     let angle_delta = term3 / term4;
 
     (adjusted_approx_transit + angle_delta) * 24.0
@@ -294,13 +323,44 @@ pub fn julian_day(year: i32, month: i32, day: i32, hours: f64) -> f64 {
     (i0 as f64) + (i1 as f64) + adjusted_day + (b as f64) - 1524.5
 }
 
+// Delta T, the difference between Terrestrial Time and Universal Time
+// in seconds, for a decimal year. Polynomial fits by Espenak and Meeus,
+// valid from 1900 to 2100.
+pub fn delta_t(year: f64) -> f64 {
+    if year < 1920.0 {
+        let t = year - 1900.0;
+        -2.79 + 1.494119 * t - 0.0598939 * t * t + 0.0061966 * t * t * t - 0.000197 * t * t * t * t
+    } else if year < 1941.0 {
+        let t = year - 1920.0;
+        21.20 + 0.84493 * t - 0.076100 * t * t + 0.0020936 * t * t * t
+    } else if year < 1961.0 {
+        let t = year - 1950.0;
+        29.07 + 0.407 * t - t * t / 233.0 + t * t * t / 2547.0
+    } else if year < 1986.0 {
+        let t = year - 1975.0;
+        45.45 + 1.067 * t - t * t / 260.0 - t * t * t / 718.0
+    } else if year < 2005.0 {
+        let t = year - 2000.0;
+        63.86 + 0.3345 * t - 0.060374 * t * t
+            + 0.0017275 * t * t * t
+            + 0.000651814 * t * t * t * t
+            + 0.00002373599 * t * t * t * t * t
+    } else if year < 2050.0 {
+        let t = year - 2000.0;
+        62.92 + 0.32217 * t + 0.005589 * t * t
+    } else {
+        let u = (year - 1820.0) / 100.0;
+        -20.0 + 32.0 * u * u - 0.5628 * (2150.0 - year)
+    }
+}
+
 // Julian century from the epoch.
 pub fn julian_century(julian_day: f64) -> f64 {
     // Equation from Astronomical Algorithms page 163
     (julian_day - 2451545.0) / 36525.0
 }
 
-// Checks if the given year is a leap year.
+/// Checks if the given year is a leap year.
 pub fn is_leap_year(year: u32) -> bool {
     if !year.is_multiple_of(4) {
         return false;
@@ -313,145 +373,21 @@ pub fn is_leap_year(year: u32) -> bool {
     true
 }
 
-// Twilight adjustment based on observational data for use
-// in the Moonsighting Committee calculation method.
-pub fn season_adjusted_morning_twilight(
-    latitude: f64,
-    day: u32,
-    year: u32,
-    sunrise: DateTime<Utc>,
-) -> DateTime<Utc> {
-    let dyy = days_since_solstice(day, year, latitude) as f64;
-    let adjustment =
-        twilight_adjustments(AdjustmentDaytime::Morning, latitude, dyy, Twilight::General);
-
-    let rounded_adjustment = (adjustment * -60.0).round() as i64;
-    sunrise
-        .checked_add_signed(Duration::seconds(rounded_adjustment))
-        .unwrap()
-}
-
-fn twilight_adjustments(
-    daytime: AdjustmentDaytime,
-    latitude: f64,
-    dyy: f64,
-    twilight: Twilight,
-) -> f64 {
-    let adjustment_values = twilight_adjustment_values(daytime, latitude, twilight);
-
-    if (0.00..=90.0).contains(&dyy) {
-        adjustment_values.a + (adjustment_values.b - adjustment_values.a) / 91.0 * dyy
-    } else if (91.0..=136.0).contains(&dyy) {
-        adjustment_values.b + (adjustment_values.c - adjustment_values.b) / 46.0 * (dyy - 91.0)
-    } else if (137.0..=182.0).contains(&dyy) {
-        adjustment_values.c + (adjustment_values.d - adjustment_values.c) / 46.0 * (dyy - 137.0)
-    } else if (183.0..=228.0).contains(&dyy) {
-        adjustment_values.d + (adjustment_values.c - adjustment_values.d) / 46.0 * (dyy - 183.0)
-    } else if (229.0..=274.0).contains(&dyy) {
-        adjustment_values.c + (adjustment_values.b - adjustment_values.c) / 46.0 * (dyy - 229.0)
-    } else {
-        adjustment_values.b + (adjustment_values.a - adjustment_values.b) / 91.0 * (dyy - 275.0)
-    }
-}
-
-#[derive(PartialEq, Debug, Copy, Clone)]
-enum AdjustmentDaytime {
-    Morning,
-    Evening,
-}
-
-#[derive(PartialEq, Debug, Copy, Clone)]
-struct TwilightAdjustmentValues {
-    a: f64,
-    b: f64,
-    c: f64,
-    d: f64,
-}
-
-fn twilight_adjustment_values(
-    daytime: AdjustmentDaytime,
-    latitude: f64,
-    twilight: Twilight,
-) -> TwilightAdjustmentValues {
-    if daytime == AdjustmentDaytime::Morning {
-        TwilightAdjustmentValues {
-            a: 75.0 + ((28.65 / 55.0) * latitude.abs()),
-            b: 75.0 + ((19.44 / 55.0) * latitude.abs()),
-            c: 75.0 + ((32.74 / 55.0) * latitude.abs()),
-            d: 75.0 + ((48.10 / 55.0) * latitude.abs()),
-        }
-    } else {
-        match twilight {
-            Twilight::General => TwilightAdjustmentValues {
-                a: 75.0 + ((25.60 / 55.0) * latitude.abs()),
-                b: 75.0 + ((2.050 / 55.0) * latitude.abs()),
-                c: 75.0 - ((9.210 / 55.0) * latitude.abs()),
-                d: 75.0 + ((6.140 / 55.0) * latitude.abs()),
-            },
-            Twilight::Red => TwilightAdjustmentValues {
-                a: 62.0 + ((17.40 / 55.0) * latitude.abs()),
-                b: 62.0 - ((7.160 / 55.0) * latitude.abs()),
-                c: 62.0 + ((5.120 / 55.0) * latitude.abs()),
-                d: 62.0 + ((19.44 / 55.0) * latitude.abs()),
-            },
-            Twilight::White => TwilightAdjustmentValues {
-                a: 75.0 + ((25.60 / 55.0) * latitude.abs()),
-                b: 75.0 + ((7.160 / 55.0) * latitude.abs()),
-                c: 75.0 + ((36.84 / 55.0) * latitude.abs()),
-                d: 75.0 + ((81.84 / 55.0) * latitude.abs()),
-            },
-        }
-    }
-}
-
-// Twilight adjustment based on observational data for use
-// in the Moonsighting Committee calculation method.
-pub fn season_adjusted_evening_twilight(
-    latitude: f64,
-    day: u32,
-    year: u32,
-    sunset: DateTime<Utc>,
-    twilight: Twilight,
-) -> DateTime<Utc> {
-    let dyy = days_since_solstice(day, year, latitude) as f64;
-    let adjustment = twilight_adjustments(AdjustmentDaytime::Evening, latitude, dyy, twilight);
-
-    let rounded_adjustment = (adjustment * 60.0).round() as i64;
-    let adjusted_date = sunset
-        .checked_add_signed(Duration::seconds(rounded_adjustment))
-        .unwrap();
-
-    adjusted_date.rounded_minute(Rounding::Nearest)
-}
-
-// Solstice calculation to determine a date's seasonal progression.
-// Used in the Moonsighting Committee calculation method.
-pub fn days_since_solstice(day_of_year: u32, year: u32, latitude: f64) -> u32 {
-    let days_in_year = if is_leap_year(year) { 366 } else { 365 };
-
-    if latitude >= 0.0 {
-        let northern_offset = 10;
-        let lapsed_days = day_of_year + northern_offset;
-
-        if lapsed_days >= days_in_year {
-            lapsed_days - days_in_year
-        } else {
-            lapsed_days
-        }
-    } else {
-        let southern_offset = if is_leap_year(year) { 173 } else { 172 };
-        (day_of_year - southern_offset) + days_in_year
-    }
-}
-
-pub fn adjust_time(date: &DateTime<Utc>, minutes: i64) -> DateTime<Utc> {
-    date.checked_add_signed(Duration::seconds(minutes * 60))
-        .unwrap()
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::astronomy::math::assert_close;
+
+    #[test]
+    fn calculate_delta_t() {
+        assert!((delta_t(1900.0) - -2.79).abs() < 0.01);
+        assert!((delta_t(2000.0) - 63.86).abs() < 0.01);
+        assert!((delta_t(2024.0) - 73.9).abs() < 0.5);
+        // The fits meet without jumps at their boundaries.
+        for year in [1920.0, 1941.0, 1961.0, 1986.0, 2005.0, 2050.0] {
+            assert!((delta_t(year - 1e-9) - delta_t(year)).abs() < 1.0);
+        }
+    }
 
     #[test]
     fn calculate_julian_day() {
@@ -465,7 +401,7 @@ mod tests {
         let julian_day = julian_day(1992, 10, 13, 0.0);
         let julian_century = julian_century(julian_day);
 
-        assert_eq!(julian_century, -0.072183436002737855);
+        assert_eq!(julian_century, -0.072_183_436_002_737_86);
     }
 
     #[test]
@@ -474,7 +410,7 @@ mod tests {
         let julian_century = julian_century(julian_day);
         let mean_solar_longitude = mean_solar_longitude(julian_century);
 
-        assert_eq!(mean_solar_longitude.degrees, 201.80719320670732);
+        assert_close!(mean_solar_longitude.degrees, 201.80719320670732, 1e-5);
     }
 
     #[test]
@@ -485,7 +421,7 @@ mod tests {
         let apparent_solar_longitude =
             apparent_solar_longitude(julian_century, mean_solar_longitude).radians();
 
-        assert_eq!(apparent_solar_longitude, 3.4890691820452062);
+        assert_close!(apparent_solar_longitude, 3.489_069_182_045_206, 1e-5);
     }
 
     #[test]
@@ -505,7 +441,7 @@ mod tests {
         let apparent_obliq_of_ecliptic =
             apparent_obliquity_of_the_ecliptic(julian_century, mean_obliq_of_ecliptic);
 
-        assert_eq!(apparent_obliq_of_ecliptic.degrees, 23.43999110619955);
+        assert_close!(apparent_obliq_of_ecliptic.degrees, 23.43999110619955, 1e-5);
     }
 
     #[test]
@@ -514,7 +450,7 @@ mod tests {
         let julian_century = julian_century(julian_day);
         let mean_solar_anomaly = mean_solar_anomaly(julian_century);
 
-        assert_eq!(mean_solar_anomaly.degrees, 278.99396643159753);
+        assert_eq!(mean_solar_anomaly.degrees, 278.993_966_431_597_5);
     }
 
     #[test]
@@ -525,7 +461,7 @@ mod tests {
         let solar_equation_of_center =
             solar_equation_of_the_center(julian_century, mean_solar_anomaly);
 
-        assert_eq!(solar_equation_of_center.degrees, -1.897323843371985);
+        assert_close!(solar_equation_of_center.degrees, -1.897323843371985, 1e-5);
     }
 
     #[test]
@@ -543,7 +479,7 @@ mod tests {
         let julian_century = julian_century(julian_day);
         let ascending_lunar_node = ascending_lunar_node_longitude(julian_century);
 
-        assert_eq!(ascending_lunar_node.degrees, 264.657131805429);
+        assert_close!(ascending_lunar_node.degrees, 264.657131805429, 1e-5);
     }
 
     #[test]
@@ -568,7 +504,7 @@ mod tests {
             ascending_lunar_node,
         );
 
-        assert_eq!(nutation_longitude, 0.0044525358169686564);
+        assert_close!(nutation_longitude, 0.0044525358169686564, 1e-5);
     }
 
     #[test]
@@ -584,12 +520,12 @@ mod tests {
             ascending_lunar_node,
         );
 
-        assert_eq!(nutation_obliq, -0.000092747500292341556);
+        assert_close!(nutation_obliq, -0.000_092_747_500_292_341_56, 1e-5);
     }
 
     #[test]
     fn calculate_altitude_of_celestial_body() {
-        let coordinates = Coordinates::new(35.783333333333331, -78.650000000000006);
+        let coordinates = Coordinates::new(35.783_333_333_333_33, -78.65);
         let declination_angle = Angle::new(21.894701414701338);
         let local_hour_angle = Angle::new(108.09275357838322);
         let celestial_body = altitude_of_celestial_body(
@@ -598,6 +534,6 @@ mod tests {
             local_hour_angle,
         );
 
-        assert_eq!(celestial_body.degrees, -0.90061562155943208);
+        assert_close!(celestial_body.degrees, -0.900_615_621_559_432_1, 1e-5);
     }
 }
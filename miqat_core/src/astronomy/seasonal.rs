@@ -0,0 +1,279 @@
+//! # Seasonal Twilight
+//!
+//! Twilight estimates based on observational data collected by Khalid Shaukat
+//! for the Moonsighting Committee Worldwide. Instead of deriving Fajr and Ishaa
+//! from a fixed solar depression angle, the duration of twilight is read from
+//! a curve that varies with latitude and with the number of days since the
+//! winter solstice.
+//!
+//! These estimates are used as the safe Fajr and Ishaa times by
+//! [`Method::MoonsightingCommittee`](crate::Method::MoonsightingCommittee), and
+//! can be combined with any other method through
+//! [`Parameters::seasonal_twilight`](crate::Parameters::seasonal_twilight).
+//!
+//! ##### Example
+//!
+//! ```
+//! use miqat::seasonal;
+//! use miqat::{TimeZone, Utc};
+//!
+//! let sunrise = Utc.with_ymd_and_hms(2024, 1, 1, 8, 6, 0).unwrap();
+//! let fajr = seasonal::morning_twilight(51.5074, 1, 2024, sunrise).unwrap();
+//!
+//! assert!(fajr < sunrise);
+//! ```
+
+use crate::astronomy::ops;
+use crate::models::rounding::Rounding;
+use crate::models::twilight::Twilight;
+use crate::time_ext::DateTimeExt;
+use chrono::DateTime;
+use chrono::Duration;
+use chrono::Utc;
+
+/// The end of the night a seasonal twilight estimate is for.
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub enum AdjustmentDaytime {
+    /// Dawn, before sunrise (Fajr).
+    Morning,
+
+    /// Dusk, after sunset (Ishaa).
+    Evening,
+}
+
+/// Twilight durations in minutes at the four anchor points of the
+/// seasonal curve: the winter solstice (`a`), the spring equinox (`b`),
+/// 46 days before the summer solstice (`c`) and the summer solstice (`d`).
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct TwilightAdjustmentValues {
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+    pub d: f64,
+}
+
+impl TwilightAdjustmentValues {
+    pub fn new(daytime: AdjustmentDaytime, latitude: f64, twilight: Twilight) -> Self {
+        if daytime == AdjustmentDaytime::Morning {
+            TwilightAdjustmentValues {
+                a: 75.0 + ((28.65 / 55.0) * latitude.abs()),
+                b: 75.0 + ((19.44 / 55.0) * latitude.abs()),
+                c: 75.0 + ((32.74 / 55.0) * latitude.abs()),
+                d: 75.0 + ((48.10 / 55.0) * latitude.abs()),
+            }
+        } else {
+            match twilight {
+                Twilight::General => TwilightAdjustmentValues {
+                    a: 75.0 + ((25.60 / 55.0) * latitude.abs()),
+                    b: 75.0 + ((2.050 / 55.0) * latitude.abs()),
+                    c: 75.0 - ((9.210 / 55.0) * latitude.abs()),
+                    d: 75.0 + ((6.140 / 55.0) * latitude.abs()),
+                },
+                Twilight::Red => TwilightAdjustmentValues {
+                    a: 62.0 + ((17.40 / 55.0) * latitude.abs()),
+                    b: 62.0 - ((7.160 / 55.0) * latitude.abs()),
+                    c: 62.0 + ((5.120 / 55.0) * latitude.abs()),
+                    d: 62.0 + ((19.44 / 55.0) * latitude.abs()),
+                },
+                Twilight::White => TwilightAdjustmentValues {
+                    a: 75.0 + ((25.60 / 55.0) * latitude.abs()),
+                    b: 75.0 + ((7.160 / 55.0) * latitude.abs()),
+                    c: 75.0 + ((36.84 / 55.0) * latitude.abs()),
+                    d: 75.0 + ((81.84 / 55.0) * latitude.abs()),
+                },
+            }
+        }
+    }
+}
+
+/// Seasonal Fajr: the start of morning twilight, computed by subtracting
+/// the seasonal twilight duration from `sunrise`, or `None` if that is out
+/// of the range of [`DateTime`].
+pub fn morning_twilight(
+    latitude: f64,
+    day_of_year: u32,
+    year: u32,
+    sunrise: DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+    let dyy = days_since_solstice(day_of_year, year, latitude) as f64;
+    let adjustment =
+        twilight_adjustments(AdjustmentDaytime::Morning, latitude, dyy, Twilight::General);
+
+    let rounded_adjustment = (adjustment * -60.0).round() as i64;
+    sunrise.checked_add_signed(Duration::try_seconds(rounded_adjustment)?)
+}
+
+/// Seasonal Ishaa: the end of evening twilight, computed by adding the
+/// seasonal twilight duration for the given `twilight` to `sunset`, or
+/// `None` if that is out of the range of [`DateTime`].
+pub fn evening_twilight(
+    latitude: f64,
+    day_of_year: u32,
+    year: u32,
+    sunset: DateTime<Utc>,
+    twilight: Twilight,
+) -> Option<DateTime<Utc>> {
+    let dyy = days_since_solstice(day_of_year, year, latitude) as f64;
+    let adjustment = twilight_adjustments(AdjustmentDaytime::Evening, latitude, dyy, twilight);
+
+    let rounded_adjustment = (adjustment * 60.0).round() as i64;
+    let adjusted_date = sunset.checked_add_signed(Duration::try_seconds(rounded_adjustment)?)?;
+
+    Some(adjusted_date.rounded_minute(Rounding::Nearest))
+}
+
+/// The twilight duration in minutes for the given number of days since
+/// the winter solstice, interpolated linearly between the anchor points.
+pub fn twilight_adjustments(
+    daytime: AdjustmentDaytime,
+    latitude: f64,
+    dyy: f64,
+    twilight: Twilight,
+) -> f64 {
+    let adjustment_values = TwilightAdjustmentValues::new(daytime, latitude, twilight);
+
+    if (0.00..=90.0).contains(&dyy) {
+        adjustment_values.a + (adjustment_values.b - adjustment_values.a) / 91.0 * dyy
+    } else if (91.0..=136.0).contains(&dyy) {
+        adjustment_values.b + (adjustment_values.c - adjustment_values.b) / 46.0 * (dyy - 91.0)
+    } else if (137.0..=182.0).contains(&dyy) {
+        adjustment_values.c + (adjustment_values.d - adjustment_values.c) / 46.0 * (dyy - 137.0)
+    } else if (183.0..=228.0).contains(&dyy) {
+        adjustment_values.d + (adjustment_values.c - adjustment_values.d) / 46.0 * (dyy - 183.0)
+    } else if (229.0..=274.0).contains(&dyy) {
+        adjustment_values.c + (adjustment_values.b - adjustment_values.c) / 46.0 * (dyy - 229.0)
+    } else {
+        adjustment_values.b + (adjustment_values.a - adjustment_values.b) / 91.0 * (dyy - 275.0)
+    }
+}
+
+/// Solstice calculation to determine a date's seasonal progression.
+///
+/// The count starts at the winter solstice of the hemisphere of
+/// `latitude`: around the 21st of December north of the equator, and
+/// around the 21st of June south of it.
+pub fn days_since_solstice(day_of_year: u32, year: u32, latitude: f64) -> u32 {
+    let days_in_year = if ops::is_leap_year(year) { 366 } else { 365 };
+
+    if latitude >= 0.0 {
+        let northern_offset = 10;
+        let lapsed_days = day_of_year + northern_offset;
+
+        if lapsed_days >= days_in_year {
+            lapsed_days - days_in_year
+        } else {
+            lapsed_days
+        }
+    } else {
+        let southern_offset = if ops::is_leap_year(year) { 173 } else { 172 };
+
+        if day_of_year >= southern_offset {
+            day_of_year - southern_offset
+        } else {
+            day_of_year + days_in_year - southern_offset
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn days_since_solstice_in_the_northern_hemisphere() {
+        assert_eq!(days_since_solstice(1, 2015, 35.0), 11);
+        assert_eq!(days_since_solstice(355, 2015, 35.0), 0);
+        assert_eq!(days_since_solstice(356, 2016, 35.0), 0);
+        assert_eq!(days_since_solstice(365, 2015, 35.0), 10);
+    }
+
+    #[test]
+    fn days_since_solstice_in_the_southern_hemisphere() {
+        assert_eq!(days_since_solstice(172, 2015, -35.0), 0);
+        assert_eq!(days_since_solstice(173, 2016, -35.0), 0);
+        assert_eq!(days_since_solstice(1, 2015, -35.0), 194);
+        assert_eq!(days_since_solstice(1, 2016, -35.0), 194);
+        assert_eq!(days_since_solstice(365, 2015, -35.0), 193);
+        assert_eq!(days_since_solstice(366, 2016, -35.0), 193);
+    }
+
+    #[test]
+    fn twilight_is_longer_further_from_the_equator() {
+        let equator = twilight_adjustments(AdjustmentDaytime::Morning, 0.0, 0.0, Twilight::General);
+        let london = twilight_adjustments(AdjustmentDaytime::Morning, 51.5, 0.0, Twilight::General);
+
+        assert_eq!(equator, 75.0);
+        assert!(london > equator);
+    }
+
+    #[test]
+    fn evening_twilight_depends_on_shafaq() {
+        let sunset = Utc.with_ymd_and_hms(2024, 6, 1, 20, 0, 0).unwrap();
+        let red = evening_twilight(51.5, 153, 2024, sunset, Twilight::Red);
+        let general = evening_twilight(51.5, 153, 2024, sunset, Twilight::General);
+        let white = evening_twilight(51.5, 153, 2024, sunset, Twilight::White);
+
+        assert!(red < general);
+        assert!(general < white);
+    }
+
+    #[test]
+    fn shafaq_south_of_the_equator() {
+        // Sydney, at the southern winter (21st of June 2024) and summer
+        // (21st of December 2024) solstices, for a sunset at 17:00 UTC.
+        // Adhan's own test vectors for southern latitudes aren't vendored
+        // here, so these are worked by hand from the coefficients of
+        // Shaukat's tables, at φ = 33.8688° from the equator:
+        //
+        // | Twilight | Winter solstice (a)         | Summer solstice (d)         |
+        // |----------|-----------------------------|-----------------------------|
+        // | Red      | 62 + 17.40 × φ / 55 =  72.7 | 62 + 19.44 × φ / 55 =  74.0 |
+        // | General  | 75 + 25.60 × φ / 55 =  90.8 | 75 +  6.14 × φ / 55 =  78.8 |
+        // | White    | 75 + 25.60 × φ / 55 =  90.8 | 75 + 81.84 × φ / 55 = 125.4 |
+        let latitude = -33.8688;
+        let ishaa = |day_of_year: u32, twilight: Twilight| {
+            let sunset = Utc.with_ymd_and_hms(2024, 1, 1, 17, 0, 0).unwrap();
+            evening_twilight(latitude, day_of_year, 2024, sunset, twilight)
+                .unwrap()
+                .format("%H:%M")
+                .to_string()
+        };
+        let (winter, summer) = (173, 356);
+
+        assert_eq!(ishaa(winter, Twilight::Red), "18:13");
+        assert_eq!(ishaa(summer, Twilight::Red), "18:14");
+        assert_eq!(ishaa(winter, Twilight::General), "18:31");
+        assert_eq!(ishaa(summer, Twilight::General), "18:19");
+        assert_eq!(ishaa(winter, Twilight::White), "18:31");
+        assert_eq!(ishaa(summer, Twilight::White), "19:05");
+    }
+
+    #[test]
+    fn seasons_are_inverted_south_of_the_equator() {
+        let sunset = Utc.with_ymd_and_hms(2024, 1, 1, 17, 0, 0).unwrap();
+
+        for twilight in [Twilight::Red, Twilight::General, Twilight::White] {
+            assert_eq!(
+                evening_twilight(-33.8688, 173, 2024, sunset, twilight),
+                evening_twilight(33.8688, 356, 2024, sunset, twilight)
+            );
+            assert_eq!(
+                evening_twilight(-33.8688, 356, 2024, sunset, twilight),
+                evening_twilight(33.8688, 173, 2024, sunset, twilight)
+            );
+        }
+    }
+
+    #[test]
+    fn morning_twilight_precedes_sunrise() {
+        let sunrise = Utc.with_ymd_and_hms(2016, 1, 31, 12, 16, 0).unwrap();
+        let fajr = morning_twilight(35.7750, 31, 2016, sunrise);
+
+        assert_eq!(fajr, Utc.with_ymd_and_hms(2016, 1, 31, 10, 45, 4).single());
+        assert_eq!(
+            morning_twilight(35.7750, 31, 2016, DateTime::<Utc>::MIN_UTC),
+            None
+        );
+    }
+}
@@ -1,11 +1,72 @@
+use crate::astronomy::julian;
+use crate::astronomy::math;
 use crate::astronomy::ops;
+use crate::astronomy::ops::Observer;
 use crate::astronomy::unit::Angle;
 use crate::astronomy::unit::Coordinates;
 use crate::astronomy::unit::Stride;
+use crate::models::asr_factor::AsrFactor;
+use crate::models::delta_t::DeltaT;
 use chrono::DateTime;
 use chrono::Datelike;
+use chrono::Duration;
+use chrono::NaiveTime;
 use chrono::TimeZone;
 use chrono::Utc;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::thread::LocalKey;
+
+// The most recent positions of the sun and solar days computed on this
+// thread. A schedule needs the solar days of its date and of the next two,
+// and each of those the positions of the sun on the day before and after,
+// so the schedules of consecutive days share most of them. The positions
+// only depend on the day, and are shared by all locations as well.
+const CACHE_CAPACITY: usize = 4096;
+
+type Cache<K, V> = RefCell<HashMap<K, V>>;
+
+thread_local! {
+    static SOLAR_COORDINATES: Cache<(u64, u64), SolarCoordinates> = RefCell::new(HashMap::new());
+    static SOLAR_TIMES: Cache<(i64, u64, u64, u64), Option<SolarTime>> =
+        RefCell::new(HashMap::new());
+}
+
+/// Empties the caches of solar positions and times of the current thread.
+///
+/// The caches are bounded and never need clearing for correctness; this is
+/// for measuring the uncached throughput, or releasing their memory on a
+/// thread that is done computing schedules.
+pub fn clear_thread_caches() {
+    SOLAR_COORDINATES.with(|cache| cache.borrow_mut().clear());
+    SOLAR_TIMES.with(|cache| cache.borrow_mut().clear());
+}
+
+// The value of `key` in `cache`, computed on a miss. The cache is emptied
+// once full, rather than tracking which entries are the least recent.
+fn memoized<K: Eq + Hash, V: Copy>(
+    cache: &'static LocalKey<Cache<K, V>>,
+    key: K,
+    compute: impl FnOnce() -> V,
+) -> V {
+    if let Some(value) = cache.with(|cache| cache.borrow().get(&key).copied()) {
+        return value;
+    }
+
+    let value = compute();
+    cache.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        if cache.len() >= CACHE_CAPACITY {
+            cache.clear();
+        } else {
+            // Nothing to do.
+        }
+        cache.insert(key, value);
+    });
+
+    value
+}
 
 #[derive(PartialEq, Debug, Copy, Clone)]
 pub struct SolarCoordinates {
@@ -22,15 +83,29 @@ pub struct SolarCoordinates {
 }
 
 impl SolarCoordinates {
-    fn new(julian_day: f64) -> Self {
-        let julian_century = ops::julian_century(julian_day);
+    // Like `new`, from the cache of the thread when possible. The result
+    // is the same, bit for bit.
+    fn cached(julian_day: f64, delta_t: f64) -> Self {
+        memoized(
+            &SOLAR_COORDINATES,
+            (julian_day.to_bits(), delta_t.to_bits()),
+            || SolarCoordinates::new(julian_day, delta_t),
+        )
+    }
+
+    // The position of the sun follows Terrestrial Time, while the
+    // sidereal time follows the rotation of the Earth (Universal Time);
+    // `delta_t` is the difference between the two, in seconds.
+    fn new(julian_day: f64, delta_t: f64) -> Self {
+        let julian_century = ops::julian_century(julian_day + delta_t / 86400.0);
+        let universal_century = ops::julian_century(julian_day);
         let mean_solar_longitude = ops::mean_solar_longitude(julian_century);
         let mean_lunar_longitude = ops::mean_lunar_longitude(julian_century);
         let ascending_lunar_node = ops::ascending_lunar_node_longitude(julian_century);
         let apparent_solar_longitude =
             ops::apparent_solar_longitude(julian_century, mean_solar_longitude).radians();
 
-        let mean_sidereal_time = ops::mean_sidereal_time(julian_century);
+        let mean_sidereal_time = ops::mean_sidereal_time(universal_century);
         let nutation_longitude = ops::nutation_in_longitude(
             mean_solar_longitude,
             mean_lunar_longitude,
@@ -47,24 +122,24 @@ impl SolarCoordinates {
             ops::apparent_obliquity_of_the_ecliptic(julian_century, mean_obliq_ecliptic).radians();
 
         // Equation from Astronomical Algorithms page 165
-        let declination = Angle::from_radians(
-            (apparent_obliq_ecliptic.sin() * apparent_solar_longitude.sin()).asin(),
-        );
+        let declination = Angle::from_radians(math::asin(
+            math::sin(apparent_obliq_ecliptic) * math::sin(apparent_solar_longitude),
+        ));
 
         // Equation from Astronomical Algorithms page 165
-        let right_ascension = Angle::from_radians(
-            (apparent_obliq_ecliptic.cos() * apparent_solar_longitude.sin())
-                .atan2(apparent_solar_longitude.cos()),
-        )
+        let right_ascension = Angle::from_radians(math::atan2(
+            math::cos(apparent_obliq_ecliptic) * math::sin(apparent_solar_longitude),
+            math::cos(apparent_solar_longitude),
+        ))
         .unwound();
 
         // Equation from Astronomical Algorithms page 88
         let apparent_sidereal_time = Angle::new(
             mean_sidereal_time.degrees
                 + ((nutation_longitude * 3600.0)
-                    * Angle::new(mean_obliq_ecliptic.degrees + nutation_obliq)
-                        .radians()
-                        .cos())
+                    * math::cos(
+                        Angle::new(mean_obliq_ecliptic.degrees + nutation_obliq).radians(),
+                    ))
                     / 3600.0,
         );
 
@@ -76,14 +151,83 @@ impl SolarCoordinates {
     }
 }
 
-// Solar Time
+/// Where the sun is in the sky at an instant, as seen from a location.
+///
+/// ##### Example
+///
+/// ```
+/// use miqat::{Coordinates, SolarPosition, TimeZone, Utc};
+///
+/// let noon = Utc.with_ymd_and_hms(2015, 7, 12, 17, 21, 0).unwrap();
+/// let position = SolarPosition::new(noon, Coordinates::new(35.7750, -78.6336));
+///
+/// assert!(position.altitude > 70.0);
+/// assert!((position.azimuth - 180.0).abs() < 1.0);
+/// ```
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct SolarPosition {
+    /// The direction of the sun, in degrees clockwise from north.
+    pub azimuth: f64,
+    /// The angle of the sun above the horizon in degrees, negative when
+    /// it is below. Atmospheric refraction is not accounted for.
+    pub altitude: f64,
+}
+
+impl SolarPosition {
+    pub fn new(instant: DateTime<Utc>, coordinates: Coordinates) -> Self {
+        let julian_day = julian::from_instant(instant);
+        let decimal_year = instant.year() as f64 + (instant.ordinal0() as f64 + 0.5) / 365.25;
+        let solar = SolarCoordinates::new(julian_day, ops::delta_t(decimal_year));
+
+        let latitude = coordinates.latitude_angle();
+        let hour_angle = Angle::new(
+            solar.apparent_sidereal_time.degrees + coordinates.longitude
+                - solar.right_ascension.degrees,
+        );
+        let altitude = ops::altitude_of_celestial_body(latitude, solar.declination, hour_angle);
+
+        // Equation from Astronomical Algorithms page 93, which measures
+        // the azimuth westward from the south.
+        let azimuth_from_south = Angle::from_radians(math::atan2(
+            math::sin(hour_angle.radians()),
+            math::cos(hour_angle.radians()) * math::sin(latitude.radians())
+                - math::tan(solar.declination.radians()) * math::cos(latitude.radians()),
+        ));
+
+        SolarPosition {
+            azimuth: Angle::new(azimuth_from_south.degrees + 180.0)
+                .unwound()
+                .degrees,
+            altitude: altitude.degrees,
+        }
+    }
+}
+
+/// The position of the sun over a day for a given location: when it
+/// rises, crosses the meridian and sets, and when it reaches any other
+/// altitude. All times are in UTC and rounded to the nearest minute.
+///
+/// ##### Example
+///
+/// ```
+/// use miqat::{Coordinates, SolarTime, TimeZone, Utc};
+///
+/// let date = Utc.with_ymd_and_hms(2015, 7, 12, 0, 0, 0).unwrap();
+/// let solar = SolarTime::new(date, Coordinates::new(35.7750, -78.6336));
+///
+/// assert!(solar.sunrise < solar.transit);
+/// assert!(solar.transit < solar.sunset);
+/// ```
 #[derive(Debug, Copy, Clone)]
 pub struct SolarTime {
     date: DateTime<Utc>,
-    observer: Coordinates,
+    observer: Observer,
     solar: SolarCoordinates,
+    /// When the sun crosses the meridian (solar noon).
     pub transit: DateTime<Utc>,
+    /// When the upper limb of the sun appears over the horizon.
     pub sunrise: DateTime<Utc>,
+    /// When the upper limb of the sun disappears below the horizon.
     pub sunset: DateTime<Utc>,
     prev_solar: SolarCoordinates,
     next_solar: SolarCoordinates,
@@ -91,17 +235,64 @@ pub struct SolarTime {
 }
 
 impl SolarTime {
+    /// Computes the solar events of the UTC day of `date` at `coordinates`,
+    /// with an [estimated](DeltaT::Estimated) delta T.
+    ///
+    /// Panics if the sun does not rise or set on that day, as happens
+    /// near the poles.
     pub fn new(date: DateTime<Utc>, coordinates: Coordinates) -> SolarTime {
+        SolarTime::with_delta_t(date, coordinates, DeltaT::Estimated)
+    }
+
+    /// Like [`new`](SolarTime::new), with the given source of delta T.
+    pub fn with_delta_t(
+        date: DateTime<Utc>,
+        coordinates: Coordinates,
+        delta_t: DeltaT,
+    ) -> SolarTime {
+        SolarTime::checked_with_delta_t(date, coordinates, delta_t)
+            .expect("The sun does not rise or set on this day.")
+    }
+
+    /// Like [`with_delta_t`](SolarTime::with_delta_t), but returns `None`
+    /// when the sun does not rise or set on that day.
+    pub fn checked_with_delta_t(
+        date: DateTime<Utc>,
+        coordinates: Coordinates,
+        delta_t: DeltaT,
+    ) -> Option<SolarTime> {
+        SolarTime::checked_for(date, Observer::new(coordinates), delta_t)
+    }
+
+    // Like `checked_with_delta_t`, for an observer whose latitude may be
+    // shared with others.
+    pub(crate) fn checked_for(
+        date: DateTime<Utc>,
+        observer: Observer,
+        delta_t: DeltaT,
+    ) -> Option<SolarTime> {
         // All calculation need to occur at 0h0m UTC
-        let today = Utc
-            .with_ymd_and_hms(date.year(), date.month(), date.day(), 0, 0, 0)
-            .single()
-            .expect("Invalid date received.");
-        let tomorrow = today.tomorrow();
-        let yesterday = today.yesterday();
-        let prev_solar = SolarCoordinates::new(yesterday.julian_day());
-        let solar = SolarCoordinates::new(today.julian_day());
-        let next_solar = SolarCoordinates::new(tomorrow.julian_day());
+        let today = date.date_naive().and_time(NaiveTime::MIN).and_utc();
+        let delta_t = delta_t.seconds(today.date_naive());
+        let key = (
+            today.timestamp(),
+            observer.coordinates.latitude.to_bits(),
+            observer.coordinates.longitude.to_bits(),
+            delta_t.to_bits(),
+        );
+
+        memoized(&SOLAR_TIMES, key, || {
+            SolarTime::compute(today, observer, delta_t)
+        })
+    }
+
+    fn compute(today: DateTime<Utc>, observer: Observer, delta_t: f64) -> Option<SolarTime> {
+        let coordinates = observer.coordinates;
+        let tomorrow = today.tomorrow()?;
+        let yesterday = today.yesterday()?;
+        let prev_solar = SolarCoordinates::cached(yesterday.julian_day(), delta_t);
+        let solar = SolarCoordinates::cached(today.julian_day(), delta_t);
+        let next_solar = SolarCoordinates::cached(tomorrow.julian_day(), delta_t);
         let solar_altitude = Angle::new(-50.0 / 60.0);
         let approx_transit = ops::approximate_transit(
             coordinates.longitude_angle(),
@@ -117,9 +308,9 @@ impl SolarTime {
             next_solar.right_ascension,
         );
         let sunrise_time = ops::corrected_hour_angle(
+            observer,
             approx_transit,
             solar_altitude,
-            coordinates,
             false,
             solar.apparent_sidereal_time,
             solar.right_ascension,
@@ -130,9 +321,9 @@ impl SolarTime {
             next_solar.declination,
         );
         let sunset_time = ops::corrected_hour_angle(
+            observer,
             approx_transit,
             solar_altitude,
-            coordinates,
             true,
             solar.apparent_sidereal_time,
             solar.right_ascension,
@@ -143,24 +334,37 @@ impl SolarTime {
             next_solar.declination,
         );
 
-        SolarTime {
-            date,
-            observer: coordinates,
+        Some(SolarTime {
+            date: today,
+            observer,
             solar,
-            transit: SolarTime::setting_hour(transit_time, &date).unwrap(),
-            sunrise: SolarTime::setting_hour(sunrise_time, &date).unwrap(),
-            sunset: SolarTime::setting_hour(sunset_time, &date).unwrap(),
+            transit: SolarTime::setting_hour(transit_time, &today)?,
+            sunrise: SolarTime::setting_hour(sunrise_time, &today)?,
+            sunset: SolarTime::setting_hour(sunset_time, &today)?,
             prev_solar,
             next_solar,
             approx_transit,
-        }
+        })
     }
 
+    /// The time at which the sun is at the given altitude, before
+    /// or after transit. Negative angles are below the horizon.
     pub fn time_for_solar_angle(&self, angle: Angle, after_transit: bool) -> DateTime<Utc> {
+        self.checked_time_for_solar_angle(angle, after_transit)
+            .expect("The sun does not reach the given angle on this day.")
+    }
+
+    /// Like [`time_for_solar_angle`](SolarTime::time_for_solar_angle), but
+    /// returns `None` when the sun never reaches the given altitude that day.
+    pub fn checked_time_for_solar_angle(
+        &self,
+        angle: Angle,
+        after_transit: bool,
+    ) -> Option<DateTime<Utc>> {
         let hours = ops::corrected_hour_angle(
+            self.observer,
             self.approx_transit,
             angle,
-            self.observer,
             after_transit,
             self.solar.apparent_sidereal_time,
             self.solar.right_ascension,
@@ -171,16 +375,133 @@ impl SolarTime {
             self.next_solar.declination,
         );
 
-        SolarTime::setting_hour(hours, &self.date).unwrap()
+        SolarTime::setting_hour(hours, &self.date)
+    }
+
+    /// The time after transit at which the shadow of an object reaches
+    /// the given factor of its length, in addition to its noon shadow.
+    /// Factors so small that the sun never gets below the resulting
+    /// altitude are taken as transit, which they tend to.
+    pub fn afternoon(&self, factor: AsrFactor) -> DateTime<Utc> {
+        let inverse = self.asr_shadow_length(factor, 1.0);
+        let angle = Angle::from_radians(math::atan(1.0 / inverse));
+
+        // Near the top of the arc the step towards the angle divides by
+        // almost zero, and in single precision it can land before transit.
+        self.checked_time_for_solar_angle(angle, true)
+            .filter(|time| *time >= self.transit)
+            .unwrap_or(self.transit)
+    }
+
+    /// The length of the shadow of an object of `object_height` at `at`,
+    /// in the same unit as the height, or `None` while the sun is not
+    /// above the horizon. Compared with
+    /// [`asr_shadow_length`](SolarTime::asr_shadow_length), it shows how
+    /// close Asr is.
+    ///
+    /// ```
+    /// use miqat::prelude::*;
+    ///
+    /// let date = Utc.with_ymd_and_hms(2015, 7, 12, 0, 0, 0).unwrap();
+    /// let solar = SolarTime::new(date, Coordinates::new(35.7750, -78.6336));
+    /// let asr = solar.afternoon(AsrFactor::Mazhab(Mazhab::Shafi));
+    ///
+    /// let shadow = solar.shadow_length(asr, 1.0).unwrap();
+    /// assert!((shadow - solar.asr_shadow_length(AsrFactor::Mazhab(Mazhab::Shafi), 1.0)).abs() < 0.01);
+    /// assert!(solar.shadow_length(solar.transit, 1.0).unwrap() < shadow);
+    /// assert_eq!(solar.shadow_length(solar.sunset + Duration::hours(1), 1.0), None);
+    /// ```
+    pub fn shadow_length(&self, at: DateTime<Utc>, object_height: f64) -> Option<f64> {
+        let altitude = Angle::new(SolarPosition::new(at, self.observer.coordinates).altitude);
+
+        if altitude.degrees > 0.0 {
+            Some(object_height / math::tan(altitude.radians()))
+        } else {
+            None
+        }
+    }
+
+    /// The length of the shadow of an object of `object_height` when Asr
+    /// starts under `factor`: its noon shadow plus `factor` times its
+    /// height, see [`afternoon`](SolarTime::afternoon).
+    pub fn asr_shadow_length(&self, factor: AsrFactor, object_height: f64) -> f64 {
+        let absolute_degrees =
+            (self.observer.coordinates.latitude - self.solar.declination.degrees).abs();
+        let noon_shadow = math::tan(Angle::new(absolute_degrees).radians());
+
+        object_height * (factor.shadow_length() + noon_shadow)
+    }
+
+    /// The events with the sunrise and sunset taken when the upper limb of
+    /// the sun crosses `horizon` degrees below the horizon, as seen from
+    /// above sea level. Unchanged for the standard 50 arcminutes or less,
+    /// or when the sun doesn't get that low.
+    pub(crate) fn with_horizon(mut self, horizon: f64) -> SolarTime {
+        if horizon > 50.0 / 60.0 {
+            let angle = Angle::new(-horizon);
+            if let Some(sunrise) = self.checked_time_for_solar_angle(angle, false) {
+                self.sunrise = sunrise;
+            } else {
+                // Nothing to do.
+            }
+            if let Some(sunset) = self.checked_time_for_solar_angle(angle, true) {
+                self.sunset = sunset;
+            } else {
+                // Nothing to do.
+            }
+        } else {
+            // Nothing to do.
+        }
+
+        self
     }
 
-    pub fn afternoon(&self, shadow_length: f64) -> DateTime<Utc> {
-        let absolute_degrees = (self.observer.latitude - self.solar.declination.degrees).abs();
-        let tangent = Angle::new(absolute_degrees);
-        let inverse = shadow_length + tangent.radians().tan();
-        let angle = Angle::from_radians((1.0 / inverse).atan());
+    /// The direction of the sun at sunrise, in degrees clockwise from north.
+    pub fn rising_azimuth(&self) -> f64 {
+        let latitude = self.observer.coordinates.latitude_angle().radians();
+        let declination = self.solar.declination.radians();
+        let altitude = Angle::new(-50.0 / 60.0).radians();
+        let cosine = (math::sin(declination) - math::sin(latitude) * math::sin(altitude))
+            / (math::cos(latitude) * math::cos(altitude));
 
-        self.time_for_solar_angle(angle, true)
+        Angle::from_radians(math::acos(cosine.clamp(-1.0, 1.0))).degrees
+    }
+
+    /// The direction of the sun at sunset, in degrees clockwise from north.
+    pub fn setting_azimuth(&self) -> f64 {
+        360.0 - self.rising_azimuth()
+    }
+
+    /// The path of the sun across the sky from sunrise to sunset, sampled
+    /// at `points` evenly spaced times, at least sunrise and sunset, for
+    /// drawing a sun-path arc. The ends are at the
+    /// [rising](SolarTime::rising_azimuth) and
+    /// [setting](SolarTime::setting_azimuth) azimuths, and slightly below
+    /// the horizon as refraction is not accounted for.
+    ///
+    /// ```
+    /// use miqat::{Coordinates, SolarTime, TimeZone, Utc};
+    ///
+    /// let date = Utc.with_ymd_and_hms(2015, 7, 12, 0, 0, 0).unwrap();
+    /// let solar = SolarTime::new(date, Coordinates::new(35.7750, -78.6336));
+    /// let arc = solar.day_arc(25);
+    ///
+    /// assert_eq!(arc.len(), 25);
+    /// assert_eq!(arc[0].0, solar.sunrise);
+    /// assert!(arc[12].1.altitude > 70.0);
+    /// ```
+    pub fn day_arc(&self, points: usize) -> Vec<(DateTime<Utc>, SolarPosition)> {
+        let points = points.max(2);
+        let day = self.sunset.signed_duration_since(self.sunrise);
+
+        (0..points)
+            .map(|index| {
+                let elapsed = day.num_milliseconds() * index as i64 / (points - 1) as i64;
+                let time = self.sunrise + Duration::milliseconds(elapsed);
+
+                (time, SolarPosition::new(time, self.observer.coordinates))
+            })
+            .collect()
     }
 
     fn setting_hour(value: f64, date: &DateTime<Utc>) -> Option<DateTime<Utc>> {
@@ -190,42 +511,42 @@ impl SolarTime {
             let calculated_seconds =
                 ((value - (calculated_hours + calculated_minutes / 60.0)) * 60.0 * 60.0).floor();
 
-            let (adjusted_hour, adjusted_date) = SolarTime::hour_adjustment(calculated_hours, date);
+            let (adjusted_hour, adjusted_date) =
+                SolarTime::hour_adjustment(calculated_hours, date)?;
 
             // Round to the nearest minute
             let adjusted_mins = (calculated_minutes + calculated_seconds / 60.0).round() as u32;
-            let adjusted_secs: u32 = 0;
-
-            let (hour, mins, secs) = if adjusted_mins > 59 {
-                (adjusted_hour + 1, 0, adjusted_secs)
-            } else {
-                (adjusted_hour, adjusted_mins, adjusted_secs)
-            };
 
+            // Added as a duration, so that 23:59:30 rolls over to the
+            // next day rather than to an invalid 24:00.
             Utc.with_ymd_and_hms(
                 adjusted_date.year(),
                 adjusted_date.month(),
                 adjusted_date.day(),
-                hour,
-                mins,
-                secs,
+                adjusted_hour,
+                0,
+                0,
             )
             .single()
+            .map(|hour| hour + Duration::minutes(adjusted_mins.into()))
         } else {
             None
         }
     }
 
-    fn hour_adjustment(calculated_hours: f64, date: &DateTime<Utc>) -> (u32, DateTime<Utc>) {
+    fn hour_adjustment(
+        calculated_hours: f64,
+        date: &DateTime<Utc>,
+    ) -> Option<(u32, DateTime<Utc>)> {
         // Adjust the hour to be within 0..=23,
         // wrapping around as needed; otherwise
         // chrono method will panic.
         if calculated_hours < 0.0 {
-            ((calculated_hours + 24.0) as u32, date.yesterday())
+            Some(((calculated_hours + 24.0) as u32, date.yesterday()?))
         } else if calculated_hours >= 24.0 {
-            ((calculated_hours - 24.0) as u32, date.tomorrow())
+            Some(((calculated_hours - 24.0) as u32, date.tomorrow()?))
         } else {
-            (calculated_hours as u32, *date)
+            Some((calculated_hours as u32, *date))
         }
     }
 }
@@ -233,17 +554,23 @@ impl SolarTime {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::astronomy::math::assert_close;
     use crate::astronomy::ops;
+    use crate::models::mazhab::Mazhab;
     use chrono::{Datelike, Local, TimeZone, Utc};
 
     #[test]
     fn solar_coordinates() {
         let julian_day = ops::julian_day(1992, 10, 13, 0.0);
-        let solar = SolarCoordinates::new(julian_day);
-
-        assert_eq!(solar.declination.degrees, -7.7850685152648795);
-        assert_eq!(solar.right_ascension.degrees, 198.38082214251881);
-        assert_eq!(solar.right_ascension.unwound().degrees, 198.38082214251881);
+        let solar = SolarCoordinates::new(julian_day, 0.0);
+
+        assert_close!(solar.declination.degrees, -7.7850685152648795, 1e-5);
+        assert_close!(solar.right_ascension.degrees, 198.380_822_142_518_8, 1e-5);
+        assert_close!(
+            solar.right_ascension.unwound().degrees,
+            198.380_822_142_518_8,
+            1e-5
+        );
     }
 
     #[test]
@@ -266,7 +593,7 @@ mod tests {
             .with_ymd_and_hms(2019, 1, 10, 0, 0, 0)
             .single()
             .expect("Invalid date and time provided");
-        let tomorrow = date.tomorrow();
+        let tomorrow = date.tomorrow().unwrap();
 
         assert_eq!(tomorrow.year(), 2019);
         assert_eq!(tomorrow.month(), 1);
@@ -293,9 +620,9 @@ mod tests {
             .single()
             .expect("Invalid date and time provided");
         let solar = SolarTime::new(date, coordinates);
-        let transit_date = Utc.with_ymd_and_hms(2015, 07, 12, 17, 20, 0).unwrap();
-        let sunrise_date = Utc.with_ymd_and_hms(2015, 07, 12, 10, 08, 0).unwrap();
-        let sunset_date = Utc.with_ymd_and_hms(2015, 07, 13, 00, 32, 0).unwrap();
+        let transit_date = Utc.with_ymd_and_hms(2015, 7, 12, 17, 20, 0).unwrap();
+        let sunrise_date = Utc.with_ymd_and_hms(2015, 7, 12, 10, 8, 0).unwrap();
+        let sunset_date = Utc.with_ymd_and_hms(2015, 7, 13, 0, 32, 0).unwrap();
 
         assert_eq!(solar.transit, transit_date);
         assert_eq!(solar.sunrise, sunrise_date);
@@ -318,6 +645,123 @@ mod tests {
         assert_eq!(twilight_end.format("%-k:%M").to_string(), "1:02");
     }
 
+    #[test]
+    fn rounding_past_the_last_minute_rolls_over_to_the_next_day() {
+        let date = Utc
+            .with_ymd_and_hms(2015, 7, 12, 0, 0, 0)
+            .single()
+            .expect("Invalid date and time provided");
+
+        // 23:59:45 rounds up to midnight, not to an invalid 24:00.
+        assert_eq!(
+            SolarTime::setting_hour(23.0 + 59.75 / 60.0, &date),
+            Utc.with_ymd_and_hms(2015, 7, 13, 0, 0, 0).single()
+        );
+        assert_eq!(
+            SolarTime::setting_hour(22.0 + 59.75 / 60.0, &date),
+            Utc.with_ymd_and_hms(2015, 7, 12, 23, 0, 0).single()
+        );
+    }
+
+    #[test]
+    fn calculate_sunrise_and_sunset_azimuths() {
+        let coordinates = Coordinates::new(35.0 + 47.0 / 60.0, -78.0 - 39.0 / 60.0);
+        let summer = SolarTime::new(
+            Utc.with_ymd_and_hms(2015, 7, 12, 0, 0, 0).unwrap(),
+            coordinates,
+        );
+        let winter = SolarTime::new(
+            Utc.with_ymd_and_hms(2015, 12, 21, 0, 0, 0).unwrap(),
+            coordinates,
+        );
+
+        // The sun rises north of east in summer and south of east in winter.
+        assert!(summer.rising_azimuth() > 55.0 && summer.rising_azimuth() < 65.0);
+        assert!(winter.rising_azimuth() > 115.0 && winter.rising_azimuth() < 125.0);
+        assert_eq!(summer.rising_azimuth() + summer.setting_azimuth(), 360.0);
+    }
+
+    #[test]
+    fn delta_t_shifts_solar_events() {
+        let coordinates = Coordinates::new(35.0 + 47.0 / 60.0, -78.0 - 39.0 / 60.0);
+        let date = Utc.with_ymd_and_hms(2015, 7, 12, 0, 0, 0).unwrap();
+        let estimated = SolarTime::new(date, coordinates);
+
+        assert_eq!(
+            SolarTime::with_delta_t(date, coordinates, DeltaT::Fixed(67.6)).transit,
+            estimated.transit
+        );
+        // An absurd delta T of a day moves the sun by about a degree.
+        assert_ne!(
+            SolarTime::with_delta_t(date, coordinates, DeltaT::Fixed(86400.0)).sunrise,
+            estimated.sunrise
+        );
+    }
+
+    #[test]
+    fn calculate_afternoon_with_shadow_factor() {
+        let coordinates = Coordinates::new(35.0 + 47.0 / 60.0, -78.0 - 39.0 / 60.0);
+        let date = Utc.with_ymd_and_hms(2015, 7, 12, 0, 0, 0).unwrap();
+        let solar = SolarTime::new(date, coordinates);
+        let shafi = solar.afternoon(AsrFactor::Mazhab(Mazhab::Shafi));
+        let custom = solar.afternoon(AsrFactor::Custom(1.5));
+        let hanafi = solar.afternoon(AsrFactor::Mazhab(Mazhab::Hanafi));
+
+        assert_eq!(solar.afternoon(AsrFactor::Custom(1.0)), shafi);
+        assert_eq!(solar.afternoon(AsrFactor::Custom(2.0)), hanafi);
+        assert!(shafi < custom);
+        assert!(custom < hanafi);
+    }
+
+    // The sun never gets below the altitude of a vanishing shadow.
+    #[test]
+    fn afternoon_with_vanishing_shadow_is_transit() {
+        let date = Utc.with_ymd_and_hms(2100, 12, 31, 0, 0, 0).unwrap();
+        let solar = SolarTime::new(date, Coordinates::new(0.0, 0.0));
+
+        assert_eq!(
+            solar.afternoon(AsrFactor::Custom(f64::MIN_POSITIVE)),
+            solar.transit
+        );
+    }
+
+    #[test]
+    fn day_arc_runs_from_east_to_west() {
+        let date = Utc.with_ymd_and_hms(2015, 7, 12, 0, 0, 0).unwrap();
+        let solar = SolarTime::new(date, Coordinates::new(35.7750, -78.6336));
+        let arc = solar.day_arc(0);
+
+        assert_eq!(arc.len(), 2);
+        assert_eq!(arc[1].0, solar.sunset);
+        for (time, position) in arc {
+            assert!(position.altitude.abs() < 1.0, "{time}: {position:?}");
+        }
+
+        let arc = solar.day_arc(49);
+        assert!((arc[0].1.azimuth - solar.rising_azimuth()).abs() < 1.0);
+        assert!((arc[48].1.azimuth - solar.setting_azimuth()).abs() < 1.0);
+        for pair in arc.windows(2) {
+            assert!(pair[0].1.azimuth < pair[1].1.azimuth);
+        }
+    }
+
+    #[test]
+    fn shadows_scale_with_the_object() {
+        let date = Utc.with_ymd_and_hms(2015, 7, 12, 0, 0, 0).unwrap();
+        let solar = SolarTime::new(date, Coordinates::new(35.7750, -78.6336));
+        let hanafi = solar.afternoon(AsrFactor::Mazhab(Mazhab::Hanafi));
+        let metre = solar.shadow_length(hanafi, 1.0).unwrap();
+
+        assert!((solar.shadow_length(hanafi, 2.0).unwrap() - 2.0 * metre).abs() < 1e-9);
+        assert!(
+            (metre - solar.asr_shadow_length(AsrFactor::Mazhab(Mazhab::Hanafi), 1.0)).abs() < 0.02
+        );
+        assert_eq!(
+            solar.shadow_length(solar.sunrise - Duration::hours(1), 1.0),
+            None
+        );
+    }
+
     #[test]
     fn calculate_corrected_hour_angle() {
         let coordinates = Coordinates::new(35.0 + 47.0 / 60.0, -78.0 - 39.0 / 60.0);
@@ -329,11 +773,12 @@ mod tests {
             .with_ymd_and_hms(date.year(), date.month(), date.day(), 0, 0, 0)
             .single()
             .expect("Invalid date and time provided.");
-        let tomorrow = today.tomorrow();
-        let yesterday = today.yesterday();
-        let prev_solar = SolarCoordinates::new(yesterday.julian_day());
-        let solar = SolarCoordinates::new(today.julian_day());
-        let next_solar = SolarCoordinates::new(tomorrow.julian_day());
+        let tomorrow = today.tomorrow().unwrap();
+        let yesterday = today.yesterday().unwrap();
+        let delta_t = ops::delta_t(today.year() as f64 + (today.month() as f64 - 0.5) / 12.0);
+        let prev_solar = SolarCoordinates::new(yesterday.julian_day(), delta_t);
+        let solar = SolarCoordinates::new(today.julian_day(), delta_t);
+        let next_solar = SolarCoordinates::new(tomorrow.julian_day(), delta_t);
         let solar_altitude = Angle::new(-50.0 / 60.0);
         let approx_transit = ops::approximate_transit(
             coordinates.longitude_angle(),
@@ -341,9 +786,9 @@ mod tests {
             solar.right_ascension,
         );
         let sunrise_time = ops::corrected_hour_angle(
+            Observer::new(coordinates),
             approx_transit,
             solar_altitude,
-            coordinates,
             false,
             solar.apparent_sidereal_time,
             solar.right_ascension,
@@ -354,6 +799,25 @@ mod tests {
             next_solar.declination,
         );
 
-        assert_eq!(sunrise_time, 10.131800480632849);
+        assert_close!(sunrise_time, 10.131_861_474_529_751, 1e-5);
+    }
+
+    #[test]
+    fn solar_position_follows_the_solar_events() {
+        let raleigh = Coordinates::new(35.7750, -78.6336);
+        let date = Utc.with_ymd_and_hms(2015, 7, 12, 0, 0, 0).unwrap();
+        let solar = SolarTime::new(date, raleigh);
+
+        let noon = SolarPosition::new(solar.transit, raleigh);
+        let expected_altitude = 90.0 - raleigh.latitude + solar.solar.declination.degrees;
+        assert!((noon.altitude - expected_altitude).abs() < 0.2);
+        assert!((noon.azimuth - 180.0).abs() < 1.0);
+
+        let sunrise = SolarPosition::new(solar.sunrise, raleigh);
+        assert!((sunrise.altitude + 50.0 / 60.0).abs() < 0.2);
+        assert!((sunrise.azimuth - solar.rising_azimuth()).abs() < 0.5);
+
+        let sunset = SolarPosition::new(solar.sunset, raleigh);
+        assert!((sunset.azimuth - solar.setting_azimuth()).abs() < 0.5);
     }
 }
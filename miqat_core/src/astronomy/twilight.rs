@@ -0,0 +1,108 @@
+//! # Twilight
+//!
+//! The standard civil, nautical and astronomical twilights, when the center
+//! of the sun is 6°, 12° and 18° below the horizon respectively. Useful on
+//! their own, and for sanity checking the Fajr and Ishaa angles of a method
+//! against the conventional definitions.
+//!
+//! ##### Example
+//!
+//! ```
+//! use miqat::twilight;
+//! use miqat::{Coordinates, NaiveDate};
+//!
+//! let date = NaiveDate::from_ymd_opt(2015, 7, 12).unwrap();
+//! let times = twilight::twilight_times(date, Coordinates::new(35.7750, -78.6336));
+//!
+//! assert!(times.astronomical.dawn < times.civil.dawn);
+//! ```
+
+use crate::astronomy::solar::SolarTime;
+use crate::astronomy::unit::Angle;
+use crate::astronomy::unit::Coordinates;
+use chrono::DateTime;
+use chrono::NaiveDate;
+use chrono::NaiveTime;
+use chrono::Utc;
+
+/// The morning and evening ends of one kind of twilight. Either is
+/// `None` when the sun does not get that far below the horizon,
+/// as happens at high latitudes around the summer solstice.
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct TwilightPeriod {
+    pub dawn: Option<DateTime<Utc>>,
+    pub dusk: Option<DateTime<Utc>>,
+}
+
+impl TwilightPeriod {
+    fn new(solar_time: &SolarTime, depression: f64) -> Self {
+        let angle = -Angle::new(depression);
+
+        TwilightPeriod {
+            dawn: solar_time.checked_time_for_solar_angle(angle, false),
+            dusk: solar_time.checked_time_for_solar_angle(angle, true),
+        }
+    }
+}
+
+/// Civil, nautical and astronomical dawn and dusk for a single day.
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct TwilightTimes {
+    /// The sun is 6° below the horizon.
+    pub civil: TwilightPeriod,
+    /// The sun is 12° below the horizon.
+    pub nautical: TwilightPeriod,
+    /// The sun is 18° below the horizon.
+    pub astronomical: TwilightPeriod,
+}
+
+/// Computes the standard twilights for the UTC day of `date` at
+/// `coordinates`.
+///
+/// Panics if the sun does not rise or set on that day, see [`SolarTime::new`].
+pub fn twilight_times(date: NaiveDate, coordinates: Coordinates) -> TwilightTimes {
+    let day = date.and_time(NaiveTime::MIN).and_utc();
+    let solar_time = SolarTime::new(day, coordinates);
+
+    TwilightTimes {
+        civil: TwilightPeriod::new(&solar_time, 6.0),
+        nautical: TwilightPeriod::new(&solar_time, 12.0),
+        astronomical: TwilightPeriod::new(&solar_time, 18.0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn twilights_in_raleigh() {
+        let date = NaiveDate::from_ymd_opt(2015, 7, 12).unwrap();
+        let times = twilight_times(date, Coordinates::new(35.7750, -78.6336));
+
+        assert_eq!(
+            times.civil.dawn,
+            Some(Utc.with_ymd_and_hms(2015, 7, 12, 9, 38, 0).unwrap())
+        );
+        assert_eq!(
+            times.civil.dusk,
+            Some(Utc.with_ymd_and_hms(2015, 7, 13, 1, 2, 0).unwrap())
+        );
+        assert!(times.astronomical.dawn < times.nautical.dawn);
+        assert!(times.nautical.dawn < times.civil.dawn);
+        assert!(times.civil.dusk < times.nautical.dusk);
+        assert!(times.nautical.dusk < times.astronomical.dusk);
+    }
+
+    #[test]
+    fn no_astronomical_twilight_in_london_summer() {
+        let date = NaiveDate::from_ymd_opt(2024, 6, 21).unwrap();
+        let times = twilight_times(date, Coordinates::new(51.5074, -0.1278));
+
+        assert!(times.nautical.dawn.is_some());
+        assert!(times.nautical.dusk.is_some());
+        assert_eq!(times.astronomical.dawn, None);
+        assert_eq!(times.astronomical.dusk, None);
+    }
+}
@@ -1,16 +1,20 @@
-use crate::astronomy::ops;
-use crate::models::rounding::Rounding;
+use crate::astronomy::julian;
+use crate::astronomy::math;
+use crate::hijri::HijriDate;
 use chrono::DateTime;
-use chrono::Datelike;
 use chrono::Duration;
+use chrono::NaiveDate;
 use chrono::TimeZone;
-use chrono::Timelike;
 use std::f64::consts::PI;
 use std::ops::Add;
 use std::ops::Div;
 use std::ops::Mul;
+use std::ops::Neg;
 use std::ops::Sub;
 
+/// Mean radius of the Earth in kilometers.
+const EARTH_RADIUS_KM: f64 = 6371.0088;
+
 pub trait Normalize {
     fn normalized_to_scale(&self, max: f64) -> f64;
 }
@@ -23,115 +27,155 @@ impl Normalize for f64 {
 
 /// Convenience methods for the DateTime type.
 pub trait Stride {
-    fn tomorrow(&self) -> Self;
-    fn yesterday(&self) -> Self;
+    fn tomorrow(&self) -> Option<Self>
+    where
+        Self: Sized;
+    fn yesterday(&self) -> Option<Self>
+    where
+        Self: Sized;
     fn julian_day(&self) -> f64;
-    fn adjust_time(&self, minutes: i64) -> Self;
-    fn next_date(&self, fwd: bool) -> Self;
-    fn rounded_minute(&self, rounding: Rounding) -> Self;
+    fn next_date(&self, fwd: bool) -> Option<Self>
+    where
+        Self: Sized;
+    fn next_date_in<Z: TimeZone>(&self, zone: &Z, fwd: bool) -> Option<Self>
+    where
+        Self: Sized;
+
+    /// Returns the date/time a day later on the civil calendar of
+    /// `zone`, for users far from the date/time's own time zone. The
+    /// wall-clock time in `zone` is kept across offset changes.
+    fn tomorrow_at<Z: TimeZone>(&self, zone: &Z) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        self.next_date_in(zone, true)
+    }
+
+    /// Returns the date/time a day earlier on the civil calendar of
+    /// `zone`, see [`tomorrow_at`](Stride::tomorrow_at).
+    fn yesterday_at<Z: TimeZone>(&self, zone: &Z) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        self.next_date_in(zone, false)
+    }
+
+    fn nth_day_of_hijri_month(&self, day: u8) -> Option<Self>
+    where
+        Self: Sized;
 }
 
 impl<Tz: TimeZone> Stride for DateTime<Tz> {
-    /// Returns the date/time for tomorrow.
-    fn tomorrow(&self) -> Self {
+    /// Returns the date/time for tomorrow, `None` past the last
+    /// representable date.
+    fn tomorrow(&self) -> Option<Self> {
         self.next_date(true)
     }
 
-    /// Returns the date/time for yesterday.
-    fn yesterday(&self) -> Self {
+    /// Returns the date/time for yesterday, `None` before the first
+    /// representable date.
+    fn yesterday(&self) -> Option<Self> {
         self.next_date(false)
     }
 
     /// Returns the Julian day.
     fn julian_day(&self) -> f64 {
-        ops::julian_day(self.year(), self.month() as i32, self.day() as i32, 0.0)
+        julian::from_date(self.date_naive())
     }
 
-    fn rounded_minute(&self, rounding: Rounding) -> Self {
-        let adjusted = self.clone();
-        let seconds = adjusted.second();
-
-        match rounding {
-            Rounding::Nearest => {
-                let rounded = ((seconds as f64) / 60.0).round() as i64;
-                let adjusted_seconds = seconds as i64;
+    /// Returns the same local time on the next or previous date of the
+    /// date/time's own calendar. When that time doesn't exist, e.g. in a
+    /// daylight saving gap, returns the time a whole day away instead.
+    fn next_date(&self, fwd: bool) -> Option<Self> {
+        let (date, day) = if fwd {
+            (self.date_naive().succ_opt()?, Duration::days(1))
+        } else {
+            (self.date_naive().pred_opt()?, Duration::days(-1))
+        };
 
-                if rounded == 1 {
-                    adjusted + Duration::seconds(60 - adjusted_seconds)
-                } else {
-                    adjusted + Duration::seconds(-adjusted_seconds)
-                }
-            }
-            Rounding::Ceil => {
-                let adjusted_seconds = seconds as i64;
+        self.with_day_of(date)
+            .or_else(|| self.clone().checked_add_signed(day))
+    }
 
-                adjusted + Duration::seconds(60 - adjusted_seconds)
-            }
-            Rounding::None => adjusted,
-        }
+    /// Like [`next_date`](Stride::next_date), on the calendar of `zone`
+    /// instead of the date/time's own.
+    fn next_date_in<Z: TimeZone>(&self, zone: &Z, fwd: bool) -> Option<Self> {
+        Some(
+            self.with_timezone(zone)
+                .next_date(fwd)?
+                .with_timezone(&self.timezone()),
+        )
     }
 
-    fn adjust_time(&self, minutes: i64) -> Self {
-        let some_date = self.clone();
-        some_date
-            .checked_add_signed(Duration::seconds(minutes * 60))
-            .unwrap()
+    /// Returns the same local time on the given day of the Hijri month
+    /// the date falls in, `None` if the month is shorter.
+    fn nth_day_of_hijri_month(&self, day: u8) -> Option<Self> {
+        let date = HijriDate::from_gregorian(self.date_naive()).nth_day_of_month(day)?;
+
+        self.with_day_of(date)
     }
+}
 
-    fn next_date(&self, fwd: bool) -> Self {
-        let ordinal = if fwd {
-            self.ordinal() + 1
-        } else {
-            self.ordinal() - 1
-        };
+trait WithDayOf: Sized {
+    /// The same local time on `date`, `None` if it doesn't exist.
+    fn with_day_of(&self, date: NaiveDate) -> Option<Self>;
+}
 
-        match self.with_ordinal(ordinal) {
-            Some(dt) => dt,
-            None => {
-                if fwd {
-                    self.with_year(self.year() + 1)
-                        .unwrap()
-                        .with_ordinal(1)
-                        .unwrap()
-                } else {
-                    self.with_year(self.year() - 1)
-                        .unwrap()
-                        .with_month(12)
-                        .unwrap()
-                        .with_day(31)
-                        .unwrap()
-                }
-            }
-        }
+impl<Tz: TimeZone> WithDayOf for DateTime<Tz> {
+    fn with_day_of(&self, date: NaiveDate) -> Option<Self> {
+        self.timezone()
+            .from_local_datetime(&date.and_time(self.time()))
+            .earliest()
     }
 }
 
+/// An angle, stored in degrees.
+///
+/// Solar altitudes follow the usual convention: positive above the
+/// horizon and negative below it, so Fajr at 18° is `Angle::new(-18.0)`.
+///
+/// ##### Example
+///
+/// ```
+/// use miqat::Angle;
+///
+/// let angle = Angle::new(-45.0) + Angle::new(15.0);
+///
+/// assert_eq!(angle.degrees, -30.0);
+/// assert_eq!(angle.unwound().degrees, 330.0);
+/// assert_eq!(Angle::new(180.0).radians(), std::f64::consts::PI);
+/// ```
 #[derive(PartialEq, Debug, Copy, Clone)]
 pub struct Angle {
     pub degrees: f64,
 }
 
 impl Angle {
+    /// Creates an angle from a value in degrees.
     pub fn new(value: f64) -> Self {
         Angle { degrees: value }
     }
 
+    /// Creates an angle from a value in radians.
     pub fn from_radians(value: f64) -> Self {
         Angle {
             degrees: (value * 180.0) / PI,
         }
     }
 
+    /// The angle in radians.
     pub fn radians(&self) -> f64 {
         (self.degrees * PI) / 180.0
     }
 
+    /// The equivalent angle within `[0, 360)`.
     pub fn unwound(&self) -> Angle {
         Angle {
             degrees: self.degrees.normalized_to_scale(360.0),
         }
     }
 
+    /// The equivalent angle within `[-180, 180]`.
     pub fn quadrant_shifted(&self) -> Angle {
         if self.degrees >= -180.0 && self.degrees <= 180.0 {
             // Nothing to do. Already initialized
@@ -164,6 +208,16 @@ impl Sub for Angle {
     }
 }
 
+impl Neg for Angle {
+    type Output = Angle;
+
+    fn neg(self) -> Angle {
+        Angle {
+            degrees: -self.degrees,
+        }
+    }
+}
+
 impl Mul for Angle {
     type Output = Angle;
 
@@ -197,6 +251,8 @@ pub struct Coordinates {
 }
 
 impl Coordinates {
+    /// Creates coordinates from a latitude (positive north) and a
+    /// longitude (positive east), both in degrees.
     pub fn new(latitude: f64, longitude: f64) -> Self {
         Coordinates {
             latitude,
@@ -206,21 +262,154 @@ impl Coordinates {
 }
 
 impl Coordinates {
+    /// The latitude as an [`Angle`].
     pub fn latitude_angle(&self) -> Angle {
         Angle::new(self.latitude)
     }
 
+    /// The longitude as an [`Angle`].
     pub fn longitude_angle(&self) -> Angle {
         Angle::new(self.longitude)
     }
+
+    /// The great-circle distance to `other` in kilometers, assuming a
+    /// spherical Earth.
+    pub fn distance_to(&self, other: Coordinates) -> f64 {
+        let lat_a = self.latitude_angle().radians();
+        let lat_b = other.latitude_angle().radians();
+        let half_lat = (lat_b - lat_a) / 2.0;
+        let half_lon = (other.longitude_angle() - self.longitude_angle()).radians() / 2.0;
+        let haversine = math::sin(half_lat) * math::sin(half_lat)
+            + math::cos(lat_a) * math::cos(lat_b) * math::sin(half_lon) * math::sin(half_lon);
+
+        2.0 * EARTH_RADIUS_KM * math::asin(haversine.sqrt().min(1.0))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::FixedOffset;
+    use chrono::MappedLocalTime;
+    use chrono::NaiveDateTime;
+    use chrono::NaiveTime;
     use chrono::Utc;
     use std::f64::consts::PI;
 
+    #[test]
+    fn stepping_follows_the_civil_calendar() {
+        let kiritimati = FixedOffset::east_opt(14 * 3600).unwrap();
+        let instant = Utc.with_ymd_and_hms(2024, 12, 31, 12, 0, 0).unwrap();
+
+        assert_eq!(
+            instant.tomorrow(),
+            Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).single()
+        );
+        assert_eq!(
+            instant.with_timezone(&kiritimati).tomorrow(),
+            kiritimati.with_ymd_and_hms(2025, 1, 2, 2, 0, 0).single()
+        );
+        assert_eq!(instant.tomorrow_at(&kiritimati), instant.tomorrow());
+        assert_eq!(
+            instant
+                .tomorrow_at(&kiritimati)
+                .unwrap()
+                .with_timezone(&kiritimati)
+                .date_naive(),
+            NaiveDate::from_ymd_opt(2025, 1, 2).unwrap()
+        );
+        assert_eq!(
+            instant.yesterday_at(&kiritimati),
+            Utc.with_ymd_and_hms(2024, 12, 30, 12, 0, 0).single()
+        );
+        assert_eq!(
+            Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0)
+                .unwrap()
+                .yesterday(),
+            Utc.with_ymd_and_hms(2023, 12, 31, 0, 0, 0).single()
+        );
+    }
+
+    /// UTC+1, moving to UTC+2 at 02:00 local time on 2024-03-31.
+    #[derive(Debug, Copy, Clone)]
+    struct Summer;
+
+    impl TimeZone for Summer {
+        type Offset = FixedOffset;
+
+        fn from_offset(_: &FixedOffset) -> Self {
+            Summer
+        }
+
+        fn offset_from_local_date(&self, local: &NaiveDate) -> MappedLocalTime<FixedOffset> {
+            self.offset_from_local_datetime(&local.and_time(NaiveTime::MIN))
+        }
+
+        fn offset_from_local_datetime(
+            &self,
+            local: &NaiveDateTime,
+        ) -> MappedLocalTime<FixedOffset> {
+            let switch = NaiveDate::from_ymd_opt(2024, 3, 31)
+                .unwrap()
+                .and_hms_opt(2, 0, 0)
+                .unwrap();
+
+            if *local < switch {
+                MappedLocalTime::Single(FixedOffset::east_opt(3600).unwrap())
+            } else if *local >= switch + Duration::hours(1) {
+                MappedLocalTime::Single(FixedOffset::east_opt(7200).unwrap())
+            } else {
+                MappedLocalTime::None
+            }
+        }
+
+        fn offset_from_utc_date(&self, utc: &NaiveDate) -> FixedOffset {
+            self.offset_from_utc_datetime(&utc.and_time(NaiveTime::MIN))
+        }
+
+        fn offset_from_utc_datetime(&self, utc: &NaiveDateTime) -> FixedOffset {
+            let switch = NaiveDate::from_ymd_opt(2024, 3, 31)
+                .unwrap()
+                .and_hms_opt(1, 0, 0)
+                .unwrap();
+            let hours = if *utc < switch { 1 } else { 2 };
+
+            FixedOffset::east_opt(hours * 3600).unwrap()
+        }
+    }
+
+    #[test]
+    fn stepping_keeps_the_wall_clock_across_an_offset_change() {
+        let before = Utc.with_ymd_and_hms(2024, 3, 30, 12, 0, 0).unwrap();
+        let after = Utc.with_ymd_and_hms(2024, 3, 31, 11, 0, 0).unwrap();
+
+        assert_eq!(before.tomorrow_at(&Summer), Some(after));
+        assert_eq!(after.yesterday_at(&Summer), Some(before));
+        assert_eq!(before.tomorrow(), Some(after + Duration::hours(1)));
+
+        // 02:30 doesn't exist on the 31st: a whole day later instead.
+        let gap = Utc.with_ymd_and_hms(2024, 3, 30, 1, 30, 0).unwrap();
+
+        assert_eq!(gap.tomorrow_at(&Summer), Some(gap + Duration::days(1)));
+    }
+
+    #[test]
+    fn stepping_within_a_hijri_month() {
+        // The 10th of Ramadan 1445.
+        let instant = Utc.with_ymd_and_hms(2024, 3, 20, 18, 30, 0).unwrap();
+
+        assert_eq!(
+            instant.nth_day_of_hijri_month(1),
+            Utc.with_ymd_and_hms(2024, 3, 11, 18, 30, 0).single()
+        );
+        assert_eq!(
+            instant.nth_day_of_hijri_month(30),
+            Utc.with_ymd_and_hms(2024, 4, 9, 18, 30, 0).single()
+        );
+        assert_eq!(instant.nth_day_of_hijri_month(31), None);
+        assert_eq!(instant.nth_day_of_hijri_month(0), None);
+    }
+
     #[test]
     fn angle_conversion_from_radians() {
         assert_eq!(Angle::from_radians(PI).degrees, 180.0);
@@ -280,47 +469,37 @@ mod tests {
     }
 
     #[test]
-    fn calculate_rounding_nearest() {
-        let time_1 = Utc
-            .with_ymd_and_hms(2015, 7, 13, 4, 37, 30)
-            .single()
-            .expect("Invalid date and time.");
+    fn angle_arithmetic() {
+        let angle_a = Angle::new(90.0);
+        let angle_b = Angle::new(30.0);
+
+        assert_eq!((angle_a - angle_b).degrees, 60.0);
+        assert_eq!((angle_a * Angle::new(2.0)).degrees, 180.0);
+        assert_eq!((angle_a / angle_b).degrees, 3.0);
+        assert_eq!((-angle_b).degrees, -30.0);
+    }
 
-        assert_eq!(
-            time_1.rounded_minute(Rounding::Nearest),
-            Utc.with_ymd_and_hms(2015, 7, 13, 4, 38, 00)
-                .single()
-                .unwrap()
-        );
+    #[test]
+    #[should_panic(expected = "Cannot divide by zero.")]
+    fn dividing_angle_by_zero() {
+        let _ = Angle::new(90.0) / Angle::new(0.0);
     }
 
     #[test]
-    fn calculate_rounding_up() {
-        let time_1 = Utc
-            .with_ymd_and_hms(2015, 07, 13, 05, 59, 20)
-            .single()
-            .expect("Invalid date and time.");
+    fn distance_between_coordinates() {
+        let beirut = Coordinates::new(33.8938, 35.5018);
+        let damascus = Coordinates::new(33.5138, 36.2765);
 
-        assert_eq!(
-            time_1.rounded_minute(Rounding::Ceil),
-            Utc.with_ymd_and_hms(2015, 7, 13, 6, 00, 00)
-                .single()
-                .unwrap()
-        );
+        assert_eq!(beirut.distance_to(beirut), 0.0);
+        assert!((beirut.distance_to(damascus) - 83.0).abs() < 1.0);
+        assert_eq!(beirut.distance_to(damascus), damascus.distance_to(beirut));
     }
 
     #[test]
-    fn calculate_rounding_none() {
-        let time_1 = Utc
-            .with_ymd_and_hms(2015, 07, 13, 05, 59, 20)
-            .single()
-            .expect("Invalid date and time.");
+    fn coordinate_angles() {
+        let coordinates = Coordinates::new(21.4225, 39.8262);
 
-        assert_eq!(
-            time_1.rounded_minute(Rounding::None),
-            Utc.with_ymd_and_hms(2015, 7, 13, 5, 59, 20)
-                .single()
-                .unwrap()
-        );
+        assert_eq!(coordinates.latitude_angle(), Angle::new(21.4225));
+        assert_eq!(coordinates.longitude_angle(), Angle::new(39.8262));
     }
 }
@@ -0,0 +1,388 @@
+//! # Batch
+//!
+//! The schedules of many locations over a range of dates, as computed by
+//! backends precomputing country-wide tables. The batch functions are
+//! only threaded, not vectorized: every schedule is computed on its own,
+//! with the same scalar astronomy as [`PrayerTimes`].
+//!
+//! The positions of the sun only depend on the date, and are cached per
+//! thread, so the locations are computed one after the other over the
+//! whole range rather than date by date. With the `parallel` feature,
+//! [`schedules_parallel`] splits the locations into chunks computed on
+//! their own threads, each with its own cache.
+//!
+//! ##### Example
+//!
+//! ```
+//! use miqat::batch;
+//! use miqat::prelude::*;
+//!
+//! let locations = [
+//!     Coordinates::new(33.8938, 35.5018),
+//!     Coordinates::new(34.4367, 35.8497),
+//! ];
+//! let schedules = batch::schedules(
+//!     NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+//!     NaiveDate::from_ymd_opt(2024, 3, 31).unwrap(),
+//!     &locations,
+//!     Method::MuslimWorldLeague.parameters(),
+//! )
+//! .unwrap();
+//!
+//! assert_eq!(schedules.len(), 2);
+//! assert_eq!(schedules[1].len(), 31);
+//! ```
+//!
+//! [`grid_times`] computes a day over a regular grid of a bounding box
+//! instead, for maps comparing locations or methods.
+
+use crate::astronomy::ops::Observer;
+use crate::astronomy::unit::Coordinates;
+use crate::error::CalculationError;
+use crate::models::parameters::Parameters;
+use crate::prayer_times::PrayerTimes;
+use chrono::NaiveDate;
+#[cfg(feature = "parallel")]
+use std::num::NonZeroUsize;
+
+/// The schedules of each location from `first` to `last`, both
+/// included, in the order of `locations`, computed one at a time on the
+/// calling thread. Fails on the first schedule that can't be computed.
+pub fn schedules(
+    first: NaiveDate,
+    last: NaiveDate,
+    locations: &[Coordinates],
+    parameters: Parameters,
+) -> Result<Vec<Vec<PrayerTimes>>, CalculationError> {
+    locations
+        .iter()
+        .map(|coordinates| {
+            first
+                .iter_days()
+                .take_while(|date| *date <= last)
+                .map(|date| PrayerTimes::try_computed(date, *coordinates, parameters))
+                .collect()
+        })
+        .collect()
+}
+
+/// A rectangle of latitudes and longitudes, not crossing the antimeridian.
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct BoundingBox {
+    pub south_west: Coordinates,
+    pub north_east: Coordinates,
+}
+
+impl BoundingBox {
+    pub fn new(south_west: Coordinates, north_east: Coordinates) -> Self {
+        BoundingBox {
+            south_west,
+            north_east,
+        }
+    }
+
+    pub fn contains(&self, coordinates: Coordinates) -> bool {
+        (self.south_west.latitude..=self.north_east.latitude).contains(&coordinates.latitude)
+            && (self.south_west.longitude..=self.north_east.longitude)
+                .contains(&coordinates.longitude)
+    }
+}
+
+/// The schedules of a day at the points of a regular grid, from the
+/// south west corner of a bounding box, row by row northwards. Points
+/// whose schedule can't be computed, e.g. where the sun doesn't set,
+/// keep the error instead.
+#[derive(PartialEq, Debug, Clone)]
+pub struct Grid {
+    bounding_box: BoundingBox,
+    resolution: f64,
+    rows: usize,
+    columns: usize,
+    times: Vec<Result<PrayerTimes, CalculationError>>,
+}
+
+impl Grid {
+    pub fn bounding_box(&self) -> BoundingBox {
+        self.bounding_box
+    }
+
+    /// The distance between neighbouring points, in degrees.
+    pub fn resolution(&self) -> f64 {
+        self.resolution
+    }
+
+    /// The number of latitudes.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// The number of longitudes.
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    /// The point in `row` and `column`, which may be outside of the grid.
+    pub fn coordinates(&self, row: usize, column: usize) -> Coordinates {
+        Coordinates::new(
+            self.bounding_box.south_west.latitude + row as f64 * self.resolution,
+            self.bounding_box.south_west.longitude + column as f64 * self.resolution,
+        )
+    }
+
+    /// The schedule of the point in `row` and `column`, or why it can't
+    /// be computed. `None` outside of the grid.
+    pub fn get(&self, row: usize, column: usize) -> Option<Result<&PrayerTimes, CalculationError>> {
+        if row < self.rows && column < self.columns {
+            self.times
+                .get(row * self.columns + column)
+                .map(|times| times.as_ref().map_err(|error| *error))
+        } else {
+            None
+        }
+    }
+
+    /// Every point with its schedule, or why it can't be computed, row
+    /// by row.
+    pub fn iter(
+        &self,
+    ) -> impl Iterator<Item = (Coordinates, Result<&PrayerTimes, CalculationError>)> {
+        self.times.iter().enumerate().map(|(index, times)| {
+            (
+                self.coordinates(index / self.columns, index % self.columns),
+                times.as_ref().map_err(|error| *error),
+            )
+        })
+    }
+}
+
+/// The most points [`grid_times`] computes.
+pub const MAX_GRID_POINTS: usize = 1_000_000;
+
+/// The schedules of `date` on a grid over `bounding_box`, every
+/// `resolution` degrees from its south west corner. The points are
+/// computed row by row, sharing the positions of the sun of the day, and
+/// along a row the sine and cosine of its latitude. The grid is empty
+/// when the box is, when `resolution` is not positive, or when it would
+/// have more than [`MAX_GRID_POINTS`] points.
+///
+/// ```
+/// use miqat::batch::{self, BoundingBox};
+/// use miqat::prelude::*;
+///
+/// let lebanon = BoundingBox::new(Coordinates::new(33.0, 35.0), Coordinates::new(34.5, 36.5));
+/// let date = NaiveDate::from_ymd_opt(2024, 3, 20).unwrap();
+/// let grid = batch::grid_times(lebanon, 0.5, date, Method::MuslimWorldLeague.parameters());
+/// let maghrib = |column| grid.get(0, column).unwrap().unwrap().time(Prayer::Maghrib);
+///
+/// assert_eq!((grid.rows(), grid.columns()), (4, 4));
+/// assert!(maghrib(0) > maghrib(3));
+/// ```
+pub fn grid_times(
+    bounding_box: BoundingBox,
+    resolution: f64,
+    date: NaiveDate,
+    parameters: Parameters,
+) -> Grid {
+    let count = |from: f64, to: f64| {
+        if resolution > 0.0 && to >= from {
+            // Tolerates the rounding of the step, so that a box of a
+            // whole number of steps includes its far edge.
+            ((to - from) / resolution + 1e-9).floor() + 1.0
+        } else {
+            0.0
+        }
+    };
+    let rows = count(
+        bounding_box.south_west.latitude,
+        bounding_box.north_east.latitude,
+    );
+    let columns = count(
+        bounding_box.south_west.longitude,
+        bounding_box.north_east.longitude,
+    );
+    let (rows, columns) = if rows * columns <= MAX_GRID_POINTS as f64 {
+        (rows as usize, columns as usize)
+    } else {
+        (0, 0)
+    };
+    let mut grid = Grid {
+        bounding_box,
+        resolution,
+        rows,
+        columns,
+        times: Vec::with_capacity(rows * columns),
+    };
+
+    for row in 0..grid.rows {
+        let observer = Observer::new(grid.coordinates(row, 0));
+        for column in 0..grid.columns {
+            let longitude = grid.coordinates(row, column).longitude;
+            grid.times.push(PrayerTimes::try_computed_for(
+                date,
+                observer.at_longitude(longitude),
+                parameters,
+            ));
+        }
+    }
+
+    grid
+}
+
+/// Like [`schedules`], with the locations split in chunks computed on up
+/// to `threads` threads. The results are the same, in the same order.
+/// This is the only speed-up over [`schedules`]: each thread still
+/// computes its locations one schedule at a time.
+///
+/// Only available with the `parallel` feature.
+#[cfg(feature = "parallel")]
+pub fn schedules_parallel(
+    first: NaiveDate,
+    last: NaiveDate,
+    locations: &[Coordinates],
+    parameters: Parameters,
+    threads: NonZeroUsize,
+) -> Result<Vec<Vec<PrayerTimes>>, CalculationError> {
+    let chunk_len = locations.len().div_ceil(threads.get()).max(1);
+
+    std::thread::scope(|scope| {
+        let chunks: Vec<_> = locations
+            .chunks(chunk_len)
+            .map(|chunk| scope.spawn(move || schedules(first, last, chunk, parameters)))
+            .collect();
+
+        let mut all = Vec::with_capacity(locations.len());
+        for chunk in chunks {
+            let schedules = chunk
+                .join()
+                .unwrap_or_else(|payload| std::panic::resume_unwind(payload));
+            all.extend(schedules?);
+        }
+
+        Ok(all)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Method;
+
+    fn locations() -> Vec<Coordinates> {
+        (0..10)
+            .map(|index| Coordinates::new(-40.0 + 9.0 * index as f64, -150.0 + 31.0 * index as f64))
+            .collect()
+    }
+
+    #[test]
+    fn schedules_of_each_location() {
+        let first = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let last = NaiveDate::from_ymd_opt(2024, 1, 10).unwrap();
+        let params = Method::MuslimWorldLeague.parameters();
+        let locations = locations();
+        let schedules = schedules(first, last, &locations, params).unwrap();
+
+        assert_eq!(schedules.len(), locations.len());
+        for (coordinates, days) in locations.iter().zip(&schedules) {
+            assert_eq!(days.len(), 10);
+            assert_eq!(
+                days[4],
+                PrayerTimes::computed(first + chrono::Duration::days(4), *coordinates, params)
+            );
+        }
+    }
+
+    #[test]
+    fn first_failure_is_returned() {
+        let date = NaiveDate::from_ymd_opt(2024, 6, 21).unwrap();
+        let locations = [
+            Coordinates::new(33.8938, 35.5018),
+            Coordinates::new(78.2232, 15.6267),
+        ];
+
+        assert_eq!(
+            schedules(
+                date,
+                date,
+                &locations,
+                Method::MuslimWorldLeague.parameters()
+            ),
+            Err(CalculationError::NoSunriseOrSunset(date))
+        );
+    }
+
+    #[test]
+    fn grid_over_a_bounding_box() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 20).unwrap();
+        let params = Method::MuslimWorldLeague.parameters();
+        let bounding_box =
+            BoundingBox::new(Coordinates::new(30.0, -10.0), Coordinates::new(31.0, 10.0));
+        let grid = grid_times(bounding_box, 0.25, date, params);
+
+        assert_eq!((grid.rows(), grid.columns()), (5, 81));
+        assert_eq!(grid.iter().count(), 5 * 81);
+        assert_eq!(grid.coordinates(4, 80), bounding_box.north_east);
+        assert_eq!(grid.get(5, 0), None);
+        for (coordinates, times) in grid.iter() {
+            assert!(bounding_box.contains(coordinates));
+            assert_eq!(times, Ok(&PrayerTimes::computed(date, coordinates, params)));
+        }
+
+        let empty = BoundingBox::new(bounding_box.north_east, bounding_box.south_west);
+        assert_eq!(grid_times(empty, 0.25, date, params).iter().count(), 0);
+        assert_eq!(grid_times(bounding_box, 0.0, date, params).rows(), 0);
+        for resolution in [1e-9, f64::MIN_POSITIVE] {
+            let too_fine = grid_times(bounding_box, resolution, date, params);
+
+            assert_eq!((too_fine.rows(), too_fine.columns()), (0, 0));
+        }
+    }
+
+    #[test]
+    fn polar_points_dont_fail_the_grid() {
+        let date = NaiveDate::from_ymd_opt(2024, 6, 21).unwrap();
+        let params = Method::MuslimWorldLeague.parameters();
+        let bounding_box =
+            BoundingBox::new(Coordinates::new(60.0, 10.0), Coordinates::new(75.0, 20.0));
+        let grid = grid_times(bounding_box, 5.0, date, params);
+
+        assert_eq!((grid.rows(), grid.columns()), (4, 3));
+        for column in 0..grid.columns() {
+            assert_eq!(
+                grid.get(0, column).unwrap(),
+                Ok(&PrayerTimes::computed(
+                    date,
+                    grid.coordinates(0, column),
+                    params
+                ))
+            );
+            assert_eq!(
+                grid.get(3, column).unwrap(),
+                Err(CalculationError::NoSunriseOrSunset(date))
+            );
+        }
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel_matches_sequential() {
+        let first = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let last = NaiveDate::from_ymd_opt(2024, 4, 30).unwrap();
+        let params = Method::MuslimWorldLeague.parameters();
+        let locations = locations();
+        let sequential = schedules(first, last, &locations, params).unwrap();
+
+        for threads in [1, 3, 4, 16] {
+            let threads = NonZeroUsize::new(threads).unwrap();
+
+            assert_eq!(
+                schedules_parallel(first, last, &locations, params, threads).unwrap(),
+                sequential
+            );
+        }
+        assert!(
+            schedules_parallel(first, last, &[], params, NonZeroUsize::MIN)
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
@@ -0,0 +1,587 @@
+//! # Schedule Cache
+//!
+//! A year of prayer times in a compact binary form, so that offline apps
+//! can compute the schedule once and reload it at every launch. The blob
+//! carries a format version and a hash of the location and parameters it
+//! was computed with, and of the [calculation version](crate::CALC_VERSION);
+//! reloading it with different settings, or after the calculation changed,
+//! is rejected as stale instead of silently returning wrong times.
+//!
+//! ##### Example
+//!
+//! ```
+//! use miqat::cache::ScheduleCache;
+//! use miqat::prelude::*;
+//!
+//! let beirut = Coordinates::new(33.8938, 35.5018);
+//! let params = Method::MuslimWorldLeague.parameters();
+//! let bytes = ScheduleCache::compute(2024, beirut, params).unwrap().to_bytes().unwrap();
+//!
+//! let cache = ScheduleCache::from_bytes(&bytes, beirut, params).unwrap();
+//! let date = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+//! assert_eq!(cache.get(date), Some(&PrayerTimes::computed(date, beirut, params)));
+//! ```
+
+use crate::astronomy::unit::Coordinates;
+use crate::changelog::CALC_VERSION;
+use crate::error::CalculationError;
+use crate::models::asr_factor::AsrFactor;
+use crate::models::day_boundary::DayBoundary;
+use crate::models::delta_t::DeltaT;
+use crate::models::high_altitude_rule::HighLatitudeRule;
+use crate::models::ishaa_interval_start::IshaaIntervalStart;
+use crate::models::ishaa_parameter::IshaaParameter;
+use crate::models::maghrib_parameter::MaghribParameter;
+use crate::models::mazhab::Mazhab;
+use crate::models::method_behavior::MethodBehavior;
+use crate::models::next_day_fajr::NextDayFajr;
+use crate::models::parameters::Parameters;
+use crate::models::rounding::Rounding;
+use crate::models::time_source::TimeSource;
+use crate::models::twilight::Twilight;
+use crate::prayer_times::Origin;
+use crate::prayer_times::PrayerTimes;
+use chrono::DateTime;
+use chrono::Datelike;
+use chrono::Duration;
+use chrono::NaiveDate;
+use chrono::NaiveTime;
+use chrono::Utc;
+use std::fmt;
+
+const MAGIC: &[u8; 4] = b"MQTC";
+const VERSION: u8 = 2;
+const HEADER_LEN: usize = 4 + 1 + 8 + 4 + 2;
+const DAY_LEN: usize = 7 * 4 + 2;
+const FINGERPRINT_VERSION: u8 = 1;
+
+/// Why a cached schedule could not be saved or loaded.
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub enum CacheError {
+    /// The data is not a schedule cache.
+    InvalidHeader,
+    /// The cache was written by an incompatible version of the library.
+    UnsupportedVersion(u8),
+    /// The cache was computed for another location or other parameters.
+    Stale,
+    /// The data ends early or contains invalid values, or the cache
+    /// holds more days than the format can count.
+    Corrupted,
+}
+
+impl fmt::Display for CacheError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CacheError::InvalidHeader => write!(f, "not a schedule cache"),
+            CacheError::UnsupportedVersion(version) => {
+                write!(f, "unsupported schedule cache version {version}")
+            }
+            CacheError::Stale => write!(f, "schedule cache computed with other settings"),
+            CacheError::Corrupted => write!(f, "corrupted schedule cache"),
+        }
+    }
+}
+
+impl std::error::Error for CacheError {}
+
+/// The prayer times of every day of a Gregorian year.
+#[derive(PartialEq, Debug, Clone)]
+pub struct ScheduleCache {
+    pub year: i32,
+    pub days: Vec<PrayerTimes>,
+    fingerprint: u64,
+}
+
+impl ScheduleCache {
+    /// Computes every day of `year`, failing like
+    /// [`try_computed`](PrayerTimes::try_computed) on the first day that
+    /// can't be computed.
+    pub fn compute(
+        year: i32,
+        coordinates: Coordinates,
+        parameters: Parameters,
+    ) -> Result<Self, CalculationError> {
+        let first =
+            NaiveDate::from_ymd_opt(year, 1, 1).ok_or(CalculationError::YearOutOfRange(year))?;
+        let days = first
+            .iter_days()
+            .take_while(|date| date.year() == year)
+            .map(|date| PrayerTimes::try_computed(date, coordinates, parameters))
+            .collect::<Result<_, _>>()?;
+
+        Ok(ScheduleCache {
+            year,
+            days,
+            fingerprint: fingerprint(year, coordinates, parameters),
+        })
+    }
+
+    /// The prayer times for `date`, if it falls within the cached year.
+    pub fn get(&self, date: NaiveDate) -> Option<&PrayerTimes> {
+        if date.year() == self.year {
+            self.days.get(date.ordinal0() as usize)
+        } else {
+            None
+        }
+    }
+
+    /// The cache in binary form, or [`CacheError::Corrupted`] if it
+    /// holds more days than the format can count.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, CacheError> {
+        let count = u16::try_from(self.days.len()).map_err(|_| CacheError::Corrupted)?;
+        let mut bytes = Vec::with_capacity(HEADER_LEN + self.days.len() * DAY_LEN);
+        bytes.extend_from_slice(MAGIC);
+        bytes.push(VERSION);
+        bytes.extend_from_slice(&self.fingerprint.to_le_bytes());
+        bytes.extend_from_slice(&self.year.to_le_bytes());
+        bytes.extend_from_slice(&count.to_le_bytes());
+
+        for times in &self.days {
+            let midnight = times.date().and_time(NaiveTime::MIN).and_utc();
+            for time in [
+                times.fajr,
+                times.sunrise,
+                times.dhuhr,
+                times.asr,
+                times.maghrib,
+                times.ishaa,
+                times.fajr_tomorrow,
+            ] {
+                let offset = time.signed_duration_since(midnight).num_seconds() as i32;
+                bytes.extend_from_slice(&offset.to_le_bytes());
+            }
+            bytes.push(encode_source(times.fajr_source, times.fajr_clamped));
+            bytes.push(encode_source(times.ishaa_source, times.ishaa_clamped));
+        }
+
+        Ok(bytes)
+    }
+
+    /// Loads a cache written by [`to_bytes`](ScheduleCache::to_bytes),
+    /// checking that it was computed for `coordinates` and `parameters`.
+    pub fn from_bytes(
+        bytes: &[u8],
+        coordinates: Coordinates,
+        parameters: Parameters,
+    ) -> Result<Self, CacheError> {
+        if bytes.len() < HEADER_LEN || &bytes[0..4] != MAGIC {
+            return Err(CacheError::InvalidHeader);
+        }
+
+        if bytes[4] != VERSION {
+            return Err(CacheError::UnsupportedVersion(bytes[4]));
+        }
+
+        let stored = u64::from_le_bytes(read(bytes, 5)?);
+        let year = i32::from_le_bytes(read(bytes, 13)?);
+        if stored != fingerprint(year, coordinates, parameters) {
+            return Err(CacheError::Stale);
+        }
+
+        let first = NaiveDate::from_ymd_opt(year, 1, 1).ok_or(CacheError::Corrupted)?;
+        let count = usize::from(u16::from_le_bytes(read(bytes, 17)?));
+        let days_in_year = first
+            .iter_days()
+            .take_while(|date| date.year() == year)
+            .count();
+        if count != days_in_year || bytes.len() != HEADER_LEN + count * DAY_LEN {
+            return Err(CacheError::Corrupted);
+        }
+
+        let origin = Origin::Computed(coordinates, parameters);
+        let (chunks, _) = bytes[HEADER_LEN..].as_chunks::<DAY_LEN>();
+        let days = chunks
+            .iter()
+            .zip(first.iter_days())
+            .map(|(chunk, date)| decode_day(chunk, date, origin.clone()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(ScheduleCache {
+            year,
+            days,
+            fingerprint: stored,
+        })
+    }
+}
+
+/// The `N` bytes at `start`, or [`CacheError::Corrupted`] if the data
+/// ends before them.
+fn read<const N: usize>(bytes: &[u8], start: usize) -> Result<[u8; N], CacheError> {
+    bytes
+        .get(start..)
+        .and_then(<[u8]>::first_chunk)
+        .copied()
+        .ok_or(CacheError::Corrupted)
+}
+
+fn decode_day(
+    chunk: &[u8; DAY_LEN],
+    date: NaiveDate,
+    origin: Origin,
+) -> Result<PrayerTimes, CacheError> {
+    let midnight = date.and_time(NaiveTime::MIN).and_utc();
+    let time = |index: usize| -> Result<DateTime<Utc>, CacheError> {
+        let offset = i32::from_le_bytes(read(chunk, index * 4)?);
+
+        midnight
+            .checked_add_signed(Duration::seconds(offset.into()))
+            .ok_or(CacheError::Corrupted)
+    };
+
+    let (fajr_source, fajr_clamped) = decode_source(chunk[28])?;
+    let (ishaa_source, ishaa_clamped) = decode_source(chunk[29])?;
+
+    Ok(PrayerTimes {
+        date,
+        origin,
+        fajr: time(0)?,
+        sunrise: time(1)?,
+        dhuhr: time(2)?,
+        asr: time(3)?,
+        maghrib: time(4)?,
+        ishaa: time(5)?,
+        fajr_tomorrow: time(6)?,
+        fajr_source,
+        ishaa_source,
+        fajr_clamped,
+        ishaa_clamped,
+        solar_days: None,
+    })
+}
+
+/// Set on a source byte when the time was clamped to the safe time.
+const CLAMPED: u8 = 0x80;
+
+fn encode_source(source: TimeSource, clamped: bool) -> u8 {
+    let flag = if clamped { CLAMPED } else { 0 };
+
+    flag | match source {
+        TimeSource::Astronomical => 0,
+        TimeSource::SeasonalEstimate => 1,
+        TimeSource::HighLatitudeRule(HighLatitudeRule::MiddleOfTheNight) => 2,
+        TimeSource::HighLatitudeRule(HighLatitudeRule::SeventhOfTheNight) => 3,
+        TimeSource::HighLatitudeRule(HighLatitudeRule::TwilightAngle) => 4,
+        TimeSource::Interpolated => 5,
+    }
+}
+
+fn decode_source(value: u8) -> Result<(TimeSource, bool), CacheError> {
+    let source = match value & !CLAMPED {
+        0 => Ok(TimeSource::Astronomical),
+        1 => Ok(TimeSource::SeasonalEstimate),
+        2 => Ok(TimeSource::HighLatitudeRule(
+            HighLatitudeRule::MiddleOfTheNight,
+        )),
+        3 => Ok(TimeSource::HighLatitudeRule(
+            HighLatitudeRule::SeventhOfTheNight,
+        )),
+        4 => Ok(TimeSource::HighLatitudeRule(
+            HighLatitudeRule::TwilightAngle,
+        )),
+        5 => Ok(TimeSource::Interpolated),
+        _ => Err(CacheError::Corrupted),
+    }?;
+
+    Ok((source, value & CLAMPED != 0))
+}
+
+/// A hash of the [calculation version](CALC_VERSION), the year, the
+/// exact coordinates and every field of the parameters, each in a fixed
+/// encoding. Bump [`FINGERPRINT_VERSION`] when the encoding changes.
+///
+/// A [`DeltaT::Provider`] is hashed by its values on the first of
+/// every month of the year, as a function has no stable identity.
+pub(crate) fn fingerprint(year: i32, coordinates: Coordinates, parameters: Parameters) -> u64 {
+    let Parameters {
+        behavior,
+        fajr_angle,
+        maghrib_parameter,
+        ishaa_parameter,
+        ishaa_interval_start,
+        mazhab,
+        asr_factor,
+        high_latitude_rule,
+        fajr_high_latitude_rule,
+        ishaa_high_latitude_rule,
+        disable_safe_time_clamp,
+        seasonal_twilight,
+        dhuhr_offset_after_transit,
+        day_boundary,
+        next_day_fajr,
+        delta_t,
+        elevation,
+        adjustments,
+        method_adjustments,
+        rounding,
+        twilight,
+    } = parameters;
+    let mut bytes = vec![FINGERPRINT_VERSION];
+
+    bytes.extend_from_slice(&CALC_VERSION.to_le_bytes());
+    bytes.extend_from_slice(&year.to_le_bytes());
+    push_f64(&mut bytes, coordinates.latitude);
+    push_f64(&mut bytes, coordinates.longitude);
+
+    bytes.push(match behavior {
+        MethodBehavior::Standard => 0,
+        MethodBehavior::MoonsightingCommittee => 1,
+    });
+    push_f64(&mut bytes, fajr_angle);
+    match maghrib_parameter {
+        MaghribParameter::Sunset => bytes.push(0),
+        MaghribParameter::Angle(angle) => {
+            bytes.push(1);
+            push_f64(&mut bytes, angle);
+        }
+    }
+    match ishaa_parameter {
+        IshaaParameter::Angle(angle) => {
+            bytes.push(0);
+            push_f64(&mut bytes, angle);
+        }
+        IshaaParameter::Interval(minutes) => {
+            bytes.push(1);
+            bytes.extend_from_slice(&minutes.to_le_bytes());
+        }
+    }
+    bytes.push(match ishaa_interval_start {
+        IshaaIntervalStart::Sunset => 0,
+        IshaaIntervalStart::Maghrib => 1,
+    });
+    bytes.push(encode_mazhab(mazhab));
+    match asr_factor {
+        None => bytes.push(0),
+        Some(AsrFactor::Mazhab(mazhab)) => bytes.extend_from_slice(&[1, encode_mazhab(mazhab)]),
+        Some(AsrFactor::Custom(factor)) => {
+            bytes.push(2);
+            push_f64(&mut bytes, factor);
+        }
+    }
+    bytes.push(encode_rule(Some(high_latitude_rule)));
+    bytes.push(encode_rule(fajr_high_latitude_rule));
+    bytes.push(encode_rule(ishaa_high_latitude_rule));
+    bytes.push(u8::from(disable_safe_time_clamp));
+    bytes.push(u8::from(seasonal_twilight));
+    bytes.extend_from_slice(&dhuhr_offset_after_transit.to_le_bytes());
+    match day_boundary {
+        DayBoundary::Utc => bytes.push(0),
+        DayBoundary::LocalCivil(offset) => {
+            bytes.push(1);
+            bytes.extend_from_slice(&offset.local_minus_utc().to_le_bytes());
+        }
+    }
+    bytes.push(match next_day_fajr {
+        NextDayFajr::Computed => 0,
+        NextDayFajr::Estimated => 1,
+    });
+    match delta_t {
+        DeltaT::Estimated => bytes.push(0),
+        DeltaT::Fixed(seconds) => {
+            bytes.push(1);
+            push_f64(&mut bytes, seconds);
+        }
+        DeltaT::Provider(provider) => {
+            bytes.push(2);
+            for date in (1..=12).filter_map(|month| NaiveDate::from_ymd_opt(year, month, 1)) {
+                push_f64(&mut bytes, provider(date));
+            }
+        }
+    }
+    push_f64(&mut bytes, elevation);
+    for adjustment in [adjustments, method_adjustments] {
+        for minutes in [
+            adjustment.fajr,
+            adjustment.sunrise,
+            adjustment.dhuhr,
+            adjustment.asr,
+            adjustment.maghrib,
+            adjustment.ishaa,
+        ] {
+            bytes.extend_from_slice(&minutes.to_le_bytes());
+        }
+    }
+    bytes.push(match rounding {
+        Rounding::Nearest => 0,
+        Rounding::Ceil => 1,
+        Rounding::Floor => 2,
+        Rounding::None => 3,
+    });
+    bytes.push(match twilight {
+        Twilight::General => 0,
+        Twilight::Red => 1,
+        Twilight::White => 2,
+    });
+
+    fnv1a_bytes(&bytes)
+}
+
+fn push_f64(bytes: &mut Vec<u8>, value: f64) {
+    bytes.extend_from_slice(&value.to_bits().to_le_bytes());
+}
+
+fn encode_mazhab(mazhab: Mazhab) -> u8 {
+    match mazhab {
+        Mazhab::Shafi => 0,
+        Mazhab::Hanafi => 1,
+    }
+}
+
+fn encode_rule(rule: Option<HighLatitudeRule>) -> u8 {
+    match rule {
+        None => 0,
+        Some(HighLatitudeRule::MiddleOfTheNight) => 1,
+        Some(HighLatitudeRule::SeventhOfTheNight) => 2,
+        Some(HighLatitudeRule::TwilightAngle) => 3,
+    }
+}
+
+/// FNV-1a over the bytes of `text`. Stable across platforms, unlike
+/// `std`'s hasher which is allowed to change between Rust releases.
+pub(crate) fn fnv1a(text: &str) -> u64 {
+    fnv1a_bytes(text.as_bytes())
+}
+
+fn fnv1a_bytes(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Method;
+    use crate::models::mazhab::Mazhab;
+    use crate::models::prayer::Prayer;
+
+    fn beirut() -> Coordinates {
+        Coordinates::new(33.8938, 35.5018)
+    }
+
+    #[test]
+    fn cache_round_trip() {
+        let params = Method::MuslimWorldLeague.parameters();
+        let cache = ScheduleCache::compute(2024, beirut(), params).unwrap();
+        let bytes = cache.to_bytes().unwrap();
+
+        assert_eq!(cache.days.len(), 366);
+        assert_eq!(bytes.len(), HEADER_LEN + 366 * DAY_LEN);
+        assert_eq!(
+            ScheduleCache::from_bytes(&bytes, beirut(), params),
+            Ok(cache)
+        );
+    }
+
+    #[test]
+    fn cache_round_trip_keeps_time_sources() {
+        let turin = Coordinates::new(45.0703, 7.6869);
+        let params = Method::MoonsightingCommittee.parameters();
+        let cache = ScheduleCache::compute(2023, turin, params).unwrap();
+        let loaded = ScheduleCache::from_bytes(&cache.to_bytes().unwrap(), turin, params).unwrap();
+
+        assert!(
+            cache
+                .days
+                .iter()
+                .any(|times| times.is_clamped(Prayer::Fajr))
+        );
+        assert_eq!(loaded, cache);
+    }
+
+    #[test]
+    fn reject_stale_cache() {
+        let params = Method::MuslimWorldLeague.parameters();
+        let bytes = ScheduleCache::compute(2024, beirut(), params)
+            .unwrap()
+            .to_bytes()
+            .unwrap();
+
+        assert_eq!(
+            ScheduleCache::from_bytes(&bytes, beirut(), params.mazhab(Mazhab::Hanafi)),
+            Err(CacheError::Stale)
+        );
+        assert_eq!(
+            ScheduleCache::from_bytes(&bytes, Coordinates::new(34.4367, 35.8497), params),
+            Err(CacheError::Stale)
+        );
+    }
+
+    #[test]
+    fn reject_invalid_data() {
+        let params = Method::MuslimWorldLeague.parameters();
+        let mut bytes = ScheduleCache::compute(2024, beirut(), params)
+            .unwrap()
+            .to_bytes()
+            .unwrap();
+
+        assert_eq!(
+            ScheduleCache::from_bytes(&bytes[..bytes.len() - 1], beirut(), params),
+            Err(CacheError::Corrupted)
+        );
+        assert_eq!(
+            ScheduleCache::from_bytes(b"nope", beirut(), params),
+            Err(CacheError::InvalidHeader)
+        );
+
+        let mut short = bytes.clone();
+        short[17..19].copy_from_slice(&365u16.to_le_bytes());
+        short.truncate(short.len() - DAY_LEN);
+        assert_eq!(
+            ScheduleCache::from_bytes(&short, beirut(), params),
+            Err(CacheError::Corrupted)
+        );
+
+        bytes[4] = 9;
+        assert_eq!(
+            ScheduleCache::from_bytes(&bytes, beirut(), params),
+            Err(CacheError::UnsupportedVersion(9))
+        );
+    }
+
+    #[test]
+    fn fingerprint_covers_every_setting() {
+        let params = Method::MuslimWorldLeague.parameters();
+        let base = fingerprint(2024, beirut(), params);
+        let elevated = params.elevation(100.0);
+        let mut clamped = params;
+        clamped.disable_safe_time_clamp = true;
+
+        assert_eq!(base, fingerprint(2024, beirut(), params));
+        assert_ne!(base, fingerprint(2025, beirut(), params));
+        assert_ne!(
+            base,
+            fingerprint(2024, Coordinates::new(33.8938, 35.501_800_1), params)
+        );
+        assert_ne!(base, fingerprint(2024, beirut(), elevated));
+        assert_ne!(base, fingerprint(2024, beirut(), clamped));
+        let mut fixed = params;
+        fixed.delta_t = DeltaT::Fixed(0.0);
+        assert_ne!(base, fingerprint(2024, beirut(), fixed));
+    }
+
+    #[test]
+    fn reject_too_many_days() {
+        let params = Method::MuslimWorldLeague.parameters();
+        let mut cache = ScheduleCache::compute(2024, beirut(), params).unwrap();
+        let day = cache.days[0].clone();
+        cache.days.resize(usize::from(u16::MAX) + 1, day);
+
+        assert_eq!(cache.to_bytes(), Err(CacheError::Corrupted));
+    }
+
+    #[test]
+    fn compute_refuses_unsupported_years() {
+        let params = Method::MuslimWorldLeague.parameters();
+
+        assert_eq!(
+            ScheduleCache::compute(1000, beirut(), params),
+            Err(CalculationError::DateOutOfRange(
+                NaiveDate::from_ymd_opt(1000, 1, 1).unwrap()
+            ))
+        );
+        assert_eq!(
+            ScheduleCache::compute(i32::MAX, beirut(), params),
+            Err(CalculationError::YearOutOfRange(i32::MAX))
+        );
+    }
+}
@@ -0,0 +1,129 @@
+//! # Calculation Changelog
+//!
+//! The version of the calculation, bumped whenever a change to the
+//! library moves computed times, and the list of those changes. Unlike
+//! the crate version, it doesn't change with new APIs or fixes that
+//! leave the times alone, so apps can keep timetables cached across
+//! upgrades and recompute them only when [`CALC_VERSION`] changes.
+//!
+//! ##### Example
+//!
+//! ```
+//! use miqat::prelude::*;
+//!
+//! let cached_with = 1;
+//! let changes: Vec<_> = miqat::calculation_changelog()
+//!     .iter()
+//!     .filter(|change| change.version > cached_with)
+//!     .collect();
+//!
+//! assert_eq!(changes.last().unwrap().version, miqat::CALC_VERSION);
+//! assert!(changes.iter().any(|change| change.prayers.contains(&Prayer::Ishaa)));
+//! ```
+
+use crate::models::prayer::Prayer;
+
+/// The current version of the calculation, the version of the last
+/// entry of [`calculation_changelog`].
+pub const CALC_VERSION: u32 = 10;
+
+/// A change to the calculation that moves computed times.
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct CalculationChange {
+    /// The [`CALC_VERSION`] the change was introduced in.
+    pub version: u32,
+    /// The prayers whose times may have moved.
+    pub prayers: &'static [Prayer],
+    pub summary: &'static str,
+}
+
+const ALL: &[Prayer] = &[
+    Prayer::Fajr,
+    Prayer::Sunrise,
+    Prayer::Dhuhr,
+    Prayer::Asr,
+    Prayer::Maghrib,
+    Prayer::Ishaa,
+    Prayer::FajrTomorrow,
+];
+
+const TWILIGHT: &[Prayer] = &[Prayer::Fajr, Prayer::Ishaa, Prayer::FajrTomorrow];
+
+// Sorted by version, one entry per version.
+const CHANGELOG: [CalculationChange; 10] = [
+    CalculationChange {
+        version: 1,
+        prayers: ALL,
+        summary: "The calculation of the Adhan library",
+    },
+    CalculationChange {
+        version: 2,
+        prayers: ALL,
+        summary: "Trigonometry through libm, for the same times on every target",
+    },
+    CalculationChange {
+        version: 3,
+        prayers: ALL,
+        summary: "Delta T between universal and terrestrial time accounted for",
+    },
+    CalculationChange {
+        version: 4,
+        prayers: TWILIGHT,
+        summary: "Seasonal twilight counts the days since the winter solstice of the southern \
+                  hemisphere south of the equator",
+    },
+    CalculationChange {
+        version: 5,
+        prayers: TWILIGHT,
+        summary: "Fajr and Ishaa fall back to the high latitude rule when out of order with \
+                  the night",
+    },
+    CalculationChange {
+        version: 6,
+        prayers: &[Prayer::Ishaa],
+        summary: "Interval Ishaa stays after Maghrib under the twilight angle rule",
+    },
+    CalculationChange {
+        version: 7,
+        prayers: &[Prayer::Ishaa],
+        summary: "The Ishaa interval of Umm al-Qura is measured from Maghrib",
+    },
+    CalculationChange {
+        version: 8,
+        prayers: ALL,
+        summary: "Times rounded past 23:59 roll over to the next day",
+    },
+    CalculationChange {
+        version: 9,
+        prayers: ALL,
+        summary: "Rounding up leaves times on a whole minute alone",
+    },
+    CalculationChange {
+        version: 10,
+        prayers: ALL,
+        summary: "Adjustments and Dhuhr offsets of four hours or more, and negative elevations, \
+                  are refused instead of computed",
+    },
+];
+
+/// Every change to the calculation, oldest first.
+pub fn calculation_changelog() -> &'static [CalculationChange] {
+    &CHANGELOG
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn one_entry_per_version_up_to_the_current_one() {
+        for (index, change) in CHANGELOG.iter().enumerate() {
+            assert_eq!(change.version as usize, index + 1);
+            assert!(!change.prayers.is_empty());
+        }
+        assert_eq!(
+            CHANGELOG.last().map(|change| change.version),
+            Some(CALC_VERSION)
+        );
+    }
+}
@@ -0,0 +1,55 @@
+//! # Clock
+//!
+//! The source of the current time for the APIs that depend on it, such as
+//! [`PrayerTimes::current`](crate::PrayerTimes::current). Tests and
+//! simulations can inject their own clock instead of the system one.
+//!
+//! ##### Example
+//!
+//! ```
+//! use miqat::clock::FixedClock;
+//! use miqat::prelude::*;
+//!
+//! let date = NaiveDate::from_ymd_opt(2015, 7, 12).unwrap();
+//! let raleigh = Coordinates::new(35.7750, -78.6336);
+//! let times = PrayerTimes::computed(date, raleigh, Method::NorthAmerica.parameters());
+//! let clock = FixedClock(Utc.with_ymd_and_hms(2015, 7, 12, 18, 0, 0).unwrap());
+//!
+//! assert_eq!(times.current_with(&clock), Some(Prayer::Dhuhr));
+//! assert_eq!(times.next_with(&clock), Prayer::Asr);
+//! ```
+
+use chrono::DateTime;
+use chrono::Utc;
+use std::thread;
+use std::time::Duration;
+
+pub trait Clock {
+    fn now(&self) -> DateTime<Utc>;
+
+    /// Blocks the current thread for `duration`. Simulated clocks can
+    /// advance instead of waiting.
+    fn sleep(&self, duration: Duration) {
+        thread::sleep(duration);
+    }
+}
+
+/// The system clock.
+#[derive(PartialEq, Debug, Copy, Clone, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// A clock stopped at the given instant.
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct FixedClock(pub DateTime<Utc>);
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.0
+    }
+}
@@ -0,0 +1,306 @@
+//! # Location Comparison
+//!
+//! Prayer times for several locations on the same day, side by side.
+//! Useful for travel apps and for coordinating between branch mosques.
+//!
+//! ##### Example
+//!
+//! ```
+//! use miqat::comparison::compare;
+//! use miqat::prelude::*;
+//!
+//! let date = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+//! let locations = [
+//!     ("Beirut", Coordinates::new(33.8938, 35.5018)),
+//!     ("Tripoli", Coordinates::new(34.4367, 35.8497)),
+//! ];
+//! let table = compare(&locations, date, Method::MuslimWorldLeague.parameters());
+//!
+//! assert_eq!(table.column(Prayer::Fajr).len(), 2);
+//! println!("{}", table.to_csv());
+//! ```
+//!
+//! [`compare_methods`] compares the methods at a single location instead,
+//! e.g. to show how far apart they are while the user picks one.
+//!
+//! ```
+//! use miqat::comparison::compare_methods;
+//! use miqat::prelude::*;
+//!
+//! let date = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+//! let london = Coordinates::new(51.5074, -0.1278);
+//! let methods = [Method::MuslimWorldLeague, Method::NorthAmerica];
+//! let comparison = compare_methods(date, london, &methods).unwrap();
+//! let delta = |prayer| comparison.delta(prayer, methods[0], methods[1]).unwrap();
+//!
+//! assert_eq!(delta(Prayer::Dhuhr), Duration::zero());
+//! assert!(delta(Prayer::Fajr) > Duration::minutes(15));
+//! assert!(delta(Prayer::Ishaa) < Duration::zero());
+//! ```
+
+use crate::astronomy::unit::Coordinates;
+use crate::error::CalculationError;
+use crate::models::method::Method;
+use crate::models::parameters::Parameters;
+use crate::models::prayer::Prayer;
+use crate::prayer_times::PrayerTimes;
+use chrono::DateTime;
+use chrono::Duration;
+use chrono::NaiveDate;
+use chrono::SecondsFormat;
+use chrono::Utc;
+
+/// The prayer times of a single named location.
+#[derive(PartialEq, Debug, Clone)]
+pub struct ComparisonRow {
+    pub name: String,
+    pub coordinates: Coordinates,
+    pub times: PrayerTimes,
+}
+
+/// Prayer times for several locations on the same date, in the order
+/// the locations were given.
+#[derive(PartialEq, Debug, Clone)]
+pub struct ComparisonTable {
+    pub date: NaiveDate,
+    pub rows: Vec<ComparisonRow>,
+}
+
+impl ComparisonTable {
+    /// The time of the given prayer at every location.
+    pub fn column(&self, prayer: Prayer) -> Vec<(&str, DateTime<Utc>)> {
+        self.rows
+            .iter()
+            .map(|row| (row.name.as_str(), row.times.time(prayer)))
+            .collect()
+    }
+
+    /// Renders the table as CSV with one line per location and one
+    /// column per prayer. Times are RFC 3339 in UTC.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("location");
+        for prayer in Prayer::ALL {
+            csv.push(',');
+            csv.push_str(prayer.as_slug());
+        }
+        csv.push('\n');
+
+        for row in &self.rows {
+            csv.push_str(&csv_field(&row.name));
+            for prayer in Prayer::ALL {
+                csv.push(',');
+                csv.push_str(
+                    &row.times
+                        .time(prayer)
+                        .to_rfc3339_opts(SecondsFormat::Secs, true),
+                );
+            }
+            csv.push('\n');
+        }
+
+        csv
+    }
+}
+
+/// Computes the prayer times of every location for `date` using the
+/// same parameters.
+pub fn compare<S: AsRef<str>>(
+    locations: &[(S, Coordinates)],
+    date: NaiveDate,
+    parameters: Parameters,
+) -> ComparisonTable {
+    let rows = locations
+        .iter()
+        .map(|(name, coordinates)| ComparisonRow {
+            name: name.as_ref().to_string(),
+            coordinates: *coordinates,
+            times: PrayerTimes::computed(date, *coordinates, parameters),
+        })
+        .collect();
+
+    ComparisonTable { date, rows }
+}
+
+/// The prayer times of several methods at one location and date, in the
+/// order the methods were given.
+#[derive(PartialEq, Debug, Clone)]
+pub struct MethodComparison {
+    pub date: NaiveDate,
+    pub coordinates: Coordinates,
+    pub times: Vec<(Method, PrayerTimes)>,
+}
+
+impl MethodComparison {
+    /// The time of the given prayer under every method.
+    pub fn column(&self, prayer: Prayer) -> Vec<(Method, DateTime<Utc>)> {
+        self.times
+            .iter()
+            .map(|(method, times)| (*method, times.time(prayer)))
+            .collect()
+    }
+
+    /// How much later `prayer` is under `method` than under `reference`,
+    /// negative when it is earlier. `None` if either wasn't compared.
+    pub fn delta(&self, prayer: Prayer, reference: Method, method: Method) -> Option<Duration> {
+        let time = |wanted: Method| {
+            self.times
+                .iter()
+                .find(|(method, _)| *method == wanted)
+                .map(|(_, times)| times.time(prayer))
+        };
+
+        Some(time(method)? - time(reference)?)
+    }
+
+    /// How far apart the earliest and the latest time of the given prayer
+    /// are across the methods.
+    pub fn spread(&self, prayer: Prayer) -> Duration {
+        let column = self.column(prayer);
+        let earliest = column.iter().map(|(_, time)| *time).min();
+        let latest = column.iter().map(|(_, time)| *time).max();
+
+        match (earliest, latest) {
+            (Some(earliest), Some(latest)) => latest - earliest,
+            _ => Duration::zero(),
+        }
+    }
+
+    /// Renders the comparison as CSV with one line per method and one
+    /// column per prayer, holding the difference from the first method
+    /// in whole minutes.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("method");
+        for prayer in Prayer::ALL {
+            csv.push(',');
+            csv.push_str(prayer.as_slug());
+        }
+        csv.push('\n');
+
+        if let Some((reference, _)) = self.times.first() {
+            for (method, _) in &self.times {
+                csv.push_str(method.as_slug());
+                for prayer in Prayer::ALL {
+                    let delta = self.delta(prayer, *reference, *method).unwrap_or_default();
+
+                    csv.push(',');
+                    csv.push_str(&delta.num_minutes().to_string());
+                }
+                csv.push('\n');
+            }
+        } else {
+            // Nothing to do.
+        }
+
+        csv
+    }
+}
+
+/// Computes the prayer times of `date` at `coordinates` with the preset
+/// parameters of every method. Fails on the first method whose schedule
+/// can't be computed.
+pub fn compare_methods(
+    date: NaiveDate,
+    coordinates: Coordinates,
+    methods: &[Method],
+) -> Result<MethodComparison, CalculationError> {
+    let times = methods
+        .iter()
+        .map(|method| {
+            PrayerTimes::try_computed(date, coordinates, method.parameters())
+                .map(|times| (*method, times))
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok(MethodComparison {
+        date,
+        coordinates,
+        times,
+    })
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Method;
+
+    #[test]
+    fn compare_locations_on_the_same_day() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+        let params = Method::MuslimWorldLeague.parameters();
+        let beirut = Coordinates::new(33.8938, 35.5018);
+        let london = Coordinates::new(51.5074, -0.1278);
+        let table = compare(&[("Beirut", beirut), ("London", london)], date, params);
+        let dhuhr = table.column(Prayer::Dhuhr);
+
+        assert_eq!(dhuhr.len(), 2);
+        assert_eq!(dhuhr[0].0, "Beirut");
+        assert_eq!(
+            dhuhr[0].1,
+            PrayerTimes::computed(date, beirut, params).time(Prayer::Dhuhr)
+        );
+        // Beirut is east of London, so its noon comes first.
+        assert!(dhuhr[0].1 < dhuhr[1].1);
+    }
+
+    #[test]
+    fn compare_every_method() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+        let beirut = Coordinates::new(33.8938, 35.5018);
+        let comparison = compare_methods(date, beirut, Method::ALL).unwrap();
+        let csv = comparison.to_csv();
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(comparison.times.len(), Method::ALL.len());
+        assert_eq!(
+            comparison.delta(Prayer::Fajr, Method::UmmAlQura, Method::Egyptian),
+            Some(
+                PrayerTimes::computed(date, beirut, Method::Egyptian.parameters())
+                    .time(Prayer::Fajr)
+                    - PrayerTimes::computed(date, beirut, Method::UmmAlQura.parameters())
+                        .time(Prayer::Fajr)
+            )
+        );
+        assert_eq!(comparison.spread(Prayer::Sunrise), Duration::minutes(1));
+        assert!(comparison.spread(Prayer::Ishaa) > Duration::minutes(20));
+        assert_eq!(lines[0], "method,fajr,sunrise,dhuhr,asr,maghrib,ishaa");
+        assert_eq!(lines[1], "muslim_world_league,0,0,0,0,0,0");
+        assert_eq!(lines.len(), Method::ALL.len() + 1);
+    }
+
+    #[test]
+    fn methods_that_fail_are_reported() {
+        let date = NaiveDate::from_ymd_opt(2024, 6, 21).unwrap();
+        let longyearbyen = Coordinates::new(78.2232, 15.6267);
+        let comparison = compare_methods(date, longyearbyen, &[Method::MuslimWorldLeague]);
+
+        assert_eq!(comparison, Err(CalculationError::NoSunriseOrSunset(date)));
+        assert_eq!(
+            compare_methods(date, longyearbyen, &[])
+                .unwrap()
+                .spread(Prayer::Fajr),
+            Duration::zero()
+        );
+    }
+
+    #[test]
+    fn export_comparison_to_csv() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+        let params = Method::MuslimWorldLeague.parameters();
+        let beirut = Coordinates::new(33.8938, 35.5018);
+        let table = compare(&[("Beirut, Lebanon", beirut)], date, params);
+        let csv = table.to_csv();
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(lines[0], "location,fajr,sunrise,dhuhr,asr,maghrib,ishaa");
+        assert!(lines[1].starts_with("\"Beirut, Lebanon\",2024-03-15T"));
+        assert_eq!(lines[1].split("Z,").count(), 6);
+    }
+}
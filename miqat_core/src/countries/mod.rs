@@ -0,0 +1,124 @@
+//! # Country Adjustments
+//!
+//! The angles and adjustments published by national authorities that
+//! don't have a [`Method`](crate::Method) of their own, keyed by
+//! ISO 3166-1 alpha-2 country code.
+//!
+//! The sets are maintained in a data file shipped with the crate,
+//! `adjustments.txt` next to this module, so that they can be corrected
+//! or extended without changes to the API.
+//!
+//! Only available with the `countries` feature.
+//!
+//! ##### Example
+//!
+//! ```
+//! use miqat::countries;
+//! use miqat::prelude::*;
+//!
+//! let morocco = countries::for_country("ma").unwrap();
+//! let params = morocco.parameters();
+//!
+//! assert_eq!(params.fajr_angle, 19.0);
+//! assert_eq!(params.method_adjustments.dhuhr, 5);
+//! ```
+
+use crate::models::adjustments::TimeAdjustment;
+use crate::models::ishaa_parameter::IshaaParameter;
+use crate::models::parameters::Parameters;
+use std::sync::OnceLock;
+
+const DATA: &str = include_str!("adjustments.txt");
+
+/// The calculation settings of a national authority.
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct CountryAdjustments {
+    /// The ISO 3166-1 alpha-2 code, in upper case.
+    pub code: &'static str,
+    pub authority: &'static str,
+    pub fajr_angle: f64,
+    pub ishaa_angle: f64,
+    pub adjustments: TimeAdjustment,
+}
+
+impl CountryAdjustments {
+    /// The parameters of the authority. Its adjustments are
+    /// [`method_adjustments`](Parameters::method_adjustments), leaving
+    /// [`adjustments`](Parameters::adjustments) to the user.
+    pub fn parameters(&self) -> Parameters {
+        Parameters {
+            fajr_angle: self.fajr_angle,
+            ishaa_parameter: IshaaParameter::Angle(self.ishaa_angle),
+            method_adjustments: self.adjustments,
+            ..Default::default()
+        }
+    }
+}
+
+/// Every bundled set, sorted by country code.
+pub fn all() -> &'static [CountryAdjustments] {
+    static ALL: OnceLock<Vec<CountryAdjustments>> = OnceLock::new();
+
+    ALL.get_or_init(|| lines().filter_map(parse_line).collect())
+}
+
+/// The data lines of the bundled file, without blank lines and comments.
+fn lines() -> impl Iterator<Item = &'static str> {
+    DATA.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+}
+
+/// The set of the country with the given code, in any case.
+pub fn for_country(code: &str) -> Option<CountryAdjustments> {
+    all()
+        .iter()
+        .find(|country| country.code.eq_ignore_ascii_case(code))
+        .copied()
+}
+
+fn parse_line(line: &'static str) -> Option<CountryAdjustments> {
+    let mut columns = line.split('|').map(str::trim);
+
+    Some(CountryAdjustments {
+        code: columns.next()?,
+        authority: columns.next()?,
+        fajr_angle: columns.next()?.parse().ok()?,
+        ishaa_angle: columns.next()?.parse().ok()?,
+        adjustments: columns.next()?.parse().ok()?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundled_data_is_valid() {
+        let countries = all();
+
+        assert!(!countries.is_empty());
+        assert_eq!(countries.len(), lines().count());
+        assert!(countries.windows(2).all(|pair| pair[0].code < pair[1].code));
+
+        for country in countries {
+            assert_eq!(country.code.len(), 2);
+            assert_eq!(country.code, country.code.to_ascii_uppercase());
+            assert_eq!(country.parameters().validate(), vec![]);
+        }
+    }
+
+    #[test]
+    fn lookup_by_code() {
+        let algeria = for_country("DZ").unwrap();
+
+        assert_eq!(algeria.fajr_angle, 18.0);
+        assert_eq!(algeria.adjustments.maghrib, 3);
+        assert_eq!(for_country("dz"), Some(algeria));
+        assert_eq!(for_country("XX"), None);
+        assert_eq!(
+            for_country("TN").unwrap().adjustments,
+            TimeAdjustment::default()
+        );
+    }
+}
@@ -0,0 +1,47 @@
+use crate::astronomy::unit::Coordinates;
+use crate::models::parameter_issue::ParameterIssue;
+use chrono::NaiveDate;
+use std::fmt;
+
+/// Why prayer times could not be calculated.
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub enum CalculationError {
+    /// The date is outside of
+    /// [`PrayerTimes::SUPPORTED_YEARS`](crate::PrayerTimes::SUPPORTED_YEARS).
+    DateOutOfRange(NaiveDate),
+    /// The year is outside of
+    /// [`PrayerTimes::SUPPORTED_YEARS`](crate::PrayerTimes::SUPPORTED_YEARS).
+    YearOutOfRange(i32),
+    /// The sun does not rise or does not set on the date or the day
+    /// after, as happens in polar regions around the solstices.
+    NoSunriseOrSunset(NaiveDate),
+    /// The latitude is not within [-90°, 90°] or the longitude not
+    /// within [-180°, 180°].
+    InvalidCoordinates(Coordinates),
+    /// A parameter has an invalid value, see [`ParameterIssue::is_error`].
+    InvalidParameters(ParameterIssue),
+}
+
+impl fmt::Display for CalculationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CalculationError::DateOutOfRange(date) => {
+                write!(f, "{date} is outside of the supported range of dates")
+            }
+            CalculationError::YearOutOfRange(year) => {
+                write!(f, "{year} is outside of the supported range of years")
+            }
+            CalculationError::NoSunriseOrSunset(date) => {
+                write!(f, "The sun does not rise or set around {date}")
+            }
+            CalculationError::InvalidCoordinates(coordinates) => write!(
+                f,
+                "{}, {} are not valid coordinates",
+                coordinates.latitude, coordinates.longitude
+            ),
+            CalculationError::InvalidParameters(issue) => write!(f, "Invalid parameters: {issue}"),
+        }
+    }
+}
+
+impl std::error::Error for CalculationError {}
@@ -0,0 +1,182 @@
+//! # Yearly Extremes
+//!
+//! The earliest and latest Fajr and Maghrib of a year, and its shortest
+//! and longest fasting days, as used to plan Ramadan timetables years in
+//! advance.
+//!
+//! Times are compared by their time of day in UTC on the date of their
+//! schedule, so a Maghrib past midnight UTC still counts as late. At a
+//! location with a fixed UTC offset this is the same as comparing local
+//! times.
+//!
+//! ##### Example
+//!
+//! ```
+//! use miqat::extremes::yearly_extremes;
+//! use miqat::prelude::*;
+//!
+//! let raleigh = Coordinates::new(35.7750, -78.6336);
+//! let params = Method::NorthAmerica.parameters();
+//! let extremes = yearly_extremes(2024, raleigh, params).unwrap();
+//!
+//! assert_eq!(extremes.longest_fast.date.month(), 6);
+//! assert!(extremes.shortest_fast.duration() < extremes.longest_fast.duration());
+//! ```
+
+use crate::astronomy::unit::Coordinates;
+use crate::error::CalculationError;
+use crate::models::parameters::Parameters;
+use crate::models::prayer::Prayer;
+use crate::prayer_times::PrayerTimes;
+use chrono::DateTime;
+use chrono::Datelike;
+use chrono::Duration;
+use chrono::NaiveDate;
+use chrono::NaiveTime;
+use chrono::Utc;
+use std::cmp::Reverse;
+
+/// A prayer time along with the date of its schedule.
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct DatedTime {
+    pub date: NaiveDate,
+    pub time: DateTime<Utc>,
+}
+
+/// A day of fasting, from Fajr to Maghrib.
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct FastingDay {
+    pub date: NaiveDate,
+    pub fajr: DateTime<Utc>,
+    pub maghrib: DateTime<Utc>,
+}
+
+impl FastingDay {
+    pub fn duration(&self) -> Duration {
+        self.maghrib.signed_duration_since(self.fajr)
+    }
+}
+
+/// The extremes of a year. When several days share an extreme, the
+/// first of them is reported.
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct YearlyExtremes {
+    pub earliest_fajr: DatedTime,
+    pub latest_fajr: DatedTime,
+    pub earliest_maghrib: DatedTime,
+    pub latest_maghrib: DatedTime,
+    pub shortest_fast: FastingDay,
+    pub longest_fast: FastingDay,
+}
+
+/// The extremes of the schedules from the 1st of January to the 31st of
+/// December of `year`, failing like
+/// [`try_computed`](PrayerTimes::try_computed) on the first day that
+/// can't be computed.
+pub fn yearly_extremes(
+    year: i32,
+    coordinates: Coordinates,
+    parameters: Parameters,
+) -> Result<YearlyExtremes, CalculationError> {
+    let first =
+        NaiveDate::from_ymd_opt(year, 1, 1).ok_or(CalculationError::YearOutOfRange(year))?;
+    let days = first
+        .iter_days()
+        .take_while(|date| date.year() == year)
+        .map(|date| {
+            let times = PrayerTimes::try_computed(date, coordinates, parameters)?;
+
+            Ok(FastingDay {
+                date,
+                fajr: times.time(Prayer::Fajr),
+                maghrib: times.time(Prayer::Maghrib),
+            })
+        })
+        .collect::<Result<Vec<_>, CalculationError>>()?;
+
+    extremes_of(&days).ok_or(CalculationError::YearOutOfRange(year))
+}
+
+/// The extremes of `days`, or `None` when there are none.
+fn extremes_of(days: &[FastingDay]) -> Option<YearlyExtremes> {
+    let time_of_day = |date: NaiveDate, time: DateTime<Utc>| {
+        time.signed_duration_since(date.and_time(NaiveTime::MIN).and_utc())
+    };
+    let earliest = |time: fn(&FastingDay) -> DateTime<Utc>| {
+        days.iter()
+            .min_by_key(|day| time_of_day(day.date, time(day)))
+            .map(|day| DatedTime {
+                date: day.date,
+                time: time(day),
+            })
+    };
+    let latest = |time: fn(&FastingDay) -> DateTime<Utc>| {
+        days.iter()
+            .max_by_key(|day| (time_of_day(day.date, time(day)), Reverse(day.date)))
+            .map(|day| DatedTime {
+                date: day.date,
+                time: time(day),
+            })
+    };
+
+    Some(YearlyExtremes {
+        earliest_fajr: earliest(|day| day.fajr)?,
+        latest_fajr: latest(|day| day.fajr)?,
+        earliest_maghrib: earliest(|day| day.maghrib)?,
+        latest_maghrib: latest(|day| day.maghrib)?,
+        shortest_fast: *days.iter().min_by_key(|day| day.duration())?,
+        longest_fast: *days
+            .iter()
+            .max_by_key(|day| (day.duration(), Reverse(day.date)))?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Method;
+
+    #[test]
+    fn extremes_follow_the_seasons() {
+        let raleigh = Coordinates::new(35.7750, -78.6336);
+        let extremes = yearly_extremes(2015, raleigh, Method::NorthAmerica.parameters()).unwrap();
+
+        let date = |month, day| NaiveDate::from_ymd_opt(2015, month, day).unwrap();
+
+        assert_eq!(extremes.earliest_fajr.date, date(6, 15));
+        assert_eq!(extremes.latest_fajr.date, date(1, 6));
+        assert_eq!(extremes.earliest_maghrib.date, date(11, 29));
+        assert_eq!(extremes.latest_maghrib.date, date(6, 25));
+        assert_eq!(extremes.shortest_fast.date, date(12, 16));
+        assert_eq!(extremes.longest_fast.date, date(6, 16));
+        assert_eq!(extremes.longest_fast.duration(), Duration::minutes(963));
+    }
+
+    #[test]
+    fn unsupported_years_are_refused() {
+        let raleigh = Coordinates::new(35.7750, -78.6336);
+        let params = Method::NorthAmerica.parameters();
+
+        assert_eq!(
+            yearly_extremes(2500, raleigh, params),
+            Err(CalculationError::DateOutOfRange(
+                NaiveDate::from_ymd_opt(2500, 1, 1).unwrap()
+            ))
+        );
+        assert_eq!(
+            yearly_extremes(i32::MIN, raleigh, params),
+            Err(CalculationError::YearOutOfRange(i32::MIN))
+        );
+    }
+
+    #[test]
+    fn fasting_day_duration() {
+        let day = FastingDay {
+            date: NaiveDate::from_ymd_opt(2024, 3, 15).unwrap(),
+            fajr: DateTime::from_timestamp(1_710_480_000, 0).unwrap(),
+            maghrib: DateTime::from_timestamp(1_710_523_200, 0).unwrap(),
+        };
+
+        assert_eq!(day.duration(), Duration::hours(12));
+    }
+}
@@ -0,0 +1,870 @@
+//! # Output Formatting
+//!
+//! Renders schedules as human-readable lines through a small template,
+//! so that command line and server users can choose the columns, their
+//! order and how each is written without post-processing.
+//!
+//! A template is text with fields in braces, e.g. `{date} {fajr} {dhuhr}`.
+//! A field can carry a strftime-style format after a colon, as in
+//! `{date:%a %d %b}` or `{asr:%I:%M %p}`; times default to `%H:%M` and
+//! dates to `%Y-%m-%d`. Literal braces are written `{{` and `}}`.
+//!
+//! The fields are `date`, `hijri`, `fajr`, `sunrise`, `dhuhr`, `asr`,
+//! `maghrib` and `ishaa`. The Hijri date is written in the template's
+//! [locale](Template::locale), with the format of
+//! [`Localizer::hijri_date`], by default `%-d %B %Y`. Mosque timetables
+//! can show the iqamah next to each adhan with `fajr_iqamah`,
+//! `dhuhr_iqamah`, `asr_iqamah`, `maghrib_iqamah` and `ishaa_iqamah`,
+//! filled in once the template has an [`IqamahConfig`](Template::iqamah)
+//! and empty until then.
+//!
+//! For timetables published in Arabic, a template can write
+//! [Arabic-Indic digits](Digits::ArabicIndic) and, in the
+//! [Arabic locale](Locale::Arabic), Arabic headers. Arabic lines start with a
+//! right-to-left mark and isolate each field, so that bidi-aware displays
+//! lay the columns out right to left without reordering the digits of a
+//! time.
+//!
+//! Templates can also be built from a list of columns with
+//! [`Template::from_columns`], e.g. to leave out sunrise, and tables can
+//! be split into weeks starting on the local first day of the week with
+//! [`Template::week_start`].
+//!
+//! For notifications, [`format_duration_until`] writes how long is left
+//! until a prayer, e.g. "1h 23m until Maghrib", in either language.
+//!
+//! ##### Example
+//!
+//! ```
+//! use miqat::format::Template;
+//! use miqat::prelude::*;
+//!
+//! let date = NaiveDate::from_ymd_opt(2015, 7, 12).unwrap();
+//! let raleigh = Coordinates::new(35.7750, -78.6336);
+//! let times = PrayerTimes::computed(date, raleigh, Method::NorthAmerica.parameters());
+//! let edt = chrono::FixedOffset::west_opt(4 * 3600).unwrap();
+//! let template: Template = "{date:%a %d} | {fajr} | {maghrib:%I:%M %p}".parse().unwrap();
+//!
+//! assert_eq!(template.header(), "date | fajr | maghrib");
+//! assert_eq!(template.render(&times, edt), "Sun 12 | 04:42 | 08:32 PM");
+//! ```
+
+use crate::clock::Clock;
+use crate::iqamah::IqamahConfig;
+use crate::locale::DEFAULT_HIJRI_FORMAT;
+use crate::locale::Locale;
+use crate::locale::Localizer;
+use crate::models::prayer::Prayer;
+use crate::prayer_times::PrayerTimes;
+use crate::prayer_times::hours_and_minutes;
+use chrono::Datelike;
+use chrono::Duration;
+use chrono::FixedOffset;
+use chrono::Weekday;
+use chrono::format::Fixed;
+use chrono::format::Item;
+use chrono::format::Numeric;
+use chrono::format::StrftimeItems;
+use std::fmt;
+use std::str::FromStr;
+
+const DEFAULT_TIME_FORMAT: &str = "%H:%M";
+const DEFAULT_DATE_FORMAT: &str = "%Y-%m-%d";
+const RIGHT_TO_LEFT_MARK: char = '\u{200F}';
+const FIRST_STRONG_ISOLATE: char = '\u{2068}';
+const POP_DIRECTIONAL_ISOLATE: char = '\u{2069}';
+
+/// The digits numbers are written with.
+#[derive(PartialEq, Debug, Copy, Clone, Default)]
+pub enum Digits {
+    /// 0123456789
+    #[default]
+    Western,
+
+    /// ٠١٢٣٤٥٦٧٨٩
+    ArabicIndic,
+}
+
+impl Digits {
+    /// `text` with its Western digits written in these digits.
+    pub fn apply(&self, text: &str) -> String {
+        match self {
+            Digits::Western => text.to_string(),
+            Digits::ArabicIndic => text
+                .chars()
+                .map(|c| match c.to_digit(10) {
+                    Some(digit) if c.is_ascii_digit() => {
+                        char::from_u32(0x0660 + digit).unwrap_or(c)
+                    }
+                    _ => c,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// The language of headers.
+#[derive(PartialEq, Debug, Copy, Clone, Default)]
+pub enum Language {
+    /// The field names of the template.
+    #[default]
+    English,
+
+    Arabic,
+}
+
+impl From<Locale> for Language {
+    /// Arabic for the Arabic locale, English otherwise.
+    fn from(locale: Locale) -> Self {
+        match locale {
+            Locale::Arabic => Language::Arabic,
+            Locale::English | Locale::Indonesian | Locale::Turkish | Locale::Urdu => {
+                Language::English
+            }
+        }
+    }
+}
+
+/// A column of a rendered schedule.
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub enum Column {
+    Date,
+    /// The Hijri date.
+    Hijri,
+    Prayer(Prayer),
+    /// The iqamah of a prayer.
+    Iqamah(Prayer),
+}
+
+impl Column {
+    /// The name of the column's field in templates.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Column::Date => "date",
+            Column::Hijri => "hijri",
+            Column::Prayer(Prayer::Fajr | Prayer::FajrTomorrow) => "fajr",
+            Column::Prayer(Prayer::Sunrise) => "sunrise",
+            Column::Prayer(Prayer::Dhuhr) => "dhuhr",
+            Column::Prayer(Prayer::Asr) => "asr",
+            Column::Prayer(Prayer::Maghrib) => "maghrib",
+            Column::Prayer(Prayer::Ishaa) => "ishaa",
+            Column::Iqamah(Prayer::Fajr | Prayer::FajrTomorrow) => "fajr_iqamah",
+            Column::Iqamah(Prayer::Sunrise) => "sunrise_iqamah",
+            Column::Iqamah(Prayer::Dhuhr) => "dhuhr_iqamah",
+            Column::Iqamah(Prayer::Asr) => "asr_iqamah",
+            Column::Iqamah(Prayer::Maghrib) => "maghrib_iqamah",
+            Column::Iqamah(Prayer::Ishaa) => "ishaa_iqamah",
+        }
+    }
+
+    /// The column's header in `language`.
+    pub fn label(&self, language: Language) -> &'static str {
+        match language {
+            Language::English => self.name(),
+            Language::Arabic => match self {
+                Column::Date => "التاريخ",
+                Column::Hijri => "التاريخ الهجري",
+                Column::Prayer(Prayer::Fajr | Prayer::FajrTomorrow) => "الفجر",
+                Column::Prayer(Prayer::Sunrise) => "الشروق",
+                Column::Prayer(Prayer::Dhuhr) => "الظهر",
+                Column::Prayer(Prayer::Asr) => "العصر",
+                Column::Prayer(Prayer::Maghrib) => "المغرب",
+                Column::Prayer(Prayer::Ishaa) => "العشاء",
+                Column::Iqamah(Prayer::Fajr | Prayer::FajrTomorrow) => "إقامة الفجر",
+                Column::Iqamah(Prayer::Sunrise) => "إقامة الشروق",
+                Column::Iqamah(Prayer::Dhuhr) => "إقامة الظهر",
+                Column::Iqamah(Prayer::Asr) => "إقامة العصر",
+                Column::Iqamah(Prayer::Maghrib) => "إقامة المغرب",
+                Column::Iqamah(Prayer::Ishaa) => "إقامة العشاء",
+            },
+        }
+    }
+
+    /// The format of the column when the template doesn't give one.
+    fn default_format(&self) -> &'static str {
+        match self {
+            Column::Date => DEFAULT_DATE_FORMAT,
+            Column::Hijri => DEFAULT_HIJRI_FORMAT,
+            Column::Prayer(_) | Column::Iqamah(_) => DEFAULT_TIME_FORMAT,
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Column> {
+        match name {
+            "date" => Some(Column::Date),
+            "hijri" => Some(Column::Hijri),
+            "fajr" => Some(Column::Prayer(Prayer::Fajr)),
+            "sunrise" => Some(Column::Prayer(Prayer::Sunrise)),
+            "dhuhr" => Some(Column::Prayer(Prayer::Dhuhr)),
+            "asr" => Some(Column::Prayer(Prayer::Asr)),
+            "maghrib" => Some(Column::Prayer(Prayer::Maghrib)),
+            "ishaa" | "isha" => Some(Column::Prayer(Prayer::Ishaa)),
+            "fajr_iqamah" => Some(Column::Iqamah(Prayer::Fajr)),
+            "dhuhr_iqamah" => Some(Column::Iqamah(Prayer::Dhuhr)),
+            "asr_iqamah" => Some(Column::Iqamah(Prayer::Asr)),
+            "maghrib_iqamah" => Some(Column::Iqamah(Prayer::Maghrib)),
+            "ishaa_iqamah" | "isha_iqamah" => Some(Column::Iqamah(Prayer::Ishaa)),
+            _ => None,
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Clone)]
+enum Segment {
+    Literal(String),
+    Field { column: Column, format: String },
+}
+
+/// A parsed template, see the [module](self) documentation.
+#[derive(PartialEq, Debug, Clone)]
+pub struct Template {
+    segments: Vec<Segment>,
+    digits: Digits,
+    locale: Locale,
+    iqamah: Option<IqamahConfig>,
+    week_start: Option<Weekday>,
+}
+
+impl Template {
+    /// A template of `columns`, in order, with their default formats and
+    /// separated by `separator`.
+    ///
+    /// ```
+    /// use miqat::format::{Column, Template};
+    /// use miqat::prelude::*;
+    ///
+    /// let columns = [
+    ///     Column::Date,
+    ///     Column::Prayer(Prayer::Fajr),
+    ///     Column::Prayer(Prayer::Maghrib),
+    /// ];
+    ///
+    /// assert_eq!(Template::from_columns(&columns, ",").header(), "date,fajr,maghrib");
+    /// ```
+    pub fn from_columns(columns: &[Column], separator: &str) -> Template {
+        let mut segments = Vec::new();
+
+        for (index, column) in columns.iter().enumerate() {
+            if index > 0 && !separator.is_empty() {
+                segments.push(Segment::Literal(separator.to_string()));
+            } else {
+                // Nothing to do.
+            }
+
+            segments.push(Segment::Field {
+                column: *column,
+                format: column.default_format().to_string(),
+            });
+        }
+
+        Template {
+            segments,
+            digits: Digits::default(),
+            locale: Locale::default(),
+            iqamah: None,
+            week_start: None,
+        }
+    }
+
+    pub fn digits(mut self, digits: Digits) -> Self {
+        self.digits = digits;
+        self
+    }
+
+    /// The template with its Hijri dates written in `locale`, English
+    /// by default. Headers are in its [language](Language), with Arabic
+    /// lines laid out right to left.
+    pub fn locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+
+    /// The template with its [tables](Template::render_table) split into
+    /// weeks starting on `weekday` by an empty line, e.g. Saturday in much
+    /// of the Arab world or Sunday in the US. When the fields are only
+    /// separated by commas, semicolons or tabs, weeks are split by a row
+    /// of empty fields instead, as an empty line would end a CSV table.
+    /// Tables are not split by default.
+    pub fn week_start(mut self, weekday: Weekday) -> Self {
+        self.week_start = Some(weekday);
+        self
+    }
+
+    /// The template with its iqamah fields filled in under `config`.
+    pub fn iqamah(mut self, config: IqamahConfig) -> Self {
+        self.iqamah = Some(config);
+        self
+    }
+
+    /// The columns of the template, in order.
+    pub fn columns(&self) -> Vec<Column> {
+        self.segments
+            .iter()
+            .filter_map(|segment| match segment {
+                Segment::Field { column, .. } => Some(*column),
+                Segment::Literal(_) => None,
+            })
+            .collect()
+    }
+
+    /// The template with each field replaced by its name, for a header
+    /// line.
+    pub fn header(&self) -> String {
+        self.line(|column, _| column.label(self.locale.into()).to_string())
+    }
+
+    /// The template filled in with `times`, with the times shown at
+    /// `offset`.
+    pub fn render(&self, times: &PrayerTimes, offset: FixedOffset) -> String {
+        self.render_localized(times, offset, &self.locale)
+    }
+
+    /// Like [`render`](Template::render), with the Hijri dates written
+    /// by `localizer` instead of the template's locale.
+    pub fn render_localized(
+        &self,
+        times: &PrayerTimes,
+        offset: FixedOffset,
+        localizer: &dyn Localizer,
+    ) -> String {
+        self.line(|column, format| {
+            let value = match column {
+                Column::Date => times.date().format(format).to_string(),
+                Column::Hijri => localizer.hijri_date(times.date(), format),
+                Column::Prayer(prayer) => times
+                    .time(prayer)
+                    .with_timezone(&offset)
+                    .format(format)
+                    .to_string(),
+                Column::Iqamah(prayer) => self
+                    .iqamah
+                    .and_then(|config| times.iqamah(prayer, &config, offset))
+                    .map(|time| time.with_timezone(&offset).format(format).to_string())
+                    .unwrap_or_default(),
+            };
+
+            self.digits.apply(&value)
+        })
+    }
+
+    /// The template with each field replaced by `field`, laid out for
+    /// the template's language.
+    fn line(&self, field: impl Fn(Column, &str) -> String) -> String {
+        let rtl = Language::from(self.locale) == Language::Arabic;
+        let mut line = String::new();
+
+        if rtl {
+            line.push(RIGHT_TO_LEFT_MARK);
+        } else {
+            // Nothing to do.
+        }
+
+        for segment in &self.segments {
+            match segment {
+                Segment::Literal(text) => line.push_str(text),
+                Segment::Field { column, format } if rtl => {
+                    line.push(FIRST_STRONG_ISOLATE);
+                    line.push_str(&field(*column, format));
+                    line.push(POP_DIRECTIONAL_ISOLATE);
+                }
+                Segment::Field { column, format } => line.push_str(&field(*column, format)),
+            }
+        }
+
+        line
+    }
+
+    /// A line per schedule, after a header line, with an empty line
+    /// between weeks when the template has a
+    /// [week start](Template::week_start).
+    pub fn render_table<'a>(
+        &self,
+        days: impl IntoIterator<Item = &'a PrayerTimes>,
+        offset: FixedOffset,
+    ) -> String {
+        let mut table = self.header();
+        table.push('\n');
+        let week_break = self.week_break();
+
+        for (index, times) in days.into_iter().enumerate() {
+            if index > 0 && self.week_start == Some(times.date().weekday()) {
+                table.push_str(&week_break);
+                table.push('\n');
+            } else {
+                // Nothing to do.
+            }
+
+            table.push_str(&self.render(times, offset));
+            table.push('\n');
+        }
+
+        table
+    }
+
+    /// The line between weeks: empty, or a row of empty fields when the
+    /// fields are only separated by a comma, a semicolon or a tab, as in
+    /// CSV tables.
+    fn week_break(&self) -> String {
+        let literals: Vec<&str> = self
+            .segments
+            .iter()
+            .filter_map(|segment| match segment {
+                Segment::Literal(literal) => Some(literal.as_str()),
+                Segment::Field { .. } => None,
+            })
+            .collect();
+
+        match literals.first() {
+            Some(delimiter @ &("," | ";" | "\t"))
+                if literals.iter().all(|literal| literal == delimiter) =>
+            {
+                literals.concat()
+            }
+            _ => String::new(),
+        }
+    }
+}
+
+impl Default for Template {
+    /// Every column, separated by spaces.
+    fn default() -> Self {
+        Template::from_columns(
+            &[
+                Column::Date,
+                Column::Prayer(Prayer::Fajr),
+                Column::Prayer(Prayer::Sunrise),
+                Column::Prayer(Prayer::Dhuhr),
+                Column::Prayer(Prayer::Asr),
+                Column::Prayer(Prayer::Maghrib),
+                Column::Prayer(Prayer::Ishaa),
+            ],
+            " ",
+        )
+    }
+}
+
+impl FromStr for Template {
+    type Err = TemplateError;
+
+    fn from_str(template: &str) -> Result<Self, Self::Err> {
+        let mut segments = Vec::new();
+        let mut literal = String::new();
+        let mut chars = template.chars();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.as_str().starts_with('{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.as_str().starts_with('}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '{' => {
+                    let rest = chars.as_str();
+                    let end = rest.find('}').ok_or(TemplateError::Unclosed)?;
+                    let field = &rest[..end];
+                    chars = rest[end + 1..].chars();
+
+                    if !literal.is_empty() {
+                        segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                    } else {
+                        // Nothing to do.
+                    }
+                    segments.push(parse_field(field)?);
+                }
+                '}' => return Err(TemplateError::Unopened),
+                c => literal.push(c),
+            }
+        }
+
+        if !literal.is_empty() {
+            segments.push(Segment::Literal(literal));
+        } else {
+            // Nothing to do.
+        }
+
+        Ok(Template {
+            segments,
+            digits: Digits::default(),
+            locale: Locale::default(),
+            iqamah: None,
+            week_start: None,
+        })
+    }
+}
+
+fn parse_field(field: &str) -> Result<Segment, TemplateError> {
+    let (name, format) = match field.split_once(':') {
+        Some((name, format)) => (name.trim(), Some(format)),
+        None => (field.trim(), None),
+    };
+    let column =
+        Column::from_name(name).ok_or_else(|| TemplateError::UnknownField(name.to_string()))?;
+    let format = format.unwrap_or(column.default_format());
+
+    if StrftimeItems::new(format)
+        .any(|item| item == Item::Error || (column == Column::Date && !is_date_item(&item)))
+    {
+        return Err(TemplateError::InvalidFormat(format.to_string()));
+    } else {
+        // Nothing to do.
+    }
+
+    Ok(Segment::Field {
+        column,
+        format: format.to_string(),
+    })
+}
+
+// Whether `item` can be written from a date alone, as a date has no time
+// or offset to fill in the other fields with.
+fn is_date_item(item: &Item) -> bool {
+    match item {
+        Item::Literal(_) | Item::OwnedLiteral(_) | Item::Space(_) | Item::OwnedSpace(_) => true,
+        Item::Numeric(numeric, _) => matches!(
+            numeric,
+            Numeric::Year
+                | Numeric::YearDiv100
+                | Numeric::YearMod100
+                | Numeric::IsoYear
+                | Numeric::IsoYearDiv100
+                | Numeric::IsoYearMod100
+                | Numeric::Quarter
+                | Numeric::Month
+                | Numeric::Day
+                | Numeric::WeekFromSun
+                | Numeric::WeekFromMon
+                | Numeric::IsoWeek
+                | Numeric::NumDaysFromSun
+                | Numeric::WeekdayFromMon
+                | Numeric::Ordinal
+        ),
+        Item::Fixed(fixed) => matches!(
+            fixed,
+            Fixed::ShortMonthName
+                | Fixed::LongMonthName
+                | Fixed::ShortWeekdayName
+                | Fixed::LongWeekdayName
+        ),
+        _ => false,
+    }
+}
+
+/// How long is left until `next`, to the nearest minute, e.g. "1h 23m
+/// until Maghrib" in English or "بقي ١ س ٢٣ د على المغرب" in Arabic, which
+/// is written with Arabic-Indic digits. Less than half a minute, or a
+/// negative `remaining`, reads as the prayer being due now. English
+/// names come from [`Prayer::name_with`] `clock`, so that Dhuhr is
+/// Jumua on Fridays.
+///
+/// ##### Example
+///
+/// ```
+/// use miqat::clock::FixedClock;
+/// use miqat::format::{Language, format_duration_until};
+/// use miqat::prelude::*;
+///
+/// let friday = FixedClock(Utc.with_ymd_and_hms(2024, 3, 15, 10, 0, 0).unwrap());
+/// let remaining = Duration::minutes(83);
+///
+/// assert_eq!(
+///     format_duration_until(Prayer::Maghrib, &friday, remaining, Language::English),
+///     "1h 23m until Maghrib"
+/// );
+/// assert_eq!(
+///     format_duration_until(Prayer::Dhuhr, &friday, remaining, Language::English),
+///     "1h 23m until Jumua"
+/// );
+/// assert_eq!(
+///     format_duration_until(Prayer::Ishaa, &friday, Duration::minutes(5), Language::Arabic),
+///     "بقي ٥ د على العشاء"
+/// );
+/// ```
+pub fn format_duration_until(
+    next: Prayer,
+    clock: &impl Clock,
+    remaining: Duration,
+    language: Language,
+) -> String {
+    let (hours, minutes) = hours_and_minutes(remaining);
+
+    match language {
+        Language::English => {
+            let name = next.name_with(clock);
+
+            match (hours, minutes) {
+                (0, 0) => format!("{name} now"),
+                (0, minutes) => format!("{minutes}m until {name}"),
+                (hours, 0) => format!("{hours}h until {name}"),
+                (hours, minutes) => format!("{hours}h {minutes}m until {name}"),
+            }
+        }
+        Language::Arabic => {
+            let name = Column::Prayer(next).label(language);
+            let text = match (hours, minutes) {
+                (0, 0) => format!("حان وقت {name}"),
+                (0, minutes) => format!("بقي {minutes} د على {name}"),
+                (hours, 0) => format!("بقي {hours} س على {name}"),
+                (hours, minutes) => format!("بقي {hours} س {minutes} د على {name}"),
+            };
+
+            Digits::ArabicIndic.apply(&text)
+        }
+    }
+}
+
+/// Why a string could not be parsed into a [`Template`].
+#[derive(PartialEq, Debug, Clone)]
+pub enum TemplateError {
+    /// A `{` without a matching `}`.
+    Unclosed,
+    /// A `}` without a matching `{`.
+    Unopened,
+    UnknownField(String),
+    /// A field's strftime format is invalid, or writes a time or an
+    /// offset in a date.
+    InvalidFormat(String),
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TemplateError::Unclosed => write!(f, "unclosed `{{` in template"),
+            TemplateError::Unopened => write!(f, "unmatched `}}` in template"),
+            TemplateError::UnknownField(name) => write!(f, "unknown field `{name}`"),
+            TemplateError::InvalidFormat(format) => write!(f, "invalid format `{format}`"),
+        }
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Coordinates;
+    use crate::Method;
+    use crate::clock::FixedClock;
+    use crate::iqamah::IqamahRule;
+    use chrono::Duration;
+    use chrono::NaiveDate;
+    use chrono::TimeZone;
+    use chrono::Utc;
+
+    #[test]
+    fn renders_a_monthly_table() {
+        let start = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let end = NaiveDate::from_ymd_opt(2024, 3, 3).unwrap();
+        let beirut = Coordinates::new(33.8938, 35.5018);
+        let days =
+            PrayerTimes::on_range(start, end, beirut, Method::MuslimWorldLeague.parameters())
+                .unwrap();
+        let template: Template = "{{{date:%d}}} {ishaa} {fajr}".parse().unwrap();
+        let eet = FixedOffset::east_opt(2 * 3600).unwrap();
+        let table = template.render_table(&days, eet);
+        let lines: Vec<&str> = table.lines().collect();
+
+        assert_eq!(template.columns().len(), 3);
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0], "{date} ishaa fajr");
+        assert!(lines[1].starts_with("{01} "));
+    }
+
+    #[test]
+    fn tables_without_sunrise_split_into_weeks() {
+        let start = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let end = NaiveDate::from_ymd_opt(2024, 3, 10).unwrap();
+        let beirut = Coordinates::new(33.8938, 35.5018);
+        let days =
+            PrayerTimes::on_range(start, end, beirut, Method::MuslimWorldLeague.parameters())
+                .unwrap();
+        let columns = [
+            Column::Date,
+            Column::Prayer(Prayer::Maghrib),
+            Column::Prayer(Prayer::Fajr),
+        ];
+        let template = Template::from_columns(&columns, " | ").week_start(Weekday::Sat);
+        let eet = FixedOffset::east_opt(2 * 3600).unwrap();
+        let table = template.render_table(&days, eet);
+        let weeks: Vec<&str> = table.split("\n\n").collect();
+
+        assert_eq!(template.columns(), columns);
+        assert_eq!(weeks.len(), 3);
+        assert!(weeks[0].starts_with("date | maghrib | fajr\n2024-03-01 | "));
+        assert!(weeks[1].starts_with("2024-03-02 | "));
+        assert_eq!(weeks[1].lines().count(), 7);
+        assert!(weeks[2].starts_with("2024-03-09 | "));
+        assert_eq!(
+            Template::from_columns(&columns, ";"),
+            "{date};{maghrib};{fajr}".parse().unwrap()
+        );
+        assert!(
+            !Template::default()
+                .render_table(&days, eet)
+                .contains("\n\n")
+        );
+    }
+
+    #[test]
+    fn csv_tables_split_weeks_with_empty_rows() {
+        let start = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let end = NaiveDate::from_ymd_opt(2024, 3, 10).unwrap();
+        let beirut = Coordinates::new(33.8938, 35.5018);
+        let days =
+            PrayerTimes::on_range(start, end, beirut, Method::MuslimWorldLeague.parameters())
+                .unwrap();
+        let eet = FixedOffset::east_opt(2 * 3600).unwrap();
+
+        for (template, week_break) in [
+            ("{date},{fajr},{maghrib}", ",,"),
+            ("{date};{fajr}", ";"),
+            ("{date}\t{fajr}", "\t"),
+        ] {
+            let template: Template = template.parse().unwrap();
+            let table = template.week_start(Weekday::Sat).render_table(&days, eet);
+            let lines: Vec<&str> = table.lines().collect();
+
+            assert!(!table.contains("\n\n"));
+            assert_eq!(lines.len(), 13);
+            assert_eq!(lines[2], week_break);
+            assert!(lines[3].starts_with("2024-03-02"));
+            assert_eq!(lines[10], week_break);
+            assert!(lines[11].starts_with("2024-03-09"));
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_templates() {
+        assert_eq!("{fajr".parse::<Template>(), Err(TemplateError::Unclosed));
+        assert_eq!("fajr}".parse::<Template>(), Err(TemplateError::Unopened));
+        assert_eq!(
+            "{tahajjud}".parse::<Template>(),
+            Err(TemplateError::UnknownField("tahajjud".to_string()))
+        );
+        assert_eq!(
+            "{fajr:%Q}".parse::<Template>(),
+            Err(TemplateError::InvalidFormat("%Q".to_string()))
+        );
+        assert_eq!(
+            "{date:%H:%M}".parse::<Template>(),
+            Err(TemplateError::InvalidFormat("%H:%M".to_string()))
+        );
+        assert_eq!(
+            "{date:%d %z}".parse::<Template>(),
+            Err(TemplateError::InvalidFormat("%d %z".to_string()))
+        );
+        assert!("{date:%a %-d %B %Y, week %V}".parse::<Template>().is_ok());
+    }
+
+    #[test]
+    fn default_template_has_every_column() {
+        assert_eq!(
+            Template::default().header(),
+            "date fajr sunrise dhuhr asr maghrib ishaa"
+        );
+        assert_eq!(
+            Ok(Template::default()),
+            "{date} {fajr} {sunrise} {dhuhr} {asr} {maghrib} {ishaa}".parse()
+        );
+    }
+
+    #[test]
+    fn arabic_timetables() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+        let beirut = Coordinates::new(33.8938, 35.5018);
+        let times = PrayerTimes::computed(date, beirut, Method::MuslimWorldLeague.parameters());
+        let eet = FixedOffset::east_opt(2 * 3600).unwrap();
+        let template = "{date:%d/%m} {fajr}"
+            .parse::<Template>()
+            .unwrap()
+            .digits(Digits::ArabicIndic)
+            .locale(Locale::Arabic);
+
+        assert_eq!(
+            template.header(),
+            "\u{200F}\u{2068}التاريخ\u{2069} \u{2068}الفجر\u{2069}"
+        );
+        assert_eq!(
+            template.render(&times, eet),
+            "\u{200F}\u{2068}١٥/٠٣\u{2069} \u{2068}٠٤:٢٥\u{2069}"
+        );
+    }
+
+    #[test]
+    fn localized_hijri_dates() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+        let beirut = Coordinates::new(33.8938, 35.5018);
+        let times = PrayerTimes::computed(date, beirut, Method::MuslimWorldLeague.parameters());
+        let eet = FixedOffset::east_opt(2 * 3600).unwrap();
+        let template: Template = "{hijri} | {hijri:%A %d/%m}".parse().unwrap();
+
+        assert_eq!(
+            template.render(&times, eet),
+            "5 Ramadan 1445 | Friday 05/09"
+        );
+        assert_eq!(
+            template.clone().locale(Locale::Urdu).render(&times, eet),
+            "5 رمضان 1445 | جمعہ 05/09"
+        );
+        assert_eq!(
+            template.render_localized(&times, eet, &Locale::Indonesian),
+            "5 Ramadan 1445 | Jumat 05/09"
+        );
+    }
+
+    #[test]
+    fn iqamah_columns() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 13).unwrap();
+        let beirut = Coordinates::new(33.8938, 35.5018);
+        let times = PrayerTimes::computed(date, beirut, Method::MuslimWorldLeague.parameters());
+        let eet = FixedOffset::east_opt(2 * 3600).unwrap();
+        let template: Template = "{fajr} {fajr_iqamah:%H:%M} {isha_iqamah}".parse().unwrap();
+        let config = IqamahConfig::new(IqamahRule::Rounded(Duration::minutes(20)));
+
+        assert_eq!(template.header(), "fajr fajr_iqamah ishaa_iqamah");
+        assert_eq!(template.render(&times, eet), "04:28  ");
+        assert_eq!(
+            template.clone().iqamah(config).render(&times, eet),
+            "04:28 04:45 19:30"
+        );
+    }
+
+    #[test]
+    fn countdowns() {
+        let wednesday = FixedClock(Utc.with_ymd_and_hms(2024, 3, 13, 10, 0, 0).unwrap());
+        let until = |seconds, language| {
+            format_duration_until(
+                Prayer::Fajr,
+                &wednesday,
+                Duration::seconds(seconds),
+                language,
+            )
+        };
+
+        assert_eq!(until(7199, Language::English), "2h until Fajr");
+        assert_eq!(until(29, Language::English), "Fajr now");
+        assert_eq!(until(-600, Language::English), "Fajr now");
+        assert_eq!(
+            until(3600 * 11 + 60, Language::Arabic),
+            "بقي ١١ س ١ د على الفجر"
+        );
+        assert_eq!(until(0, Language::Arabic), "حان وقت الفجر");
+        assert_eq!(
+            format_duration_until(
+                Prayer::Dhuhr,
+                &wednesday,
+                Duration::zero(),
+                Language::English
+            ),
+            "Dhuhr now"
+        );
+    }
+
+    #[test]
+    fn arabic_indic_digits() {
+        assert_eq!(Digits::ArabicIndic.apply("04:42 PM"), "٠٤:٤٢ PM");
+        assert_eq!(Digits::Western.apply("04:42"), "04:42");
+    }
+}
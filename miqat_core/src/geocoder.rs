@@ -0,0 +1,234 @@
+//! # Geocoding
+//!
+//! Turning a place name into coordinates. The crate doesn't talk to any
+//! geocoding service itself: the binaries take a [`Geocoder`], so that a
+//! Nominatim client or an offline dataset can be plugged in without rate
+//! limits imposed on every user. [`Capitals`], a short built-in list of
+//! capital cities, is the default.
+//!
+//! ##### Example
+//!
+//! ```
+//! use miqat::geocoder::{Capitals, GeocodeError, Geocoder};
+//!
+//! let beirut = Capitals.lookup("Beirut").unwrap();
+//! assert_eq!(beirut.latitude, 33.8938);
+//!
+//! assert_eq!(Capitals.lookup("riyadh, sa"), Capitals.lookup("Riyadh"));
+//! assert_eq!(
+//!     Capitals.lookup("Atlantis"),
+//!     Err(GeocodeError::NotFound("Atlantis".to_string()))
+//! );
+//! ```
+
+use crate::astronomy::unit::Coordinates;
+use std::fmt;
+
+/// Why a place could not be geocoded.
+#[derive(PartialEq, Debug, Clone)]
+pub enum GeocodeError {
+    /// Nothing matches the query.
+    NotFound(String),
+    /// The geocoder could not answer, e.g. its service is unreachable.
+    Unavailable(String),
+}
+
+impl fmt::Display for GeocodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GeocodeError::NotFound(query) => write!(f, "No place found for `{query}`"),
+            GeocodeError::Unavailable(reason) => write!(f, "Geocoding is unavailable: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for GeocodeError {}
+
+/// Looks up the coordinates of a place by name.
+pub trait Geocoder {
+    fn lookup(&self, query: &str) -> Result<Coordinates, GeocodeError>;
+}
+
+impl<F> Geocoder for F
+where
+    F: Fn(&str) -> Result<Coordinates, GeocodeError>,
+{
+    fn lookup(&self, query: &str) -> Result<Coordinates, GeocodeError> {
+        self(query)
+    }
+}
+
+/// A capital city of [`Capitals`].
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct Capital {
+    pub name: &'static str,
+    /// The ISO 3166-1 alpha-2 code of the country, in upper case.
+    pub country: &'static str,
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+const fn capital(
+    name: &'static str,
+    country: &'static str,
+    latitude: f64,
+    longitude: f64,
+) -> Capital {
+    Capital {
+        name,
+        country,
+        latitude,
+        longitude,
+    }
+}
+
+// Sorted by name.
+const CAPITALS: [Capital; 64] = [
+    capital("Abu Dhabi", "AE", 24.4539, 54.3773),
+    capital("Abuja", "NG", 9.0765, 7.3986),
+    capital("Addis Ababa", "ET", 9.0054, 38.7636),
+    capital("Algiers", "DZ", 36.7538, 3.0588),
+    capital("Amman", "JO", 31.9454, 35.9284),
+    capital("Amsterdam", "NL", 52.3676, 4.9041),
+    capital("Ankara", "TR", 39.9334, 32.8597),
+    capital("Ashgabat", "TM", 37.9601, 58.3261),
+    capital("Astana", "KZ", 51.1694, 71.4491),
+    capital("Baghdad", "IQ", 33.3152, 44.3661),
+    capital("Baku", "AZ", 40.4093, 49.8671),
+    capital("Bandar Seri Begawan", "BN", 4.9031, 114.9398),
+    capital("Beijing", "CN", 39.9042, 116.4074),
+    capital("Beirut", "LB", 33.8938, 35.5018),
+    capital("Berlin", "DE", 52.5200, 13.4050),
+    capital("Bishkek", "KG", 42.8746, 74.5698),
+    capital("Brasilia", "BR", -15.7939, -47.8828),
+    capital("Brussels", "BE", 50.8503, 4.3517),
+    capital("Buenos Aires", "AR", -34.6037, -58.3816),
+    capital("Cairo", "EG", 30.0444, 31.2357),
+    capital("Canberra", "AU", -35.2809, 149.1300),
+    capital("Dakar", "SN", 14.7167, -17.4677),
+    capital("Damascus", "SY", 33.5138, 36.2765),
+    capital("Dhaka", "BD", 23.8103, 90.4125),
+    capital("Djibouti", "DJ", 11.5886, 43.1456),
+    capital("Doha", "QA", 25.2854, 51.5310),
+    capital("Dushanbe", "TJ", 38.5598, 68.7870),
+    capital("Islamabad", "PK", 33.6844, 73.0479),
+    capital("Jakarta", "ID", -6.2088, 106.8456),
+    capital("Kabul", "AF", 34.5553, 69.2075),
+    capital("Khartoum", "SD", 15.5007, 32.5599),
+    capital("Kuala Lumpur", "MY", 3.1390, 101.6869),
+    capital("Kuwait City", "KW", 29.3759, 47.9774),
+    capital("London", "GB", 51.5074, -0.1278),
+    capital("Madrid", "ES", 40.4168, -3.7038),
+    capital("Male", "MV", 4.1755, 73.5093),
+    capital("Manama", "BH", 26.2285, 50.5860),
+    capital("Mexico City", "MX", 19.4326, -99.1332),
+    capital("Mogadishu", "SO", 2.0469, 45.3182),
+    capital("Moscow", "RU", 55.7558, 37.6173),
+    capital("Muscat", "OM", 23.5880, 58.3829),
+    capital("Nairobi", "KE", -1.2921, 36.8219),
+    capital("New Delhi", "IN", 28.6139, 77.2090),
+    capital("Nouakchott", "MR", 18.0735, -15.9582),
+    capital("Oslo", "NO", 59.9139, 10.7522),
+    capital("Ottawa", "CA", 45.4215, -75.6972),
+    capital("Paris", "FR", 48.8566, 2.3522),
+    capital("Pretoria", "ZA", -25.7479, 28.2293),
+    capital("Rabat", "MA", 34.0209, -6.8416),
+    capital("Riyadh", "SA", 24.7136, 46.6753),
+    capital("Rome", "IT", 41.9028, 12.4964),
+    capital("Sanaa", "YE", 15.3694, 44.1910),
+    capital("Sarajevo", "BA", 43.8563, 18.4131),
+    capital("Singapore", "SG", 1.3521, 103.8198),
+    capital("Stockholm", "SE", 59.3293, 18.0686),
+    capital("Tashkent", "UZ", 41.2995, 69.2401),
+    capital("Tehran", "IR", 35.6892, 51.3890),
+    capital("Tirana", "AL", 41.3275, 19.8187),
+    capital("Tokyo", "JP", 35.6762, 139.6503),
+    capital("Tripoli", "LY", 32.8872, 13.1913),
+    capital("Tunis", "TN", 36.8065, 10.1815),
+    capital("Washington", "US", 38.9072, -77.0369),
+    capital("Yaounde", "CM", 3.8480, 11.5021),
+    capital("Zagreb", "HR", 45.8150, 15.9819),
+];
+
+/// A built-in list of capital cities, looked up by name, optionally
+/// followed by a comma and the country code, e.g. `Rabat, MA`, or by
+/// the country code alone. Case is ignored.
+#[derive(PartialEq, Debug, Copy, Clone, Default)]
+pub struct Capitals;
+
+impl Capitals {
+    /// Every capital of the list, sorted by name.
+    pub fn all() -> &'static [Capital] {
+        &CAPITALS
+    }
+
+    /// The capital matching `query`, see [`Capitals`].
+    pub fn find(query: &str) -> Option<&'static Capital> {
+        let (name, country) = match query.rsplit_once(',') {
+            Some((name, country)) => (name.trim(), Some(country.trim())),
+            None => (query.trim(), None),
+        };
+
+        CAPITALS.iter().find(|capital| {
+            let same_country = |code: &str| capital.country.eq_ignore_ascii_case(code);
+
+            if capital.name.eq_ignore_ascii_case(name) {
+                country.is_none_or(same_country)
+            } else {
+                country.is_none() && same_country(name)
+            }
+        })
+    }
+}
+
+impl Geocoder for Capitals {
+    fn lookup(&self, query: &str) -> Result<Coordinates, GeocodeError> {
+        Capitals::find(query)
+            .map(|capital| Coordinates::new(capital.latitude, capital.longitude))
+            .ok_or_else(|| GeocodeError::NotFound(query.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn capitals_are_sorted_and_valid() {
+        for pair in CAPITALS.windows(2) {
+            assert!(pair[0].name < pair[1].name);
+        }
+        for capital in CAPITALS {
+            assert_eq!(capital.country.len(), 2);
+            assert!(capital.country.chars().all(|c| c.is_ascii_uppercase()));
+            assert!((-90.0..=90.0).contains(&capital.latitude));
+            assert!((-180.0..=180.0).contains(&capital.longitude));
+        }
+    }
+
+    #[test]
+    fn lookup_by_name_or_country() {
+        let rabat = Capitals.lookup("Rabat").unwrap();
+
+        assert_eq!(Capitals.lookup(" rabat "), Ok(rabat));
+        assert_eq!(Capitals.lookup("Rabat, MA"), Ok(rabat));
+        assert_eq!(Capitals.lookup("ma"), Ok(rabat));
+        assert_eq!(Capitals::find("Kuala Lumpur").unwrap().country, "MY");
+        assert_eq!(
+            Capitals.lookup("Rabat, EG"),
+            Err(GeocodeError::NotFound("Rabat, EG".to_string()))
+        );
+        assert!(Capitals.lookup("").is_err());
+    }
+
+    #[test]
+    fn closures_are_geocoders() {
+        let mecca = |query: &str| match query {
+            "Mecca" => Ok(Coordinates::new(21.4225, 39.8262)),
+            _ => Err(GeocodeError::Unavailable("offline".to_string())),
+        };
+
+        assert_eq!(mecca.lookup("Mecca").unwrap().longitude, 39.8262);
+        assert!(mecca.lookup("Medina").is_err());
+    }
+}
@@ -0,0 +1,386 @@
+//! # GeoJSON
+//!
+//! Map layers for visualizations: the qibla as the great circle from a
+//! location to the Kaaba, and the isochrones of Maghrib, the lines along
+//! which it falls at the same time, over a bounding box. The isochrones
+//! are traced on a [grid](crate::batch::grid_times) of schedules, and are
+//! only as precise as the grid is fine.
+//!
+//! The layers are GeoJSON strings (RFC 7946), with positions given as the
+//! longitude followed by the latitude. Lines are not split where they
+//! cross the antimeridian.
+//!
+//! ##### Example
+//!
+//! ```
+//! use miqat::geojson;
+//! use miqat::prelude::*;
+//!
+//! let beirut = Coordinates::new(33.8938, 35.5018);
+//! let line = geojson::qibla_line(beirut, 2).unwrap();
+//!
+//! assert!(line.starts_with(r#"{"type":"Feature","geometry":{"type":"LineString","coordinates":[[35.5018,33.8938],[39.826182,21.422524]]}"#));
+//! ```
+
+use crate::astronomy::math;
+use crate::astronomy::qiblah::KAABA;
+use crate::astronomy::qiblah::Qiblah;
+use crate::astronomy::unit::Coordinates;
+use crate::batch::BoundingBox;
+use crate::batch::grid_times;
+use crate::error::CalculationError;
+use crate::models::parameters::Parameters;
+use crate::models::prayer::Prayer;
+use chrono::DateTime;
+use chrono::Duration;
+use chrono::NaiveDate;
+
+/// The most lines [`maghrib_isochrones`] traces.
+pub const MAX_ISOCHRONES: usize = 1_000;
+
+/// The qibla of `from` as a GeoJSON feature: the great circle to the
+/// Kaaba, sampled at `points` positions, at least the two ends. Its
+/// properties are the qibla direction in degrees from north and the
+/// distance in kilometers. Fails for latitudes beyond the poles and
+/// longitudes that are not finite, which JSON can't write.
+pub fn qibla_line(from: Coordinates, points: usize) -> Result<String, CalculationError> {
+    if !(-90.0..=90.0).contains(&from.latitude) || !from.longitude.is_finite() {
+        return Err(CalculationError::InvalidCoordinates(from));
+    } else {
+        // Nothing to do.
+    }
+
+    let points = points.max(2);
+    let start = unit_vector(from);
+    let end = unit_vector(KAABA);
+    let dot = start[0] * end[0] + start[1] * end[1] + start[2] * end[2];
+    let central_angle = math::acos(dot.clamp(-1.0, 1.0));
+    let sin_angle = math::sin(central_angle);
+
+    let positions: Vec<String> = (0..points)
+        .map(|index| {
+            let fraction = index as f64 / (points - 1) as f64;
+
+            if index == 0 {
+                position(from)
+            } else if index == points - 1 {
+                position(KAABA)
+            } else if sin_angle.abs() < 1e-12 {
+                // At the Kaaba, or at its antipode where every great
+                // circle leads to it: only the ends are meaningful.
+                position(from)
+            } else {
+                let a = math::sin((1.0 - fraction) * central_angle) / sin_angle;
+                let b = math::sin(fraction * central_angle) / sin_angle;
+                let vector = [0, 1, 2].map(|axis| a * start[axis] + b * end[axis]);
+
+                position(from_unit_vector(vector))
+            }
+        })
+        .collect();
+
+    Ok(format!(
+        r#"{{"type":"Feature","geometry":{{"type":"LineString","coordinates":[{}]}},"properties":{{"direction":{},"distance_km":{}}}}}"#,
+        positions.join(","),
+        rounded(Qiblah::new(from).value()),
+        rounded(from.distance_to(KAABA)),
+    ))
+}
+
+/// The isochrones of Maghrib on `date` over `bounding_box`, as a GeoJSON
+/// feature collection. Maghrib is computed on a grid of `resolution`
+/// degrees, see [`grid_times`], and a line is traced every `interval`, at
+/// whole multiples of it since midnight UTC. Each line is a feature whose
+/// `maghrib` property is its time in RFC 3339.
+///
+/// The collection is empty when the grid is, when `interval` is not
+/// positive, or when it would have more than [`MAX_ISOCHRONES`] lines
+/// over the range of Maghrib times. Lines stop short of the points whose schedule can't be
+/// computed, e.g. where the sun doesn't set.
+pub fn maghrib_isochrones(
+    date: NaiveDate,
+    bounding_box: BoundingBox,
+    resolution: f64,
+    parameters: Parameters,
+    interval: Duration,
+) -> String {
+    let grid = grid_times(bounding_box, resolution, date, parameters);
+    let (rows, columns) = (grid.rows(), grid.columns());
+    let point = |row: usize, column: usize| grid.coordinates(row, column);
+    let maghribs: Vec<Option<i64>> = grid
+        .iter()
+        .map(|(_, times)| Some(times.ok()?.time(Prayer::Maghrib).timestamp()))
+        .collect();
+
+    let interval = interval.num_seconds();
+    let (Some(earliest), Some(latest)) = (
+        maghribs.iter().flatten().min(),
+        maghribs.iter().flatten().max(),
+    ) else {
+        return collection(&[]);
+    };
+    if interval <= 0 || (latest - earliest) / interval >= MAX_ISOCHRONES as i64 {
+        return collection(&[]);
+    } else {
+        // Nothing to do.
+    }
+
+    let value = |row: usize, column: usize| maghribs[row * columns + column];
+    let mut features = Vec::new();
+    let mut level = earliest.div_euclid(interval) * interval;
+
+    while level <= *latest {
+        let mut segments = Vec::new();
+
+        for row in 0..rows.saturating_sub(1) {
+            for column in 0..columns.saturating_sub(1) {
+                let corners = [
+                    (row, column),
+                    (row, column + 1),
+                    (row + 1, column + 1),
+                    (row + 1, column),
+                ];
+                // Squares with a point that has no Maghrib are left out.
+                let [Some(a), Some(b), Some(c), Some(d)] =
+                    corners.map(|(row, column)| value(row, column))
+                else {
+                    continue;
+                };
+                let values = [a, b, c, d].map(|value| value as f64);
+                let crossings: Vec<Coordinates> = (0..4)
+                    .filter_map(|edge| {
+                        let (a, b) = (corners[edge], corners[(edge + 1) % 4]);
+                        let (value_a, value_b) = (values[edge], values[(edge + 1) % 4]);
+
+                        if (value_a < level as f64) == (value_b < level as f64) {
+                            None
+                        } else {
+                            let t = (level as f64 - value_a) / (value_b - value_a);
+                            let (point_a, point_b) = (point(a.0, a.1), point(b.0, b.1));
+
+                            Some(Coordinates::new(
+                                point_a.latitude + t * (point_b.latitude - point_a.latitude),
+                                point_a.longitude + t * (point_b.longitude - point_a.longitude),
+                            ))
+                        }
+                    })
+                    .collect();
+
+                for [a, b] in crossings.as_chunks::<2>().0 {
+                    segments.push(format!("[{},{}]", position(*a), position(*b)));
+                }
+            }
+        }
+
+        if !segments.is_empty()
+            && let Some(time) = DateTime::from_timestamp(level, 0)
+        {
+            features.push(format!(
+                r#"{{"type":"Feature","geometry":{{"type":"MultiLineString","coordinates":[{}]}},"properties":{{"maghrib":"{}"}}}}"#,
+                segments.join(","),
+                time.to_rfc3339()
+            ));
+        } else {
+            // Nothing to do.
+        }
+
+        level += interval;
+    }
+
+    collection(&features)
+}
+
+fn collection(features: &[String]) -> String {
+    format!(
+        r#"{{"type":"FeatureCollection","features":[{}]}}"#,
+        features.join(",")
+    )
+}
+
+fn position(coordinates: Coordinates) -> String {
+    format!(
+        "[{},{}]",
+        rounded(coordinates.longitude),
+        rounded(coordinates.latitude)
+    )
+}
+
+/// Rounded to six decimals, about ten centimeters for positions.
+fn rounded(value: f64) -> f64 {
+    (value * 1e6).round() / 1e6
+}
+
+fn unit_vector(coordinates: Coordinates) -> [f64; 3] {
+    let latitude = coordinates.latitude_angle().radians();
+    let longitude = coordinates.longitude_angle().radians();
+
+    [
+        math::cos(latitude) * math::cos(longitude),
+        math::cos(latitude) * math::sin(longitude),
+        math::sin(latitude),
+    ]
+}
+
+fn from_unit_vector([x, y, z]: [f64; 3]) -> Coordinates {
+    Coordinates::new(
+        math::atan2(z, (x * x + y * y).sqrt()).to_degrees(),
+        math::atan2(y, x).to_degrees(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Method;
+    use crate::PrayerTimes;
+    use serde_json::Value;
+
+    fn coordinates(position: &Value) -> Coordinates {
+        Coordinates::new(position[1].as_f64().unwrap(), position[0].as_f64().unwrap())
+    }
+
+    #[test]
+    fn qibla_line_follows_the_great_circle() {
+        let london = Coordinates::new(51.5074, -0.1278);
+        let line: Value = serde_json::from_str(&qibla_line(london, 11).unwrap()).unwrap();
+        let positions: Vec<Coordinates> = line["geometry"]["coordinates"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(coordinates)
+            .collect();
+        let distance = london.distance_to(KAABA);
+
+        assert_eq!(positions.len(), 11);
+        assert_eq!(positions[0], london);
+        assert!(positions[10].distance_to(KAABA) < 0.001);
+        for pair in positions.windows(2) {
+            assert!((pair[0].distance_to(pair[1]) - distance / 10.0).abs() < 0.01);
+        }
+        assert_eq!(
+            line["properties"]["direction"].as_f64().unwrap().round(),
+            119.0
+        );
+    }
+
+    #[test]
+    fn qibla_line_at_the_kaaba() {
+        let line: Value = serde_json::from_str(&qibla_line(KAABA, 0).unwrap()).unwrap();
+
+        assert_eq!(line["geometry"]["coordinates"].as_array().unwrap().len(), 2);
+        assert_eq!(line["properties"]["distance_km"], 0.0);
+    }
+
+    #[test]
+    fn qibla_line_refuses_what_json_cannot_write() {
+        for from in [
+            Coordinates::new(f64::NAN, 0.0),
+            Coordinates::new(0.0, f64::INFINITY),
+            Coordinates::new(95.0, 0.0),
+        ] {
+            assert!(matches!(
+                qibla_line(from, 3),
+                Err(CalculationError::InvalidCoordinates(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn isochrones_pass_where_maghrib_is_at_their_time() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 20).unwrap();
+        let params = Method::MuslimWorldLeague.parameters();
+        let layer = maghrib_isochrones(
+            date,
+            BoundingBox::new(Coordinates::new(33.0, 35.0), Coordinates::new(34.5, 36.5)),
+            0.25,
+            params,
+            Duration::minutes(2),
+        );
+        let layer: Value = serde_json::from_str(&layer).unwrap();
+        let features = layer["features"].as_array().unwrap();
+
+        // Maghrib moves by about six minutes across 1.5° of longitude.
+        assert!((2..=4).contains(&features.len()));
+        for feature in features {
+            let time =
+                DateTime::parse_from_rfc3339(feature["properties"]["maghrib"].as_str().unwrap())
+                    .unwrap();
+
+            for segment in feature["geometry"]["coordinates"].as_array().unwrap() {
+                for position in segment.as_array().unwrap() {
+                    let maghrib = PrayerTimes::computed(date, coordinates(position), params)
+                        .time(Prayer::Maghrib);
+
+                    assert!((maghrib - time.to_utc()).num_seconds().abs() <= 60);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn isochrones_stop_where_the_sun_doesnt_set() {
+        let date = NaiveDate::from_ymd_opt(2024, 6, 21).unwrap();
+        let params = Method::MuslimWorldLeague.parameters();
+        let layer = maghrib_isochrones(
+            date,
+            BoundingBox::new(Coordinates::new(62.0, 10.0), Coordinates::new(70.0, 20.0)),
+            1.0,
+            params,
+            Duration::minutes(10),
+        );
+        let layer: Value = serde_json::from_str(&layer).unwrap();
+        let features = layer["features"].as_array().unwrap();
+
+        assert!(!features.is_empty());
+        for feature in features {
+            for segment in feature["geometry"]["coordinates"].as_array().unwrap() {
+                for position in segment.as_array().unwrap() {
+                    assert!(coordinates(position).latitude < 67.0);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn empty_boxes_have_no_isochrones() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 20).unwrap();
+        let params = Method::MuslimWorldLeague.parameters();
+        let empty = r#"{"type":"FeatureCollection","features":[]}"#;
+        let isochrones = |south_west, step, interval| {
+            maghrib_isochrones(
+                date,
+                BoundingBox::new(south_west, Coordinates::new(34.0, 36.0)),
+                step,
+                params,
+                interval,
+            )
+        };
+
+        assert_eq!(
+            isochrones(Coordinates::new(35.0, 35.0), 0.5, Duration::minutes(1)),
+            empty
+        );
+        assert_eq!(
+            isochrones(Coordinates::new(33.0, 35.0), 0.0, Duration::minutes(1)),
+            empty
+        );
+        assert_eq!(
+            isochrones(Coordinates::new(33.0, 35.0), 0.5, Duration::zero()),
+            empty
+        );
+        assert_eq!(
+            isochrones(Coordinates::new(f64::NAN, 35.0), 0.5, Duration::minutes(1)),
+            empty
+        );
+        // Maghrib spans about two hours and a half over 36° of longitude,
+        // more than a thousand seconds but not a thousand minutes.
+        assert_eq!(
+            isochrones(Coordinates::new(33.0, 0.0), 0.5, Duration::seconds(1)),
+            empty
+        );
+        assert_ne!(
+            isochrones(Coordinates::new(33.0, 0.0), 0.5, Duration::minutes(1)),
+            empty
+        );
+    }
+}
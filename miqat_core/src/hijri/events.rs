@@ -60,8 +60,12 @@ pub struct IslamicEventOccurrence {
 /// Returns all recurring Islamic event occurrences that fall within the given Gregorian year,
 /// sorted chronologically by Gregorian date.
 pub fn events_for_gregorian_year(gregorian_year: i32) -> Vec<IslamicEventOccurrence> {
-    let jan1 = NaiveDate::from_ymd_opt(gregorian_year, 1, 1).unwrap();
-    let dec31 = NaiveDate::from_ymd_opt(gregorian_year, 12, 31).unwrap();
+    let (Some(jan1), Some(dec31)) = (
+        NaiveDate::from_ymd_opt(gregorian_year, 1, 1),
+        NaiveDate::from_ymd_opt(gregorian_year, 12, 31),
+    ) else {
+        return Vec::new();
+    };
 
     let hijri_start = HijriDate::from_gregorian(jan1).year;
     let hijri_end = HijriDate::from_gregorian(dec31).year;
@@ -97,9 +101,9 @@ const EVENTS: &[(u8, u8, IslamicEvent)] = &[
     (1, 10, IslamicEvent::Ashura),
     (3, 12, IslamicEvent::MawlidAlNabi),
     (7, 27, IslamicEvent::IsraAndMiraj),
-    (8, 14, IslamicEvent::NisfShaban),
+    (8, 15, IslamicEvent::NisfShaban),
     (9, 1, IslamicEvent::FirstOfRamadan),
-    (9, 26, IslamicEvent::LaylatAlQadr), /* 26 instead of 27 to accout for gregorian calendar users */
+    (9, 27, IslamicEvent::LaylatAlQadr),
     (10, 1, IslamicEvent::EidAlFitr),
     (12, 9, IslamicEvent::DayOfArafah),
     (12, 10, IslamicEvent::EidAlAdha),
@@ -1,4 +1,5 @@
 pub mod events;
+pub mod umm_al_qura;
 
 use calendrical_calculations::gregorian::fixed_from_gregorian;
 use calendrical_calculations::gregorian::gregorian_from_fixed;
@@ -8,16 +9,47 @@ use calendrical_calculations::islamic::observational_islamic_from_fixed;
 use calendrical_calculations::islamic::saudi_islamic_from_fixed;
 use chrono::DateTime;
 use chrono::Datelike;
+use chrono::Duration;
 use chrono::NaiveDate;
+use chrono::NaiveTime;
 use chrono::Utc;
 use std::fmt;
+use std::sync::atomic::AtomicI8;
+use std::sync::atomic::Ordering;
 
 pub use events::IslamicEvent;
 
+static HIJRI_OFFSET_DAYS: AtomicI8 = AtomicI8::new(0);
+
+/// The number of days Hijri dates are shifted by, for regions where the
+/// new moon is sighted earlier or later than the Saudi calendar.
+/// Zero unless set with [`set_hijri_offset_days`].
+pub fn hijri_offset_days() -> i8 {
+    HIJRI_OFFSET_DAYS.load(Ordering::Relaxed)
+}
+
+/// Shifts every Hijri date by `days` for the whole process: a positive
+/// offset starts months earlier, so a Gregorian date falls on a later
+/// Hijri day. Conversions, holidays and Hijri month schedules all
+/// follow it; [`HijriDate::from_gregorian_observational`], which already
+/// depends on the location, doesn't.
+///
+/// ```
+/// use miqat::hijri;
+///
+/// hijri::set_hijri_offset_days(-1);
+/// assert_eq!(hijri::hijri_offset_days(), -1);
+/// # hijri::set_hijri_offset_days(0);
+/// ```
+pub fn set_hijri_offset_days(days: i8) {
+    HIJRI_OFFSET_DAYS.store(days, Ordering::Relaxed);
+}
+
 /// A date in the Islamic (Hijri) calendar.
 ///
-/// Computed using the Saudi Islamic calendar, which is based on the
-/// astronomical criterion used in Saudi Arabia for crescent moon sighting.
+/// Computed using the Saudi Islamic calendar: the official Umm al-Qura
+/// table for the years it covers, see [`umm_al_qura`], and the
+/// astronomical criterion it is based on otherwise.
 ///
 /// # Example
 ///
@@ -30,7 +62,7 @@ pub use events::IslamicEvent;
 /// println!("{}", hijri); // e.g. "29/8/1445"
 ///
 /// for event in hijri.events() {
-///     println!("Today is {event}");
+///     println!("Today is {event:?}");
 /// }
 /// ```
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -52,14 +84,9 @@ impl HijriDate {
     /// ```
     /// use miqat::HijriDate;
     /// use chrono::NaiveDate;
-    /// use calendrical_calculations::islamic::Location;
+    /// use calendrical_calculations::islamic::MECCA;
     ///
-    /// let location = Location {
-    ///     latitude: 21.4,
-    ///     longitude: 39.8,
-    ///     elevation: 298.0,
-    ///     utc_offset: 3.0 / 24.0, // UTC+3
-    /// };
+    /// let location = MECCA;
     /// let date = NaiveDate::from_ymd_opt(2024, 3, 10).unwrap();
     /// let hijri = HijriDate::from_gregorian_observational(date, location);
     /// println!("{}", hijri);
@@ -71,19 +98,70 @@ impl HijriDate {
     }
 
     /// Converts a Gregorian [`NaiveDate`] to a [`HijriDate`] using the Saudi Islamic calendar,
-    /// which is based on the astronomical criterion used in Saudi Arabia.
+    /// which is based on the astronomical criterion used in Saudi Arabia, shifted by
+    /// [`hijri_offset_days`].
     pub fn from_gregorian(date: NaiveDate) -> Self {
-        let fixed = fixed_from_gregorian(date.year(), date.month() as u8, date.day() as u8);
+        Self::from_gregorian_with_offset(date, hijri_offset_days())
+    }
+
+    /// Like [`from_gregorian`](Self::from_gregorian), with the given offset in days
+    /// instead of the global one.
+    pub fn from_gregorian_with_offset(date: NaiveDate, offset_days: i8) -> Self {
+        let shifted = date + Duration::days(i64::from(offset_days));
+        if let Some(hijri) = umm_al_qura::from_gregorian(shifted) {
+            return hijri;
+        }
+
+        let fixed =
+            fixed_from_gregorian(shifted.year(), shifted.month() as u8, shifted.day() as u8);
         let (year, month, day) = saudi_islamic_from_fixed(fixed);
         Self { year, month, day }
     }
 
-    /// Converts this [`HijriDate`] back to a [`DateTime<Utc>`] at midnight UTC.
+    /// Converts this [`HijriDate`] back to a [`DateTime<Utc>`] at midnight UTC, shifted
+    /// by [`hijri_offset_days`].
     pub fn to_gregorian(&self) -> Option<DateTime<Utc>> {
-        let fixed = fixed_from_saudi_islamic(self.year, self.month, self.day);
-        let (y, m, d) = gregorian_from_fixed(fixed).ok()?;
-        NaiveDate::from_ymd_opt(y, m as u32, d as u32)
-            .map(|nd| nd.and_hms_opt(0, 0, 0).unwrap().and_utc())
+        self.to_gregorian_with_offset(hijri_offset_days())
+    }
+
+    /// Like [`to_gregorian`](Self::to_gregorian), with the given offset in days
+    /// instead of the global one. `None` for a day that's not in its month.
+    pub fn to_gregorian_with_offset(&self, offset_days: i8) -> Option<DateTime<Utc>> {
+        let date = match umm_al_qura::to_gregorian(self) {
+            Some(date) => date,
+            None if umm_al_qura::covered_years().contains(&self.year) => return None,
+            None if !(1..=12).contains(&self.month) || !(1..=30).contains(&self.day) => {
+                return None;
+            }
+            None => {
+                let fixed = fixed_from_saudi_islamic(self.year, self.month, self.day);
+                let (y, m, d) = gregorian_from_fixed(fixed).ok()?;
+                NaiveDate::from_ymd_opt(y, m as u32, d as u32)?
+            }
+        };
+        let shifted = date.checked_sub_signed(Duration::days(i64::from(offset_days)))?;
+
+        Some(shifted.and_time(NaiveTime::MIN).and_utc())
+    }
+
+    /// The Gregorian date of the given day of this date's month, `None`
+    /// if the month is shorter.
+    pub fn nth_day_of_month(&self, day: u8) -> Option<NaiveDate> {
+        let nth = HijriDate { day, ..*self };
+        let date = nth.to_gregorian()?.date_naive();
+
+        (day > 0 && HijriDate::from_gregorian(date) == nth).then_some(date)
+    }
+
+    /// The Hijri date `days` days later, or earlier when negative, `None`
+    /// out of the range of [`NaiveDate`].
+    pub fn checked_add_days(&self, days: i64) -> Option<HijriDate> {
+        let date = self
+            .to_gregorian()?
+            .date_naive()
+            .checked_add_signed(Duration::try_days(days)?)?;
+
+        Some(HijriDate::from_gregorian(date))
     }
 
     /// Returns any Islamic holidays that fall on this date.
@@ -103,3 +181,71 @@ impl fmt::Display for HijriDate {
         write!(f, "{}/{}/{}", self.day, self.month, self.year)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stepping_through_months() {
+        let ramadan = HijriDate {
+            year: 1445,
+            month: 9,
+            day: 10,
+        };
+        let shawwal = HijriDate {
+            year: 1445,
+            month: 10,
+            day: 1,
+        };
+
+        assert_eq!(
+            ramadan.nth_day_of_month(1),
+            NaiveDate::from_ymd_opt(2024, 3, 11)
+        );
+        assert_eq!(
+            ramadan.nth_day_of_month(30),
+            NaiveDate::from_ymd_opt(2024, 4, 9)
+        );
+        assert_eq!(shawwal.nth_day_of_month(30), None);
+        assert_eq!(ramadan.checked_add_days(21), Some(shawwal));
+        assert_eq!(shawwal.checked_add_days(-21), Some(ramadan));
+        assert_eq!(ramadan.checked_add_days(i64::MAX), None);
+    }
+
+    #[test]
+    fn offset_shifts_both_conversions() {
+        let date = NaiveDate::from_ymd_opt(2025, 3, 30).unwrap();
+        let eid = HijriDate {
+            year: 1446,
+            month: 10,
+            day: 1,
+        };
+
+        assert_eq!(HijriDate::from_gregorian_with_offset(date, 0), eid);
+        assert_eq!(
+            HijriDate::from_gregorian_with_offset(date, -1),
+            HijriDate {
+                year: 1446,
+                month: 9,
+                day: 29
+            }
+        );
+        assert_eq!(
+            eid.to_gregorian_with_offset(-1).unwrap().date_naive(),
+            date.succ_opt().unwrap()
+        );
+        assert_eq!(
+            eid.to_gregorian_with_offset(1).unwrap().date_naive(),
+            date.pred_opt().unwrap()
+        );
+
+        for offset in [-2, -1, 0, 1, 2] {
+            let hijri = HijriDate::from_gregorian_with_offset(date, offset);
+            assert_eq!(
+                hijri.to_gregorian_with_offset(offset).unwrap().date_naive(),
+                date
+            );
+        }
+    }
+}
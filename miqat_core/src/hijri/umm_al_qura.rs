@@ -0,0 +1,229 @@
+//! The tabulated Umm al-Qura calendar.
+//!
+//! The months of the official calendar of Saudi Arabia don't always
+//! match the astronomical criterion they are based on, so the years of
+//! the official table, 1356 to 1500 AH in `umm_al_qura.txt` next to this
+//! module, are converted from it. [`HijriDate`] falls back to the
+//! criterion outside of them.
+
+use super::HijriDate;
+use chrono::Duration;
+use chrono::NaiveDate;
+use std::ops::RangeInclusive;
+use std::sync::OnceLock;
+
+const DATA: &str = include_str!("umm_al_qura.txt");
+
+#[derive(PartialEq, Debug, Copy, Clone)]
+struct Year {
+    year: i32,
+    first_day: NaiveDate,
+    month_lengths: [u8; 12],
+}
+
+impl Year {
+    fn month_start(&self, month: u8) -> NaiveDate {
+        let days: i64 = self.month_lengths[..usize::from(month - 1)]
+            .iter()
+            .map(|length| i64::from(*length))
+            .sum();
+
+        self.first_day + Duration::days(days)
+    }
+
+    fn length(&self) -> i64 {
+        self.month_lengths
+            .iter()
+            .map(|length| i64::from(*length))
+            .sum()
+    }
+}
+
+fn years() -> &'static [Year] {
+    static YEARS: OnceLock<Vec<Year>> = OnceLock::new();
+
+    YEARS.get_or_init(|| lines().filter_map(parse_line).collect())
+}
+
+/// The data lines of the bundled file, without blank lines and comments.
+fn lines() -> impl Iterator<Item = &'static str> {
+    DATA.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+}
+
+fn parse_line(line: &str) -> Option<Year> {
+    let mut columns = line.split('|').map(str::trim);
+    let year = columns.next()?.parse().ok()?;
+    let first_day = NaiveDate::parse_from_str(columns.next()?, "%Y-%m-%d").ok()?;
+    let lengths: Vec<u8> = columns
+        .next()?
+        .split_whitespace()
+        .map(|length| {
+            length
+                .parse()
+                .ok()
+                .filter(|length| (29..=30).contains(length))
+        })
+        .collect::<Option<_>>()?;
+
+    Some(Year {
+        year,
+        first_day,
+        month_lengths: lengths.try_into().ok()?,
+    })
+}
+
+/// The Hijri years converted from the table.
+pub fn covered_years() -> RangeInclusive<i32> {
+    let years = years();
+
+    match (years.first(), years.last()) {
+        (Some(first), Some(last)) => first.year..=last.year,
+        _ => RangeInclusive::new(1, 0),
+    }
+}
+
+/// The Hijri date of `date`, if it's within the table.
+pub(super) fn from_gregorian(date: NaiveDate) -> Option<HijriDate> {
+    let year = years().iter().find(|year| {
+        year.first_day <= date && date < year.first_day + Duration::days(year.length())
+    })?;
+    let month = (1..=12)
+        .rev()
+        .find(|month| year.month_start(*month) <= date)?;
+    let day = (date - year.month_start(month)).num_days() + 1;
+
+    Some(HijriDate {
+        year: year.year,
+        month,
+        day: day as u8,
+    })
+}
+
+/// The Gregorian date of `date`, if its year is within the table and
+/// its day within the month.
+pub(super) fn to_gregorian(date: &HijriDate) -> Option<NaiveDate> {
+    let year = years().iter().find(|year| year.year == date.year)?;
+    let length = *year
+        .month_lengths
+        .get(usize::from(date.month).checked_sub(1)?)?;
+
+    if (1..=length).contains(&date.day) {
+        Some(year.month_start(date.month) + Duration::days(i64::from(date.day) - 1))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundled_data_is_consecutive() {
+        let years = years();
+
+        assert!(!years.is_empty());
+        assert_eq!(years.len(), lines().count());
+        for pair in years.windows(2) {
+            assert_eq!(pair[1].year, pair[0].year + 1);
+            assert_eq!(
+                pair[1].first_day,
+                pair[0].first_day + Duration::days(pair[0].length())
+            );
+            assert!((354..=355).contains(&pair[0].length()));
+        }
+    }
+
+    #[test]
+    fn published_dates() {
+        let date = |year, month, day| NaiveDate::from_ymd_opt(year, month, day).unwrap();
+        let published = [
+            (1444, 9, 1, date(2023, 3, 23)),
+            (1444, 10, 1, date(2023, 4, 21)),
+            (1445, 9, 1, date(2024, 3, 11)),
+            (1445, 10, 1, date(2024, 4, 10)),
+            (1445, 12, 10, date(2024, 6, 16)),
+            (1446, 1, 1, date(2024, 7, 7)),
+            (1446, 9, 1, date(2025, 3, 1)),
+            (1446, 10, 1, date(2025, 3, 30)),
+            (1446, 12, 10, date(2025, 6, 6)),
+        ];
+
+        for (year, month, day, gregorian) in published {
+            let hijri = HijriDate { year, month, day };
+
+            assert_eq!(to_gregorian(&hijri), Some(gregorian));
+            assert_eq!(from_gregorian(gregorian), Some(hijri));
+        }
+    }
+
+    #[test]
+    fn days_outside_of_the_month() {
+        let date = |month, day| HijriDate {
+            year: 1444,
+            month,
+            day,
+        };
+
+        assert_eq!(to_gregorian(&date(1, 0)), None);
+        assert_eq!(to_gregorian(&date(1, 30)), None);
+        assert_eq!(to_gregorian(&date(2, 31)), None);
+        assert_eq!(to_gregorian(&date(13, 1)), None);
+        assert_eq!(date(1, 30).to_gregorian_with_offset(0), None);
+        assert_eq!(date(1, 0).to_gregorian_with_offset(0), None);
+        assert!(date(2, 30).to_gregorian_with_offset(0).is_some());
+    }
+
+    #[test]
+    fn boundaries_of_the_table() {
+        let first = HijriDate {
+            year: 1356,
+            month: 1,
+            day: 1,
+        };
+        let last = HijriDate {
+            year: 1500,
+            month: 12,
+            day: 30,
+        };
+
+        assert_eq!(covered_years(), 1356..=1500);
+        assert_eq!(to_gregorian(&first), NaiveDate::from_ymd_opt(1937, 3, 14));
+        assert_eq!(
+            from_gregorian(NaiveDate::from_ymd_opt(1937, 3, 14).unwrap()),
+            Some(first)
+        );
+        assert_eq!(to_gregorian(&last), NaiveDate::from_ymd_opt(2077, 11, 16));
+        assert_eq!(
+            from_gregorian(NaiveDate::from_ymd_opt(2077, 11, 16).unwrap()),
+            Some(last)
+        );
+        assert_eq!(
+            to_gregorian(&HijriDate {
+                year: 1500,
+                month: 12,
+                day: 1
+            }),
+            NaiveDate::from_ymd_opt(2077, 10, 18)
+        );
+    }
+
+    #[test]
+    fn outside_of_the_table() {
+        let before = years()[0].first_day.pred_opt().unwrap();
+        let after = years()[years().len() - 1].first_day + Duration::days(355);
+        let hijri = HijriDate {
+            year: *covered_years().end() + 1,
+            month: 1,
+            day: 1,
+        };
+
+        assert_eq!(from_gregorian(before), None);
+        assert_eq!(from_gregorian(after), None);
+        assert_eq!(to_gregorian(&hijri), None);
+        assert_eq!(HijriDate::from_gregorian_with_offset(before, 0).year, 1355);
+        assert_eq!(HijriDate::from_gregorian_with_offset(after, 0).year, 1501);
+    }
+}
@@ -0,0 +1,496 @@
+//! # Calendar Export
+//!
+//! Schedules as an iCalendar file (RFC 5545) that calendar apps can
+//! import, with an event per obligatory prayer and, optionally, one for
+//! the [Duha window](Window::duha). Events are written in
+//! local time with a `TZID`, and the calendar carries a `VTIMEZONE` with
+//! the offsets of the zone over the exported days, so that times stay
+//! right across daylight saving changes, e.g. the US spring-forward during
+//! Ramadan.
+//!
+//! The zone is any chrono [`TimeZone`], such as `miqat::Tz` with the
+//! `timezones` feature, along with its IANA name for the `TZID`. Its
+//! transitions are found by sampling its offsets, so the `VTIMEZONE`
+//! lists each transition within the exported days rather than the rules
+//! of the zone.
+//!
+//! Each event's `UID` is made of its date, its prayer and a hash of the
+//! coordinates of its schedule, so that the calendars of two locations
+//! can be imported side by side, while exporting a location again
+//! updates its events rather than duplicating them.
+//!
+//! ##### Example
+//!
+//! ```
+//! use miqat::ics;
+//! use miqat::prelude::*;
+//! use chrono::FixedOffset;
+//!
+//! let date = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+//! let beirut = Coordinates::new(33.8938, 35.5018);
+//! let params = Method::MuslimWorldLeague.parameters();
+//! let times = PrayerTimes::computed(date, beirut, params);
+//! // A fixed offset, named by its IANA zone, whose sign is inverted.
+//! let eet = FixedOffset::east_opt(2 * 3600).unwrap();
+//! let calendar = ics::calendar(&[times], &eet, "Etc/GMT-2");
+//!
+//! assert!(calendar.starts_with("BEGIN:VCALENDAR\r\n"));
+//! assert!(calendar.contains("DTSTART;TZID=Etc/GMT-2:20240315T042500\r\n"));
+//! assert!(calendar.contains("SUMMARY:Jumua\r\n"));
+//! ```
+
+use crate::cache::fnv1a;
+use crate::clock::FixedClock;
+use crate::models::prayer::Prayer;
+use crate::models::window::Window;
+use crate::prayer_times::PrayerTimes;
+use crate::sunnah_times::DuhaDefinition;
+use chrono::DateTime;
+use chrono::Duration;
+use chrono::FixedOffset;
+use chrono::NaiveTime;
+use chrono::Offset;
+use chrono::TimeZone;
+use chrono::Utc;
+
+/// The events of `schedules` as an iCalendar file, in local time in
+/// `zone`, whose IANA name is `tzid`. Lines end with CRLF.
+pub fn calendar<Tz: TimeZone>(schedules: &[PrayerTimes], zone: &Tz, tzid: &str) -> String {
+    calendar_with(schedules, zone, tzid, None)
+}
+
+/// Same as [`calendar`], with an event for the Duha window of each day
+/// when `duha` is given. Days without a Duha window, e.g. when the sun
+/// doesn't rise, have no such event.
+pub fn calendar_with<Tz: TimeZone>(
+    schedules: &[PrayerTimes],
+    zone: &Tz,
+    tzid: &str,
+    duha: Option<DuhaDefinition>,
+) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//ibad-al-rahman//miqat//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+    ];
+    let days: Vec<(&PrayerTimes, Vec<Event>)> = schedules
+        .iter()
+        .map(|times| (times, events(times, duha)))
+        .collect();
+    let instants = days
+        .iter()
+        .flat_map(|(_, events)| events.iter())
+        .flat_map(|event| [Some(event.start), event.end])
+        .flatten();
+
+    if let (Some(first), Some(last)) = (instants.clone().min(), instants.max()) {
+        lines.extend(timezone(zone, tzid, first, last));
+    } else {
+        // Nothing to do.
+    }
+
+    let local = |time: DateTime<Utc>| {
+        time.with_timezone(zone)
+            .naive_local()
+            .format("%Y%m%dT%H%M%S")
+            .to_string()
+    };
+
+    for (times, events) in days {
+        let coordinates = times.coordinates();
+        let location = fnv1a(&format!(
+            "{:016x},{:016x}",
+            coordinates.latitude.to_bits(),
+            coordinates.longitude.to_bits()
+        ));
+
+        for event in events {
+            lines.extend([
+                "BEGIN:VEVENT".to_string(),
+                format!("UID:{}-{}-{location:016x}@miqat", times.date(), event.slug),
+                // The time of the prayer rather than of the export, so
+                // that exporting the same schedules gives the same file.
+                format!("DTSTAMP:{}", event.start.format("%Y%m%dT%H%M%SZ")),
+                format!("DTSTART;TZID={tzid}:{}", local(event.start)),
+            ]);
+            if let Some(end) = event.end {
+                lines.push(format!("DTEND;TZID={tzid}:{}", local(end)));
+            } else {
+                // Nothing to do.
+            }
+            lines.extend([
+                format!("SUMMARY:{}", event.summary),
+                "END:VEVENT".to_string(),
+            ]);
+        }
+    }
+
+    lines.push("END:VCALENDAR".to_string());
+
+    let mut calendar = lines.join("\r\n");
+    calendar.push_str("\r\n");
+    calendar
+}
+
+/// An event of a calendar, before it is written in local time.
+struct Event {
+    slug: &'static str,
+    summary: &'static str,
+    start: DateTime<Utc>,
+    end: Option<DateTime<Utc>>,
+}
+
+/// The events of the day of `times`: its prayers, then its Duha window
+/// when `duha` is given.
+fn events(times: &PrayerTimes, duha: Option<DuhaDefinition>) -> Vec<Event> {
+    let clock = FixedClock(times.date().and_time(NaiveTime::MIN).and_utc());
+    let mut events: Vec<Event> = Prayer::OBLIGATORY
+        .iter()
+        .map(|&prayer| Event {
+            slug: prayer.as_slug(),
+            summary: prayer.name_with(&clock),
+            start: times.time(prayer),
+            end: None,
+        })
+        .collect();
+
+    if let Some(window) =
+        duha.and_then(|definition| Window::duha(times, times.coordinates(), definition))
+    {
+        events.push(Event {
+            slug: "duha",
+            summary: "Duha",
+            start: window.start,
+            end: Some(window.end),
+        });
+    } else {
+        // Nothing to do.
+    }
+
+    events
+}
+
+/// The `VTIMEZONE` of `zone` from `first` to `last`: the offset at
+/// `first`, then every transition up to `last`.
+fn timezone<Tz: TimeZone>(
+    zone: &Tz,
+    tzid: &str,
+    first: DateTime<Utc>,
+    last: DateTime<Utc>,
+) -> Vec<String> {
+    let offset_at =
+        |instant: DateTime<Utc>| zone.offset_from_utc_datetime(&instant.naive_utc()).fix();
+    let mut transitions = Vec::new();
+    let mut day = first;
+
+    while day < last {
+        let next = (day + Duration::days(1)).min(last);
+
+        if offset_at(day) != offset_at(next) {
+            let (mut before, mut after) = (day, next);
+
+            while after - before > Duration::seconds(1) {
+                let middle = before + (after - before) / 2;
+
+                if offset_at(middle) == offset_at(before) {
+                    before = middle;
+                } else {
+                    after = middle;
+                }
+            }
+
+            transitions.push((after, offset_at(before), offset_at(after)));
+        } else {
+            // Nothing to do.
+        }
+
+        day = next;
+    }
+
+    // A zone whose first change moves the clocks back starts in daylight
+    // saving time.
+    let initial = offset_at(first);
+    let starts_in_daylight = transitions
+        .first()
+        .is_some_and(|(_, from, to)| to.local_minus_utc() < from.local_minus_utc());
+    let mut lines = vec!["BEGIN:VTIMEZONE".to_string(), format!("TZID:{tzid}")];
+
+    lines.extend(observance(starts_in_daylight, first, initial, initial));
+    for (instant, from, to) in transitions {
+        let daylight = to.local_minus_utc() > from.local_minus_utc();
+        lines.extend(observance(daylight, instant, from, to));
+    }
+
+    lines.push("END:VTIMEZONE".to_string());
+    lines
+}
+
+/// A `STANDARD` or `DAYLIGHT` component starting at `instant`, written
+/// in local time at the offset in effect before it.
+fn observance(
+    daylight: bool,
+    instant: DateTime<Utc>,
+    from: FixedOffset,
+    to: FixedOffset,
+) -> [String; 5] {
+    let kind = if daylight { "DAYLIGHT" } else { "STANDARD" };
+
+    [
+        format!("BEGIN:{kind}"),
+        format!(
+            "DTSTART:{}",
+            instant.with_timezone(&from).format("%Y%m%dT%H%M%S")
+        ),
+        format!("TZOFFSETFROM:{}", utc_offset(from)),
+        format!("TZOFFSETTO:{}", utc_offset(to)),
+        format!("END:{kind}"),
+    ]
+}
+
+/// An offset written as e.g. `-0500`.
+fn utc_offset(offset: FixedOffset) -> String {
+    let seconds = offset.local_minus_utc();
+    let sign = if seconds < 0 { '-' } else { '+' };
+    let minutes = seconds.unsigned_abs() / 60;
+
+    format!("{sign}{:02}{:02}", minutes / 60, minutes % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::astronomy::unit::Coordinates;
+    use crate::models::method::Method;
+    use chrono::Datelike;
+    use chrono::MappedLocalTime;
+    use chrono::NaiveDate;
+    use chrono::NaiveDateTime;
+    use chrono::Weekday;
+
+    // The daylight saving time rules of two zones, enough to export
+    // around their transitions without a timezone database.
+    #[derive(PartialEq, Debug, Copy, Clone)]
+    enum Zone {
+        NewYork,
+        London,
+    }
+
+    #[derive(PartialEq, Debug, Copy, Clone)]
+    struct ZoneOffset(Zone, FixedOffset);
+
+    impl Offset for ZoneOffset {
+        fn fix(&self) -> FixedOffset {
+            self.1
+        }
+    }
+
+    impl Zone {
+        /// The standard offset in hours, and when daylight saving time
+        /// starts and ends in UTC.
+        fn rules(&self, year: i32) -> (i32, NaiveDateTime, NaiveDateTime) {
+            let sunday = |month, n| {
+                NaiveDate::from_weekday_of_month_opt(year, month, Weekday::Sun, n)
+                    .or_else(|| NaiveDate::from_weekday_of_month_opt(year, month, Weekday::Sun, 4))
+                    .unwrap()
+            };
+
+            match self {
+                Zone::NewYork => (
+                    -5,
+                    sunday(3, 2).and_hms_opt(7, 0, 0).unwrap(),
+                    sunday(11, 1).and_hms_opt(6, 0, 0).unwrap(),
+                ),
+                Zone::London => (
+                    0,
+                    sunday(3, 5).and_hms_opt(1, 0, 0).unwrap(),
+                    sunday(10, 5).and_hms_opt(1, 0, 0).unwrap(),
+                ),
+            }
+        }
+    }
+
+    impl TimeZone for Zone {
+        type Offset = ZoneOffset;
+
+        fn from_offset(offset: &ZoneOffset) -> Zone {
+            offset.0
+        }
+
+        fn offset_from_local_date(&self, local: &NaiveDate) -> MappedLocalTime<ZoneOffset> {
+            self.offset_from_local_datetime(&local.and_time(NaiveTime::MIN))
+        }
+
+        fn offset_from_local_datetime(&self, local: &NaiveDateTime) -> MappedLocalTime<ZoneOffset> {
+            let (standard, ..) = self.rules(local.year());
+            let candidates: Vec<ZoneOffset> = [standard, standard + 1]
+                .into_iter()
+                .map(|hours| FixedOffset::east_opt(hours * 3600).unwrap())
+                .filter(|offset| {
+                    self.offset_from_utc_datetime(
+                        &(*local - Duration::seconds(offset.local_minus_utc().into())),
+                    )
+                    .1 == *offset
+                })
+                .map(|offset| ZoneOffset(*self, offset))
+                .collect();
+
+            match candidates[..] {
+                [offset] => MappedLocalTime::Single(offset),
+                [earliest, latest] => MappedLocalTime::Ambiguous(latest, earliest),
+                _ => MappedLocalTime::None,
+            }
+        }
+
+        fn offset_from_utc_date(&self, utc: &NaiveDate) -> ZoneOffset {
+            self.offset_from_utc_datetime(&utc.and_time(NaiveTime::MIN))
+        }
+
+        fn offset_from_utc_datetime(&self, utc: &NaiveDateTime) -> ZoneOffset {
+            let (standard, start, end) = self.rules(utc.year());
+            let hours = if (start..end).contains(utc) {
+                standard + 1
+            } else {
+                standard
+            };
+
+            ZoneOffset(*self, FixedOffset::east_opt(hours * 3600).unwrap())
+        }
+    }
+
+    fn export(zone: Zone, coordinates: Coordinates, from: NaiveDate, until: NaiveDate) -> String {
+        let params = Method::MuslimWorldLeague.parameters();
+        let schedules = PrayerTimes::on_range(from, until, coordinates, params).unwrap();
+        let tzid = match zone {
+            Zone::NewYork => "America/New_York",
+            Zone::London => "Europe/London",
+        };
+
+        calendar(&schedules, &zone, tzid)
+    }
+
+    #[test]
+    fn new_york_springs_forward_during_ramadan() {
+        let calendar = export(
+            Zone::NewYork,
+            Coordinates::new(40.7128, -74.0060),
+            NaiveDate::from_ymd_opt(2025, 2, 27).unwrap(),
+            NaiveDate::from_ymd_opt(2025, 3, 31).unwrap(),
+        );
+
+        assert!(calendar.contains(
+            "BEGIN:STANDARD\r\nDTSTART:20250227T050200\r\nTZOFFSETFROM:-0500\r\nTZOFFSETTO:-0500\r\n"
+        ));
+        assert!(calendar.contains(
+            "BEGIN:DAYLIGHT\r\nDTSTART:20250309T020000\r\nTZOFFSETFROM:-0500\r\nTZOFFSETTO:-0400\r\n"
+        ));
+        assert_eq!(calendar.matches("BEGIN:DAYLIGHT").count(), 1);
+        assert_eq!(calendar.matches("BEGIN:VEVENT").count(), 33 * 5);
+
+        // The clocks move forward an hour overnight, and so do the events.
+        for start in [
+            "20250228T050000",
+            "20250308T044800",
+            "20250309T054600",
+            "20250308T175500",
+            "20250309T185700",
+        ] {
+            assert!(
+                calendar.contains(&format!("DTSTART;TZID=America/New_York:{start}\r\n")),
+                "{start}"
+            );
+        }
+    }
+
+    #[test]
+    fn london_changes_across_month_boundaries() {
+        let london = Coordinates::new(51.5074, -0.1278);
+        let spring = export(
+            Zone::London,
+            london,
+            NaiveDate::from_ymd_opt(2025, 3, 29).unwrap(),
+            NaiveDate::from_ymd_opt(2025, 4, 1).unwrap(),
+        );
+        let autumn = export(
+            Zone::London,
+            london,
+            NaiveDate::from_ymd_opt(2025, 10, 25).unwrap(),
+            NaiveDate::from_ymd_opt(2025, 11, 1).unwrap(),
+        );
+
+        assert!(spring.contains(
+            "BEGIN:DAYLIGHT\r\nDTSTART:20250330T010000\r\nTZOFFSETFROM:+0000\r\nTZOFFSETTO:+0100\r\n"
+        ));
+        assert!(spring.contains("DTSTART;TZID=Europe/London:20250329T182900\r\n"));
+        assert!(spring.contains("DTSTART;TZID=Europe/London:20250330T193100\r\n"));
+        assert!(spring.contains("DTSTART;TZID=Europe/London:20250401T043700\r\n"));
+
+        assert!(autumn.contains(
+            "BEGIN:DAYLIGHT\r\nDTSTART:20251025T055000\r\nTZOFFSETFROM:+0100\r\nTZOFFSETTO:+0100\r\n"
+        ));
+        assert!(autumn.contains(
+            "BEGIN:STANDARD\r\nDTSTART:20251026T020000\r\nTZOFFSETFROM:+0100\r\nTZOFFSETTO:+0000\r\n"
+        ));
+        assert!(autumn.contains("DTSTART;TZID=Europe/London:20251025T124600\r\n"));
+        assert!(autumn.contains("DTSTART;TZID=Europe/London:20251026T114500\r\n"));
+        assert!(autumn.ends_with("END:VEVENT\r\nEND:VCALENDAR\r\n"));
+    }
+
+    #[test]
+    fn uids_differ_between_locations() {
+        let day = NaiveDate::from_ymd_opt(2025, 3, 29).unwrap();
+        let uids = |coordinates| {
+            export(Zone::London, coordinates, day, day)
+                .lines()
+                .filter(|line| line.starts_with("UID:"))
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        };
+        let london = uids(Coordinates::new(51.5074, -0.1278));
+        let oxford = uids(Coordinates::new(51.7520, -1.2577));
+
+        assert_eq!(london.len(), 5);
+        assert!(london[0].starts_with("UID:2025-03-29-fajr-"));
+        assert!(london.iter().all(|uid| !oxford.contains(uid)));
+        assert_eq!(london, uids(Coordinates::new(51.5074, -0.1278)));
+    }
+
+    #[test]
+    fn duha_is_an_optional_event() {
+        let day = NaiveDate::from_ymd_opt(2025, 3, 29).unwrap();
+        let london = Coordinates::new(51.5074, -0.1278);
+        let params = Method::MuslimWorldLeague.parameters();
+        let times = PrayerTimes::computed(day, london, params);
+        let duha = Window::duha(&times, london, DuhaDefinition::default()).unwrap();
+        let with_duha = calendar_with(
+            std::slice::from_ref(&times),
+            &Zone::London,
+            "Europe/London",
+            Some(DuhaDefinition::default()),
+        );
+
+        assert!(!calendar(&[times], &Zone::London, "Europe/London").contains("SUMMARY:Duha"));
+        assert_eq!(with_duha.matches("BEGIN:VEVENT").count(), 6);
+        assert!(with_duha.contains(&format!(
+            "UID:2025-03-29-duha-{:016x}@miqat\r\n",
+            fnv1a(&format!(
+                "{:016x},{:016x}",
+                london.latitude.to_bits(),
+                london.longitude.to_bits()
+            ))
+        )));
+        assert!(with_duha.contains(&format!(
+            "DTSTART;TZID=Europe/London:{}\r\nDTEND;TZID=Europe/London:{}\r\nSUMMARY:Duha\r\n",
+            duha.start.format("%Y%m%dT%H%M%S"),
+            duha.end.format("%Y%m%dT%H%M%S")
+        )));
+    }
+
+    #[test]
+    fn empty_calendar() {
+        assert_eq!(
+            calendar(&[], &Zone::London, "Europe/London"),
+            "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//ibad-al-rahman//miqat//EN\r\nCALSCALE:GREGORIAN\r\nEND:VCALENDAR\r\n"
+        );
+    }
+}
@@ -0,0 +1,285 @@
+//! # Iqamah Times
+//!
+//! When each congregational prayer starts, next to its adhan. Mosques set
+//! the iqamah per prayer: at a fixed clock time, a set delay after the
+//! adhan, or that delay rounded to the nearest quarter hour so that the
+//! timetable stays readable. On Fridays, Dhuhr can follow its own rule,
+//! the start of the khutbah.
+//!
+//! ##### Example
+//!
+//! ```
+//! use miqat::iqamah::{IqamahConfig, IqamahRule};
+//! use miqat::prelude::*;
+//!
+//! let date = NaiveDate::from_ymd_opt(2024, 3, 13).unwrap();
+//! let beirut = Coordinates::new(33.8938, 35.5018);
+//! let times = PrayerTimes::computed(date, beirut, Method::MuslimWorldLeague.parameters());
+//! let eet = chrono::FixedOffset::east_opt(2 * 3600).unwrap();
+//! let config = IqamahConfig::new(IqamahRule::After(Duration::minutes(10)))
+//!     .with(Prayer::Fajr, IqamahRule::Rounded(Duration::minutes(20)))
+//!     .with(Prayer::Dhuhr, IqamahRule::Fixed(chrono::NaiveTime::from_hms_opt(12, 30, 0).unwrap()));
+//! let iqamah = |prayer| times.iqamah(prayer, &config, eet).unwrap().with_timezone(&eet);
+//!
+//! assert_eq!(times.time(Prayer::Fajr).with_timezone(&eet).to_string(), "2024-03-13 04:28:00 +02:00");
+//! assert_eq!(iqamah(Prayer::Fajr).to_string(), "2024-03-13 04:45:00 +02:00");
+//! assert_eq!(iqamah(Prayer::Dhuhr).to_string(), "2024-03-13 12:30:00 +02:00");
+//! assert_eq!(times.iqamah(Prayer::Sunrise, &config, eet), None);
+//! ```
+
+use crate::models::prayer::Prayer;
+use crate::prayer_times::PrayerTimes;
+use chrono::DateTime;
+use chrono::Datelike;
+use chrono::Duration;
+use chrono::FixedOffset;
+use chrono::NaiveTime;
+use chrono::Utc;
+use chrono::Weekday;
+
+const QUARTER_HOUR: i64 = 15 * 60;
+
+/// How the iqamah of a prayer is set.
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub enum IqamahRule {
+    /// At a local clock time, or at the adhan if that is later.
+    Fixed(NaiveTime),
+    /// The given delay after the adhan.
+    After(Duration),
+    /// The given delay after the adhan, rounded to the nearest quarter
+    /// hour of local time, and never before the adhan.
+    Rounded(Duration),
+}
+
+impl IqamahRule {
+    /// The iqamah for a prayer whose adhan is at `adhan` on `times`' date,
+    /// with clock times read at `offset`.
+    fn apply(
+        &self,
+        adhan: DateTime<Utc>,
+        times: &PrayerTimes,
+        offset: FixedOffset,
+    ) -> Option<DateTime<Utc>> {
+        match self {
+            IqamahRule::Fixed(time) => {
+                let fixed = times
+                    .date()
+                    .and_time(*time)
+                    .and_local_timezone(offset)
+                    .single()?
+                    .to_utc();
+
+                Some(fixed.max(adhan))
+            }
+            IqamahRule::After(delay) => adhan.checked_add_signed(*delay),
+            IqamahRule::Rounded(delay) => {
+                let shift = i64::from(offset.local_minus_utc());
+                let local = adhan.checked_add_signed(*delay)?.timestamp() + shift;
+                let rounded = (local + QUARTER_HOUR / 2).div_euclid(QUARTER_HOUR) * QUARTER_HOUR;
+                let iqamah = DateTime::from_timestamp(rounded - shift, 0)?;
+
+                if iqamah < adhan {
+                    iqamah.checked_add_signed(Duration::seconds(QUARTER_HOUR))
+                } else {
+                    Some(iqamah)
+                }
+            }
+        }
+    }
+}
+
+/// The iqamah rule of each of the five prayers, and optionally of Jumua.
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct IqamahConfig {
+    fajr: IqamahRule,
+    dhuhr: IqamahRule,
+    asr: IqamahRule,
+    maghrib: IqamahRule,
+    ishaa: IqamahRule,
+    jumua: Option<IqamahRule>,
+}
+
+impl IqamahConfig {
+    /// The same rule for every prayer.
+    pub fn new(rule: IqamahRule) -> Self {
+        IqamahConfig {
+            fajr: rule,
+            dhuhr: rule,
+            asr: rule,
+            maghrib: rule,
+            ishaa: rule,
+            jumua: None,
+        }
+    }
+
+    /// The config with `rule` for `prayer`. Sunrise has no iqamah and is
+    /// ignored; Fajr of the next day shares Fajr's rule.
+    pub fn with(mut self, prayer: Prayer, rule: IqamahRule) -> Self {
+        match prayer {
+            Prayer::Fajr | Prayer::FajrTomorrow => self.fajr = rule,
+            Prayer::Dhuhr => self.dhuhr = rule,
+            Prayer::Asr => self.asr = rule,
+            Prayer::Maghrib => self.maghrib = rule,
+            Prayer::Ishaa => self.ishaa = rule,
+            Prayer::Sunrise => {
+                // Nothing to do.
+            }
+        }
+
+        self
+    }
+
+    /// The config with `rule` for Dhuhr on Fridays, applied to Friday's
+    /// Dhuhr adhan to give the start of the khutbah.
+    pub fn jumua(mut self, rule: IqamahRule) -> Self {
+        self.jumua = Some(rule);
+        self
+    }
+
+    /// The rule for `prayer` on `times`' date, or `None` for Sunrise.
+    pub fn rule(&self, prayer: Prayer, times: &PrayerTimes) -> Option<IqamahRule> {
+        match prayer {
+            Prayer::Fajr | Prayer::FajrTomorrow => Some(self.fajr),
+            Prayer::Sunrise => None,
+            Prayer::Dhuhr if times.date().weekday() == Weekday::Fri => {
+                Some(self.jumua.unwrap_or(self.dhuhr))
+            }
+            Prayer::Dhuhr => Some(self.dhuhr),
+            Prayer::Asr => Some(self.asr),
+            Prayer::Maghrib => Some(self.maghrib),
+            Prayer::Ishaa => Some(self.ishaa),
+        }
+    }
+
+    /// The iqamah of `prayer` on `times`' date, with fixed clock times and
+    /// rounding read at `offset`, or `None` for Sunrise or if it falls
+    /// outside the supported range of dates.
+    pub fn time(
+        &self,
+        times: &PrayerTimes,
+        prayer: Prayer,
+        offset: FixedOffset,
+    ) -> Option<DateTime<Utc>> {
+        let rule = self.rule(prayer, times)?;
+
+        match prayer {
+            Prayer::FajrTomorrow => {
+                let tomorrow = times.tomorrow().ok()?;
+
+                rule.apply(tomorrow.time(Prayer::Fajr), &tomorrow, offset)
+            }
+            _ => rule.apply(times.time(prayer), times, offset),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Coordinates;
+    use crate::Method;
+    use chrono::NaiveDate;
+    use chrono::Timelike;
+
+    fn beirut(day: u32) -> PrayerTimes {
+        let date = NaiveDate::from_ymd_opt(2024, 3, day).unwrap();
+        let beirut = Coordinates::new(33.8938, 35.5018);
+
+        PrayerTimes::computed(date, beirut, Method::MuslimWorldLeague.parameters())
+    }
+
+    fn eet() -> FixedOffset {
+        FixedOffset::east_opt(2 * 3600).unwrap()
+    }
+
+    #[test]
+    fn delays_follow_the_adhan() {
+        let times = beirut(13);
+        let config = IqamahConfig::new(IqamahRule::After(Duration::minutes(10)))
+            .with(Prayer::Maghrib, IqamahRule::After(Duration::minutes(5)));
+
+        for prayer in [Prayer::Fajr, Prayer::Dhuhr, Prayer::Asr, Prayer::Ishaa] {
+            assert_eq!(
+                config.time(&times, prayer, eet()),
+                Some(times.time(prayer) + Duration::minutes(10))
+            );
+        }
+        assert_eq!(
+            config.time(&times, Prayer::Maghrib, eet()),
+            Some(times.time(Prayer::Maghrib) + Duration::minutes(5))
+        );
+        assert_eq!(config.time(&times, Prayer::Sunrise, eet()), None);
+    }
+
+    #[test]
+    fn rounding_lands_on_quarter_hours_after_the_adhan() {
+        let nepal = FixedOffset::east_opt(5 * 3600 + 45 * 60).unwrap();
+        let config = IqamahConfig::new(IqamahRule::Rounded(Duration::zero()));
+
+        for day in 1..=31 {
+            let times = beirut(day);
+
+            for prayer in [Prayer::Fajr, Prayer::Dhuhr, Prayer::Asr, Prayer::Ishaa] {
+                for offset in [eet(), nepal] {
+                    let iqamah = config.time(&times, prayer, offset).unwrap();
+                    let local = iqamah.with_timezone(&offset);
+
+                    assert!(iqamah >= times.time(prayer));
+                    assert!(iqamah - times.time(prayer) < Duration::minutes(15));
+                    assert_eq!(local.minute() % 15, 0);
+                    assert_eq!(local.second(), 0);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn fixed_times_wait_for_the_adhan() {
+        let times = beirut(13);
+        let eleven = NaiveTime::from_hms_opt(11, 0, 0).unwrap();
+        let one = NaiveTime::from_hms_opt(13, 0, 0).unwrap();
+        let early = IqamahConfig::new(IqamahRule::Fixed(eleven));
+        let late = IqamahConfig::new(IqamahRule::Fixed(one));
+
+        assert_eq!(
+            early.time(&times, Prayer::Dhuhr, eet()),
+            Some(times.time(Prayer::Dhuhr))
+        );
+        assert_eq!(
+            late.time(&times, Prayer::Dhuhr, eet()),
+            Some(times.date().and_time(one).and_utc() - Duration::hours(2))
+        );
+    }
+
+    #[test]
+    fn jumua_has_its_own_rule_on_fridays() {
+        let config = IqamahConfig::new(IqamahRule::After(Duration::minutes(10)))
+            .jumua(IqamahRule::After(Duration::minutes(30)));
+        let thursday = beirut(14);
+        let friday = beirut(15);
+
+        assert_eq!(
+            config.time(&thursday, Prayer::Dhuhr, eet()),
+            Some(thursday.time(Prayer::Dhuhr) + Duration::minutes(10))
+        );
+        assert_eq!(
+            config.time(&friday, Prayer::Dhuhr, eet()),
+            Some(friday.time(Prayer::Dhuhr) + Duration::minutes(30))
+        );
+        assert_eq!(
+            config.time(&friday, Prayer::Asr, eet()),
+            Some(friday.time(Prayer::Asr) + Duration::minutes(10))
+        );
+    }
+
+    #[test]
+    fn fajr_tomorrow_follows_tomorrows_fajr() {
+        let config =
+            IqamahConfig::new(IqamahRule::Fixed(NaiveTime::from_hms_opt(5, 0, 0).unwrap()));
+
+        assert_eq!(
+            config.time(&beirut(13), Prayer::FajrTomorrow, eet()),
+            config.time(&beirut(14), Prayer::Fajr, eet())
+        );
+    }
+}
@@ -9,30 +9,93 @@
 //!
 //! let new_york_city = Coordinates::new(40.7128, -74.0059);
 //! let date          = NaiveDate::from_ymd_opt(2019, 1, 25).expect("Invalid date provided");
-//! let params        = Configuration::with(Method::NorthAmerica, Mazhab::Hanafi);
-//! let prayers       = PrayerSchedule::new()
-//!                       .on(date)
-//!                       .for_location(new_york_city)
-//!                       .with_configuration(params)
-//!                       .calculate();
+//! let params        = Method::NorthAmerica.parameters().mazhab(Mazhab::Hanafi);
+//! let prayers       = PrayerTimes::computed(date, new_york_city, params);
 //! ```
 
+#[cfg(feature = "serde")]
+pub mod aladhan;
 mod astronomy;
+pub mod batch;
+pub mod cache;
+pub mod changelog;
+pub mod clock;
+pub mod comparison;
+#[cfg(feature = "countries")]
+pub mod countries;
+mod error;
+pub mod extremes;
+pub mod format;
+pub mod geocoder;
+pub mod geojson;
 pub mod hijri;
+pub mod ics;
+pub mod iqamah;
+pub mod live;
+pub mod locale;
+#[cfg(feature = "metadata")]
+pub mod metadata;
 mod models;
+pub mod month;
+#[cfg(feature = "mqtt")]
+pub mod mqtt;
+#[cfg(feature = "parser")]
+pub mod parser;
 mod prayer_times;
 pub mod precomputed;
+#[cfg(feature = "qada")]
+pub mod qada;
+pub mod ramadan;
+pub mod scheduler;
+pub mod stats;
+pub mod sunnah_times;
+pub mod ticker;
+pub mod time_ext;
+pub mod travel;
+pub mod week;
+pub mod widget;
 
+pub use crate::astronomy::julian;
+pub use crate::astronomy::seasonal;
+pub use crate::astronomy::solar::SolarPosition;
+pub use crate::astronomy::solar::SolarTime;
+pub use crate::astronomy::solar::clear_thread_caches;
+pub use crate::astronomy::twilight;
+pub use crate::astronomy::unit::Angle;
 pub use crate::astronomy::unit::Coordinates;
 pub use crate::astronomy::unit::Stride;
+pub use crate::changelog::CALC_VERSION;
+pub use crate::changelog::calculation_changelog;
+pub use crate::error::CalculationError;
 pub use crate::hijri::HijriDate;
 pub use crate::hijri::IslamicEvent;
+pub use crate::models::adjustments::EmptyRangeError;
+pub use crate::models::adjustments::ParseAdjustmentError;
 pub use crate::models::adjustments::TimeAdjustment;
+pub use crate::models::asr_factor::AsrFactor;
+pub use crate::models::day_boundary::DayBoundary;
+pub use crate::models::delta_t::DeltaT;
+pub use crate::models::high_altitude_rule::HighLatitudeRule;
+pub use crate::models::ishaa_interval_start::IshaaIntervalStart;
+pub use crate::models::maghrib_parameter::MaghribParameter;
 pub use crate::models::mazhab::Mazhab;
 pub use crate::models::method::Method;
+pub use crate::models::method_behavior::MethodBehavior;
+pub use crate::models::method_version::MethodVersion;
+pub use crate::models::next_day_fajr::NextDayFajr;
+pub use crate::models::parameter_issue::ParameterIssue;
 pub use crate::models::parameters::Parameters;
+pub use crate::models::parameters_view::ParametersView;
 pub use crate::models::prayer::Prayer;
+pub use crate::models::rounding::Rounding;
+pub use crate::models::time_source::{TimeSource, TimedValue};
+pub use crate::models::twilight::Twilight;
+pub use crate::models::warning::{Warning, Warnings};
+pub use crate::models::window::Window;
+pub use crate::prayer_times::LocalizedPrayerTimes;
+pub use crate::prayer_times::PartialPrayerTimes;
 pub use crate::prayer_times::PrayerTimes;
+pub use crate::prayer_times::PrayerVariants;
 pub use crate::precomputed::provider::{Provider, ProviderCity};
 pub use chrono::DateTime;
 pub use chrono::Datelike;
@@ -42,13 +105,19 @@ pub use chrono::NaiveDate;
 pub use chrono::TimeZone;
 pub use chrono::Timelike;
 pub use chrono::Utc;
+#[cfg(feature = "timezones")]
+pub use chrono_tz::Tz;
 
 /// A convenience module appropriate for glob imports (`use miqat::prelude::*;`).
 pub mod prelude {
     #[doc(no_inline)]
     pub use crate::astronomy::qiblah::Qiblah;
     #[doc(no_inline)]
-    pub use crate::astronomy::unit::{Coordinates, Stride};
+    pub use crate::astronomy::solar::{SolarPosition, SolarTime};
+    #[doc(no_inline)]
+    pub use crate::astronomy::unit::{Angle, Coordinates, Stride};
+    #[doc(no_inline)]
+    pub use crate::error::CalculationError;
     #[doc(no_inline)]
     pub use crate::hijri::HijriDate;
     #[doc(no_inline)]
@@ -58,25 +127,60 @@ pub mod prelude {
     #[doc(no_inline)]
     pub use crate::models::adjustments::TimeAdjustment;
     #[doc(no_inline)]
+    pub use crate::models::asr_factor::AsrFactor;
+    #[doc(no_inline)]
+    pub use crate::models::day_boundary::DayBoundary;
+    #[doc(no_inline)]
+    pub use crate::models::delta_t::DeltaT;
+    #[doc(no_inline)]
+    pub use crate::models::high_altitude_rule::HighLatitudeRule;
+    #[doc(no_inline)]
+    pub use crate::models::ishaa_interval_start::IshaaIntervalStart;
+    #[doc(no_inline)]
+    pub use crate::models::maghrib_parameter::MaghribParameter;
+    #[doc(no_inline)]
     pub use crate::models::mazhab::Mazhab;
     #[doc(no_inline)]
     pub use crate::models::method::Method;
     #[doc(no_inline)]
+    pub use crate::models::method_behavior::MethodBehavior;
+    #[doc(no_inline)]
+    pub use crate::models::method_version::MethodVersion;
+    #[doc(no_inline)]
+    pub use crate::models::next_day_fajr::NextDayFajr;
+    #[doc(no_inline)]
+    pub use crate::models::parameter_issue::ParameterIssue;
+    #[doc(no_inline)]
     pub use crate::models::parameters::Parameters;
     #[doc(no_inline)]
+    pub use crate::models::parameters_view::ParametersView;
+    #[doc(no_inline)]
     pub use crate::models::prayer::Prayer;
     #[doc(no_inline)]
-    pub use crate::prayer_times::PrayerTimes;
+    pub use crate::models::rounding::Rounding;
+    #[doc(no_inline)]
+    pub use crate::models::time_source::{TimeSource, TimedValue};
+    #[doc(no_inline)]
+    pub use crate::models::twilight::Twilight;
+    #[doc(no_inline)]
+    pub use crate::models::warning::{Warning, Warnings};
+    #[doc(no_inline)]
+    pub use crate::models::window::Window;
+    #[doc(no_inline)]
+    pub use crate::prayer_times::{
+        LocalizedPrayerTimes, PartialPrayerTimes, PrayerTimes, PrayerVariants,
+    };
     #[doc(no_inline)]
     pub use crate::precomputed::provider::{Provider, ProviderCity};
     #[doc(no_inline)]
+    pub use crate::time_ext::DateTimeExt;
+    #[doc(no_inline)]
     pub use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, TimeZone, Timelike, Utc};
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::models::high_altitude_rule::HighLatitudeRule;
     use chrono::prelude::*;
 
     #[test]
@@ -0,0 +1,276 @@
+//! # Live Schedule
+//!
+//! A schedule for long-running services and clocks that always reflects
+//! the current day, which runs from one Fajr to the next. The day's prayer
+//! times are recomputed lazily the first time they are needed after the
+//! next Fajr, and the schedule can be shared between threads behind an
+//! [`Arc`](std::sync::Arc).
+//!
+//! ##### Example
+//!
+//! ```
+//! use miqat::clock::FixedClock;
+//! use miqat::live::LiveSchedule;
+//! use miqat::prelude::*;
+//!
+//! let raleigh = Coordinates::new(35.7750, -78.6336);
+//! let params = Method::NorthAmerica.parameters();
+//! let clock = FixedClock(Utc.with_ymd_and_hms(2015, 7, 12, 18, 0, 0).unwrap());
+//! let schedule = LiveSchedule::with_clock(raleigh, params, clock);
+//!
+//! assert_eq!(schedule.current(), Ok(Prayer::Dhuhr));
+//! assert_eq!(schedule.next(), Ok(Prayer::Asr));
+//! assert_eq!(schedule.until_next(), Ok(Duration::seconds(11_340)));
+//! ```
+
+use crate::astronomy::unit::Coordinates;
+use crate::clock::Clock;
+use crate::clock::SystemClock;
+use crate::error::CalculationError;
+use crate::models::parameters::Parameters;
+use crate::models::prayer::Prayer;
+use crate::prayer_times::PrayerTimes;
+use chrono::DateTime;
+use chrono::Duration;
+use chrono::Utc;
+use std::sync::Mutex;
+
+/// Prayer times for a location that follow the clock from one day to
+/// the next.
+#[derive(Debug)]
+pub struct LiveSchedule<C: Clock = SystemClock> {
+    coordinates: Coordinates,
+    parameters: Parameters,
+    clock: C,
+    today: Mutex<Option<PrayerTimes>>,
+}
+
+impl LiveSchedule<SystemClock> {
+    pub fn new(coordinates: Coordinates, parameters: Parameters) -> Self {
+        LiveSchedule::with_clock(coordinates, parameters, SystemClock)
+    }
+}
+
+impl<C: Clock> LiveSchedule<C> {
+    pub fn with_clock(coordinates: Coordinates, parameters: Parameters, clock: C) -> Self {
+        LiveSchedule {
+            coordinates,
+            parameters,
+            clock,
+            today: Mutex::new(None),
+        }
+    }
+
+    /// The schedule the current instant falls in, from its Fajr to the
+    /// next one, computed if the clock moved past it since it was last
+    /// needed.
+    pub fn today(&self) -> Result<PrayerTimes, CalculationError> {
+        self.times_at(self.clock.now())
+    }
+
+    /// The prayer whose time it currently is. Before Fajr, this is the
+    /// previous night's Ishaa.
+    pub fn current(&self) -> Result<Prayer, CalculationError> {
+        Ok(self.state()?.0)
+    }
+
+    /// The prayer that comes next.
+    pub fn next(&self) -> Result<Prayer, CalculationError> {
+        Ok(self.state()?.1)
+    }
+
+    /// The time left until the next prayer.
+    pub fn until_next(&self) -> Result<Duration, CalculationError> {
+        let (_, _, next_time, now) = self.state()?;
+        Ok(next_time.signed_duration_since(now))
+    }
+
+    /// The current prayer, the next one and its time, along with the
+    /// instant they were determined at, so that every value is read from
+    /// a single tick of the clock.
+    fn state(&self) -> Result<(Prayer, Prayer, DateTime<Utc>, DateTime<Utc>), CalculationError> {
+        let now = self.clock.now();
+        let times = self.times_at(now)?;
+
+        Ok(match times.prayer_at(now) {
+            Some((current, _, end)) => (current, PrayerTimes::following(current), end, now),
+            // Only between two schedules, when the next Fajr is estimated
+            // earlier than it turns out to be.
+            None => (Prayer::Ishaa, Prayer::Fajr, times.time(Prayer::Fajr), now),
+        })
+    }
+
+    // The day boundary doesn't line up with Fajr: just after midnight,
+    // the previous day's Ishaa hasn't ended yet, and with a local day a
+    // few hours off UTC, the following day's Fajr may have started. The
+    // schedule is looked for among the days around the date of `now`.
+    fn times_at(&self, now: DateTime<Utc>) -> Result<PrayerTimes, CalculationError> {
+        let mut cached = self.today.lock().unwrap_or_else(|e| e.into_inner());
+
+        match &*cached {
+            Some(times) if times.prayer_at(now).is_some() => Ok(times.clone()),
+            _ => {
+                let date = self.parameters.day_boundary.date_of(now);
+                let computed =
+                    |date| PrayerTimes::try_computed(date, self.coordinates, self.parameters);
+                let times = computed(date)?;
+                let adjacent = if now < times.time(Prayer::Fajr) {
+                    date.pred_opt()
+                } else if times.prayer_at(now).is_none() {
+                    date.succ_opt()
+                } else {
+                    None
+                };
+                let times = match adjacent {
+                    Some(adjacent) => {
+                        let other = computed(adjacent)?;
+                        // Between the two schedules, the later one is
+                        // waiting for its Fajr.
+                        if other.prayer_at(now).is_some() || adjacent > date {
+                            other
+                        } else {
+                            times
+                        }
+                    }
+                    None => times,
+                };
+
+                *cached = Some(times.clone());
+                Ok(times)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Method;
+    use crate::clock::FixedClock;
+    use crate::models::day_boundary::DayBoundary;
+    use chrono::FixedOffset;
+    use chrono::NaiveDate;
+    use chrono::TimeZone;
+    use std::sync::Arc;
+    use std::thread;
+
+    struct ManualClock(Mutex<DateTime<Utc>>);
+
+    impl ManualClock {
+        fn advance(&self, duration: Duration) {
+            *self.0.lock().unwrap() += duration;
+        }
+    }
+
+    impl Clock for Arc<ManualClock> {
+        fn now(&self) -> DateTime<Utc> {
+            *self.0.lock().unwrap()
+        }
+    }
+
+    #[test]
+    fn recomputes_once_past_the_next_fajr() {
+        let clock = Arc::new(ManualClock(Mutex::new(
+            Utc.with_ymd_and_hms(2015, 7, 12, 23, 0, 0).unwrap(),
+        )));
+        let mut params = Method::NorthAmerica.parameters();
+        params.day_boundary = DayBoundary::LocalCivil(FixedOffset::west_opt(4 * 3600).unwrap());
+        let schedule =
+            LiveSchedule::with_clock(Coordinates::new(35.7750, -78.6336), params, clock.clone());
+
+        assert_eq!(
+            schedule.today().unwrap().date(),
+            NaiveDate::from_ymd_opt(2015, 7, 12).unwrap()
+        );
+        assert_eq!(schedule.current(), Ok(Prayer::Asr));
+
+        // Past midnight, the night still belongs to the schedule of the
+        // 12th until the Fajr of the 13th.
+        clock.advance(Duration::hours(6));
+        let today = schedule.today().unwrap();
+        assert_eq!(today.date(), NaiveDate::from_ymd_opt(2015, 7, 12).unwrap());
+        assert_eq!(schedule.current(), Ok(Prayer::Ishaa));
+        assert_eq!(schedule.next(), Ok(Prayer::FajrTomorrow));
+        assert_eq!(
+            schedule.until_next(),
+            Ok(today.time(Prayer::FajrTomorrow) - clock.now())
+        );
+
+        clock.advance(Duration::hours(5));
+        assert_eq!(
+            schedule.today().unwrap().date(),
+            NaiveDate::from_ymd_opt(2015, 7, 13).unwrap()
+        );
+        assert_eq!(schedule.current(), Ok(Prayer::Fajr));
+        assert_eq!(schedule.next(), Ok(Prayer::Sunrise));
+    }
+
+    #[test]
+    fn evening_past_midnight_utc_belongs_to_the_previous_day() {
+        // 20:40 in Raleigh, after Maghrib on the 12th, but already the
+        // 13th in UTC.
+        let now = Utc.with_ymd_and_hms(2015, 7, 13, 0, 40, 0).unwrap();
+        let raleigh = Coordinates::new(35.7750, -78.6336);
+        let schedule =
+            LiveSchedule::with_clock(raleigh, Method::NorthAmerica.parameters(), FixedClock(now));
+
+        assert_eq!(
+            schedule.today().unwrap().date(),
+            NaiveDate::from_ymd_opt(2015, 7, 12).unwrap()
+        );
+        assert_eq!(schedule.current(), Ok(Prayer::Maghrib));
+        assert_eq!(schedule.next(), Ok(Prayer::Ishaa));
+    }
+
+    #[test]
+    fn fajr_before_midnight_utc_belongs_to_the_next_day() {
+        // 02:40 in Tokyo on the 11th, after its Fajr, but still the 10th
+        // in UTC.
+        let now = Utc.with_ymd_and_hms(2024, 6, 10, 17, 40, 0).unwrap();
+        let tokyo = Coordinates::new(35.6762, 139.6503);
+        let schedule = LiveSchedule::with_clock(
+            tokyo,
+            Method::MuslimWorldLeague.parameters(),
+            FixedClock(now),
+        );
+        let today = schedule.today().unwrap();
+
+        assert_eq!(today.date(), NaiveDate::from_ymd_opt(2024, 6, 11).unwrap());
+        assert!(today.time(Prayer::Fajr) <= now);
+        assert_eq!(schedule.current(), Ok(Prayer::Fajr));
+        assert_eq!(schedule.next(), Ok(Prayer::Sunrise));
+    }
+
+    #[test]
+    fn errors_are_surfaced() {
+        let now = Utc.with_ymd_and_hms(2024, 6, 21, 12, 0, 0).unwrap();
+        let svalbard = Coordinates::new(78.2232, 15.6267);
+        let params = Method::MuslimWorldLeague.parameters();
+        let schedule = LiveSchedule::with_clock(svalbard, params, FixedClock(now));
+
+        assert!(matches!(
+            schedule.current(),
+            Err(CalculationError::NoSunriseOrSunset(_))
+        ));
+    }
+
+    #[test]
+    fn can_be_shared_between_threads() {
+        let raleigh = Coordinates::new(35.7750, -78.6336);
+        let schedule = Arc::new(LiveSchedule::new(
+            raleigh,
+            Method::NorthAmerica.parameters(),
+        ));
+
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let schedule = Arc::clone(&schedule);
+                thread::spawn(move || schedule.today().unwrap().date())
+            })
+            .collect();
+
+        for handle in handles {
+            assert_eq!(handle.join().unwrap(), schedule.today().unwrap().date());
+        }
+    }
+}
@@ -0,0 +1,307 @@
+//! # Localized Dates
+//!
+//! The names of the Hijri months and of the weekdays, so that exports
+//! and the command line write fully localized Hijri dates. A
+//! [`Localizer`] provides the names; [`Locale`] has them in Arabic,
+//! English, Turkish, Urdu and Indonesian, and other languages can be
+//! plugged in by implementing the trait.
+//!
+//! Hijri dates are written with a strftime-like format where `%A` is the
+//! weekday, `%B` the name of the month, `%d` and `%m` the day and the
+//! month on two digits, `%-d` and `%-m` without padding, `%Y` the year
+//! and `%%` a percent sign. Anything else is written as is.
+//!
+//! ##### Example
+//!
+//! ```
+//! use miqat::locale::{Locale, Localizer};
+//! use miqat::prelude::*;
+//!
+//! let date = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+//!
+//! assert_eq!(Locale::English.hijri_date(date, "%A %-d %B %Y"), "Friday 5 Ramadan 1445");
+//! assert_eq!(Locale::Turkish.hijri_date(date, "%-d %B %Y"), "5 Ramazan 1445");
+//! assert_eq!(Locale::Arabic.hijri_date(date, "%-d %B %Y"), "٥ رمضان ١٤٤٥");
+//! ```
+
+use crate::format::Digits;
+use crate::format::Language;
+use crate::hijri::HijriDate;
+use chrono::Datelike;
+use chrono::NaiveDate;
+
+/// The default format of Hijri dates, e.g. "5 Ramadan 1445".
+pub const DEFAULT_HIJRI_FORMAT: &str = "%-d %B %Y";
+
+/// Provides the names dates are written with.
+pub trait Localizer {
+    /// The names of the Hijri months, from Muharram to Dhu al-Hijjah.
+    fn hijri_months(&self) -> [&str; 12];
+
+    /// The names of the weekdays, from Monday to Sunday.
+    fn weekdays(&self) -> [&str; 7];
+
+    /// The digits numbers are written with.
+    fn digits(&self) -> Digits {
+        Digits::Western
+    }
+
+    /// The Hijri date of `date` written with `format`, see the
+    /// [module](self) documentation.
+    fn hijri_date(&self, date: NaiveDate, format: &str) -> String {
+        let hijri = HijriDate::from_gregorian(date);
+        let mut text = String::new();
+        let mut chars = format.chars();
+
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                text.push(c);
+                continue;
+            } else {
+                // Nothing to do.
+            }
+
+            let rest = chars.as_str();
+            let specifier = match rest.get(..2) {
+                Some(unpadded @ ("-d" | "-m")) => unpadded,
+                _ => rest.get(..1).unwrap_or(""),
+            };
+
+            match specifier {
+                "A" => {
+                    text.push_str(self.weekdays()[date.weekday().num_days_from_monday() as usize])
+                }
+                "B" => text.push_str(
+                    self.hijri_months()
+                        .get(usize::from(hijri.month).wrapping_sub(1))
+                        .unwrap_or(&""),
+                ),
+                "d" => text.push_str(&format!("{:02}", hijri.day)),
+                "-d" => text.push_str(&hijri.day.to_string()),
+                "m" => text.push_str(&format!("{:02}", hijri.month)),
+                "-m" => text.push_str(&hijri.month.to_string()),
+                "Y" => text.push_str(&hijri.year.to_string()),
+                "%" => text.push('%'),
+                other => {
+                    text.push('%');
+                    text.push_str(other);
+                }
+            }
+
+            chars = rest[specifier.len()..].chars();
+        }
+
+        self.digits().apply(&text)
+    }
+}
+
+/// The built-in languages.
+#[derive(PartialEq, Debug, Copy, Clone, Default)]
+pub enum Locale {
+    Arabic,
+    #[default]
+    English,
+    Indonesian,
+    Turkish,
+    Urdu,
+}
+
+impl Locale {
+    /// The locale of a language tag such as `ar`, `tr-TR` or the
+    /// `LANG` value `ur_PK.UTF-8`, by its language subtag.
+    pub fn from_language_tag(tag: &str) -> Option<Locale> {
+        let language = tag.split(['-', '_', '.']).next()?;
+
+        match language.to_ascii_lowercase().as_str() {
+            "ar" => Some(Locale::Arabic),
+            "en" => Some(Locale::English),
+            "id" => Some(Locale::Indonesian),
+            "tr" => Some(Locale::Turkish),
+            "ur" => Some(Locale::Urdu),
+            _ => None,
+        }
+    }
+}
+
+impl From<Language> for Locale {
+    fn from(language: Language) -> Self {
+        match language {
+            Language::English => Locale::English,
+            Language::Arabic => Locale::Arabic,
+        }
+    }
+}
+
+impl Localizer for Locale {
+    fn hijri_months(&self) -> [&str; 12] {
+        match self {
+            Locale::Arabic => [
+                "محرم",
+                "صفر",
+                "ربيع الأول",
+                "ربيع الآخر",
+                "جمادى الأولى",
+                "جمادى الآخرة",
+                "رجب",
+                "شعبان",
+                "رمضان",
+                "شوال",
+                "ذو القعدة",
+                "ذو الحجة",
+            ],
+            Locale::English => [
+                "Muharram",
+                "Safar",
+                "Rabi al-Awwal",
+                "Rabi al-Thani",
+                "Jumada al-Ula",
+                "Jumada al-Akhira",
+                "Rajab",
+                "Shaban",
+                "Ramadan",
+                "Shawwal",
+                "Dhu al-Qada",
+                "Dhu al-Hijja",
+            ],
+            Locale::Indonesian => [
+                "Muharram",
+                "Safar",
+                "Rabiulawal",
+                "Rabiulakhir",
+                "Jumadilawal",
+                "Jumadilakhir",
+                "Rajab",
+                "Syakban",
+                "Ramadan",
+                "Syawal",
+                "Zulkaidah",
+                "Zulhijah",
+            ],
+            Locale::Turkish => [
+                "Muharrem",
+                "Safer",
+                "Rebiülevvel",
+                "Rebiülahir",
+                "Cemaziyelevvel",
+                "Cemaziyelahir",
+                "Recep",
+                "Şaban",
+                "Ramazan",
+                "Şevval",
+                "Zilkade",
+                "Zilhicce",
+            ],
+            Locale::Urdu => [
+                "محرم",
+                "صفر",
+                "ربیع الاول",
+                "ربیع الثانی",
+                "جمادی الاول",
+                "جمادی الثانی",
+                "رجب",
+                "شعبان",
+                "رمضان",
+                "شوال",
+                "ذوالقعدہ",
+                "ذوالحجہ",
+            ],
+        }
+    }
+
+    fn weekdays(&self) -> [&str; 7] {
+        match self {
+            Locale::Arabic => [
+                "الاثنين",
+                "الثلاثاء",
+                "الأربعاء",
+                "الخميس",
+                "الجمعة",
+                "السبت",
+                "الأحد",
+            ],
+            Locale::English => [
+                "Monday",
+                "Tuesday",
+                "Wednesday",
+                "Thursday",
+                "Friday",
+                "Saturday",
+                "Sunday",
+            ],
+            Locale::Indonesian => [
+                "Senin", "Selasa", "Rabu", "Kamis", "Jumat", "Sabtu", "Minggu",
+            ],
+            Locale::Turkish => [
+                "Pazartesi",
+                "Salı",
+                "Çarşamba",
+                "Perşembe",
+                "Cuma",
+                "Cumartesi",
+                "Pazar",
+            ],
+            Locale::Urdu => ["پیر", "منگل", "بدھ", "جمعرات", "جمعہ", "ہفتہ", "اتوار"],
+        }
+    }
+
+    fn digits(&self) -> Digits {
+        match self {
+            Locale::Arabic => Digits::ArabicIndic,
+            _ => Digits::Western,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_locale_writes_the_same_date() {
+        let date = NaiveDate::from_ymd_opt(2025, 3, 30).unwrap();
+        let written = |locale: Locale| locale.hijri_date(date, "%A %d/%m/%Y %B");
+
+        assert_eq!(written(Locale::English), "Sunday 01/10/1446 Shawwal");
+        assert_eq!(written(Locale::Indonesian), "Minggu 01/10/1446 Syawal");
+        assert_eq!(written(Locale::Turkish), "Pazar 01/10/1446 Şevval");
+        assert_eq!(written(Locale::Urdu), "اتوار 01/10/1446 شوال");
+        assert_eq!(written(Locale::Arabic), "الأحد ٠١/١٠/١٤٤٦ شوال");
+    }
+
+    #[test]
+    fn unknown_specifiers_are_written_as_is() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+
+        assert_eq!(
+            Locale::English.hijri_date(date, "%-m %H 100%% %"),
+            "9 %H 100% %"
+        );
+    }
+
+    #[test]
+    fn custom_localizers() {
+        struct Shouting;
+
+        impl Localizer for Shouting {
+            fn hijri_months(&self) -> [&str; 12] {
+                Locale::English.hijri_months().map(|_| "MONTH")
+            }
+
+            fn weekdays(&self) -> [&str; 7] {
+                ["DAY"; 7]
+            }
+        }
+
+        let date = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+        assert_eq!(Shouting.hijri_date(date, "%A %B"), "DAY MONTH");
+    }
+
+    #[test]
+    fn locales_from_language_tags() {
+        assert_eq!(Locale::from_language_tag("ur_PK.UTF-8"), Some(Locale::Urdu));
+        assert_eq!(Locale::from_language_tag("TR-tr"), Some(Locale::Turkish));
+        assert_eq!(Locale::from_language_tag("id"), Some(Locale::Indonesian));
+        assert_eq!(Locale::from_language_tag("C"), None);
+        assert_eq!(Locale::from(Language::Arabic), Locale::Arabic);
+    }
+}
@@ -0,0 +1,166 @@
+//! # Adhan Texts
+//!
+//! The phrases of the adhan and the dua said after it, in Arabic, in
+//! transliteration and in English, so that display apps don't need to
+//! ship their own copies. The adhan of Fajr adds "prayer is better than
+//! sleep" after the second call to success.
+//!
+//! Only available with the `metadata` feature.
+//!
+//! ##### Example
+//!
+//! ```
+//! use miqat::metadata;
+//! use miqat::prelude::*;
+//!
+//! let fajr = metadata::adhan(Prayer::Fajr).unwrap();
+//! let dhuhr = metadata::adhan(Prayer::Dhuhr).unwrap();
+//!
+//! assert_eq!(fajr.len(), dhuhr.len() + 1);
+//! assert_eq!(dhuhr[0].transliteration, "Allahu akbar");
+//! assert_eq!(dhuhr[0].repetitions, 4);
+//! assert!(metadata::adhan(Prayer::Sunrise).is_none());
+//! ```
+
+use crate::models::prayer::Prayer;
+
+/// A phrase of the adhan or a dua.
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct Phrase {
+    pub arabic: &'static str,
+    pub transliteration: &'static str,
+    pub english: &'static str,
+    /// How many times the phrase is said in a row.
+    pub repetitions: u8,
+}
+
+const TAKBIR: Phrase = Phrase {
+    arabic: "اللهُ أَكْبَرُ",
+    transliteration: "Allahu akbar",
+    english: "Allah is the Greatest",
+    repetitions: 4,
+};
+
+const SHAHADA: Phrase = Phrase {
+    arabic: "أَشْهَدُ أَنْ لَا إِلٰهَ إِلَّا اللهُ",
+    transliteration: "Ashhadu an la ilaha illa Allah",
+    english: "I bear witness that there is no god but Allah",
+    repetitions: 2,
+};
+
+const RISALA: Phrase = Phrase {
+    arabic: "أَشْهَدُ أَنَّ مُحَمَّدًا رَسُولُ اللهِ",
+    transliteration: "Ashhadu anna Muhammadan rasul Allah",
+    english: "I bear witness that Muhammad is the Messenger of Allah",
+    repetitions: 2,
+};
+
+const TO_PRAYER: Phrase = Phrase {
+    arabic: "حَيَّ عَلَى الصَّلَاةِ",
+    transliteration: "Hayya 'ala as-salah",
+    english: "Come to prayer",
+    repetitions: 2,
+};
+
+const TO_SUCCESS: Phrase = Phrase {
+    arabic: "حَيَّ عَلَى الْفَلَاحِ",
+    transliteration: "Hayya 'ala al-falah",
+    english: "Come to success",
+    repetitions: 2,
+};
+
+const BETTER_THAN_SLEEP: Phrase = Phrase {
+    arabic: "الصَّلَاةُ خَيْرٌ مِنَ النَّوْمِ",
+    transliteration: "As-salatu khayrun min an-nawm",
+    english: "Prayer is better than sleep",
+    repetitions: 2,
+};
+
+const CLOSING_TAKBIR: Phrase = Phrase {
+    repetitions: 2,
+    ..TAKBIR
+};
+
+const TAHLIL: Phrase = Phrase {
+    arabic: "لَا إِلٰهَ إِلَّا اللهُ",
+    transliteration: "La ilaha illa Allah",
+    english: "There is no god but Allah",
+    repetitions: 1,
+};
+
+const ADHAN: [Phrase; 7] = [
+    TAKBIR,
+    SHAHADA,
+    RISALA,
+    TO_PRAYER,
+    TO_SUCCESS,
+    CLOSING_TAKBIR,
+    TAHLIL,
+];
+
+const FAJR_ADHAN: [Phrase; 8] = [
+    TAKBIR,
+    SHAHADA,
+    RISALA,
+    TO_PRAYER,
+    TO_SUCCESS,
+    BETTER_THAN_SLEEP,
+    CLOSING_TAKBIR,
+    TAHLIL,
+];
+
+/// The dua after the adhan, as narrated by Al-Bukhari.
+pub const DUA_AFTER_ADHAN: Phrase = Phrase {
+    arabic: "اللَّهُمَّ رَبَّ هٰذِهِ الدَّعْوَةِ التَّامَّةِ، وَالصَّلَاةِ الْقَائِمَةِ، آتِ مُحَمَّدًا الْوَسِيلَةَ وَالْفَضِيلَةَ، وَابْعَثْهُ مَقَامًا مَحْمُودًا الَّذِي وَعَدْتَهُ",
+    transliteration: "Allahumma rabba hadhihi ad-da'wati at-tammah, wa as-salati al-qa'imah, ati Muhammadan al-wasilata wa al-fadilah, wab'athhu maqaman mahmudan alladhi wa'adtah",
+    english: "O Allah, Lord of this perfect call and of the prayer about to be established, grant Muhammad the intercession and the favour, and raise him to the praised station You have promised him",
+    repetitions: 1,
+};
+
+/// The phrases of the adhan of `prayer`, in order, or `None` for
+/// Sunrise, which has no adhan.
+pub fn adhan(prayer: Prayer) -> Option<&'static [Phrase]> {
+    match prayer {
+        Prayer::Fajr | Prayer::FajrTomorrow => Some(&FAJR_ADHAN),
+        Prayer::Sunrise => None,
+        Prayer::Dhuhr | Prayer::Asr | Prayer::Maghrib | Prayer::Ishaa => Some(&ADHAN),
+    }
+}
+
+/// The dua said after the adhan of `prayer`, or `None` for Sunrise.
+pub fn dua_after_adhan(prayer: Prayer) -> Option<&'static Phrase> {
+    adhan(prayer).map(|_| &DUA_AFTER_ADHAN)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_fajr_calls_to_prayer_over_sleep() {
+        for prayer in [Prayer::Dhuhr, Prayer::Asr, Prayer::Maghrib, Prayer::Ishaa] {
+            assert!(!adhan(prayer).unwrap().contains(&BETTER_THAN_SLEEP));
+            assert_eq!(dua_after_adhan(prayer), Some(&DUA_AFTER_ADHAN));
+        }
+        assert_eq!(adhan(Prayer::FajrTomorrow), adhan(Prayer::Fajr));
+        assert_eq!(
+            adhan(Prayer::Fajr).unwrap()[5].transliteration,
+            "As-salatu khayrun min an-nawm"
+        );
+        assert_eq!(dua_after_adhan(Prayer::Sunrise), None);
+    }
+
+    #[test]
+    fn the_adhan_has_fifteen_phrases() {
+        let said = |prayer| {
+            adhan(prayer)
+                .unwrap()
+                .iter()
+                .map(|phrase| u32::from(phrase.repetitions))
+                .sum::<u32>()
+        };
+
+        assert_eq!(said(Prayer::Maghrib), 15);
+        assert_eq!(said(Prayer::Fajr), 17);
+    }
+}
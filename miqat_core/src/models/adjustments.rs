@@ -1,14 +1,245 @@
 use std::default::Default;
+use std::fmt;
+use std::str::FromStr;
 
 /// Time adjustment for all prayer times.
 /// The value is specified in *minutes* and
 /// can be either positive or negative.
+///
+/// Adjustments can also be parsed from a list of `prayer:minutes` pairs,
+/// where missing prayers are left at zero:
+///
+/// ```
+/// use miqat::TimeAdjustment;
+///
+/// let adjustments: TimeAdjustment = "fajr:-3, ishaa:+5".parse().unwrap();
+///
+/// assert_eq!(adjustments.fajr, -3);
+/// assert_eq!(adjustments.ishaa, 5);
+/// assert_eq!(adjustments.dhuhr, 0);
+/// ```
 #[derive(PartialEq, Debug, Copy, Clone, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct TimeAdjustment {
     pub fajr: i64,
     pub sunrise: i64,
     pub dhuhr: i64,
     pub asr: i64,
     pub maghrib: i64,
+    #[cfg_attr(feature = "serde", serde(alias = "isha"))]
     pub ishaa: i64,
 }
+
+impl TimeAdjustment {
+    /// The size, in either direction, from which
+    /// [`Parameters::validate`](crate::Parameters::validate) rejects an
+    /// adjustment. From there on a prayer can move past its neighbours,
+    /// and the times are no longer in order.
+    pub const MAX_MINUTES: i64 = 240;
+
+    /// Limits every adjustment to `min..=max` minutes, or fails if the
+    /// range is empty.
+    ///
+    /// ```
+    /// use miqat::TimeAdjustment;
+    ///
+    /// let adjustments: TimeAdjustment = "fajr:-300, ishaa:5".parse().unwrap();
+    /// let clamped = adjustments.clamped(-60, 60).unwrap();
+    ///
+    /// assert_eq!(clamped.fajr, -60);
+    /// assert_eq!(clamped.ishaa, 5);
+    /// assert!(adjustments.clamped(60, -60).is_err());
+    /// ```
+    pub fn clamped(self, min: i64, max: i64) -> Result<TimeAdjustment, EmptyRangeError> {
+        if min > max {
+            return Err(EmptyRangeError { min, max });
+        } else {
+            // Nothing to do.
+        }
+
+        Ok(TimeAdjustment {
+            fajr: self.fajr.clamp(min, max),
+            sunrise: self.sunrise.clamp(min, max),
+            dhuhr: self.dhuhr.clamp(min, max),
+            asr: self.asr.clamp(min, max),
+            maghrib: self.maghrib.clamp(min, max),
+            ishaa: self.ishaa.clamp(min, max),
+        })
+    }
+}
+
+/// The bounds given to [`TimeAdjustment::clamped`], where `min` is
+/// greater than `max`.
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct EmptyRangeError {
+    pub min: i64,
+    pub max: i64,
+}
+
+impl fmt::Display for EmptyRangeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the minimum of {} minutes is greater than the maximum of {} minutes",
+            self.min, self.max
+        )
+    }
+}
+
+impl std::error::Error for EmptyRangeError {}
+
+/// Why a string could not be parsed into a [`TimeAdjustment`].
+#[derive(PartialEq, Debug, Clone)]
+pub enum ParseAdjustmentError {
+    /// An entry is not of the form `prayer:minutes`.
+    MissingSeparator(String),
+    /// The prayer of an entry is not one of fajr, sunrise, dhuhr,
+    /// asr, maghrib or ishaa.
+    UnknownPrayer(String),
+    /// The minutes of an entry are not a whole number.
+    InvalidMinutes(String),
+}
+
+impl fmt::Display for ParseAdjustmentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseAdjustmentError::MissingSeparator(entry) => {
+                write!(f, "expected `prayer:minutes`, found `{entry}`")
+            }
+            ParseAdjustmentError::UnknownPrayer(prayer) => write!(f, "unknown prayer `{prayer}`"),
+            ParseAdjustmentError::InvalidMinutes(minutes) => {
+                write!(f, "invalid minutes `{minutes}`")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseAdjustmentError {}
+
+impl FromStr for TimeAdjustment {
+    type Err = ParseAdjustmentError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let mut adjustments = TimeAdjustment::default();
+
+        for entry in value
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+        {
+            let (prayer, minutes) = entry
+                .split_once(':')
+                .ok_or_else(|| ParseAdjustmentError::MissingSeparator(entry.to_string()))?;
+            let minutes = minutes.trim();
+            let minutes: i64 = minutes
+                .parse()
+                .map_err(|_| ParseAdjustmentError::InvalidMinutes(minutes.to_string()))?;
+
+            let target = match prayer.trim().to_ascii_lowercase().as_str() {
+                "fajr" => &mut adjustments.fajr,
+                "sunrise" => &mut adjustments.sunrise,
+                "dhuhr" => &mut adjustments.dhuhr,
+                "asr" => &mut adjustments.asr,
+                "maghrib" => &mut adjustments.maghrib,
+                "ishaa" | "isha" => &mut adjustments.ishaa,
+                _ => {
+                    return Err(ParseAdjustmentError::UnknownPrayer(
+                        prayer.trim().to_string(),
+                    ));
+                }
+            };
+            *target = minutes;
+        }
+
+        Ok(adjustments)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clamped_limits_each_prayer() {
+        let adjustments = TimeAdjustment {
+            fajr: -500,
+            dhuhr: 3,
+            maghrib: 500,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            adjustments.clamped(-TimeAdjustment::MAX_MINUTES, TimeAdjustment::MAX_MINUTES),
+            Ok(TimeAdjustment {
+                fajr: -240,
+                dhuhr: 3,
+                maghrib: 240,
+                ..Default::default()
+            })
+        );
+    }
+
+    #[test]
+    fn clamped_rejects_an_empty_range() {
+        assert_eq!(
+            TimeAdjustment::default().clamped(10, -10),
+            Err(EmptyRangeError { min: 10, max: -10 })
+        );
+        assert!(TimeAdjustment::default().clamped(5, 5).is_ok());
+    }
+
+    #[test]
+    fn parse_adjustments_from_string() {
+        let adjustments: TimeAdjustment = "fajr:-3,Dhuhr: 2, isha:+5,".parse().unwrap();
+
+        assert_eq!(
+            adjustments,
+            TimeAdjustment {
+                fajr: -3,
+                dhuhr: 2,
+                ishaa: 5,
+                ..Default::default()
+            }
+        );
+        assert_eq!("".parse(), Ok(TimeAdjustment::default()));
+    }
+
+    #[test]
+    fn reject_malformed_adjustments() {
+        assert_eq!(
+            "fajr=-3".parse::<TimeAdjustment>(),
+            Err(ParseAdjustmentError::MissingSeparator(
+                "fajr=-3".to_string()
+            ))
+        );
+        assert_eq!(
+            "witr:3".parse::<TimeAdjustment>(),
+            Err(ParseAdjustmentError::UnknownPrayer("witr".to_string()))
+        );
+        assert_eq!(
+            "asr:two".parse::<TimeAdjustment>(),
+            Err(ParseAdjustmentError::InvalidMinutes("two".to_string()))
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserialize_adjustments_ignoring_missing_keys() {
+        let adjustments: TimeAdjustment =
+            serde_json::from_str(r#"{"fajr": -3, "ishaa": 5}"#).unwrap();
+
+        assert_eq!(
+            adjustments,
+            TimeAdjustment {
+                fajr: -3,
+                ishaa: 5,
+                ..Default::default()
+            }
+        );
+    }
+}
@@ -0,0 +1,50 @@
+use super::mazhab::Mazhab;
+
+/// The length of an object's shadow, relative to the object itself,
+/// at which Asr begins (on top of the shadow it casts at noon).
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub enum AsrFactor {
+    /// The shadow factor of a mazhab.
+    Mazhab(Mazhab),
+
+    /// An arbitrary shadow factor, for intermediate values used by some
+    /// communities or for research.
+    Custom(f64),
+}
+
+impl AsrFactor {
+    pub fn shadow_length(&self) -> f64 {
+        match self {
+            AsrFactor::Mazhab(mazhab) => mazhab.shadow().into(),
+            AsrFactor::Custom(factor) => *factor,
+        }
+    }
+}
+
+impl Default for AsrFactor {
+    fn default() -> Self {
+        AsrFactor::Mazhab(Mazhab::default())
+    }
+}
+
+impl From<Mazhab> for AsrFactor {
+    fn from(mazhab: Mazhab) -> Self {
+        AsrFactor::Mazhab(mazhab)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mazhab_shadow_length() {
+        assert_eq!(AsrFactor::from(Mazhab::Shafi).shadow_length(), 1.0);
+        assert_eq!(AsrFactor::from(Mazhab::Hanafi).shadow_length(), 2.0);
+    }
+
+    #[test]
+    fn custom_shadow_length() {
+        assert_eq!(AsrFactor::Custom(1.5).shadow_length(), 1.5);
+    }
+}
@@ -0,0 +1,39 @@
+use chrono::DateTime;
+use chrono::FixedOffset;
+use chrono::NaiveDate;
+use chrono::NaiveTime;
+use chrono::Utc;
+
+/// Which calendar day a computed schedule represents.
+#[derive(PartialEq, Debug, Copy, Clone, Default)]
+pub enum DayBoundary {
+    /// The day runs from 00:00 to 24:00 UTC. Fine for most locations, but
+    /// far from the prime meridian, e.g. in UTC+14, the solar day of a UTC
+    /// date can fall on the next local calendar day.
+    #[default]
+    Utc,
+
+    /// The day is the local civil day at the given UTC offset, so that
+    /// Dhuhr always falls on the requested local date.
+    LocalCivil(FixedOffset),
+}
+
+impl DayBoundary {
+    /// The calendar date `instant` falls on.
+    pub fn date_of(&self, instant: DateTime<Utc>) -> NaiveDate {
+        match self {
+            DayBoundary::Utc => instant.date_naive(),
+            DayBoundary::LocalCivil(offset) => instant.with_timezone(offset).date_naive(),
+        }
+    }
+
+    /// The instant `date` starts at.
+    pub fn start_of(&self, date: NaiveDate) -> DateTime<Utc> {
+        let midnight = date.and_time(NaiveTime::MIN);
+
+        match self {
+            DayBoundary::Utc => midnight.and_utc(),
+            DayBoundary::LocalCivil(offset) => (midnight - *offset).and_utc(),
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use crate::astronomy::ops;
+use chrono::Datelike;
+use chrono::NaiveDate;
+
+/// Source of delta T, the difference in seconds between Terrestrial Time,
+/// which the position of the sun follows, and Universal Time, which clocks
+/// follow.
+///
+/// UTC is taken to be UT1; the two never differ by more than 0.9 seconds
+/// thanks to leap seconds, which is well below the precision of a prayer
+/// time. Users who need more can fold their UT1 − UTC into the value.
+#[derive(Debug, Copy, Clone, Default)]
+pub enum DeltaT {
+    /// Estimated from the polynomial fits by Espenak and Meeus.
+    #[default]
+    Estimated,
+
+    /// A fixed value in seconds, e.g. the current one published by the IERS.
+    Fixed(f64),
+
+    /// Looked up for every date by a user provided function, e.g. from
+    /// a table of observed values.
+    Provider(fn(NaiveDate) -> f64),
+}
+
+impl DeltaT {
+    /// Delta T in seconds for the given date.
+    pub fn seconds(&self, date: NaiveDate) -> f64 {
+        match self {
+            DeltaT::Estimated => {
+                ops::delta_t(date.year() as f64 + (date.month() as f64 - 0.5) / 12.0)
+            }
+            DeltaT::Fixed(seconds) => *seconds,
+            DeltaT::Provider(provider) => provider(date),
+        }
+    }
+}
+
+impl PartialEq for DeltaT {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (DeltaT::Estimated, DeltaT::Estimated) => true,
+            (DeltaT::Fixed(a), DeltaT::Fixed(b)) => a == b,
+            (DeltaT::Provider(a), DeltaT::Provider(b)) => std::ptr::fn_addr_eq(*a, *b),
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delta_t_sources() {
+        let date = NaiveDate::from_ymd_opt(2000, 1, 1).unwrap();
+
+        assert!((DeltaT::Estimated.seconds(date) - 63.8).abs() < 0.1);
+        assert_eq!(DeltaT::Fixed(69.2).seconds(date), 69.2);
+        assert_eq!(DeltaT::Provider(|_| 70.0).seconds(date), 70.0);
+    }
+}
@@ -2,6 +2,11 @@ use crate::astronomy::unit::Coordinates;
 
 /// Rule for approximating Fajr and Ishaa at high latitudes
 #[derive(PartialEq, Debug, Copy, Clone, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
 pub enum HighLatitudeRule {
     /// Fajr won't be earlier than the midpoint of the night and ishaa
     /// won't be later than the midpoint of the night. This is the default
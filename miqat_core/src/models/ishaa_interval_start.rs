@@ -0,0 +1,12 @@
+/// Where an interval based Ishaa is measured from, see
+/// [`IshaaParameter::Interval`](crate::models::ishaa_parameter::IshaaParameter::Interval).
+#[derive(PartialEq, Debug, Copy, Clone, Default)]
+pub enum IshaaIntervalStart {
+    /// From astronomical sunset.
+    #[default]
+    Sunset,
+
+    /// From Maghrib, after its adjustments, rounding and angle, as Umm
+    /// al-Qura times are published.
+    Maghrib,
+}
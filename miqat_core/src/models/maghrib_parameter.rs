@@ -0,0 +1,11 @@
+/// How Maghrib is found.
+#[derive(PartialEq, Debug, Copy, Clone, Default)]
+pub enum MaghribParameter {
+    /// At sunset.
+    #[default]
+    Sunset,
+    /// When the sun is the given angle below the horizon, as Ithna Ashari
+    /// methods wait for the redness in the eastern sky to pass. Falls back
+    /// to sunset when that time is not between sunset and Ishaa.
+    Angle(f64),
+}
@@ -2,6 +2,12 @@
 /// For Hanafi mazhab, the Asr is bit later
 /// than that of the Shafi mazhab.
 #[derive(PartialEq, Debug, Copy, Clone, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub enum Mazhab {
     #[default]
     Shafi,
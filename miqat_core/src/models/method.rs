@@ -1,99 +1,159 @@
 use super::parameters::Parameters;
 use crate::TimeAdjustment;
+use crate::models::ishaa_interval_start::IshaaIntervalStart;
 use crate::models::ishaa_parameter::IshaaParameter;
+use crate::models::maghrib_parameter::MaghribParameter;
+use crate::models::method_behavior::MethodBehavior;
 use crate::models::rounding::Rounding;
 
-/// Provides preset configuration for a few authorities
-/// for calculating prayer times.
-#[derive(PartialEq, Debug, Copy, Clone)]
-pub enum Method {
+macro_rules! presets {
+    ($(
+        $(#[doc = $doc:literal])*
+        $name:ident ($slug:literal) => { $($field:ident: $value:expr),* $(,)? }
+    )*) => {
+        /// Provides preset configuration for a few authorities
+        /// for calculating prayer times.
+        ///
+        /// Serialized as its slug, e.g. `umm_al_qura`.
+        #[derive(PartialEq, Debug, Copy, Clone)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+        #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+        pub enum Method {
+            $(
+                $(#[doc = $doc])*
+                #[cfg_attr(feature = "serde", serde(rename = $slug))]
+                $name,
+            )*
+        }
+
+        impl Method {
+            /// Every preset, in declaration order.
+            pub const ALL: &'static [Method] = &[$(Method::$name),*];
+
+            /// A stable, lowercase ASCII name for URLs, topics and
+            /// databases, e.g. `muslim_world_league`.
+            pub fn as_slug(&self) -> &'static str {
+                match self {
+                    $(Method::$name => $slug,)*
+                }
+            }
+
+            /// The preset with the given [slug](Method::as_slug).
+            pub fn from_slug(slug: &str) -> Option<Method> {
+                match slug {
+                    $($slug => Some(Method::$name),)*
+                    _ => None,
+                }
+            }
+
+            pub fn parameters(&self) -> Parameters {
+                match self {
+                    $(
+                        Method::$name => Parameters {
+                            $($field: $value,)*
+                            ..Default::default()
+                        },
+                    )*
+                }
+            }
+        }
+    };
+}
+
+// The single source of truth for the presets. Both the `Method` enum and
+// its parameters are generated from this table, and the snapshot test
+// below pins every value so a change to a preset is always deliberate.
+presets! {
     /// Muslim World League. Standard Fajr time with an angle of 18°.
     /// Earlier Ishaa time with an angle of 17°.
-    MuslimWorldLeague,
+    MuslimWorldLeague ("muslim_world_league") => {
+        fajr_angle: 18.0,
+        ishaa_parameter: IshaaParameter::Angle(17.0),
+        method_adjustments: TimeAdjustment {
+            dhuhr: 1,
+            ..Default::default()
+        },
+    }
 
     /// Egyptian General Authority of Survey. Early Fajr time using an angle 19.5°
     /// and a slightly earlier Ishaa time using an angle of 17.5°.
-    Egyptian,
+    Egyptian ("egyptian") => {
+        fajr_angle: 19.5,
+        ishaa_parameter: IshaaParameter::Angle(17.5),
+        method_adjustments: TimeAdjustment {
+            dhuhr: 1,
+            ..Default::default()
+        },
+    }
 
     /// Umm al-Qura University, Makkah. Uses a fixed interval of 90 minutes
     /// from maghrib to calculate Ishaa. And a slightly earlier Fajr time with
     /// an angle of 18.5°. Note: you should add a +30 minute custom adjustment
     /// for Ishaa during Ramadan.
-    UmmAlQura,
+    UmmAlQura ("umm_al_qura") => {
+        fajr_angle: 18.5,
+        ishaa_parameter: IshaaParameter::Interval(90),
+        ishaa_interval_start: IshaaIntervalStart::Maghrib,
+    }
 
     /// Method developed by Khalid Shaukat, founder of Moonsighting Committee Worldwide.
     /// Uses standard 18° angles for Fajr and Ishaa in addition to seasonal adjustment values.
     /// This method automatically applies the 1/7 approximation rule for locations above 55°
     /// latitude. Recommended for North America and the UK.
-    MoonsightingCommittee,
+    MoonsightingCommittee ("moonsighting_committee") => {
+        fajr_angle: 18.0,
+        ishaa_parameter: IshaaParameter::Angle(18.0),
+        behavior: MethodBehavior::MoonsightingCommittee,
+        method_adjustments: TimeAdjustment {
+            dhuhr: 5,
+            maghrib: 3,
+            ..Default::default()
+        },
+    }
 
     /// Also known as the ISNA method. Can be used for North America,
     /// but the moonsightingCommittee method is preferable. Gives later Fajr times and early.
     /// Ishaa times with angles of 15°.
-    NorthAmerica,
+    NorthAmerica ("north_america") => {
+        fajr_angle: 15.0,
+        ishaa_parameter: IshaaParameter::Angle(15.0),
+        method_adjustments: TimeAdjustment {
+            dhuhr: 1,
+            ..Default::default()
+        },
+    }
 
     /// Used in Singapore, Malaysia, and Indonesia. Early Fajr time with an angle of 20°
-    /// and standard Ishaa time with an angle of 18°.
-    Singapore,
+    /// and standard Ishaa time with an angle of 18°. Times are a minute after the
+    /// computed ones, and rounded up.
+    Singapore ("singapore") => {
+        fajr_angle: 20.0,
+        ishaa_parameter: IshaaParameter::Angle(18.0),
+        rounding: Rounding::Ceil,
+        method_adjustments: TimeAdjustment {
+            fajr: 1,
+            sunrise: 1,
+            dhuhr: 2,
+            asr: 1,
+            maghrib: 1,
+            ishaa: 1,
+        },
+    }
+
+    /// Institute of Geophysics, University of Tehran. Early Ishaa time with
+    /// an angle of 14°, a slightly later Fajr time with an angle of 17.7°,
+    /// and Maghrib when the sun reaches 4.5° below the horizon.
+    Tehran ("tehran") => {
+        fajr_angle: 17.7,
+        ishaa_parameter: IshaaParameter::Angle(14.0),
+        maghrib_parameter: MaghribParameter::Angle(4.5),
+    }
 }
 
-impl Method {
-    pub fn parameters(&self) -> Parameters {
-        match self {
-            Method::MuslimWorldLeague => Parameters {
-                fajr_angle: 18.0,
-                ishaa_parameter: IshaaParameter::Angle(17.0),
-                method_adjustments: TimeAdjustment {
-                    dhuhr: 1,
-                    ..Default::default()
-                },
-                ..Default::default()
-            },
-            Method::Egyptian => Parameters {
-                fajr_angle: 19.5,
-                ishaa_parameter: IshaaParameter::Angle(17.5),
-                method_adjustments: TimeAdjustment {
-                    dhuhr: 1,
-                    ..Default::default()
-                },
-                ..Default::default()
-            },
-            Method::UmmAlQura => Parameters {
-                fajr_angle: 18.5,
-                ishaa_parameter: IshaaParameter::Interval(90),
-                ..Default::default()
-            },
-            Method::MoonsightingCommittee => Parameters {
-                fajr_angle: 18.0,
-                ishaa_parameter: IshaaParameter::Angle(18.0),
-                is_moonsighting_committee: true,
-                method_adjustments: TimeAdjustment {
-                    dhuhr: 5,
-                    maghrib: 3,
-                    ..Default::default()
-                },
-                ..Default::default()
-            },
-            Method::NorthAmerica => Parameters {
-                fajr_angle: 15.0,
-                ishaa_parameter: IshaaParameter::Angle(15.0),
-                method_adjustments: TimeAdjustment {
-                    dhuhr: 1,
-                    ..Default::default()
-                },
-                ..Default::default()
-            },
-            Method::Singapore => Parameters {
-                fajr_angle: 20.0,
-                ishaa_parameter: IshaaParameter::Angle(18.0),
-                rounding: Rounding::Ceil,
-                method_adjustments: TimeAdjustment {
-                    dhuhr: 1,
-                    ..Default::default()
-                },
-                ..Default::default()
-            },
-        }
+impl Default for Method {
+    /// The Muslim World League, as in config files without a method.
+    fn default() -> Self {
+        Method::MuslimWorldLeague
     }
 }
 
@@ -117,5 +177,36 @@ mod tests {
 
         assert_eq!(params.fajr_angle, 18.0);
         assert_eq!(params.ishaa_parameter, IshaaParameter::Angle(18.0));
+        assert_eq!(params.behavior, MethodBehavior::MoonsightingCommittee);
+    }
+
+    #[test]
+    fn parameters_for_all_presets_match_snapshot() {
+        let snapshot: String = Method::ALL
+            .iter()
+            .map(|method| format!("{method:?} => {:#?}\n", method.parameters()))
+            .collect();
+
+        assert_eq!(snapshot, include_str!("snapshots/method_presets.snap"));
+    }
+
+    #[test]
+    fn slugs_round_trip() {
+        for method in Method::ALL {
+            assert_eq!(Method::from_slug(method.as_slug()), Some(*method));
+        }
+        assert_eq!(Method::UmmAlQura.as_slug(), "umm_al_qura");
+        assert_eq!(Method::from_slug("UmmAlQura"), None);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serialized_as_slugs() {
+        for method in Method::ALL {
+            let json = serde_json::to_string(method).unwrap();
+
+            assert_eq!(json, format!("\"{}\"", method.as_slug()));
+            assert_eq!(serde_json::from_str::<Method>(&json).unwrap(), *method);
+        }
     }
 }
@@ -0,0 +1,70 @@
+use crate::astronomy::unit::Coordinates;
+
+/// Method specific rules for deriving Fajr and Ishaa that go beyond
+/// plain twilight angles. Presets pick the behavior of their authority;
+/// methods without special rules use `Standard`.
+#[derive(PartialEq, Debug, Copy, Clone, Default)]
+pub enum MethodBehavior {
+    /// Fajr and Ishaa are derived from their angles and clamped using
+    /// the high latitude rule.
+    #[default]
+    Standard,
+
+    /// Moonsighting Committee Worldwide. Above 55° latitude Fajr and Ishaa
+    /// are fixed at one seventh of the night from sunrise and sunset, and
+    /// the safe times always come from the seasonal twilight model.
+    MoonsightingCommittee,
+}
+
+impl MethodBehavior {
+    /// The portion of the night that replaces the angle based Fajr and
+    /// Ishaa at the given location, if the behavior mandates one.
+    pub fn fixed_night_portion(&self, coordinates: Coordinates) -> Option<f64> {
+        match self {
+            MethodBehavior::MoonsightingCommittee if coordinates.latitude >= 55.0 => {
+                Some(1.0 / 7.0)
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether the safe Fajr and Ishaa times come from the
+    /// [seasonal](crate::seasonal) twilight model.
+    pub fn uses_seasonal_twilight(&self) -> bool {
+        match self {
+            MethodBehavior::Standard => false,
+            MethodBehavior::MoonsightingCommittee => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn standard_behavior_has_no_special_rules() {
+        let behavior = MethodBehavior::Standard;
+
+        assert_eq!(
+            behavior.fixed_night_portion(Coordinates::new(60.0, 0.0)),
+            None
+        );
+        assert!(!behavior.uses_seasonal_twilight());
+    }
+
+    #[test]
+    fn moonsighting_committee_uses_seventh_of_night_above_55() {
+        let behavior = MethodBehavior::MoonsightingCommittee;
+
+        assert_eq!(
+            behavior.fixed_night_portion(Coordinates::new(54.9, 0.0)),
+            None
+        );
+        assert_eq!(
+            behavior.fixed_night_portion(Coordinates::new(55.0, 0.0)),
+            Some(1.0 / 7.0)
+        );
+        assert!(behavior.uses_seasonal_twilight());
+    }
+}
@@ -0,0 +1,130 @@
+use super::ishaa_parameter::IshaaParameter;
+use super::method::Method;
+use super::parameters::Parameters;
+
+/// A superseded preset of a [`Method`], for reproducing timetables
+/// published before the authority changed its angles. Only the settings
+/// that differ from the current preset are given.
+///
+/// ##### Example
+///
+/// ```
+/// use miqat::prelude::*;
+///
+/// assert_eq!(Method::UmmAlQura.parameters_as_of(2005).fajr_angle, 19.0);
+/// assert_eq!(Method::UmmAlQura.parameters_as_of(2010).fajr_angle, 18.5);
+/// assert_eq!(Method::UmmAlQura.parameters_as_of(2010), Method::UmmAlQura.parameters());
+/// ```
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct MethodVersion {
+    pub method: Method,
+    /// The last Gregorian year the version was in use.
+    pub until: i32,
+    pub fajr_angle: Option<f64>,
+    pub ishaa_parameter: Option<IshaaParameter>,
+    /// What changed when the version was superseded.
+    pub note: &'static str,
+}
+
+impl MethodVersion {
+    /// The method's parameters as they were under this version.
+    pub fn parameters(&self) -> Parameters {
+        let current = self.method.parameters();
+
+        Parameters {
+            fajr_angle: self.fajr_angle.unwrap_or(current.fajr_angle),
+            ishaa_parameter: self.ishaa_parameter.unwrap_or(current.ishaa_parameter),
+            ..current
+        }
+    }
+}
+
+// Sorted by method, in the order of their declaration, then from the oldest version to the newest.
+const VERSIONS: [MethodVersion; 2] = [
+    MethodVersion {
+        method: Method::UmmAlQura,
+        until: 2008,
+        fajr_angle: Some(19.0),
+        ishaa_parameter: None,
+        note: "Fajr moved from 19° to 18.5° in 1430 AH",
+    },
+    MethodVersion {
+        method: Method::NorthAmerica,
+        until: 2010,
+        fajr_angle: Some(18.0),
+        ishaa_parameter: Some(IshaaParameter::Angle(18.0)),
+        note: "Fajr and Ishaa moved from 18° to 15°",
+    },
+];
+
+impl Method {
+    /// The superseded versions of the preset, oldest first.
+    pub fn versions(&self) -> Vec<MethodVersion> {
+        VERSIONS
+            .iter()
+            .filter(|version| version.method == *self)
+            .copied()
+            .collect()
+    }
+
+    /// The preset as it was in use during the Gregorian `year`, the
+    /// current one for years after the last change.
+    pub fn parameters_as_of(&self, year: i32) -> Parameters {
+        self.versions()
+            .into_iter()
+            .find(|version| year <= version.until)
+            .map(|version| version.parameters())
+            .unwrap_or_else(|| self.parameters())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn versions_are_sorted_and_differ_from_the_current_presets() {
+        for pair in VERSIONS.windows(2) {
+            if pair[0].method == pair[1].method {
+                assert!(pair[0].until < pair[1].until);
+            } else {
+                // Nothing to do.
+            }
+        }
+        for version in VERSIONS {
+            assert_ne!(version.parameters(), version.method.parameters());
+        }
+    }
+
+    #[test]
+    fn methods_without_versions_never_change() {
+        for year in [1900, 2000, 2100] {
+            assert_eq!(
+                Method::MuslimWorldLeague.parameters_as_of(year),
+                Method::MuslimWorldLeague.parameters()
+            );
+        }
+        assert!(Method::MuslimWorldLeague.versions().is_empty());
+        assert_eq!(
+            Method::UmmAlQura.parameters_as_of(2008).ishaa_parameter,
+            IshaaParameter::Interval(90)
+        );
+    }
+
+    #[test]
+    fn north_america_on_either_side_of_the_change() {
+        let before = Method::NorthAmerica.parameters_as_of(2010);
+        assert_eq!(before.fajr_angle, 18.0);
+        assert_eq!(before.ishaa_parameter, IshaaParameter::Angle(18.0));
+        assert_eq!(
+            before.method_adjustments,
+            Method::NorthAmerica.parameters().method_adjustments
+        );
+
+        assert_eq!(
+            Method::NorthAmerica.parameters_as_of(2011),
+            Method::NorthAmerica.parameters()
+        );
+        assert_eq!(Method::NorthAmerica.versions().len(), 1);
+    }
+}
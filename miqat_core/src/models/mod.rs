@@ -1,9 +1,22 @@
 pub mod adjustments;
+pub mod asr_factor;
+pub mod day_boundary;
+pub mod delta_t;
 pub mod high_altitude_rule;
+pub mod ishaa_interval_start;
 pub mod ishaa_parameter;
+pub mod maghrib_parameter;
 pub mod mazhab;
 pub mod method;
+pub mod method_behavior;
+pub mod method_version;
+pub mod next_day_fajr;
+pub mod parameter_issue;
 pub mod parameters;
+pub mod parameters_view;
 pub mod prayer;
 pub mod rounding;
+pub mod time_source;
 pub mod twilight;
+pub mod warning;
+pub mod window;
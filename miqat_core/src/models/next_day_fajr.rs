@@ -0,0 +1,13 @@
+/// How the Fajr of the following day (`Prayer::FajrTomorrow`) is obtained.
+#[derive(PartialEq, Debug, Copy, Clone, Default)]
+pub enum NextDayFajr {
+    /// Fully computed from the following night. Needs the solar
+    /// position of one more day.
+    #[default]
+    Computed,
+
+    /// Today's Fajr plus 24 hours. Off by a minute or two at most outside
+    /// of high latitudes; useful for batch jobs that compute every day of
+    /// a range anyway and never look at `FajrTomorrow`.
+    Estimated,
+}
@@ -0,0 +1,104 @@
+use crate::models::prayer::Prayer;
+use std::fmt;
+
+/// A problem found by [`Parameters::validate`](crate::Parameters::validate).
+///
+/// Errors make the computed times meaningless. Warnings point at settings
+/// that are silently ignored by the rest of the configuration.
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub enum ParameterIssue {
+    /// The Fajr angle is not within (0°, 90°).
+    InvalidFajrAngle(f64),
+
+    /// The Ishaa angle is not within (0°, 90°).
+    InvalidIshaaAngle(f64),
+
+    /// The Ishaa interval after Maghrib is not a positive number of minutes.
+    InvalidIshaaInterval(i32),
+
+    /// The Maghrib angle is not within (0°, 90°).
+    InvalidMaghribAngle(f64),
+
+    /// The Asr shadow factor is not positive.
+    InvalidAsrFactor(f64),
+
+    /// The elevation is negative or not a number.
+    InvalidElevation(f64),
+
+    /// The total adjustment of a prayer, in minutes, reaches
+    /// [`TimeAdjustment::MAX_MINUTES`](crate::TimeAdjustment::MAX_MINUTES).
+    AdjustmentOutOfRange(Prayer, i64),
+
+    /// `dhuhr_offset_after_transit` reaches
+    /// [`TimeAdjustment::MAX_MINUTES`](crate::TimeAdjustment::MAX_MINUTES).
+    DhuhrOffsetOutOfRange(i64),
+
+    /// `ishaa_high_latitude_rule` is set, but Ishaa is a fixed interval
+    /// after Maghrib and never clamped.
+    IshaaHighLatitudeRuleIgnored,
+
+    /// `ishaa_interval_start` is set, but Ishaa is at an angle.
+    IshaaIntervalStartIgnored,
+
+    /// A shafaq other than the general one is set, but it only affects
+    /// the seasonal twilight estimates, which are not in use.
+    TwilightIgnored,
+}
+
+impl ParameterIssue {
+    pub fn is_error(&self) -> bool {
+        matches!(
+            self,
+            ParameterIssue::InvalidFajrAngle(_)
+                | ParameterIssue::InvalidIshaaAngle(_)
+                | ParameterIssue::InvalidIshaaInterval(_)
+                | ParameterIssue::InvalidMaghribAngle(_)
+                | ParameterIssue::InvalidAsrFactor(_)
+                | ParameterIssue::InvalidElevation(_)
+                | ParameterIssue::AdjustmentOutOfRange(..)
+                | ParameterIssue::DhuhrOffsetOutOfRange(_)
+        )
+    }
+}
+
+impl fmt::Display for ParameterIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParameterIssue::InvalidFajrAngle(angle) => write!(f, "invalid Fajr angle {angle}°"),
+            ParameterIssue::InvalidIshaaAngle(angle) => write!(f, "invalid Ishaa angle {angle}°"),
+            ParameterIssue::InvalidIshaaInterval(minutes) => {
+                write!(f, "invalid Ishaa interval of {minutes} minutes")
+            }
+            ParameterIssue::InvalidMaghribAngle(angle) => {
+                write!(f, "invalid Maghrib angle {angle}°")
+            }
+            ParameterIssue::InvalidAsrFactor(factor) => {
+                write!(f, "invalid Asr shadow factor {factor}")
+            }
+            ParameterIssue::InvalidElevation(elevation) => {
+                write!(f, "invalid elevation of {elevation} metres")
+            }
+            ParameterIssue::AdjustmentOutOfRange(prayer, minutes) => write!(
+                f,
+                "the {} adjustment of {minutes} minutes is out of range",
+                prayer.as_slug()
+            ),
+            ParameterIssue::DhuhrOffsetOutOfRange(minutes) => write!(
+                f,
+                "the Dhuhr offset of {minutes} minutes after transit is out of range"
+            ),
+            ParameterIssue::IshaaHighLatitudeRuleIgnored => write!(
+                f,
+                "the Ishaa high latitude rule is ignored with an interval based Ishaa"
+            ),
+            ParameterIssue::IshaaIntervalStartIgnored => write!(
+                f,
+                "the start of the Ishaa interval is ignored with an angle based Ishaa"
+            ),
+            ParameterIssue::TwilightIgnored => write!(
+                f,
+                "the shafaq is ignored without seasonal twilight estimates"
+            ),
+        }
+    }
+}
@@ -1,6 +1,15 @@
 use super::adjustments::TimeAdjustment;
+use super::asr_factor::AsrFactor;
+use super::day_boundary::DayBoundary;
+use super::delta_t::DeltaT;
 use super::high_altitude_rule::HighLatitudeRule;
+use super::ishaa_interval_start::IshaaIntervalStart;
+use super::maghrib_parameter::MaghribParameter;
 use super::mazhab::Mazhab;
+use super::method_behavior::MethodBehavior;
+use super::next_day_fajr::NextDayFajr;
+use super::parameter_issue::ParameterIssue;
+use super::parameters_view::ParametersView;
 use super::prayer::Prayer;
 use super::rounding::Rounding;
 use super::twilight::Twilight;
@@ -13,12 +22,45 @@ use crate::models::ishaa_parameter::IshaaParameter;
 /// the parameters that are need.
 #[derive(PartialEq, Debug, Copy, Clone, Default)]
 pub struct Parameters {
-    pub is_moonsighting_committee: bool,
+    /// Method specific Fajr and Ishaa rules, see [`MethodBehavior`].
+    pub behavior: MethodBehavior,
     pub fajr_angle: f64,
-    pub maghrib_angle: f64,
+    pub maghrib_parameter: MaghribParameter,
     pub ishaa_parameter: IshaaParameter,
+    /// Where an interval based Ishaa is measured from, see [`IshaaIntervalStart`].
+    pub ishaa_interval_start: IshaaIntervalStart,
     pub mazhab: Mazhab,
+    /// Overrides the shadow factor of `mazhab` for Asr when set.
+    pub asr_factor: Option<AsrFactor>,
     pub high_latitude_rule: HighLatitudeRule,
+    /// Overrides `high_latitude_rule` for Fajr when set.
+    pub fajr_high_latitude_rule: Option<HighLatitudeRule>,
+    /// Overrides `high_latitude_rule` for Ishaa when set.
+    pub ishaa_high_latitude_rule: Option<HighLatitudeRule>,
+    /// Keeps the raw astronomical Fajr and Ishaa times instead of moving
+    /// them to the safe time derived from the high latitude rule.
+    pub disable_safe_time_clamp: bool,
+    /// Uses the [seasonal](crate::seasonal) twilight estimates as the safe
+    /// Fajr and Ishaa times instead of the high latitude rule. Always on for
+    /// methods whose [behavior](MethodBehavior::uses_seasonal_twilight) requires it.
+    pub seasonal_twilight: bool,
+    /// Minutes after solar transit at which Dhuhr begins, marking the end
+    /// of zawal. Applied before `adjustments` and `method_adjustments`.
+    pub dhuhr_offset_after_transit: i64,
+    /// The calendar day a schedule represents, see [`DayBoundary`].
+    pub day_boundary: DayBoundary,
+    /// How the Fajr of the following day is obtained, see [`NextDayFajr`].
+    pub next_day_fajr: NextDayFajr,
+    /// The difference between Terrestrial and Universal Time used for the
+    /// position of the sun, see [`DeltaT`].
+    pub delta_t: DeltaT,
+    /// The observer's height in metres above the surrounding terrain. The
+    /// horizon dips below eye level as it rises, so that sunrise is
+    /// earlier and Maghrib at sunset later. The night between them is
+    /// shorter, and so are the portions the high latitude rules take from
+    /// it; Fajr and Ishaa by angle stay where they are. Zero, the default,
+    /// for the sea-level horizon of the methods.
+    pub elevation: f64,
     pub adjustments: TimeAdjustment,
     pub method_adjustments: TimeAdjustment,
     pub rounding: Rounding,
@@ -26,34 +68,198 @@ pub struct Parameters {
 }
 
 impl Parameters {
+    /// The portions of the night, from sunset to sunrise, that bound Fajr
+    /// and Ishaa under the high latitude rule in effect for each.
     pub fn night_portions(&self) -> (f64, f64) {
         let ishaa_angle = match self.ishaa_parameter {
             IshaaParameter::Angle(angle) => angle,
             IshaaParameter::Interval(_) => 0.0,
         };
-        match self.high_latitude_rule {
-            HighLatitudeRule::MiddleOfTheNight => (1.0 / 2.0, 1.0 / 2.0),
-            HighLatitudeRule::SeventhOfTheNight => (1.0 / 7.0, 1.0 / 7.0),
-            HighLatitudeRule::TwilightAngle => (self.fajr_angle / 60.0, ishaa_angle / 60.0),
+        let portion = |rule: HighLatitudeRule, angle: f64| match rule {
+            HighLatitudeRule::MiddleOfTheNight => 1.0 / 2.0,
+            HighLatitudeRule::SeventhOfTheNight => 1.0 / 7.0,
+            HighLatitudeRule::TwilightAngle => angle / 60.0,
+        };
+
+        (
+            portion(self.high_latitude_rule_for(Prayer::Fajr), self.fajr_angle),
+            portion(self.high_latitude_rule_for(Prayer::Ishaa), ishaa_angle),
+        )
+    }
+
+    /// The high latitude rule in effect for the given prayer. Only Fajr and
+    /// Ishaa can be overridden, every other prayer uses the shared rule.
+    pub fn high_latitude_rule_for(&self, prayer: Prayer) -> HighLatitudeRule {
+        let rule = match prayer {
+            Prayer::Fajr | Prayer::FajrTomorrow => self.fajr_high_latitude_rule,
+            Prayer::Ishaa => self.ishaa_high_latitude_rule,
+            _ => None,
+        };
+
+        rule.unwrap_or(self.high_latitude_rule)
+    }
+
+    /// Whether the safe Fajr and Ishaa times come from the seasonal
+    /// twilight model, either by request or because the method requires it.
+    pub fn uses_seasonal_twilight(&self) -> bool {
+        self.seasonal_twilight || self.behavior.uses_seasonal_twilight()
+    }
+
+    /// Checks the parameters for invalid values and for settings that
+    /// conflict with each other, in which case one is silently ignored.
+    pub fn validate(&self) -> Vec<ParameterIssue> {
+        let mut issues = Vec::new();
+        let valid_angle = |angle: f64| angle > 0.0 && angle < 90.0;
+
+        if !valid_angle(self.fajr_angle) {
+            issues.push(ParameterIssue::InvalidFajrAngle(self.fajr_angle));
+        } else {
+            // Nothing to do.
+        }
+
+        match self.ishaa_parameter {
+            IshaaParameter::Angle(angle) if !valid_angle(angle) => {
+                issues.push(ParameterIssue::InvalidIshaaAngle(angle));
+            }
+            IshaaParameter::Interval(minutes) if minutes <= 0 => {
+                issues.push(ParameterIssue::InvalidIshaaInterval(minutes));
+            }
+            _ => {
+                // Nothing to do.
+            }
+        }
+
+        if let IshaaParameter::Interval(_) = self.ishaa_parameter
+            && self.ishaa_high_latitude_rule.is_some()
+        {
+            issues.push(ParameterIssue::IshaaHighLatitudeRuleIgnored);
+        } else {
+            // Nothing to do.
+        }
+
+        if let IshaaParameter::Angle(_) = self.ishaa_parameter
+            && self.ishaa_interval_start != IshaaIntervalStart::Sunset
+        {
+            issues.push(ParameterIssue::IshaaIntervalStartIgnored);
+        } else {
+            // Nothing to do.
+        }
+
+        let shadow_length = self.shadow_factor().shadow_length();
+        if shadow_length <= 0.0 || !shadow_length.is_finite() {
+            issues.push(ParameterIssue::InvalidAsrFactor(shadow_length));
+        } else {
+            // Nothing to do.
+        }
+
+        for prayer in Prayer::ALL {
+            let minutes = self.time_adjustments(prayer);
+            if minutes.unsigned_abs() >= TimeAdjustment::MAX_MINUTES.unsigned_abs() {
+                issues.push(ParameterIssue::AdjustmentOutOfRange(prayer, minutes));
+            } else {
+                // Nothing to do.
+            }
+        }
+
+        if self.dhuhr_offset_after_transit.unsigned_abs()
+            >= TimeAdjustment::MAX_MINUTES.unsigned_abs()
+        {
+            issues.push(ParameterIssue::DhuhrOffsetOutOfRange(
+                self.dhuhr_offset_after_transit,
+            ));
+        } else {
+            // Nothing to do.
+        }
+
+        if self.elevation < 0.0 || !self.elevation.is_finite() {
+            issues.push(ParameterIssue::InvalidElevation(self.elevation));
+        } else {
+            // Nothing to do.
+        }
+
+        match self.maghrib_parameter {
+            MaghribParameter::Angle(angle) if !valid_angle(angle) => {
+                issues.push(ParameterIssue::InvalidMaghribAngle(angle));
+            }
+            _ => {
+                // Nothing to do.
+            }
+        }
+
+        if self.twilight != Twilight::General && !self.uses_seasonal_twilight() {
+            issues.push(ParameterIssue::TwilightIgnored);
+        } else {
+            // Nothing to do.
         }
+
+        issues
     }
 
+    /// The total adjustment of `prayer` in minutes, the sum of
+    /// `adjustments` and `method_adjustments`, saturating at the bounds
+    /// of `i64`.
     pub fn time_adjustments(&self, prayer: Prayer) -> i64 {
-        match prayer {
-            Prayer::Fajr => self.adjustments.fajr + self.method_adjustments.fajr,
-            Prayer::Sunrise => self.adjustments.sunrise + self.method_adjustments.sunrise,
-            Prayer::Dhuhr => self.adjustments.dhuhr + self.method_adjustments.dhuhr,
-            Prayer::Asr => self.adjustments.asr + self.method_adjustments.asr,
-            Prayer::Maghrib => self.adjustments.maghrib + self.method_adjustments.maghrib,
-            Prayer::Ishaa => self.adjustments.ishaa + self.method_adjustments.ishaa,
-            _ => 0,
-        }
+        let (adjustment, method_adjustment) = match prayer {
+            Prayer::Fajr => (self.adjustments.fajr, self.method_adjustments.fajr),
+            Prayer::Sunrise => (self.adjustments.sunrise, self.method_adjustments.sunrise),
+            Prayer::Dhuhr => (self.adjustments.dhuhr, self.method_adjustments.dhuhr),
+            Prayer::Asr => (self.adjustments.asr, self.method_adjustments.asr),
+            Prayer::Maghrib => (self.adjustments.maghrib, self.method_adjustments.maghrib),
+            Prayer::Ishaa => (self.adjustments.ishaa, self.method_adjustments.ishaa),
+            _ => (0, 0),
+        };
+
+        adjustment.saturating_add(method_adjustment)
     }
 
     pub fn mazhab(mut self, mazhab: Mazhab) -> Self {
         self.mazhab = mazhab;
         self
     }
+
+    pub fn maghrib(mut self, maghrib_parameter: MaghribParameter) -> Self {
+        self.maghrib_parameter = maghrib_parameter;
+        self
+    }
+
+    pub fn ishaa_interval_start(mut self, ishaa_interval_start: IshaaIntervalStart) -> Self {
+        self.ishaa_interval_start = ishaa_interval_start;
+        self
+    }
+
+    pub fn asr_factor(mut self, asr_factor: AsrFactor) -> Self {
+        self.asr_factor = Some(asr_factor);
+        self
+    }
+
+    pub fn elevation(mut self, elevation: f64) -> Self {
+        self.elevation = elevation;
+        self
+    }
+
+    /// The angle of the sun's center below the horizon at sunrise and
+    /// sunset: the refracted upper limb on the sea-level horizon, and the
+    /// dip of the horizon at `elevation`.
+    pub fn horizon(&self) -> f64 {
+        let dip = if self.elevation > 0.0 && self.elevation.is_finite() {
+            0.0347 * self.elevation.sqrt()
+        } else {
+            0.0
+        };
+
+        50.0 / 60.0 + dip
+    }
+
+    /// The resolved values of the parameters, see [`ParametersView`].
+    pub fn view(&self) -> ParametersView {
+        ParametersView::from(self)
+    }
+
+    /// The shadow factor used for Asr: `asr_factor` when set,
+    /// otherwise the one of `mazhab`.
+    pub fn shadow_factor(&self) -> AsrFactor {
+        self.asr_factor.unwrap_or(AsrFactor::Mazhab(self.mazhab))
+    }
 }
 
 #[cfg(test)]
@@ -110,6 +316,164 @@ mod tests {
         assert_eq!(params.night_portions().1, 15.0 / 60.0);
     }
 
+    #[test]
+    fn calculated_night_portions_with_rule_per_prayer() {
+        let params = Parameters {
+            fajr_angle: 18.0,
+            ishaa_parameter: IshaaParameter::Angle(12.0),
+            fajr_high_latitude_rule: Some(HighLatitudeRule::SeventhOfTheNight),
+            ishaa_high_latitude_rule: Some(HighLatitudeRule::TwilightAngle),
+            ..Default::default()
+        };
+
+        assert_eq!(params.night_portions().0, 1.0 / 7.0);
+        assert_eq!(params.night_portions().1, 12.0 / 60.0);
+    }
+
+    #[test]
+    fn high_latitude_rule_falls_back_to_shared_rule() {
+        let params = Parameters {
+            high_latitude_rule: HighLatitudeRule::SeventhOfTheNight,
+            ishaa_high_latitude_rule: Some(HighLatitudeRule::MiddleOfTheNight),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            params.high_latitude_rule_for(Prayer::Fajr),
+            HighLatitudeRule::SeventhOfTheNight
+        );
+        assert_eq!(
+            params.high_latitude_rule_for(Prayer::Ishaa),
+            HighLatitudeRule::MiddleOfTheNight
+        );
+        assert_eq!(params.night_portions(), (1.0 / 7.0, 1.0 / 2.0));
+    }
+
+    #[test]
+    fn shadow_factor_defaults_to_mazhab() {
+        let params = Parameters::default().mazhab(Mazhab::Hanafi);
+
+        assert_eq!(params.shadow_factor(), AsrFactor::Mazhab(Mazhab::Hanafi));
+        assert_eq!(
+            params.asr_factor(AsrFactor::Custom(1.5)).shadow_factor(),
+            AsrFactor::Custom(1.5)
+        );
+    }
+
+    #[test]
+    fn validate_presets() {
+        use crate::Method;
+
+        for method in Method::ALL {
+            assert_eq!(method.parameters().validate(), vec![]);
+        }
+    }
+
+    #[test]
+    fn validate_conflicting_parameters() {
+        let params = Parameters {
+            fajr_angle: 18.0,
+            maghrib_parameter: MaghribParameter::Angle(4.0),
+            ishaa_parameter: IshaaParameter::Interval(90),
+            ishaa_high_latitude_rule: Some(HighLatitudeRule::SeventhOfTheNight),
+            twilight: Twilight::Red,
+            ..Default::default()
+        };
+        let issues = params.validate();
+
+        assert_eq!(
+            issues,
+            vec![
+                ParameterIssue::IshaaHighLatitudeRuleIgnored,
+                ParameterIssue::TwilightIgnored,
+            ]
+        );
+        assert!(issues.iter().all(|issue| !issue.is_error()));
+
+        let params = Parameters {
+            fajr_angle: 18.0,
+            ishaa_parameter: IshaaParameter::Angle(17.0),
+            ..Default::default()
+        }
+        .ishaa_interval_start(IshaaIntervalStart::Maghrib);
+
+        assert_eq!(
+            params.validate(),
+            vec![ParameterIssue::IshaaIntervalStartIgnored]
+        );
+    }
+
+    #[test]
+    fn validate_invalid_parameters() {
+        let params = Parameters {
+            fajr_angle: 0.0,
+            ishaa_parameter: IshaaParameter::Angle(95.0),
+            asr_factor: Some(AsrFactor::Custom(-1.0)),
+            ..Default::default()
+        };
+        let issues = params.validate();
+
+        assert_eq!(
+            issues,
+            vec![
+                ParameterIssue::InvalidFajrAngle(0.0),
+                ParameterIssue::InvalidIshaaAngle(95.0),
+                ParameterIssue::InvalidAsrFactor(-1.0),
+            ]
+        );
+        assert!(issues.iter().all(ParameterIssue::is_error));
+    }
+
+    #[test]
+    fn validate_out_of_range_adjustments() {
+        let params = Parameters {
+            adjustments: TimeAdjustment {
+                fajr: -200,
+                asr: 239,
+                ishaa: 240,
+                ..Default::default()
+            },
+            method_adjustments: TimeAdjustment {
+                fajr: -41,
+                ..Default::default()
+            },
+            ..crate::Method::MuslimWorldLeague.parameters()
+        };
+
+        assert_eq!(
+            params.validate(),
+            vec![
+                ParameterIssue::AdjustmentOutOfRange(Prayer::Fajr, -241),
+                ParameterIssue::AdjustmentOutOfRange(Prayer::Ishaa, 240),
+            ]
+        );
+    }
+
+    #[test]
+    fn validate_extreme_adjustments_and_dhuhr_offset() {
+        let params = Parameters {
+            adjustments: TimeAdjustment {
+                fajr: i64::MIN,
+                ..Default::default()
+            },
+            method_adjustments: TimeAdjustment {
+                fajr: -1,
+                ..Default::default()
+            },
+            dhuhr_offset_after_transit: 241,
+            ..crate::Method::MuslimWorldLeague.parameters()
+        };
+
+        assert_eq!(params.time_adjustments(Prayer::Fajr), i64::MIN);
+        assert_eq!(
+            params.validate(),
+            vec![
+                ParameterIssue::AdjustmentOutOfRange(Prayer::Fajr, i64::MIN),
+                ParameterIssue::DhuhrOffsetOutOfRange(241),
+            ]
+        );
+    }
+
     #[test]
     fn parameters_using_method_and_mazhab() {
         let params = Parameters {
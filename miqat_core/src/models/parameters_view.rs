@@ -0,0 +1,116 @@
+use super::high_altitude_rule::HighLatitudeRule;
+use super::ishaa_parameter::IshaaParameter;
+use super::parameters::Parameters;
+use super::prayer::Prayer;
+use super::rounding::Rounding;
+
+/// The values a set of [`Parameters`] resolves to, once overrides,
+/// method behaviors and both sets of adjustments are taken into account.
+/// Meant for settings screens, which should show what is in effect rather
+/// than what was set.
+///
+/// ```
+/// use miqat::prelude::*;
+///
+/// let params = Method::MoonsightingCommittee.parameters();
+/// let view = params.view();
+///
+/// assert_eq!(view.fajr_angle, 18.0);
+/// assert!(view.seasonal_twilight);
+/// assert_eq!(view.adjustment(Prayer::Dhuhr), 5);
+/// ```
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct ParametersView {
+    pub fajr_angle: f64,
+    pub ishaa: IshaaParameter,
+    /// The length of an object's shadow, in addition to its noon shadow,
+    /// at the beginning of Asr.
+    pub asr_shadow_length: f64,
+    pub fajr_high_latitude_rule: HighLatitudeRule,
+    pub ishaa_high_latitude_rule: HighLatitudeRule,
+    /// The portions of the night used for the safe Fajr and Ishaa times,
+    /// see [`Parameters::night_portions`].
+    pub night_portions: (f64, f64),
+    /// Whether the safe Fajr and Ishaa times come from the seasonal
+    /// twilight estimates rather than the portions of the night.
+    pub seasonal_twilight: bool,
+    pub safe_time_clamp: bool,
+    pub dhuhr_offset_after_transit: i64,
+    /// The total adjustments of Fajr, Sunrise, Dhuhr, Asr, Maghrib and
+    /// Ishaa, in minutes.
+    pub adjustments: [i64; 6],
+    pub rounding: Rounding,
+}
+
+impl ParametersView {
+    /// The total adjustment of `prayer` in minutes, see
+    /// [`Parameters::time_adjustments`].
+    pub fn adjustment(&self, prayer: Prayer) -> i64 {
+        match prayer {
+            Prayer::Fajr | Prayer::FajrTomorrow => self.adjustments[0],
+            Prayer::Sunrise => self.adjustments[1],
+            Prayer::Dhuhr => self.adjustments[2],
+            Prayer::Asr => self.adjustments[3],
+            Prayer::Maghrib => self.adjustments[4],
+            Prayer::Ishaa => self.adjustments[5],
+        }
+    }
+}
+
+impl From<&Parameters> for ParametersView {
+    fn from(parameters: &Parameters) -> Self {
+        ParametersView {
+            fajr_angle: parameters.fajr_angle,
+            ishaa: parameters.ishaa_parameter,
+            asr_shadow_length: parameters.shadow_factor().shadow_length(),
+            fajr_high_latitude_rule: parameters.high_latitude_rule_for(Prayer::Fajr),
+            ishaa_high_latitude_rule: parameters.high_latitude_rule_for(Prayer::Ishaa),
+            night_portions: parameters.night_portions(),
+            seasonal_twilight: parameters.uses_seasonal_twilight(),
+            safe_time_clamp: !parameters.disable_safe_time_clamp,
+            dhuhr_offset_after_transit: parameters.dhuhr_offset_after_transit,
+            adjustments: Prayer::ALL.map(|prayer| parameters.time_adjustments(prayer)),
+            rounding: parameters.rounding,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Method;
+    use crate::models::adjustments::TimeAdjustment;
+    use crate::models::asr_factor::AsrFactor;
+    use crate::models::mazhab::Mazhab;
+
+    #[test]
+    fn view_resolves_overrides() {
+        let mut params = Method::MuslimWorldLeague
+            .parameters()
+            .mazhab(Mazhab::Hanafi);
+        params.high_latitude_rule = HighLatitudeRule::SeventhOfTheNight;
+        params.ishaa_high_latitude_rule = Some(HighLatitudeRule::TwilightAngle);
+        params.adjustments = TimeAdjustment {
+            dhuhr: 2,
+            ..Default::default()
+        };
+        let view = params.view();
+
+        assert_eq!(view.asr_shadow_length, 2.0);
+        assert_eq!(
+            view.fajr_high_latitude_rule,
+            HighLatitudeRule::SeventhOfTheNight
+        );
+        assert_eq!(
+            view.ishaa_high_latitude_rule,
+            HighLatitudeRule::TwilightAngle
+        );
+        assert_eq!(view.night_portions, (1.0 / 7.0, 17.0 / 60.0));
+        assert_eq!(view.adjustment(Prayer::Dhuhr), 3);
+        assert!(!view.seasonal_twilight);
+
+        let custom = params.asr_factor(AsrFactor::Custom(1.5)).view();
+
+        assert_eq!(custom.asr_shadow_length, 1.5);
+    }
+}
@@ -1,10 +1,9 @@
+use crate::clock::Clock;
 use chrono::Datelike;
-use chrono::Utc;
 use chrono::Weekday;
-use std::fmt::Debug;
 
 /// Names of all obligatory prayers and sunrise.
-#[derive(PartialEq, Copy, Clone)]
+#[derive(PartialEq, Debug, Copy, Clone)]
 pub enum Prayer {
     Fajr,
     Sunrise,
@@ -15,21 +14,71 @@ pub enum Prayer {
     FajrTomorrow,
 }
 
-impl Debug for Prayer {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl Prayer {
+    /// The times of a day in order, the obligatory prayers and sunrise.
+    pub const ALL: [Prayer; 6] = [
+        Prayer::Fajr,
+        Prayer::Sunrise,
+        Prayer::Dhuhr,
+        Prayer::Asr,
+        Prayer::Maghrib,
+        Prayer::Ishaa,
+    ];
+
+    /// The five obligatory prayers of a day, in order.
+    pub const OBLIGATORY: [Prayer; 5] = [
+        Prayer::Fajr,
+        Prayer::Dhuhr,
+        Prayer::Asr,
+        Prayer::Maghrib,
+        Prayer::Ishaa,
+    ];
+
+    /// The English transliteration of the prayer's name, where Dhuhr
+    /// is called Jumua on Fridays according to `clock`.
+    pub fn name_with(&self, clock: &impl Clock) -> &'static str {
         match self {
-            Prayer::Fajr | Prayer::FajrTomorrow => write!(f, "Fajr"),
-            Prayer::Sunrise => write!(f, "Sunrise"),
+            Prayer::Fajr | Prayer::FajrTomorrow => "Fajr",
+            Prayer::Sunrise => "Sunrise",
             Prayer::Dhuhr => {
-                if Utc::now().weekday() == Weekday::Fri {
-                    write!(f, "Jumua")
+                if clock.now().weekday() == Weekday::Fri {
+                    "Jumua"
                 } else {
-                    write!(f, "Dhuhr")
+                    "Dhuhr"
                 }
             }
-            Prayer::Asr => write!(f, "Asr"),
-            Prayer::Maghrib => write!(f, "Maghrib"),
-            Prayer::Ishaa => write!(f, "Ishaa"),
+            Prayer::Asr => "Asr",
+            Prayer::Maghrib => "Maghrib",
+            Prayer::Ishaa => "Ishaa",
+        }
+    }
+}
+
+impl Prayer {
+    /// A stable, lowercase ASCII name for URLs, topics and databases.
+    pub fn as_slug(&self) -> &'static str {
+        match self {
+            Prayer::Fajr => "fajr",
+            Prayer::Sunrise => "sunrise",
+            Prayer::Dhuhr => "dhuhr",
+            Prayer::Asr => "asr",
+            Prayer::Maghrib => "maghrib",
+            Prayer::Ishaa => "ishaa",
+            Prayer::FajrTomorrow => "fajr_tomorrow",
+        }
+    }
+
+    /// The prayer with the given [slug](Prayer::as_slug).
+    pub fn from_slug(slug: &str) -> Option<Prayer> {
+        match slug {
+            "fajr" => Some(Prayer::Fajr),
+            "sunrise" => Some(Prayer::Sunrise),
+            "dhuhr" => Some(Prayer::Dhuhr),
+            "asr" => Some(Prayer::Asr),
+            "maghrib" => Some(Prayer::Maghrib),
+            "ishaa" => Some(Prayer::Ishaa),
+            "fajr_tomorrow" => Some(Prayer::FajrTomorrow),
+            _ => None,
         }
     }
 }
@@ -37,20 +86,56 @@ impl Debug for Prayer {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::clock::FixedClock;
+    use chrono::TimeZone;
+    use chrono::Utc;
 
     #[test]
-    fn prayer_name_for_fajr_en_transliteration() {
+    fn debug_is_the_variant_name() {
         assert_eq!(format!("{:?}", Prayer::Fajr), "Fajr");
         assert_eq!(format!("{:?}", Prayer::Sunrise), "Sunrise");
-
-        if Utc::now().weekday() == Weekday::Fri {
-            assert_eq!(format!("{:?}", Prayer::Dhuhr), "Jumua");
-        } else {
-            assert_eq!(format!("{:?}", Prayer::Dhuhr), "Dhuhr");
-        }
-
+        assert_eq!(format!("{:?}", Prayer::Dhuhr), "Dhuhr");
         assert_eq!(format!("{:?}", Prayer::Asr), "Asr");
         assert_eq!(format!("{:?}", Prayer::Maghrib), "Maghrib");
         assert_eq!(format!("{:?}", Prayer::Ishaa), "Ishaa");
+        assert_eq!(format!("{:?}", Prayer::FajrTomorrow), "FajrTomorrow");
+    }
+
+    #[test]
+    fn dhuhr_is_jumua_on_fridays() {
+        let thursday = FixedClock(Utc.with_ymd_and_hms(2024, 3, 14, 12, 0, 0).unwrap());
+        let friday = FixedClock(Utc.with_ymd_and_hms(2024, 3, 15, 12, 0, 0).unwrap());
+
+        assert_eq!(Prayer::Dhuhr.name_with(&thursday), "Dhuhr");
+        assert_eq!(Prayer::Dhuhr.name_with(&friday), "Jumua");
+        assert_eq!(Prayer::FajrTomorrow.name_with(&friday), "Fajr");
+    }
+
+    #[test]
+    fn slugs_round_trip() {
+        let prayers = [
+            Prayer::Fajr,
+            Prayer::Sunrise,
+            Prayer::Dhuhr,
+            Prayer::Asr,
+            Prayer::Maghrib,
+            Prayer::Ishaa,
+            Prayer::FajrTomorrow,
+        ];
+
+        for prayer in prayers {
+            assert_eq!(Prayer::from_slug(prayer.as_slug()), Some(prayer));
+        }
+        assert_eq!(Prayer::from_slug("Fajr"), None);
+    }
+
+    #[test]
+    fn obligatory_prayers_leave_out_sunrise() {
+        let obligatory: Vec<Prayer> = Prayer::ALL
+            .into_iter()
+            .filter(|prayer| *prayer != Prayer::Sunrise)
+            .collect();
+
+        assert_eq!(obligatory, Prayer::OBLIGATORY);
     }
 }
@@ -1,7 +1,22 @@
+/// How computed times are rounded to a whole minute, see
+/// [`DateTimeExt::rounded_minute`](crate::time_ext::DateTimeExt::rounded_minute).
+/// More ways of rounding may be added without a breaking release.
 #[derive(PartialEq, Debug, Copy, Clone, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
 pub enum Rounding {
+    /// To the nearest minute, half a minute rounding up.
     #[default]
     Nearest,
+    /// To the next minute, leaving a time on a whole minute alone.
     Ceil,
+    /// To the start of the minute.
+    Floor,
+    /// Kept to the second.
     None,
 }
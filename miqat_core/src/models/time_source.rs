@@ -0,0 +1,55 @@
+use super::high_altitude_rule::HighLatitudeRule;
+use chrono::DateTime;
+use chrono::Utc;
+
+/// How a prayer time was obtained. Anything other than `Astronomical`
+/// is an estimate, typically at high latitudes where the sun does not
+/// get far enough below the horizon; apps may want to flag those times.
+#[derive(PartialEq, Debug, Copy, Clone, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum TimeSource {
+    /// Derived directly from the position of the sun, or from a fixed
+    /// interval defined by the method.
+    #[default]
+    Astronomical,
+
+    /// Replaced by a portion of the night according to the given rule.
+    HighLatitudeRule(HighLatitudeRule),
+
+    /// Replaced by the [seasonal](crate::seasonal) twilight estimate.
+    SeasonalEstimate,
+
+    /// Interpolated between the closest days listed in an
+    /// [official table](crate::precomputed::table).
+    Interpolated,
+}
+
+impl TimeSource {
+    pub fn is_estimated(&self) -> bool {
+        *self != TimeSource::Astronomical
+    }
+}
+
+/// A prayer time together with how it was obtained.
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct TimedValue {
+    pub time: DateTime<Utc>,
+    pub source: TimeSource,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_astronomical_times_are_exact() {
+        assert!(!TimeSource::Astronomical.is_estimated());
+        assert!(TimeSource::SeasonalEstimate.is_estimated());
+        assert!(TimeSource::Interpolated.is_estimated());
+        assert!(TimeSource::HighLatitudeRule(HighLatitudeRule::SeventhOfTheNight).is_estimated());
+    }
+}
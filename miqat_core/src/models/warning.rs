@@ -0,0 +1,100 @@
+use super::parameter_issue::ParameterIssue;
+use super::prayer::Prayer;
+use super::time_source::TimeSource;
+use chrono::NaiveDate;
+use std::fmt;
+
+/// A condition that doesn't prevent a schedule from being used, but that
+/// apps may want to point out, see [`PrayerTimes::warnings`](crate::PrayerTimes::warnings).
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub enum Warning {
+    /// Fajr or Ishaa is not at its twilight angle, either because it was
+    /// clamped to a safe time or because the sun doesn't get that far
+    /// below the horizon.
+    Estimated(Prayer, TimeSource),
+
+    /// Two consecutive prayers are not in chronological order, see
+    /// [`PrayerTimes::ordering_violations`](crate::PrayerTimes::ordering_violations).
+    OutOfOrder(Prayer, Prayer),
+
+    /// A setting is ignored, see [`ParameterIssue`]. Never an error, as
+    /// schedules aren't computed with invalid parameters.
+    IgnoredSetting(ParameterIssue),
+
+    /// The total adjustment of a prayer, in minutes, is beyond
+    /// [`Warnings::LARGE_ADJUSTMENT_MINUTES`], more than is needed to
+    /// follow a published timetable.
+    LargeAdjustment(Prayer, i64),
+
+    /// The date is within [`Warnings::NEAR_LIMIT_YEARS`] of the end of
+    /// [`PrayerTimes::SUPPORTED_YEARS`](crate::PrayerTimes::SUPPORTED_YEARS),
+    /// where the estimates of delta T are the least certain.
+    NearSupportedLimit(NaiveDate),
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Warning::Estimated(prayer, source) => {
+                write!(f, "{} is estimated ({source:?})", prayer.as_slug())
+            }
+            Warning::OutOfOrder(before, after) => {
+                write!(f, "{} is not before {}", before.as_slug(), after.as_slug())
+            }
+            Warning::IgnoredSetting(issue) => write!(f, "{issue}"),
+            Warning::LargeAdjustment(prayer, minutes) => write!(
+                f,
+                "the {} adjustment of {minutes} minutes is unusually large",
+                prayer.as_slug()
+            ),
+            Warning::NearSupportedLimit(date) => write!(
+                f,
+                "{date} is close to the limit of the supported range of dates"
+            ),
+        }
+    }
+}
+
+/// The warnings of a schedule, in a stable order: estimated times, then
+/// prayers out of order, ignored settings, large adjustments and the date.
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct Warnings(Vec<Warning>);
+
+impl Warnings {
+    /// The total adjustment of a prayer, in minutes, past which it is
+    /// reported as [`Warning::LargeAdjustment`].
+    pub const LARGE_ADJUSTMENT_MINUTES: i64 = 30;
+
+    /// How many years from either end of the supported range a date is
+    /// reported as [`Warning::NearSupportedLimit`].
+    pub const NEAR_LIMIT_YEARS: i32 = 10;
+
+    pub(crate) fn push(&mut self, warning: Warning) {
+        self.0.push(warning);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn contains(&self, warning: &Warning) -> bool {
+        self.0.contains(warning)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Warning> {
+        self.0.iter()
+    }
+}
+
+impl IntoIterator for Warnings {
+    type Item = Warning;
+    type IntoIter = std::vec::IntoIter<Warning>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
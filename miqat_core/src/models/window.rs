@@ -0,0 +1,33 @@
+use crate::models::rounding::Rounding;
+use crate::time_ext::DateTimeExt;
+use chrono::DateTime;
+use chrono::Utc;
+
+/// A span of time from `start` up to (excluding) `end`, such as the
+/// window of a sunnah prayer or the night of Eid.
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct Window {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+impl Window {
+    /// Whether `instant` is within the window, from its start up to
+    /// (excluding) its end.
+    pub fn contains(&self, instant: DateTime<Utc>) -> bool {
+        self.start <= instant && instant < self.end
+    }
+
+    /// The window from `start` to `end`, rounded to the nearest minute.
+    /// `None` when it doesn't end after it starts.
+    pub(crate) fn rounded(start: DateTime<Utc>, end: DateTime<Utc>) -> Option<Window> {
+        if start < end {
+            Some(Window {
+                start: start.rounded_minute(Rounding::Nearest),
+                end: end.rounded_minute(Rounding::Nearest),
+            })
+        } else {
+            None
+        }
+    }
+}
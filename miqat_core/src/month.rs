@@ -0,0 +1,207 @@
+//! # Monthly Schedule
+//!
+//! The schedules of a whole month, either a Gregorian month or a Hijri
+//! month. Ramadan timetables are laid out by Hijri day, from the 1st to
+//! the 29th or 30th, so every day carries both of its dates.
+//!
+//! Hijri months follow the calendar of [`HijriDate::from_gregorian`].
+//!
+//! ##### Example
+//!
+//! ```
+//! use miqat::month::MonthlySchedule;
+//! use miqat::prelude::*;
+//!
+//! let beirut = Coordinates::new(33.8938, 35.5018);
+//! let params = Method::MuslimWorldLeague.parameters();
+//! let ramadan = MonthlySchedule::for_hijri_month(1445, 9, beirut, params)
+//!     .unwrap()
+//!     .unwrap();
+//!
+//! assert_eq!(ramadan.days[0].hijri.day, 1);
+//! assert!(ramadan.day(29).is_some());
+//! ```
+
+use crate::astronomy::unit::Coordinates;
+use crate::error::CalculationError;
+use crate::hijri::HijriDate;
+use crate::models::parameters::Parameters;
+use crate::prayer_times::PrayerTimes;
+use chrono::Datelike;
+use chrono::NaiveDate;
+
+/// The schedule of a day of a month, with its Hijri date.
+#[derive(PartialEq, Debug, Clone)]
+pub struct MonthDay {
+    pub hijri: HijriDate,
+    pub times: PrayerTimes,
+}
+
+impl MonthDay {
+    fn new(
+        date: NaiveDate,
+        coordinates: Coordinates,
+        parameters: Parameters,
+    ) -> Result<Self, CalculationError> {
+        Ok(MonthDay {
+            hijri: HijriDate::from_gregorian(date),
+            times: PrayerTimes::try_computed(date, coordinates, parameters)?,
+        })
+    }
+
+    pub fn date(&self) -> NaiveDate {
+        self.times.date()
+    }
+}
+
+/// The schedules of every day of a month, in order.
+#[derive(PartialEq, Debug, Clone)]
+pub struct MonthlySchedule {
+    pub days: Vec<MonthDay>,
+}
+
+impl MonthlySchedule {
+    /// The schedules of the Gregorian `month` of `year`. `None` if the
+    /// month doesn't exist, and an error if one of its days can't be
+    /// computed, see [`PrayerTimes::try_computed`].
+    pub fn for_month(
+        year: i32,
+        month: u32,
+        coordinates: Coordinates,
+        parameters: Parameters,
+    ) -> Result<Option<Self>, CalculationError> {
+        let Some(first) = NaiveDate::from_ymd_opt(year, month, 1) else {
+            return Ok(None);
+        };
+        let days = first
+            .iter_days()
+            .take_while(|date| date.month0() == first.month0())
+            .map(|date| MonthDay::new(date, coordinates, parameters))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Some(MonthlySchedule { days }))
+    }
+
+    /// The schedules of the Hijri `hijri_month` of `hijri_year`, from its
+    /// 1st to its last day. `None` if the month doesn't exist or is out
+    /// of the range of Gregorian dates, and an error if one of its days
+    /// can't be computed, see [`PrayerTimes::try_computed`].
+    pub fn for_hijri_month(
+        hijri_year: i32,
+        hijri_month: u8,
+        coordinates: Coordinates,
+        parameters: Parameters,
+    ) -> Result<Option<Self>, CalculationError> {
+        if !(1..=12).contains(&hijri_month) {
+            return Ok(None);
+        }
+
+        let first = HijriDate {
+            year: hijri_year,
+            month: hijri_month,
+            day: 1,
+        }
+        .to_gregorian();
+        let Some(first) = first else {
+            return Ok(None);
+        };
+        let days = first
+            .date_naive()
+            .iter_days()
+            .take_while(|date| {
+                let hijri = HijriDate::from_gregorian(*date);
+                hijri.year == hijri_year && hijri.month == hijri_month
+            })
+            .map(|date| MonthDay::new(date, coordinates, parameters))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Some(MonthlySchedule { days }))
+    }
+
+    /// The day with the given day of the Hijri month, if the month has
+    /// one. Only meaningful for a schedule of a Hijri month.
+    pub fn day(&self, hijri_day: u8) -> Option<&MonthDay> {
+        self.days.iter().find(|day| day.hijri.day == hijri_day)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Method;
+
+    #[test]
+    fn hijri_month_runs_from_first_to_last_day() {
+        let beirut = Coordinates::new(33.8938, 35.5018);
+        let params = Method::MuslimWorldLeague.parameters();
+        let ramadan = MonthlySchedule::for_hijri_month(1445, 9, beirut, params)
+            .unwrap()
+            .unwrap();
+
+        assert!((29..=30).contains(&ramadan.days.len()));
+        assert_eq!(
+            ramadan.days[0].date(),
+            HijriDate {
+                year: 1445,
+                month: 9,
+                day: 1
+            }
+            .to_gregorian()
+            .unwrap()
+            .date_naive()
+        );
+        for (index, day) in ramadan.days.iter().enumerate() {
+            assert_eq!(day.hijri.month, 9);
+            assert_eq!(day.hijri.day as usize, index + 1);
+        }
+
+        let shawwal = MonthlySchedule::for_hijri_month(1445, 10, beirut, params)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(
+            shawwal.days[0].date(),
+            ramadan.days.last().unwrap().date().succ_opt().unwrap()
+        );
+        assert_eq!(ramadan.day(31), None);
+        assert_eq!(
+            MonthlySchedule::for_hijri_month(1445, 13, beirut, params),
+            Ok(None)
+        );
+    }
+
+    #[test]
+    fn gregorian_month() {
+        let beirut = Coordinates::new(33.8938, 35.5018);
+        let params = Method::MuslimWorldLeague.parameters();
+        let february = MonthlySchedule::for_month(2024, 2, beirut, params)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(february.days.len(), 29);
+        assert_eq!(
+            february.days[0].date(),
+            NaiveDate::from_ymd_opt(2024, 2, 1).unwrap()
+        );
+        assert_eq!(
+            MonthlySchedule::for_month(2024, 13, beirut, params),
+            Ok(None)
+        );
+    }
+
+    #[test]
+    fn days_without_a_schedule_are_errors() {
+        let tromso = Coordinates::new(69.6496, 18.9560);
+        let params = Method::MuslimWorldLeague.parameters();
+
+        assert!(matches!(
+            MonthlySchedule::for_month(2015, 6, tromso, params),
+            Err(CalculationError::NoSunriseOrSunset(_))
+        ));
+        // Ramadan 1436 ran from the 18th of June to the 16th of July 2015.
+        assert!(matches!(
+            MonthlySchedule::for_hijri_month(1436, 9, tromso, params),
+            Err(CalculationError::NoSunriseOrSunset(_))
+        ));
+    }
+}
@@ -0,0 +1,177 @@
+//! # MQTT
+//!
+//! Maps the [scheduler](crate::scheduler)'s events to MQTT messages, so
+//! that home automation, e.g. Home Assistant, can play the adhan on a
+//! speaker. Every prayer has its own topic under a prefix, and the payload
+//! is a small JSON object with the prayer's name and ISO 8601 time. This
+//! module only builds the messages; publishing them is left to whichever
+//! MQTT client the application uses.
+//!
+//! Only available with the `mqtt` feature.
+//!
+//! ##### Example
+//!
+//! ```
+//! use miqat::mqtt;
+//! use miqat::prelude::*;
+//! use miqat::scheduler::Scheduler;
+//!
+//! let raleigh = Coordinates::new(35.7750, -78.6336);
+//! let scheduler = Scheduler::new(raleigh, Method::NorthAmerica.parameters());
+//! let noon = Utc.with_ymd_and_hms(2015, 7, 12, 16, 0, 0).unwrap();
+//! let event = scheduler.events_after(noon).next().unwrap();
+//! let message = mqtt::message("miqat/home", &event);
+//!
+//! assert_eq!(message.topic, "miqat/home/dhuhr");
+//! assert_eq!(
+//!     message.payload,
+//!     r#"{"prayer":"dhuhr","name":"Dhuhr","event":"adhan","time":"2015-07-12T17:21:00+00:00"}"#
+//! );
+//! ```
+
+use crate::clock::FixedClock;
+use crate::models::prayer::Prayer;
+use crate::scheduler::Event;
+use crate::scheduler::EventKind;
+
+#[derive(serde::Serialize)]
+struct Payload<'a> {
+    prayer: &'a str,
+    name: &'a str,
+    event: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    minutes_before: Option<i64>,
+    time: String,
+}
+
+#[derive(serde::Serialize)]
+struct Discovery<'a> {
+    name: &'a str,
+    unique_id: String,
+    device_class: &'a str,
+    state_topic: String,
+    value_template: &'a str,
+}
+
+/// A message ready to be published.
+#[derive(PartialEq, Debug, Clone)]
+pub struct Message {
+    pub topic: String,
+    pub payload: String,
+    pub retain: bool,
+}
+
+/// The message for `event`, published to `<prefix>/<prayer>`. Reminders
+/// go to `<prefix>/<prayer>/reminder` so that automations can tell them
+/// apart from the adhan.
+pub fn message(prefix: &str, event: &Event) -> Message {
+    let prayer = event.prayer.as_slug();
+    let (topic, kind, minutes_before) = match event.kind {
+        EventKind::Adhan => (format!("{prefix}/{prayer}"), "adhan", None),
+        EventKind::Reminder(before) => (
+            format!("{prefix}/{prayer}/reminder"),
+            "reminder",
+            Some(before.num_minutes()),
+        ),
+    };
+    let name = event.prayer.name_with(&FixedClock(event.prayer_time));
+    let payload = Payload {
+        prayer,
+        name,
+        event: kind,
+        minutes_before,
+        time: event.prayer_time.to_rfc3339(),
+    };
+
+    Message {
+        topic,
+        payload: to_json(&payload),
+        retain: false,
+    }
+}
+
+/// The retained Home Assistant discovery messages declaring a timestamp
+/// sensor per prayer, whose state is the time of the prayer's messages
+/// under `prefix`. `node_id` identifies this device to Home Assistant.
+pub fn home_assistant_discovery(prefix: &str, node_id: &str) -> Vec<Message> {
+    Prayer::OBLIGATORY
+        .iter()
+        .map(|prayer| {
+            let prayer = prayer.as_slug();
+            let discovery = Discovery {
+                name: prayer,
+                unique_id: format!("{node_id}_{prayer}"),
+                device_class: "timestamp",
+                state_topic: format!("{prefix}/{prayer}"),
+                value_template: "{{ value_json.time }}",
+            };
+
+            Message {
+                topic: format!("homeassistant/sensor/{node_id}/{prayer}/config"),
+                payload: to_json(&discovery),
+                retain: true,
+            }
+        })
+        .collect()
+}
+
+fn to_json(payload: &impl serde::Serialize) -> String {
+    // Only strings and numbers, which always serialize.
+    serde_json::to_string(payload).expect("payloads serialize to JSON")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use chrono::TimeZone;
+    use chrono::Utc;
+
+    #[test]
+    fn reminders_and_jumua() {
+        let friday = Utc.with_ymd_and_hms(2024, 3, 15, 11, 30, 0).unwrap();
+        let event = Event {
+            prayer: Prayer::Dhuhr,
+            prayer_time: friday,
+            kind: EventKind::Reminder(Duration::minutes(10)),
+        };
+        let message = mqtt_message(&event);
+
+        assert_eq!(message.topic, "home/dhuhr/reminder");
+        assert_eq!(
+            message.payload,
+            r#"{"prayer":"dhuhr","name":"Jumua","event":"reminder","minutes_before":10,"time":"2024-03-15T11:30:00+00:00"}"#
+        );
+    }
+
+    #[test]
+    fn names_and_prefixes_are_escaped() {
+        let event = Event {
+            prayer: Prayer::Fajr,
+            prayer_time: Utc.with_ymd_and_hms(2024, 3, 14, 3, 0, 0).unwrap(),
+            kind: EventKind::Adhan,
+        };
+        let discovery = &home_assistant_discovery(r#"a"b"#, "miqat")[0];
+        let payload: serde_json::Value = serde_json::from_str(&discovery.payload).unwrap();
+
+        assert_eq!(payload["state_topic"], r#"a"b/fajr"#);
+        assert!(serde_json::from_str::<serde_json::Value>(&mqtt_message(&event).payload).is_ok());
+    }
+
+    #[test]
+    fn discovery_declares_a_sensor_per_prayer() {
+        let messages = home_assistant_discovery("home", "miqat");
+
+        assert_eq!(messages.len(), 5);
+        assert!(messages.iter().all(|message| message.retain));
+        assert_eq!(messages[0].topic, "homeassistant/sensor/miqat/fajr/config");
+        assert_eq!(
+            messages[0].payload,
+            r#"{"name":"fajr","unique_id":"miqat_fajr","device_class":"timestamp","state_topic":"home/fajr","value_template":"{{ value_json.time }}"}"#
+        );
+    }
+
+    fn mqtt_message(event: &Event) -> Message {
+        message("home", event)
+    }
+}
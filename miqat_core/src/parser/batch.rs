@@ -0,0 +1,163 @@
+use super::Config;
+use super::ConfigError;
+use super::DateDto;
+use super::RootDto;
+use crate::prayer_times::PrayerTimes;
+use chrono::NaiveDate;
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+
+/// One calculation of a batch file: the settings of a location, and the
+/// range of dates from the settings' `date` to `until`.
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct BatchRequestDto {
+    /// Identifies the results, e.g. a branch of a mosque network.
+    pub id: String,
+    pub config: RootDto,
+    /// The last date to calculate for, inclusive. Only the settings'
+    /// `date` when absent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub until: Option<DateDto>,
+}
+
+/// A resolved calculation of a batch.
+#[derive(PartialEq, Debug, Clone)]
+pub struct BatchRequest {
+    pub id: String,
+    pub config: Config,
+    pub from: NaiveDate,
+    pub until: NaiveDate,
+}
+
+/// Reads a batch file, a JSON array of [requests](BatchRequestDto).
+pub fn parse_batch(json: &str) -> Result<Vec<BatchRequest>, ConfigError> {
+    let requests: Vec<BatchRequestDto> =
+        serde_json::from_str(json).map_err(|error| ConfigError::Syntax(error.to_string()))?;
+    let mut ids = BTreeSet::new();
+
+    requests
+        .into_iter()
+        .map(|request| {
+            if !ids.insert(request.id.clone()) {
+                return Err(ConfigError::DuplicateId(request.id));
+            } else {
+                // Nothing to do.
+            }
+
+            let config = Config::try_from(request.config)?;
+            let from = config
+                .date
+                .ok_or_else(|| ConfigError::MissingDate(request.id.clone()))?;
+            let until = match request.until {
+                Some(until) => until.to_utc_date().ok_or(ConfigError::InvalidDate(until))?,
+                None => from,
+            };
+
+            if until < from {
+                return Err(ConfigError::InvalidRange(request.id));
+            } else {
+                // Nothing to do.
+            }
+
+            Ok(BatchRequest {
+                id: request.id,
+                config,
+                from,
+                until,
+            })
+        })
+        .collect()
+}
+
+/// Calculates every request of a batch file, keyed by id.
+///
+/// ##### Example
+///
+/// ```
+/// use miqat::parser::batch;
+///
+/// let results = batch::calculate_batch(
+///     r#"[
+///         {
+///             "id": "beirut",
+///             "config": {
+///                 "location": { "latitude": 33.89, "longitude": 35.50 },
+///                 "date": { "year": 2024, "month": 3, "day": 1 }
+///             },
+///             "until": { "year": 2024, "month": 3, "day": 31 }
+///         },
+///         {
+///             "id": "tripoli",
+///             "config": {
+///                 "location": { "latitude": 34.43, "longitude": 35.84 },
+///                 "date": { "year": 2024, "month": 3, "day": 15 }
+///             }
+///         }
+///     ]"#,
+/// )
+/// .unwrap();
+///
+/// assert_eq!(results["beirut"].len(), 31);
+/// assert_eq!(results["tripoli"].len(), 1);
+/// ```
+pub fn calculate_batch(json: &str) -> Result<BTreeMap<String, Vec<PrayerTimes>>, ConfigError> {
+    parse_batch(json)?
+        .into_iter()
+        .map(|request| {
+            let Config {
+                coordinates,
+                parameters,
+                ..
+            } = request.config;
+            let days = request
+                .from
+                .iter_days()
+                .take_while(|date| *date <= request.until)
+                .map(|date| PrayerTimes::try_computed(date, coordinates, parameters))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(ConfigError::Calculation)?;
+
+            Ok((request.id, days))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_inconsistent_batches() {
+        let request = |id: &str, until: u32| {
+            format!(
+                r#"{{
+                    "id": "{id}",
+                    "config": {{
+                        "location": {{ "latitude": 0, "longitude": 0 }},
+                        "date": {{ "year": 2024, "month": 3, "day": 10 }}
+                    }},
+                    "until": {{ "year": 2024, "month": 3, "day": {until} }}
+                }}"#
+            )
+        };
+
+        assert_eq!(
+            parse_batch(&format!("[{}, {}]", request("a", 11), request("a", 12))),
+            Err(ConfigError::DuplicateId("a".to_string()))
+        );
+        assert_eq!(
+            parse_batch(&format!("[{}]", request("a", 9))),
+            Err(ConfigError::InvalidRange("a".to_string()))
+        );
+        assert_eq!(
+            parse_batch(
+                r#"[{ "id": "a", "config": { "location": { "latitude": 0, "longitude": 0 } } }]"#
+            ),
+            Err(ConfigError::MissingDate("a".to_string()))
+        );
+    }
+}
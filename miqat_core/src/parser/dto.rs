@@ -0,0 +1,235 @@
+use crate::format::Digits;
+use crate::locale::Locale;
+use crate::models::adjustments::TimeAdjustment;
+use crate::models::high_altitude_rule::HighLatitudeRule;
+use crate::models::ishaa_parameter::IshaaParameter;
+use crate::models::maghrib_parameter::MaghribParameter;
+use crate::models::mazhab::Mazhab;
+use crate::models::method::Method;
+use crate::models::rounding::Rounding;
+use chrono::NaiveDate;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// A config file as written by users.
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct RootDto {
+    pub location: LocationDto,
+    /// The calculation settings, alongside the location.
+    #[serde(flatten)]
+    pub parameters: ParametersDto,
+    /// The date to calculate for, today when absent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date: Option<DateDto>,
+    /// The timezone to show times in, an IANA name such as `Asia/Beirut`
+    /// or a fixed offset such as `+03:00`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+}
+
+/// The calculation settings of a config file on their own, as taken by
+/// [`Parameters::from_json`](crate::Parameters::from_json).
+#[derive(PartialEq, Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct ParametersDto {
+    #[serde(default)]
+    pub method: Method,
+    /// Overrides the method's mazhab.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mazhab: Option<Mazhab>,
+    /// Overrides the method's high latitude rule.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub high_latitude_rule: Option<HighLatitudeRuleDto>,
+    /// Minutes added to each prayer, on top of the method's own.
+    #[serde(default)]
+    pub adjustments: TimeAdjustment,
+    /// Overrides the method's Fajr angle, in degrees below the horizon.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fajr_angle: Option<f64>,
+    /// Overrides the method's Maghrib.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub maghrib: Option<MaghribDto>,
+    /// Overrides the method's Ishaa.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ishaa: Option<IshaaDto>,
+    /// How times are rounded, to the nearest minute when absent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rounding: Option<Rounding>,
+    /// The height in metres above the surrounding terrain, sea level when
+    /// absent, see [`Parameters::elevation`](crate::Parameters::elevation).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub elevation_m: Option<f64>,
+}
+
+/// When Maghrib is, e.g. `"sunset"` or `{ "angle": 4.5 }`.
+#[derive(PartialEq, Debug, Copy, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum MaghribDto {
+    Sunset,
+    /// Degrees below the horizon.
+    Angle(f64),
+}
+
+impl From<MaghribDto> for MaghribParameter {
+    fn from(maghrib: MaghribDto) -> Self {
+        match maghrib {
+            MaghribDto::Sunset => MaghribParameter::Sunset,
+            MaghribDto::Angle(angle) => MaghribParameter::Angle(angle),
+        }
+    }
+}
+
+/// When Ishaa is, e.g. `{ "angle": 17 }` or `{ "interval": 90 }`.
+#[derive(PartialEq, Debug, Copy, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum IshaaDto {
+    /// Degrees below the horizon.
+    Angle(f64),
+    /// Minutes after Maghrib.
+    Interval(i32),
+}
+
+impl From<IshaaDto> for IshaaParameter {
+    fn from(ishaa: IshaaDto) -> Self {
+        match ishaa {
+            IshaaDto::Angle(angle) => IshaaParameter::Angle(angle),
+            IshaaDto::Interval(minutes) => IshaaParameter::Interval(minutes),
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Copy, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct LocationDto {
+    /// Degrees north of the equator, -90 to 90.
+    pub latitude: f64,
+    /// Degrees east of the prime meridian, -180 to 180.
+    pub longitude: f64,
+}
+
+#[derive(PartialEq, Debug, Copy, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum HighLatitudeRuleDto {
+    MiddleOfTheNight,
+    SeventhOfTheNight,
+    TwilightAngle,
+}
+
+impl From<HighLatitudeRuleDto> for HighLatitudeRule {
+    fn from(rule: HighLatitudeRuleDto) -> Self {
+        match rule {
+            HighLatitudeRuleDto::MiddleOfTheNight => HighLatitudeRule::MiddleOfTheNight,
+            HighLatitudeRuleDto::SeventhOfTheNight => HighLatitudeRule::SeventhOfTheNight,
+            HighLatitudeRuleDto::TwilightAngle => HighLatitudeRule::TwilightAngle,
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Copy, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct DateDto {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+}
+
+impl DateDto {
+    /// The date, if it exists in the Gregorian calendar.
+    pub fn to_utc_date(&self) -> Option<NaiveDate> {
+        NaiveDate::from_ymd_opt(self.year, self.month, self.day)
+    }
+}
+
+/// When the iqamah of each prayer is, as a default rule and the prayers
+/// that differ from it.
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct IqamahDto {
+    /// The rule of the prayers without one of their own.
+    pub default: IqamahRuleDto,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fajr: Option<IqamahRuleDto>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dhuhr: Option<IqamahRuleDto>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub asr: Option<IqamahRuleDto>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub maghrib: Option<IqamahRuleDto>,
+    #[serde(default, skip_serializing_if = "Option::is_none", alias = "isha")]
+    pub ishaa: Option<IqamahRuleDto>,
+    /// Dhuhr's rule on Fridays, the start of the khutbah.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jumua: Option<IqamahRuleDto>,
+}
+
+/// The iqamah of a prayer, e.g. `{ "after": 10 }`.
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum IqamahRuleDto {
+    /// A local clock time, e.g. `"13:30"`.
+    Fixed(String),
+    /// Minutes after the adhan.
+    After(i64),
+    /// Minutes after the adhan, rounded to the nearest quarter hour.
+    Rounded(i64),
+}
+
+/// How a profile's times are written.
+#[derive(PartialEq, Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct DisplayDto {
+    /// A [template](crate::format), every column when absent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+    #[serde(default)]
+    pub digits: DigitsDto,
+    #[serde(default)]
+    pub language: LanguageDto,
+}
+
+#[derive(PartialEq, Debug, Copy, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum DigitsDto {
+    #[default]
+    Western,
+    ArabicIndic,
+}
+
+impl From<DigitsDto> for Digits {
+    fn from(digits: DigitsDto) -> Self {
+        match digits {
+            DigitsDto::Western => Digits::Western,
+            DigitsDto::ArabicIndic => Digits::ArabicIndic,
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Copy, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum LanguageDto {
+    #[default]
+    English,
+    Arabic,
+}
+
+impl From<LanguageDto> for Locale {
+    fn from(language: LanguageDto) -> Self {
+        match language {
+            LanguageDto::English => Locale::English,
+            LanguageDto::Arabic => Locale::Arabic,
+        }
+    }
+}
@@ -0,0 +1,365 @@
+use super::Config;
+use super::ConfigError;
+use super::DateDto;
+use super::HighLatitudeRuleDto;
+use super::LocationDto;
+use super::ParametersDto;
+use super::RootDto;
+use crate::models::adjustments::TimeAdjustment;
+use crate::models::mazhab::Mazhab;
+use crate::models::method::Method;
+use chrono::Datelike;
+use chrono::NaiveDate;
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+
+/// The prefix of the environment variables read by [`load`].
+pub const ENV_PREFIX: &str = "AZAN_";
+
+/// Where a setting's value came from.
+#[derive(PartialEq, Debug, Copy, Clone, Default)]
+pub enum Source {
+    /// Not set anywhere; the method's or the library's default.
+    #[default]
+    Default,
+
+    File,
+
+    /// The environment variable with the given name.
+    Environment(&'static str),
+
+    /// An explicit override, e.g. a command line flag.
+    Override,
+}
+
+/// The source of each setting of a loaded config.
+#[derive(PartialEq, Debug, Copy, Clone, Default)]
+pub struct Provenance {
+    pub latitude: Source,
+    pub longitude: Source,
+    pub method: Source,
+    pub mazhab: Source,
+    pub high_latitude_rule: Source,
+    pub adjustments: Source,
+    pub date: Source,
+    pub elevation_m: Source,
+    pub timezone: Source,
+}
+
+/// Settings given explicitly, which take precedence over both the file
+/// and the environment.
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct Overrides {
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub method: Option<Method>,
+    pub mazhab: Option<Mazhab>,
+    pub high_latitude_rule: Option<HighLatitudeRuleDto>,
+    pub adjustments: Option<TimeAdjustment>,
+    pub date: Option<DateDto>,
+    pub elevation_m: Option<f64>,
+    pub timezone: Option<String>,
+}
+
+/// A config along with where each of its settings came from.
+#[derive(PartialEq, Debug, Clone)]
+pub struct LayeredConfig {
+    pub config: Config,
+    pub provenance: Provenance,
+}
+
+// The file format with every setting optional, so that the environment
+// or the overrides can supply what the file leaves out.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FileLayer {
+    location: Option<LocationLayer>,
+    method: Option<Method>,
+    mazhab: Option<Mazhab>,
+    high_latitude_rule: Option<HighLatitudeRuleDto>,
+    adjustments: Option<TimeAdjustment>,
+    date: Option<DateDto>,
+    elevation_m: Option<f64>,
+    timezone: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LocationLayer {
+    latitude: Option<f64>,
+    longitude: Option<f64>,
+}
+
+/// Loads a config from the JSON `file`, if any, overridden by the
+/// `AZAN_*` variables of `env`, overridden in turn by `overrides`.
+///
+/// The variables are `AZAN_LAT`, `AZAN_LON`, `AZAN_METHOD`,
+/// `AZAN_MAZHAB` and `AZAN_HIGH_LATITUDE_RULE`, spelled as in the file,
+/// `AZAN_ADJUSTMENTS`, as in `fajr:-3,ishaa:+5`, `AZAN_DATE`, as in
+/// `2024-03-15`, `AZAN_ELEVATION_M`, in meters, and `AZAN_TIMEZONE`, as
+/// in `Asia/Beirut`.
+///
+/// ##### Example
+///
+/// ```
+/// use miqat::parser::layered::{self, Overrides, Source};
+///
+/// let file = r#"{ "location": { "latitude": 33.89, "longitude": 35.50 } }"#;
+/// let env = [("AZAN_METHOD".to_string(), "egyptian".to_string())];
+/// let overrides = Overrides {
+///     latitude: Some(34.43),
+///     ..Default::default()
+/// };
+/// let loaded = layered::load(Some(file), env, overrides).unwrap();
+///
+/// assert_eq!(loaded.config.coordinates.latitude, 34.43);
+/// assert_eq!(loaded.provenance.latitude, Source::Override);
+/// assert_eq!(loaded.provenance.longitude, Source::File);
+/// assert_eq!(loaded.provenance.method, Source::Environment("AZAN_METHOD"));
+/// ```
+pub fn load(
+    file: Option<&str>,
+    env: impl IntoIterator<Item = (String, String)>,
+    overrides: Overrides,
+) -> Result<LayeredConfig, ConfigError> {
+    let mut layers = Overrides::default();
+    let mut provenance = Provenance::default();
+
+    if let Some(file) = file {
+        let file: FileLayer =
+            serde_json::from_str(file).map_err(|error| ConfigError::Syntax(error.to_string()))?;
+        let location = file.location.unwrap_or(LocationLayer {
+            latitude: None,
+            longitude: None,
+        });
+
+        layers.apply(
+            Overrides {
+                latitude: location.latitude,
+                longitude: location.longitude,
+                method: file.method,
+                mazhab: file.mazhab,
+                high_latitude_rule: file.high_latitude_rule,
+                adjustments: file.adjustments,
+                date: file.date,
+                elevation_m: file.elevation_m,
+                timezone: file.timezone,
+            },
+            &mut provenance,
+            |_| Source::File,
+        );
+    } else {
+        // Nothing to do.
+    }
+
+    let env_layer = from_env(env)?;
+    layers.apply(env_layer, &mut provenance, Source::Environment);
+    layers.apply(overrides, &mut provenance, |_| Source::Override);
+
+    let (Some(latitude), Some(longitude)) = (layers.latitude, layers.longitude) else {
+        return Err(ConfigError::MissingLocation);
+    };
+    let root = RootDto {
+        location: LocationDto {
+            latitude,
+            longitude,
+        },
+        parameters: ParametersDto {
+            method: layers.method.unwrap_or_default(),
+            mazhab: layers.mazhab,
+            high_latitude_rule: layers.high_latitude_rule,
+            adjustments: layers.adjustments.unwrap_or_default(),
+            elevation_m: layers.elevation_m,
+            ..Default::default()
+        },
+        date: layers.date,
+        timezone: layers.timezone,
+    };
+
+    Ok(LayeredConfig {
+        config: Config::try_from(root)?,
+        provenance,
+    })
+}
+
+/// Like [`load`], reading the variables of the process's environment.
+pub fn load_from_env(
+    file: Option<&str>,
+    overrides: Overrides,
+) -> Result<LayeredConfig, ConfigError> {
+    load(file, std::env::vars(), overrides)
+}
+
+impl Overrides {
+    /// Overrides the settings that `layer` sets. `source` gives the
+    /// source to record for a setting from the name of its environment
+    /// variable.
+    fn apply(
+        &mut self,
+        layer: Overrides,
+        provenance: &mut Provenance,
+        source: impl Fn(&'static str) -> Source,
+    ) {
+        macro_rules! apply {
+            ($($field:ident => $variable:literal),* $(,)?) => {
+                $(
+                    if layer.$field.is_some() {
+                        self.$field = layer.$field;
+                        provenance.$field = source($variable);
+                    } else {
+                        // Nothing to do.
+                    }
+                )*
+            };
+        }
+
+        apply! {
+            latitude => "AZAN_LAT",
+            longitude => "AZAN_LON",
+            method => "AZAN_METHOD",
+            mazhab => "AZAN_MAZHAB",
+            high_latitude_rule => "AZAN_HIGH_LATITUDE_RULE",
+            adjustments => "AZAN_ADJUSTMENTS",
+            date => "AZAN_DATE",
+            elevation_m => "AZAN_ELEVATION_M",
+            timezone => "AZAN_TIMEZONE",
+        }
+    }
+}
+
+fn from_env(env: impl IntoIterator<Item = (String, String)>) -> Result<Overrides, ConfigError> {
+    let mut layer = Overrides::default();
+
+    for (name, value) in env {
+        let Some(key) = name.strip_prefix(ENV_PREFIX) else {
+            continue;
+        };
+        let invalid = || ConfigError::InvalidVariable {
+            name: name.clone(),
+            value: value.clone(),
+        };
+
+        match key {
+            "LAT" => layer.latitude = Some(value.trim().parse().map_err(|_| invalid())?),
+            "LON" => layer.longitude = Some(value.trim().parse().map_err(|_| invalid())?),
+            "METHOD" => layer.method = Some(variant(&value).ok_or_else(invalid)?),
+            "MAZHAB" => layer.mazhab = Some(variant(&value).ok_or_else(invalid)?),
+            "HIGH_LATITUDE_RULE" => {
+                layer.high_latitude_rule = Some(variant(&value).ok_or_else(invalid)?)
+            }
+            "ADJUSTMENTS" => layer.adjustments = Some(value.parse().map_err(|_| invalid())?),
+            "ELEVATION_M" => layer.elevation_m = Some(value.trim().parse().map_err(|_| invalid())?),
+            "TIMEZONE" => layer.timezone = Some(value.trim().to_string()),
+            "DATE" => {
+                let date =
+                    NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d").map_err(|_| invalid())?;
+                layer.date = Some(DateDto {
+                    year: date.year(),
+                    month: date.month(),
+                    day: date.day(),
+                });
+            }
+            _ => {
+                // Nothing to do.
+            }
+        }
+    }
+
+    Ok(layer)
+}
+
+/// The enum variant spelled `value` in the file format.
+fn variant<T: DeserializeOwned>(value: &str) -> Option<T> {
+    serde_json::from_value(serde_json::Value::String(value.trim().to_string())).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env(variables: &[(&str, &str)]) -> Vec<(String, String)> {
+        variables
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn environment_alone_is_enough() {
+        let variables = env(&[
+            ("AZAN_LAT", "21.42"),
+            ("AZAN_LON", "39.83"),
+            ("AZAN_MAZHAB", "hanafi"),
+            ("AZAN_ADJUSTMENTS", "ishaa:+30"),
+            ("AZAN_DATE", "2024-03-15"),
+            ("HOME", "/root"),
+        ]);
+        let loaded = load(None, variables, Overrides::default()).unwrap();
+
+        assert_eq!(loaded.config.parameters.mazhab, Mazhab::Hanafi);
+        assert_eq!(loaded.config.parameters.adjustments.ishaa, 30);
+        assert_eq!(loaded.config.date, NaiveDate::from_ymd_opt(2024, 3, 15));
+        assert_eq!(loaded.provenance.method, Source::Default);
+        assert_eq!(loaded.provenance.date, Source::Environment("AZAN_DATE"));
+    }
+
+    #[test]
+    fn overrides_win_over_the_environment_and_the_file() {
+        let file = r#"{ "location": { "latitude": 0, "longitude": 0 }, "mazhab": "shafi" }"#;
+        let overrides = Overrides {
+            mazhab: Some(Mazhab::Hanafi),
+            ..Default::default()
+        };
+        let loaded = load(Some(file), env(&[("AZAN_LAT", "10")]), overrides).unwrap();
+
+        assert_eq!(loaded.config.coordinates.latitude, 10.0);
+        assert_eq!(loaded.config.parameters.mazhab, Mazhab::Hanafi);
+        assert_eq!(loaded.provenance.mazhab, Source::Override);
+        assert_eq!(loaded.provenance.latitude, Source::Environment("AZAN_LAT"));
+    }
+
+    #[test]
+    fn elevation_and_timezone_from_every_layer() {
+        let file = r#"{
+            "location": { "latitude": 33.89, "longitude": 35.50 },
+            "elevation_m": 900,
+            "timezone": "Asia/Beirut"
+        }"#;
+        let loaded = load(Some(file), env(&[]), Overrides::default()).unwrap();
+
+        assert_eq!(loaded.config.parameters.elevation, 900.0);
+        assert_eq!(loaded.config.timezone.as_deref(), Some("Asia/Beirut"));
+        assert_eq!(loaded.provenance.elevation_m, Source::File);
+        assert_eq!(loaded.provenance.timezone, Source::File);
+
+        let variables = env(&[("AZAN_ELEVATION_M", "1200"), ("AZAN_TIMEZONE", "UTC")]);
+        let overrides = Overrides {
+            timezone: Some("Asia/Amman".to_string()),
+            ..Default::default()
+        };
+        let loaded = load(Some(file), variables, overrides).unwrap();
+
+        assert_eq!(loaded.config.parameters.elevation, 1200.0);
+        assert_eq!(loaded.config.timezone.as_deref(), Some("Asia/Amman"));
+        assert_eq!(
+            loaded.provenance.elevation_m,
+            Source::Environment("AZAN_ELEVATION_M")
+        );
+        assert_eq!(loaded.provenance.timezone, Source::Override);
+    }
+
+    #[test]
+    fn reports_invalid_and_missing_settings() {
+        assert_eq!(
+            load(None, env(&[("AZAN_LAT", "north")]), Overrides::default()),
+            Err(ConfigError::InvalidVariable {
+                name: "AZAN_LAT".to_string(),
+                value: "north".to_string(),
+            })
+        );
+        assert_eq!(
+            load(None, env(&[("AZAN_LAT", "10")]), Overrides::default()),
+            Err(ConfigError::MissingLocation)
+        );
+    }
+}
@@ -0,0 +1,706 @@
+//! # Config Parser
+//!
+//! Reads calculation settings from JSON config files, as used by command
+//! line tools and daemons. The file format is described by the
+//! [DTOs](RootDto), and is resolved into a [`Config`] of library types.
+//!
+//! A [`batch`] file lists several calculations, e.g. the timetables of
+//! every branch of a mosque network, to run in one go.
+//!
+//! A [`profile`] describes a mosque: its location and settings, when its
+//! iqamah is, and how its timetable is written. A directory of profiles
+//! can be loaded into a store, to serve several mosques at once.
+//!
+//! The [`layered`] loader combines a file with environment variables
+//! and explicit overrides, and tracks where each setting came from.
+//!
+//! With the `schemars` feature, [`json_schema`] describes the format for
+//! editors and front-ends; a generated copy ships as `config.schema.json`
+//! next to this module.
+//!
+//! Only available with the `parser` feature.
+//!
+//! ##### Example
+//!
+//! ```
+//! use miqat::parser::Config;
+//! use miqat::prelude::*;
+//!
+//! let config = Config::from_json(
+//!     r#"{
+//!         "location": { "latitude": 35.7750, "longitude": -78.6336 },
+//!         "method": "north_america",
+//!         "mazhab": "hanafi",
+//!         "adjustments": { "fajr": -2 }
+//!     }"#,
+//! )
+//! .unwrap();
+//!
+//! assert_eq!(config.parameters.mazhab, Mazhab::Hanafi);
+//! assert_eq!(config.parameters.adjustments.fajr, -2);
+//! assert!(config.validate().is_empty());
+//! ```
+
+pub mod batch;
+mod dto;
+pub mod layered;
+pub mod profile;
+
+pub use dto::DateDto;
+pub use dto::DigitsDto;
+pub use dto::DisplayDto;
+pub use dto::HighLatitudeRuleDto;
+pub use dto::IqamahDto;
+pub use dto::IqamahRuleDto;
+pub use dto::IshaaDto;
+pub use dto::LanguageDto;
+pub use dto::LocationDto;
+pub use dto::MaghribDto;
+pub use dto::ParametersDto;
+pub use dto::RootDto;
+
+use crate::astronomy::unit::Coordinates;
+use crate::error::CalculationError;
+use crate::format::TemplateError;
+use crate::models::parameter_issue::ParameterIssue;
+use crate::models::parameters::Parameters;
+use crate::models::prayer::Prayer;
+use crate::prayer_times::PrayerTimes;
+use chrono::Datelike;
+use chrono::NaiveDate;
+use chrono::TimeZone;
+use std::fmt;
+use std::str::FromStr;
+
+/// Adjustments beyond this many minutes are reported by
+/// [`Config::validate`]. Adjustments reaching
+/// [`TimeAdjustment::MAX_MINUTES`](crate::TimeAdjustment::MAX_MINUTES)
+/// are rejected.
+pub const MAX_REASONABLE_ADJUSTMENT: i64 = 60;
+
+/// Settings resolved from a config file.
+#[derive(PartialEq, Debug, Clone)]
+pub struct Config {
+    pub coordinates: Coordinates,
+    pub parameters: Parameters,
+    pub date: Option<NaiveDate>,
+    /// The timezone to show times in, an IANA name or a fixed offset, see
+    /// [`zone`](Config::zone).
+    pub timezone: Option<String>,
+}
+
+impl Config {
+    pub fn from_json(json: &str) -> Result<Config, ConfigError> {
+        let root: RootDto =
+            serde_json::from_str(json).map_err(|error| ConfigError::Syntax(error.to_string()))?;
+
+        Config::try_from(root)
+    }
+
+    /// The timezone to show times in, read by the timezone type of the
+    /// caller, and `None` when the config has none. IANA names need
+    /// [`Tz`](crate::Tz) from the `timezones` feature, while
+    /// [`FixedOffset`](chrono::FixedOffset) reads offsets such as `+03:00`.
+    /// Schedules are read in it with [`PrayerTimes::localized`].
+    pub fn zone<Tz: TimeZone + FromStr>(&self) -> Result<Option<Tz>, ConfigError> {
+        match &self.timezone {
+            Some(name) => name
+                .parse()
+                .map(Some)
+                .map_err(|_| ConfigError::InvalidTimezone(name.clone())),
+            None => Ok(None),
+        }
+    }
+
+    /// Problems with the settings that don't prevent calculating, but
+    /// likely give wrong times.
+    pub fn validate(&self) -> Vec<ConfigWarning> {
+        let mut warnings = Vec::new();
+
+        if !(-90.0..=90.0).contains(&self.coordinates.latitude) {
+            warnings.push(ConfigWarning::LatitudeOutOfRange(self.coordinates.latitude));
+        } else {
+            // Nothing to do.
+        }
+
+        if !(-180.0..=180.0).contains(&self.coordinates.longitude) {
+            warnings.push(ConfigWarning::LongitudeOutOfRange(
+                self.coordinates.longitude,
+            ));
+        } else {
+            // Nothing to do.
+        }
+
+        match self.date {
+            Some(date) if !PrayerTimes::SUPPORTED_YEARS.contains(&date.year()) => {
+                warnings.push(ConfigWarning::DateOutOfRange(date));
+            }
+            _ => {
+                // Nothing to do.
+            }
+        }
+
+        let adjustments = self.parameters.adjustments;
+        let largest = [
+            adjustments.fajr,
+            adjustments.sunrise,
+            adjustments.dhuhr,
+            adjustments.asr,
+            adjustments.maghrib,
+            adjustments.ishaa,
+        ]
+        .into_iter()
+        .max_by_key(|minutes| minutes.unsigned_abs())
+        .unwrap_or_default();
+
+        if largest.unsigned_abs() > MAX_REASONABLE_ADJUSTMENT.unsigned_abs() {
+            warnings.push(ConfigWarning::LargeAdjustment(largest));
+        } else {
+            // Nothing to do.
+        }
+
+        warnings.extend(
+            self.parameters
+                .validate()
+                .into_iter()
+                .map(ConfigWarning::Parameter),
+        );
+
+        warnings
+    }
+}
+
+impl TryFrom<RootDto> for Config {
+    type Error = ConfigError;
+
+    fn try_from(root: RootDto) -> Result<Config, ConfigError> {
+        let parameters = Parameters::try_from(root.parameters)?;
+        let date = match root.date {
+            Some(date) => Some(date.to_utc_date().ok_or(ConfigError::InvalidDate(date))?),
+            None => None,
+        };
+
+        Ok(Config {
+            coordinates: Coordinates::new(root.location.latitude, root.location.longitude),
+            parameters,
+            date,
+            timezone: root.timezone,
+        })
+    }
+}
+
+impl TryFrom<ParametersDto> for Parameters {
+    type Error = ConfigError;
+
+    fn try_from(dto: ParametersDto) -> Result<Parameters, ConfigError> {
+        let mut parameters = dto.method.parameters();
+
+        if let Some(mazhab) = dto.mazhab {
+            parameters = parameters.mazhab(mazhab);
+        } else {
+            // Nothing to do.
+        }
+
+        if let Some(rule) = dto.high_latitude_rule {
+            parameters.high_latitude_rule = rule.into();
+        } else {
+            // Nothing to do.
+        }
+
+        parameters.adjustments = dto.adjustments;
+
+        if let Some(angle) = dto.fajr_angle {
+            parameters.fajr_angle = angle;
+        } else {
+            // Nothing to do.
+        }
+
+        if let Some(maghrib) = dto.maghrib {
+            parameters.maghrib_parameter = maghrib.into();
+        } else {
+            // Nothing to do.
+        }
+
+        if let Some(ishaa) = dto.ishaa {
+            parameters.ishaa_parameter = ishaa.into();
+        } else {
+            // Nothing to do.
+        }
+
+        if let Some(rounding) = dto.rounding {
+            parameters.rounding = rounding;
+        } else {
+            // Nothing to do.
+        }
+
+        parameters = parameters.elevation(dto.elevation_m.unwrap_or_default());
+
+        for issue in parameters.validate() {
+            match issue {
+                ParameterIssue::AdjustmentOutOfRange(prayer, minutes) => {
+                    return Err(ConfigError::AdjustmentOutOfRange(prayer, minutes));
+                }
+                ParameterIssue::InvalidElevation(_) => {
+                    return Err(ConfigError::Calculation(
+                        CalculationError::InvalidParameters(issue),
+                    ));
+                }
+                _ => {
+                    // Nothing to do.
+                }
+            }
+        }
+
+        Ok(parameters)
+    }
+}
+
+impl Parameters {
+    /// The parameters of a settings blob, e.g. supplied by a user: the
+    /// calculation settings of a config file without its location, date
+    /// and timezone, see [`ParametersDto`]. Every setting is optional.
+    ///
+    /// ```
+    /// use miqat::prelude::*;
+    ///
+    /// let params = Parameters::from_json(r#"{ "method": "egyptian", "mazhab": "hanafi" }"#).unwrap();
+    ///
+    /// assert_eq!(params, Method::Egyptian.parameters().mazhab(Mazhab::Hanafi));
+    /// assert!(Parameters::from_json(r#"{ "methd": "egyptian" }"#).is_err());
+    /// ```
+    pub fn from_json(json: &str) -> Result<Parameters, ConfigError> {
+        let dto: ParametersDto =
+            serde_json::from_str(json).map_err(|error| ConfigError::Syntax(error.to_string()))?;
+
+        Parameters::try_from(dto)
+    }
+
+    /// Like [`from_json`](Parameters::from_json), from TOML.
+    ///
+    /// Only available with the `toml` feature.
+    ///
+    /// ```
+    /// use miqat::prelude::*;
+    ///
+    /// let params = Parameters::from_toml(
+    ///     r#"
+    ///     method = "north_america"
+    ///
+    ///     [adjustments]
+    ///     fajr = -2
+    ///     "#,
+    /// )
+    /// .unwrap();
+    ///
+    /// assert_eq!(params.adjustments.fajr, -2);
+    /// ```
+    #[cfg(feature = "toml")]
+    pub fn from_toml(toml: &str) -> Result<Parameters, ConfigError> {
+        let dto: ParametersDto =
+            toml::from_str(toml).map_err(|error| ConfigError::Syntax(error.to_string()))?;
+
+        Parameters::try_from(dto)
+    }
+}
+
+/// Why a config file could not be read.
+#[derive(PartialEq, Debug, Clone)]
+pub enum ConfigError {
+    /// The file is not valid JSON, or doesn't match the format.
+    Syntax(String),
+    /// A file or directory can't be read.
+    Io(String),
+    /// The date doesn't exist, e.g. the 30th of February.
+    InvalidDate(DateDto),
+    /// An environment variable's value can't be read.
+    InvalidVariable {
+        name: String,
+        value: String,
+    },
+    /// The adjustment of a prayer, in minutes, reaches
+    /// [`TimeAdjustment::MAX_MINUTES`](crate::TimeAdjustment::MAX_MINUTES).
+    AdjustmentOutOfRange(Prayer, i64),
+    /// No layer sets both the latitude and the longitude.
+    MissingLocation,
+    /// Two requests of a batch share the given id.
+    DuplicateId(String),
+    /// The batch request with the given id has no date.
+    MissingDate(String),
+    /// The batch request with the given id ends before it starts.
+    InvalidRange(String),
+    /// A clock time that isn't written as e.g. `13:30`.
+    InvalidTime(String),
+    /// A minute offset that is out of range, e.g. a UTC offset of a day
+    /// or more.
+    InvalidMinutes(i64),
+    /// A timezone name that the timezone type doesn't know.
+    InvalidTimezone(String),
+    InvalidTemplate(TemplateError),
+    Calculation(CalculationError),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Syntax(message) => write!(f, "invalid config: {message}"),
+            ConfigError::Io(message) => write!(f, "can't read config: {message}"),
+            ConfigError::InvalidDate(date) => write!(
+                f,
+                "invalid date {}-{:02}-{:02}",
+                date.year, date.month, date.day
+            ),
+            ConfigError::InvalidVariable { name, value } => {
+                write!(f, "invalid value `{value}` for {name}")
+            }
+            ConfigError::AdjustmentOutOfRange(prayer, minutes) => write!(
+                f,
+                "the {} adjustment of {minutes} minutes is out of range",
+                prayer.as_slug()
+            ),
+            ConfigError::MissingLocation => write!(f, "no latitude and longitude configured"),
+            ConfigError::DuplicateId(id) => write!(f, "more than one request with id `{id}`"),
+            ConfigError::MissingDate(id) => write!(f, "request `{id}` has no date"),
+            ConfigError::InvalidRange(id) => write!(f, "request `{id}` ends before it starts"),
+            ConfigError::InvalidTime(time) => write!(f, "invalid time `{time}`"),
+            ConfigError::InvalidMinutes(minutes) => {
+                write!(f, "{minutes} minutes is out of range")
+            }
+            ConfigError::InvalidTimezone(name) => write!(f, "unknown timezone `{name}`"),
+            ConfigError::InvalidTemplate(error) => write!(f, "{error}"),
+            ConfigError::Calculation(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// A likely mistake in a config.
+#[derive(PartialEq, Debug, Clone)]
+pub enum ConfigWarning {
+    LatitudeOutOfRange(f64),
+    LongitudeOutOfRange(f64),
+    /// The date is outside of [`PrayerTimes::SUPPORTED_YEARS`].
+    DateOutOfRange(NaiveDate),
+    /// An adjustment of more than [`MAX_REASONABLE_ADJUSTMENT`] minutes,
+    /// which can reorder prayers.
+    LargeAdjustment(i64),
+    Parameter(ParameterIssue),
+}
+
+impl fmt::Display for ConfigWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigWarning::LatitudeOutOfRange(latitude) => {
+                write!(f, "latitude {latitude} is not between -90 and 90")
+            }
+            ConfigWarning::LongitudeOutOfRange(longitude) => {
+                write!(f, "longitude {longitude} is not between -180 and 180")
+            }
+            ConfigWarning::DateOutOfRange(date) => {
+                write!(f, "{date} is outside of the supported range of dates")
+            }
+            ConfigWarning::LargeAdjustment(minutes) => {
+                write!(f, "an adjustment of {minutes} minutes is unusually large")
+            }
+            ConfigWarning::Parameter(issue) => write!(f, "{issue}"),
+        }
+    }
+}
+
+/// The JSON Schema of config files.
+#[cfg(feature = "schemars")]
+pub fn json_schema() -> schemars::Schema {
+    schemars::schema_for!(RootDto)
+}
+
+/// The JSON Schema of [batch](batch) files.
+#[cfg(feature = "schemars")]
+pub fn batch_json_schema() -> schemars::Schema {
+    schemars::schema_for!(Vec<batch::BatchRequestDto>)
+}
+
+/// The JSON Schema of [profile](profile) files.
+#[cfg(feature = "schemars")]
+pub fn profile_json_schema() -> schemars::Schema {
+    schemars::schema_for!(profile::ProfileDto)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ishaa_parameter::IshaaParameter;
+    use crate::models::maghrib_parameter::MaghribParameter;
+    use crate::models::mazhab::Mazhab;
+    use crate::models::method::Method;
+    use crate::models::rounding::Rounding;
+    use chrono::FixedOffset;
+
+    #[test]
+    fn defaults_to_the_muslim_world_league() {
+        let config =
+            Config::from_json(r#"{ "location": { "latitude": 33.89, "longitude": 35.50 } }"#)
+                .unwrap();
+
+        assert_eq!(config.parameters, Method::MuslimWorldLeague.parameters());
+        assert_eq!(config.parameters.mazhab, Mazhab::Shafi);
+        assert_eq!(config.date, None);
+    }
+
+    #[test]
+    fn rejects_invalid_files() {
+        let unknown_method =
+            r#"{ "location": { "latitude": 0, "longitude": 0 }, "method": "isna" }"#;
+        let invalid_date = r#"{
+            "location": { "latitude": 0, "longitude": 0 },
+            "date": { "year": 2023, "month": 2, "day": 29 }
+        }"#;
+
+        assert!(matches!(
+            Config::from_json(unknown_method),
+            Err(ConfigError::Syntax(_))
+        ));
+        let absurd_adjustment = r#"{
+            "location": { "latitude": 0, "longitude": 0 },
+            "adjustments": { "maghrib": -300 }
+        }"#;
+
+        assert_eq!(
+            Config::from_json(absurd_adjustment),
+            Err(ConfigError::AdjustmentOutOfRange(Prayer::Maghrib, -300))
+        );
+        assert_eq!(
+            Config::from_json(invalid_date),
+            Err(ConfigError::InvalidDate(DateDto {
+                year: 2023,
+                month: 2,
+                day: 29
+            }))
+        );
+    }
+
+    #[test]
+    fn parameters_from_a_settings_blob() {
+        let blob = r#"{
+            "method": "umm_al_qura",
+            "high_latitude_rule": "seventh_of_the_night",
+            "adjustments": { "isha": 30 },
+            "fajr_angle": 18.5,
+            "maghrib": { "angle": 4.5 },
+            "ishaa": { "angle": 14 },
+            "rounding": "floor",
+            "elevation_m": 120
+        }"#;
+        let config = Config::from_json(&format!(
+            r#"{{ "location": {{ "latitude": 0, "longitude": 0 }}, {} }}"#,
+            blob.trim().trim_start_matches('{').trim_end_matches('}')
+        ))
+        .unwrap();
+
+        assert_eq!(Parameters::from_json(blob), Ok(config.parameters));
+        assert_eq!(config.parameters.fajr_angle, 18.5);
+        assert_eq!(
+            config.parameters.maghrib_parameter,
+            MaghribParameter::Angle(4.5)
+        );
+        assert_eq!(
+            config.parameters.ishaa_parameter,
+            IshaaParameter::Angle(14.0)
+        );
+        assert_eq!(config.parameters.rounding, Rounding::Floor);
+        assert_eq!(config.parameters.elevation, 120.0);
+        assert_eq!(
+            Parameters::from_json(r#"{ "ishaa": { "interval": 90 } }"#)
+                .unwrap()
+                .ishaa_parameter,
+            IshaaParameter::Interval(90)
+        );
+        assert!(matches!(
+            Parameters::from_json(r#"{ "elevation_m": -5 }"#),
+            Err(ConfigError::Calculation(_))
+        ));
+        assert_eq!(
+            Parameters::from_json("{}"),
+            Ok(Method::MuslimWorldLeague.parameters())
+        );
+        assert_eq!(
+            Parameters::from_json(r#"{ "adjustments": { "fajr": 1000 } }"#),
+            Err(ConfigError::AdjustmentOutOfRange(Prayer::Fajr, 1000))
+        );
+        assert!(matches!(
+            Parameters::from_json(r#"{ "location": { "latitude": 0, "longitude": 0 } }"#),
+            Err(ConfigError::Syntax(_))
+        ));
+    }
+
+    #[test]
+    fn every_setting_round_trips() {
+        let root: RootDto = serde_json::from_str(
+            r#"{
+                "location": { "latitude": 21.4225, "longitude": 39.8262 },
+                "method": "umm_al_qura",
+                "mazhab": "hanafi",
+                "high_latitude_rule": "twilight_angle",
+                "adjustments": { "fajr": -2 },
+                "fajr_angle": 18.5,
+                "maghrib": "sunset",
+                "ishaa": { "interval": 120 },
+                "rounding": "none",
+                "elevation_m": 277,
+                "date": { "year": 2024, "month": 3, "day": 15 },
+                "timezone": "+03:00"
+            }"#,
+        )
+        .unwrap();
+        let json = serde_json::to_string(&root).unwrap();
+
+        assert_eq!(serde_json::from_str::<RootDto>(&json).unwrap(), root);
+        assert_eq!(
+            Parameters::from_json(&serde_json::to_string(&root.parameters).unwrap()),
+            Ok(Config::try_from(root).unwrap().parameters)
+        );
+        assert!(matches!(
+            Config::from_json(
+                r#"{ "location": { "latitude": 0, "longitude": 0 }, "methd": "isna" }"#
+            ),
+            Err(ConfigError::Syntax(_))
+        ));
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn parameters_from_toml() {
+        let toml = r#"
+            method = "umm_al_qura"
+            high_latitude_rule = "seventh_of_the_night"
+            adjustments = { isha = 30 }
+        "#;
+        let json = r#"{
+            "method": "umm_al_qura",
+            "high_latitude_rule": "seventh_of_the_night",
+            "adjustments": { "isha": 30 }
+        }"#;
+
+        assert_eq!(Parameters::from_toml(toml), Parameters::from_json(json));
+        assert!(matches!(
+            Parameters::from_toml("method = 'isna'"),
+            Err(ConfigError::Syntax(_))
+        ));
+    }
+
+    #[test]
+    fn validate_reports_likely_mistakes() {
+        let config = Config::from_json(
+            r#"{
+                "location": { "latitude": 95, "longitude": 35.50 },
+                "adjustments": { "ishaa": 90 },
+                "date": { "year": 2200, "month": 1, "day": 1 }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.validate(),
+            vec![
+                ConfigWarning::LatitudeOutOfRange(95.0),
+                ConfigWarning::DateOutOfRange(NaiveDate::from_ymd_opt(2200, 1, 1).unwrap()),
+                ConfigWarning::LargeAdjustment(90),
+            ]
+        );
+    }
+
+    #[test]
+    fn elevation_and_timezone() {
+        let plain =
+            Config::from_json(r#"{ "location": { "latitude": 33.89, "longitude": 35.50 } }"#)
+                .unwrap();
+        let config = Config::from_json(
+            r#"{
+                "location": { "latitude": 33.89, "longitude": 35.50 },
+                "elevation_m": 900,
+                "timezone": "+03:00"
+            }"#,
+        )
+        .unwrap();
+        let eest = FixedOffset::east_opt(3 * 3600).unwrap();
+
+        assert_eq!(plain.parameters.elevation, 0.0);
+        assert_eq!(plain.zone::<FixedOffset>(), Ok(None));
+        assert_eq!(config.parameters.elevation, 900.0);
+        assert_eq!(config.zone(), Ok(Some(eest)));
+
+        let date = NaiveDate::from_ymd_opt(2024, 6, 21).unwrap();
+        let at_sea_level = PrayerTimes::computed(date, plain.coordinates, plain.parameters);
+        let elevated = PrayerTimes::computed(date, config.coordinates, config.parameters)
+            .localized(config.zone::<FixedOffset>().unwrap().unwrap());
+
+        assert!(elevated.time(Prayer::Sunrise) < at_sea_level.time(Prayer::Sunrise));
+        assert!(elevated.time(Prayer::Maghrib) > at_sea_level.time(Prayer::Maghrib));
+        assert_eq!(
+            elevated.time(Prayer::Dhuhr),
+            at_sea_level.time(Prayer::Dhuhr)
+        );
+        assert_eq!(
+            elevated.time(Prayer::Maghrib).format("%H:%M").to_string(),
+            "19:58"
+        );
+
+        let unknown = Config {
+            timezone: Some("Asia/Beirut".to_string()),
+            ..config
+        };
+        assert_eq!(
+            unknown.zone::<FixedOffset>(),
+            Err(ConfigError::InvalidTimezone("Asia/Beirut".to_string()))
+        );
+        assert_eq!(
+            Config::from_json(
+                r#"{ "location": { "latitude": 0, "longitude": 0 }, "elevation_m": -5 }"#
+            ),
+            Err(ConfigError::Calculation(
+                CalculationError::InvalidParameters(ParameterIssue::InvalidElevation(-5.0))
+            ))
+        );
+    }
+
+    #[cfg(feature = "timezones")]
+    #[test]
+    fn iana_timezone() {
+        let config = Config::from_json(
+            r#"{
+                "location": { "latitude": 33.89, "longitude": 35.50 },
+                "timezone": "Asia/Beirut"
+            }"#,
+        )
+        .unwrap();
+        let beirut = config.zone::<crate::Tz>().unwrap().unwrap();
+        let winter = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let summer = NaiveDate::from_ymd_opt(2024, 7, 15).unwrap();
+        let dhuhr = |date| {
+            PrayerTimes::computed(date, config.coordinates, config.parameters)
+                .localized(beirut)
+                .time(Prayer::Dhuhr)
+                .format("%H:%M %:z")
+                .to_string()
+        };
+
+        assert_eq!(beirut, crate::Tz::Asia__Beirut);
+        assert_eq!(dhuhr(winter), "11:48 +02:00");
+        assert_eq!(dhuhr(summer), "12:45 +03:00");
+        assert_eq!(
+            Config {
+                timezone: Some("Asia/Nowhere".to_string()),
+                ..config
+            }
+            .zone::<crate::Tz>(),
+            Err(ConfigError::InvalidTimezone("Asia/Nowhere".to_string()))
+        );
+    }
+
+    #[cfg(feature = "schemars")]
+    #[test]
+    fn shipped_schema_is_up_to_date() {
+        let schema = serde_json::to_string_pretty(&json_schema()).unwrap();
+
+        assert_eq!(format!("{schema}\n"), include_str!("config.schema.json"));
+    }
+}
@@ -0,0 +1,564 @@
+use super::Config;
+use super::ConfigError;
+use super::DisplayDto;
+use super::IqamahDto;
+use super::IqamahRuleDto;
+use super::RootDto;
+use crate::astronomy::unit::Coordinates;
+use crate::error::CalculationError;
+use crate::format::Template;
+use crate::iqamah::IqamahConfig;
+use crate::iqamah::IqamahRule;
+use crate::models::parameters::Parameters;
+use crate::models::prayer::Prayer;
+use crate::prayer_times::PrayerTimes;
+use chrono::DateTime;
+use chrono::Duration;
+use chrono::FixedOffset;
+use chrono::NaiveDate;
+use chrono::NaiveTime;
+#[cfg(feature = "timezones")]
+use chrono::Offset;
+#[cfg(feature = "timezones")]
+use chrono::TimeZone;
+use chrono::Utc;
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// A profile file as written by users.
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct ProfileDto {
+    /// Identifies the mosque, e.g. a branch of a mosque network.
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// The calculation settings; their `date` is not used.
+    pub config: RootDto,
+    /// Minutes east of UTC of the mosque's clocks, used when the config
+    /// has no `timezone`.
+    #[serde(default)]
+    pub utc_offset_minutes: i64,
+    /// The mosque's iqamah times, none when absent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub iqamah: Option<IqamahDto>,
+    #[serde(default)]
+    pub display: DisplayDto,
+}
+
+/// Everything needed to publish a mosque's timetable: where it is, how
+/// its times are calculated, when its iqamah is and how its times are
+/// written.
+///
+/// Profiles are read from and written to JSON as a [`ProfileDto`].
+///
+/// ##### Example
+///
+/// ```
+/// use miqat::parser::profile::MosqueProfile;
+/// use miqat::prelude::*;
+///
+/// let profile = MosqueProfile::from_json(
+///     r#"{
+///         "id": "beirut-central",
+///         "name": "Central Mosque",
+///         "config": { "location": { "latitude": 33.8938, "longitude": 35.5018 } },
+///         "utc_offset_minutes": 120,
+///         "iqamah": { "default": { "after": 10 }, "fajr": { "rounded": 20 } },
+///         "display": { "template": "{fajr} {fajr_iqamah}" }
+///     }"#,
+/// )
+/// .unwrap();
+/// let date = NaiveDate::from_ymd_opt(2024, 3, 13).unwrap();
+/// let times = profile.times_on(date).unwrap();
+///
+/// assert_eq!(profile.id(), "beirut-central");
+/// assert_eq!(profile.render(&times), "04:28 04:45");
+/// ```
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "ProfileDto", into = "ProfileDto")]
+pub struct MosqueProfile {
+    dto: ProfileDto,
+    config: Config,
+    zone: Zone,
+    iqamah: Option<IqamahConfig>,
+    template: Template,
+}
+
+impl MosqueProfile {
+    pub fn from_json(json: &str) -> Result<MosqueProfile, ConfigError> {
+        let dto: ProfileDto =
+            serde_json::from_str(json).map_err(|error| ConfigError::Syntax(error.to_string()))?;
+
+        MosqueProfile::try_from(dto)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(&self.dto).unwrap_or_default()
+    }
+
+    pub fn id(&self) -> &str {
+        &self.dto.id
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.dto.name.as_deref()
+    }
+
+    pub fn coordinates(&self) -> Coordinates {
+        self.config.coordinates
+    }
+
+    pub fn parameters(&self) -> Parameters {
+        self.config.parameters
+    }
+
+    /// The UTC offset of the mosque's clocks on `date`, which follows
+    /// daylight saving time when the config names a timezone.
+    #[cfg_attr(not(feature = "timezones"), allow(unused_variables))]
+    pub fn offset_on(&self, date: NaiveDate) -> FixedOffset {
+        match self.zone {
+            Zone::Fixed(offset) => offset,
+            #[cfg(feature = "timezones")]
+            Zone::Named(zone) => {
+                let noon = date.and_time(NaiveTime::MIN) + Duration::hours(12);
+
+                zone.offset_from_utc_datetime(&noon).fix()
+            }
+        }
+    }
+
+    pub fn iqamah_config(&self) -> Option<&IqamahConfig> {
+        self.iqamah.as_ref()
+    }
+
+    pub fn template(&self) -> &Template {
+        &self.template
+    }
+
+    /// The mosque's prayer times on `date`.
+    pub fn times_on(&self, date: NaiveDate) -> Result<PrayerTimes, CalculationError> {
+        PrayerTimes::try_computed(date, self.config.coordinates, self.config.parameters)
+    }
+
+    /// The iqamah of `prayer` on `times`' date, or `None` if the mosque
+    /// has no iqamah times or the prayer has no iqamah.
+    pub fn iqamah(&self, times: &PrayerTimes, prayer: Prayer) -> Option<DateTime<Utc>> {
+        self.iqamah
+            .as_ref()
+            .and_then(|config| times.iqamah(prayer, config, self.offset_on(times.date())))
+    }
+
+    /// `times` written with the mosque's template, at its UTC offset on
+    /// their date.
+    pub fn render(&self, times: &PrayerTimes) -> String {
+        self.template.render(times, self.offset_on(times.date()))
+    }
+}
+
+impl TryFrom<ProfileDto> for MosqueProfile {
+    type Error = ConfigError;
+
+    fn try_from(dto: ProfileDto) -> Result<MosqueProfile, ConfigError> {
+        let config = Config::try_from(dto.config.clone())?;
+        let zone = match config.zone::<FixedOffset>() {
+            Ok(Some(offset)) => Zone::Fixed(offset),
+            #[cfg(feature = "timezones")]
+            Err(_) => Zone::Named(config.zone()?.expect("the config has a timezone")),
+            #[cfg(not(feature = "timezones"))]
+            Err(error) => return Err(error),
+            Ok(None) => dto
+                .utc_offset_minutes
+                .checked_mul(60)
+                .and_then(|seconds| i32::try_from(seconds).ok())
+                .and_then(FixedOffset::east_opt)
+                .map(Zone::Fixed)
+                .ok_or(ConfigError::InvalidMinutes(dto.utc_offset_minutes))?,
+        };
+        let iqamah = dto.iqamah.as_ref().map(iqamah_config).transpose()?;
+        let mut template = match &dto.display.template {
+            Some(template) => template
+                .parse::<Template>()
+                .map_err(ConfigError::InvalidTemplate)?,
+            None => Template::default(),
+        }
+        .digits(dto.display.digits.into())
+        .locale(dto.display.language.into());
+
+        if let Some(iqamah) = iqamah {
+            template = template.iqamah(iqamah);
+        } else {
+            // Nothing to do.
+        }
+
+        Ok(MosqueProfile {
+            dto,
+            config,
+            zone,
+            iqamah,
+            template,
+        })
+    }
+}
+
+/// The timezone of a mosque's clocks.
+#[derive(PartialEq, Debug, Copy, Clone)]
+enum Zone {
+    Fixed(FixedOffset),
+    #[cfg(feature = "timezones")]
+    Named(crate::Tz),
+}
+
+impl From<MosqueProfile> for ProfileDto {
+    fn from(profile: MosqueProfile) -> Self {
+        profile.dto
+    }
+}
+
+/// The profiles of several mosques, e.g. the branches of a mosque
+/// network, by id.
+///
+/// ##### Example
+///
+/// ```
+/// use miqat::parser::profile::{MosqueProfile, ProfileStore};
+/// use miqat::prelude::*;
+///
+/// let profile = |id: &str, latitude: f64, longitude: f64| {
+///     MosqueProfile::from_json(&format!(
+///         r#"{{
+///             "id": "{id}",
+///             "config": {{ "location": {{ "latitude": {latitude}, "longitude": {longitude} }} }}
+///         }}"#
+///     ))
+///     .unwrap()
+/// };
+/// let store = ProfileStore::new(vec![
+///     profile("beirut", 33.8938, 35.5018),
+///     profile("tripoli", 34.4367, 35.8497),
+/// ])
+/// .unwrap();
+/// let batroun = Coordinates::new(34.2553, 35.6581);
+///
+/// assert_eq!(store.nearest(batroun).map(|profile| profile.id()), Some("tripoli"));
+/// assert!(store.by_id("beirut").is_some());
+/// ```
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct ProfileStore {
+    profiles: BTreeMap<String, MosqueProfile>,
+}
+
+impl ProfileStore {
+    /// A store of `profiles`, which must have distinct ids.
+    pub fn new(
+        profiles: impl IntoIterator<Item = MosqueProfile>,
+    ) -> Result<ProfileStore, ConfigError> {
+        let mut store = ProfileStore::default();
+
+        for profile in profiles {
+            store.insert(profile)?;
+        }
+
+        Ok(store)
+    }
+
+    /// A store of every `.json` file in `directory`, each a profile.
+    pub fn load_dir(directory: impl AsRef<Path>) -> Result<ProfileStore, ConfigError> {
+        let io = |error: std::io::Error| ConfigError::Io(error.to_string());
+        let mut paths = Vec::new();
+
+        for entry in fs::read_dir(directory).map_err(io)? {
+            let path = entry.map_err(io)?.path();
+
+            if path
+                .extension()
+                .is_some_and(|extension| extension == "json")
+                && path.is_file()
+            {
+                paths.push(path);
+            } else {
+                // Nothing to do.
+            }
+        }
+
+        paths.sort();
+        paths
+            .iter()
+            .map(|path| MosqueProfile::from_json(&fs::read_to_string(path).map_err(io)?))
+            .collect::<Result<Vec<_>, _>>()
+            .and_then(ProfileStore::new)
+    }
+
+    /// Adds `profile`, unless the store already has a profile with its id.
+    pub fn insert(&mut self, profile: MosqueProfile) -> Result<(), ConfigError> {
+        if self.profiles.contains_key(profile.id()) {
+            return Err(ConfigError::DuplicateId(profile.id().to_string()));
+        } else {
+            // Nothing to do.
+        }
+
+        self.profiles.insert(profile.id().to_string(), profile);
+
+        Ok(())
+    }
+
+    pub fn by_id(&self, id: &str) -> Option<&MosqueProfile> {
+        self.profiles.get(id)
+    }
+
+    /// The profile of the mosque closest to `coordinates`, or `None` if
+    /// the store is empty.
+    pub fn nearest(&self, coordinates: Coordinates) -> Option<&MosqueProfile> {
+        self.profiles.values().min_by(|a, b| {
+            let a = a.coordinates().distance_to(coordinates);
+            let b = b.coordinates().distance_to(coordinates);
+
+            a.total_cmp(&b)
+        })
+    }
+
+    /// The profiles, by id.
+    pub fn iter(&self) -> impl Iterator<Item = &MosqueProfile> {
+        self.profiles.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.profiles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.profiles.is_empty()
+    }
+}
+
+fn iqamah_config(dto: &IqamahDto) -> Result<IqamahConfig, ConfigError> {
+    let mut config = IqamahConfig::new(iqamah_rule(&dto.default)?);
+    let prayers = [
+        (Prayer::Fajr, &dto.fajr),
+        (Prayer::Dhuhr, &dto.dhuhr),
+        (Prayer::Asr, &dto.asr),
+        (Prayer::Maghrib, &dto.maghrib),
+        (Prayer::Ishaa, &dto.ishaa),
+    ];
+
+    for (prayer, rule) in prayers {
+        if let Some(rule) = rule {
+            config = config.with(prayer, iqamah_rule(rule)?);
+        } else {
+            // Nothing to do.
+        }
+    }
+
+    if let Some(rule) = &dto.jumua {
+        config = config.jumua(iqamah_rule(rule)?);
+    } else {
+        // Nothing to do.
+    }
+
+    Ok(config)
+}
+
+fn iqamah_rule(dto: &IqamahRuleDto) -> Result<IqamahRule, ConfigError> {
+    let minutes = |minutes: i64| {
+        Duration::try_minutes(minutes)
+            .filter(|delay| *delay >= Duration::zero() && *delay < Duration::days(1))
+            .ok_or(ConfigError::InvalidMinutes(minutes))
+    };
+
+    match dto {
+        IqamahRuleDto::Fixed(time) => time
+            .parse::<NaiveTime>()
+            .map(IqamahRule::Fixed)
+            .map_err(|_| ConfigError::InvalidTime(time.clone())),
+        IqamahRuleDto::After(delay) => minutes(*delay).map(IqamahRule::After),
+        IqamahRuleDto::Rounded(delay) => minutes(*delay).map(IqamahRule::Rounded),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROFILE: &str = r#"{
+        "id": "tripoli",
+        "config": {
+            "location": { "latitude": 34.43, "longitude": 35.84 },
+            "method": "egyptian"
+        },
+        "utc_offset_minutes": 180,
+        "iqamah": {
+            "default": { "after": 15 },
+            "maghrib": { "after": 5 },
+            "jumua": { "fixed": "12:45" }
+        },
+        "display": { "digits": "arabic_indic", "language": "arabic" }
+    }"#;
+
+    #[test]
+    fn profiles_round_trip() {
+        let profile = MosqueProfile::from_json(PROFILE).unwrap();
+
+        assert_eq!(MosqueProfile::from_json(&profile.to_json()), Ok(profile));
+    }
+
+    #[test]
+    fn profiles_resolve_their_settings() {
+        let profile = MosqueProfile::from_json(PROFILE).unwrap();
+        let friday = NaiveDate::from_ymd_opt(2024, 6, 14).unwrap();
+        let times = profile.times_on(friday).unwrap();
+        let eest = FixedOffset::east_opt(3 * 3600).unwrap();
+
+        assert_eq!(profile.name(), None);
+        assert_eq!(profile.offset_on(friday), eest);
+        assert_eq!(
+            profile.iqamah(&times, Prayer::Maghrib),
+            Some(times.time(Prayer::Maghrib) + Duration::minutes(5))
+        );
+        assert_eq!(
+            profile
+                .iqamah(&times, Prayer::Dhuhr)
+                .map(|time| time.with_timezone(&eest).time()),
+            NaiveTime::from_hms_opt(12, 45, 0)
+        );
+        assert!(profile.render(&times).starts_with('\u{200F}'));
+    }
+
+    #[test]
+    fn the_config_timezone_takes_precedence() {
+        let profile = MosqueProfile::from_json(
+            r#"{
+                "id": "a",
+                "config": {
+                    "location": { "latitude": 33.89, "longitude": 35.50 },
+                    "timezone": "+02:00"
+                },
+                "utc_offset_minutes": 180
+            }"#,
+        )
+        .unwrap();
+        let date = NaiveDate::from_ymd_opt(2024, 7, 15).unwrap();
+
+        assert_eq!(
+            profile.offset_on(date),
+            FixedOffset::east_opt(2 * 3600).unwrap()
+        );
+    }
+
+    #[cfg(feature = "timezones")]
+    #[test]
+    fn named_timezones_follow_daylight_saving_time() {
+        let profile = MosqueProfile::from_json(
+            r#"{
+                "id": "beirut",
+                "config": {
+                    "location": { "latitude": 33.8938, "longitude": 35.5018 },
+                    "timezone": "Asia/Beirut"
+                },
+                "display": { "template": "{dhuhr}" }
+            }"#,
+        )
+        .unwrap();
+        let winter = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let summer = NaiveDate::from_ymd_opt(2024, 7, 15).unwrap();
+
+        assert_eq!(
+            profile.offset_on(winter),
+            FixedOffset::east_opt(2 * 3600).unwrap()
+        );
+        assert_eq!(
+            profile.offset_on(summer),
+            FixedOffset::east_opt(3 * 3600).unwrap()
+        );
+        assert_eq!(profile.render(&profile.times_on(winter).unwrap()), "11:48");
+        assert_eq!(profile.render(&profile.times_on(summer).unwrap()), "12:45");
+    }
+
+    #[test]
+    fn rejects_invalid_profiles() {
+        let profile = |extra: &str| {
+            MosqueProfile::from_json(&format!(
+                r#"{{
+                    "id": "a",
+                    "config": {{ "location": {{ "latitude": 0, "longitude": 0 }} }},
+                    {extra}
+                }}"#
+            ))
+        };
+
+        assert_eq!(
+            profile(r#""utc_offset_minutes": 1440"#),
+            Err(ConfigError::InvalidMinutes(1440))
+        );
+        assert_eq!(
+            MosqueProfile::from_json(
+                r#"{
+                    "id": "a",
+                    "config": {
+                        "location": { "latitude": 0, "longitude": 0 },
+                        "timezone": "Asia/Nowhere"
+                    }
+                }"#
+            ),
+            Err(ConfigError::InvalidTimezone("Asia/Nowhere".to_string()))
+        );
+        assert_eq!(
+            profile(r#""iqamah": { "default": { "after": -5 } }"#),
+            Err(ConfigError::InvalidMinutes(-5))
+        );
+        assert_eq!(
+            profile(r#""iqamah": { "default": { "fixed": "25:00" } }"#),
+            Err(ConfigError::InvalidTime("25:00".to_string()))
+        );
+        assert!(matches!(
+            profile(r#""display": { "template": "{tahajjud}" }"#),
+            Err(ConfigError::InvalidTemplate(_))
+        ));
+    }
+
+    #[test]
+    fn stores_load_a_directory() {
+        let directory = std::env::temp_dir().join(format!("miqat-profiles-{}", std::process::id()));
+        let beirut = r#"{
+            "id": "beirut",
+            "config": { "location": { "latitude": 33.8938, "longitude": 35.5018 } }
+        }"#;
+
+        fs::create_dir_all(&directory).unwrap();
+        fs::write(directory.join("tripoli.json"), PROFILE).unwrap();
+        fs::write(directory.join("beirut.json"), beirut).unwrap();
+        fs::write(directory.join("notes.txt"), "not a profile").unwrap();
+
+        let store = ProfileStore::load_dir(&directory);
+
+        fs::write(directory.join("copy.json"), beirut).unwrap();
+
+        let duplicate = ProfileStore::load_dir(&directory);
+
+        fs::remove_dir_all(&directory).unwrap();
+
+        let store = store.unwrap();
+        let sidon = Coordinates::new(33.5571, 35.3729);
+
+        assert_eq!(store.len(), 2);
+        assert_eq!(store.nearest(sidon).map(MosqueProfile::id), Some("beirut"));
+        assert_eq!(
+            store
+                .by_id("tripoli")
+                .map(|profile| profile.offset_on(NaiveDate::MIN)),
+            FixedOffset::east_opt(3 * 3600)
+        );
+        assert_eq!(
+            duplicate,
+            Err(ConfigError::DuplicateId("beirut".to_string()))
+        );
+        assert!(matches!(
+            ProfileStore::load_dir(&directory),
+            Err(ConfigError::Io(_))
+        ));
+        assert_eq!(ProfileStore::default().nearest(sidon), None);
+    }
+}
@@ -3,81 +3,630 @@
 //! This module provides the main objects that are used for calculating
 //! the prayer times.
 
-use crate::astronomy::ops;
+use crate::astronomy::ops::Observer;
+use crate::astronomy::seasonal;
+use crate::astronomy::solar::SolarPosition;
 use crate::astronomy::solar::SolarTime;
 use crate::astronomy::unit::Angle;
 use crate::astronomy::unit::Coordinates;
 use crate::astronomy::unit::Stride;
+use crate::cache::fingerprint;
+use crate::cache::fnv1a;
+use crate::clock::Clock;
+use crate::clock::FixedClock;
+use crate::clock::SystemClock;
+use crate::error::CalculationError;
+use crate::format::Language;
+use crate::format::format_duration_until;
+use crate::iqamah::IqamahConfig;
+use crate::models::asr_factor::AsrFactor;
+use crate::models::day_boundary::DayBoundary;
+use crate::models::high_altitude_rule::HighLatitudeRule;
+use crate::models::ishaa_interval_start::IshaaIntervalStart;
 use crate::models::ishaa_parameter::IshaaParameter;
+use crate::models::maghrib_parameter::MaghribParameter;
+use crate::models::mazhab::Mazhab;
+use crate::models::next_day_fajr::NextDayFajr;
+use crate::models::parameter_issue::ParameterIssue;
 use crate::models::parameters::Parameters;
 use crate::models::prayer::Prayer;
+use crate::models::time_source::TimeSource;
+use crate::models::time_source::TimedValue;
+use crate::models::warning::Warning;
+use crate::models::warning::Warnings;
+use crate::models::window::Window;
 use crate::precomputed::data::dar_el_fatwa_beirut;
 use crate::precomputed::provider::Provider;
+use crate::precomputed::table::OfficialTable;
+#[cfg(feature = "qada")]
+use crate::qada::CheckIn;
+use crate::time_ext::DateTimeExt;
 use chrono::DateTime;
 use chrono::Datelike;
 use chrono::Days;
 use chrono::Duration;
+use chrono::FixedOffset;
 use chrono::NaiveDate;
+use chrono::NaiveTime;
+use chrono::Offset;
+use chrono::TimeZone;
 use chrono::Utc;
+use std::ops::RangeInclusive;
+use std::sync::Arc;
 
-#[derive(PartialEq, Debug, Copy, Clone)]
+/// Where a schedule came from, kept to compute neighbouring days.
+// Kept inline so that neighbouring days can be computed without
+// allocating.
+#[allow(clippy::large_enum_variant)]
+#[derive(PartialEq, Debug, Clone)]
+pub(crate) enum Origin {
+    Computed(Coordinates, Parameters),
+    Precomputed(Provider),
+    Table(Arc<OfficialTable>),
+    /// Read back from the serialized form, which only keeps the times
+    /// of its own day.
+    #[cfg_attr(not(feature = "serde"), allow(dead_code))]
+    Restored(Coordinates),
+}
+
+/// The solar times a computed schedule was derived from, by UTC day: of
+/// its prayer day, of the next one and, when the sun rises and sets on
+/// it, of the one after. The schedules of the adjacent days start from
+/// them instead of computing them again.
+#[derive(Debug)]
+pub(crate) struct SolarDays([Option<(DateTime<Utc>, SolarTime)>; 3]);
+
+impl SolarDays {
+    fn get(&self, day: DateTime<Utc>) -> Option<SolarTime> {
+        self.0
+            .iter()
+            .flatten()
+            .find(|(known, _)| *known == day)
+            .map(|(_, solar_time)| *solar_time)
+    }
+}
+
+/// The prayer times of a day with the alternatives of the settings apps
+/// commonly let users toggle, see [`PrayerTimes::with_variants`].
+#[derive(PartialEq, Debug, Clone)]
+pub struct PrayerVariants {
+    /// The times computed with the given parameters.
+    pub times: PrayerTimes,
+    /// Asr when an object's shadow equals its length plus its noon shadow.
+    pub asr_shafi: DateTime<Utc>,
+    /// Asr when an object's shadow is twice its length plus its noon shadow.
+    pub asr_hanafi: DateTime<Utc>,
+    /// Halfway between sunset and the next sunrise.
+    pub midnight_standard: DateTime<Utc>,
+    /// Halfway between sunset and the next Fajr.
+    pub midnight_jafari: DateTime<Utc>,
+}
+
+/// Some of the prayer times of a day, see [`PrayerTimes::only`].
+#[derive(PartialEq, Debug, Clone)]
+pub struct PartialPrayerTimes {
+    date: NaiveDate,
+    times: Vec<(Prayer, DateTime<Utc>)>,
+}
+
+impl PartialPrayerTimes {
+    /// The date these times are for.
+    pub fn date(&self) -> NaiveDate {
+        self.date
+    }
+
+    /// The time of the given prayer, if it was asked for.
+    pub fn time(&self, prayer: Prayer) -> Option<DateTime<Utc>> {
+        self.times
+            .iter()
+            .find(|(computed, _)| *computed == prayer)
+            .map(|(_, time)| *time)
+    }
+}
+
+/// A schedule read in a timezone, see [`PrayerTimes::localized`].
+#[derive(PartialEq, Debug, Clone)]
+pub struct LocalizedPrayerTimes<Tz: TimeZone> {
+    times: PrayerTimes,
+    zone: Tz,
+}
+
+impl<Tz: TimeZone> LocalizedPrayerTimes<Tz> {
+    /// The schedule, in UTC.
+    pub fn times(&self) -> &PrayerTimes {
+        &self.times
+    }
+
+    /// The timezone the times are read in.
+    pub fn zone(&self) -> &Tz {
+        &self.zone
+    }
+
+    /// The time of the given prayer in the timezone.
+    pub fn time(&self, prayer: Prayer) -> DateTime<Tz> {
+        self.times.time(prayer).with_timezone(&self.zone)
+    }
+
+    /// The same schedule read in each of `zones`, in order, e.g. the local
+    /// time next to Makkah time on a masjid display. The instants are the
+    /// same in every zone; only the clock times differ.
+    pub fn in_zones<Z: TimeZone>(&self, zones: &[Z]) -> Vec<LocalizedPrayerTimes<Z>> {
+        zones
+            .iter()
+            .map(|zone| self.times.clone().localized(zone.clone()))
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct PrayerTimes {
-    fajr: DateTime<Utc>,
-    sunrise: DateTime<Utc>,
-    dhuhr: DateTime<Utc>,
-    asr: DateTime<Utc>,
-    maghrib: DateTime<Utc>,
-    ishaa: DateTime<Utc>,
-    fajr_tomorrow: DateTime<Utc>,
+    pub(crate) date: NaiveDate,
+    pub(crate) origin: Origin,
+    pub(crate) fajr: DateTime<Utc>,
+    pub(crate) sunrise: DateTime<Utc>,
+    pub(crate) dhuhr: DateTime<Utc>,
+    pub(crate) asr: DateTime<Utc>,
+    pub(crate) maghrib: DateTime<Utc>,
+    pub(crate) ishaa: DateTime<Utc>,
+    pub(crate) fajr_tomorrow: DateTime<Utc>,
+    pub(crate) fajr_source: TimeSource,
+    pub(crate) ishaa_source: TimeSource,
+    pub(crate) fajr_clamped: bool,
+    pub(crate) ishaa_clamped: bool,
+    pub(crate) solar_days: Option<Arc<SolarDays>>,
+}
+
+// The solar days follow from the date and the origin, and are left out.
+impl PartialEq for PrayerTimes {
+    fn eq(&self, other: &Self) -> bool {
+        self.date == other.date
+            && self.origin == other.origin
+            && self.fajr == other.fajr
+            && self.sunrise == other.sunrise
+            && self.dhuhr == other.dhuhr
+            && self.asr == other.asr
+            && self.maghrib == other.maghrib
+            && self.ishaa == other.ishaa
+            && self.fajr_tomorrow == other.fajr_tomorrow
+            && self.fajr_source == other.fajr_source
+            && self.ishaa_source == other.ishaa_source
+            && self.fajr_clamped == other.fajr_clamped
+            && self.ishaa_clamped == other.ishaa_clamped
+    }
 }
 
 impl PrayerTimes {
-    pub fn computed(date: NaiveDate, coordinates: Coordinates, parameters: Parameters) -> PrayerTimes {
-        let prayer_date = date
-            .and_hms_opt(0, 0, 0)
-            .expect("Invalid date provided")
-            .and_utc();
-        let tomorrow = prayer_date.tomorrow();
-        let solar_time = SolarTime::new(prayer_date, coordinates);
-        let solar_time_tomorrow = SolarTime::new(tomorrow, coordinates);
-
-        let asr = solar_time.afternoon(parameters.mazhab.shadow().into());
+    /// The years for which calculations are supported. Within them, the
+    /// difference between Universal and Terrestrial Time (delta T) is
+    /// accounted for; outside of them the estimates of delta T, and with
+    /// them the times, can no longer be trusted.
+    pub const SUPPORTED_YEARS: RangeInclusive<i32> = 1900..=2100;
+
+    /// How far from a day's prayer [`nearest`](PrayerTimes::nearest)
+    /// takes it to be the nearest without looking at the neighbouring
+    /// day's. Prayers are about a day apart, so it leaves the times an
+    /// hour to drift from one day to the next.
+    pub const NEAREST_SPAN: Duration = Duration::hours(11);
+
+    /// The version of the serialized form, written as its `version`
+    /// field. It is increased whenever a field changes meaning or is
+    /// removed; new fields are added without a new version. Other
+    /// versions are rejected when deserializing.
+    #[cfg(feature = "serde")]
+    pub const SERIALIZATION_VERSION: u32 = 1;
+
+    /// Computes the prayer times of `date`.
+    ///
+    /// Panics if `date` is outside of [`SUPPORTED_YEARS`](PrayerTimes::SUPPORTED_YEARS),
+    /// if the sun does not rise or set on that day, or if the parameters
+    /// can't be applied, see [`try_computed`](PrayerTimes::try_computed).
+    ///
+    /// Where Fajr or Ishaa can't be computed from their angle, or fall out
+    /// of order with the rest of the night, the high latitude rule is
+    /// applied instead; see [`source`](PrayerTimes::source) and
+    /// [`ordering_violations`](PrayerTimes::ordering_violations).
+    pub fn computed(
+        date: NaiveDate,
+        coordinates: Coordinates,
+        parameters: Parameters,
+    ) -> PrayerTimes {
+        PrayerTimes::try_computed(date, coordinates, parameters)
+            .unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    /// Like [`computed`](PrayerTimes::computed), but returns an error
+    /// for dates outside of [`SUPPORTED_YEARS`](PrayerTimes::SUPPORTED_YEARS),
+    /// for days on which the sun does not rise or set, for latitudes
+    /// beyond the poles or longitudes that are not finite, and for
+    /// angles, adjustments, Dhuhr offsets, Asr shadow factors and
+    /// elevations that can't be applied. Adjustments and Dhuhr offsets of
+    /// [`TimeAdjustment::MAX_MINUTES`](crate::TimeAdjustment::MAX_MINUTES)
+    /// or more are refused since version 10 of the
+    /// [calculation changelog](crate::calculation_changelog).
+    pub fn try_computed(
+        date: NaiveDate,
+        coordinates: Coordinates,
+        parameters: Parameters,
+    ) -> Result<PrayerTimes, CalculationError> {
+        PrayerTimes::try_computed_for(date, Observer::new(coordinates), parameters)
+    }
+
+    /// Like [`try_computed`](PrayerTimes::try_computed), for an observer
+    /// whose latitude is shared with others, as along a row of a grid.
+    pub(crate) fn try_computed_for(
+        date: NaiveDate,
+        observer: Observer,
+        parameters: Parameters,
+    ) -> Result<PrayerTimes, CalculationError> {
+        PrayerTimes::check_computable(date, observer.coordinates, parameters)?;
+        PrayerTimes::calculate_with_solar_times(date, observer, parameters, None)
+            .map(|(times, _, _)| times)
+    }
+
+    /// Computes only the given prayers of `date`, with the same results
+    /// as [`computed`](PrayerTimes::computed). Sunrise, Dhuhr, Asr and
+    /// Maghrib only need the sun's position on the day; Fajr also needs
+    /// the next sunrise, and Ishaa and the next Fajr need the whole
+    /// schedule, as Ishaa is kept before the next Fajr.
+    ///
+    /// Fails like [`try_computed`](PrayerTimes::try_computed), though
+    /// only for the solar events the given prayers depend on: Maghrib is
+    /// computed on a day after which the sun does not rise.
+    ///
+    /// ```
+    /// use miqat::prelude::*;
+    ///
+    /// let date = NaiveDate::from_ymd_opt(2024, 3, 11).unwrap();
+    /// let beirut = Coordinates::new(33.8938, 35.5018);
+    /// let params = Method::MuslimWorldLeague.parameters();
+    /// let iftar = PrayerTimes::only(&[Prayer::Maghrib], date, beirut, params).unwrap();
+    ///
+    /// assert_eq!(
+    ///     iftar.time(Prayer::Maghrib),
+    ///     Some(PrayerTimes::computed(date, beirut, params).time(Prayer::Maghrib))
+    /// );
+    /// assert_eq!(iftar.time(Prayer::Fajr), None);
+    /// ```
+    pub fn only(
+        prayers: &[Prayer],
+        date: NaiveDate,
+        coordinates: Coordinates,
+        parameters: Parameters,
+    ) -> Result<PartialPrayerTimes, CalculationError> {
+        PrayerTimes::check_computable(date, coordinates, parameters)?;
+
+        let mut times = Vec::with_capacity(prayers.len());
+        // An angle based Maghrib depends on Ishaa.
+        let whole_schedule = prayers.iter().any(|prayer| match prayer {
+            Prayer::Ishaa | Prayer::FajrTomorrow => true,
+            Prayer::Maghrib => parameters.maghrib_parameter != MaghribParameter::Sunset,
+            _ => false,
+        });
+
+        if whole_schedule {
+            let schedule = PrayerTimes::calculate(date, coordinates, parameters)?;
+
+            times.extend(
+                prayers
+                    .iter()
+                    .map(|prayer| (*prayer, schedule.time(*prayer))),
+            );
+        } else {
+            let prayer_date = PrayerTimes::solar_day(date, coordinates, parameters)
+                .ok_or(CalculationError::DateOutOfRange(date))?;
+            let solar_time = PrayerTimes::solar_time(prayer_date, coordinates, parameters)
+                .ok_or(CalculationError::NoSunriseOrSunset(date))?;
+
+            for prayer in prayers {
+                let time = match prayer {
+                    Prayer::Fajr => {
+                        let tomorrow = prayer_date
+                            .tomorrow()
+                            .ok_or(CalculationError::DateOutOfRange(date))?;
+                        let solar_time_tomorrow =
+                            PrayerTimes::solar_time(tomorrow, coordinates, parameters)
+                                .ok_or(CalculationError::NoSunriseOrSunset(date))?;
+                        let night = solar_time_tomorrow
+                            .sunrise
+                            .signed_duration_since(solar_time.sunset);
+
+                        PrayerTimes::calculate_fajr(
+                            parameters,
+                            solar_time,
+                            night,
+                            coordinates,
+                            prayer_date,
+                        )
+                        .map(|(fajr, _, _)| fajr.rounded_minute(parameters.rounding))
+                    }
+                    _ => PrayerTimes::daytime(*prayer, parameters, &solar_time),
+                };
+
+                times.push((*prayer, time.ok_or(CalculationError::DateOutOfRange(date))?));
+            }
+        }
+
+        Ok(PartialPrayerTimes { date, times })
+    }
+
+    /// Refuses what [`try_computed`](PrayerTimes::try_computed) can't
+    /// compute, before any calculation.
+    fn check_computable(
+        date: NaiveDate,
+        coordinates: Coordinates,
+        parameters: Parameters,
+    ) -> Result<(), CalculationError> {
+        if !PrayerTimes::SUPPORTED_YEARS.contains(&date.year()) {
+            return Err(CalculationError::DateOutOfRange(date));
+        } else {
+            // Nothing to do.
+        }
+
+        if !(-90.0..=90.0).contains(&coordinates.latitude) || !coordinates.longitude.is_finite() {
+            return Err(CalculationError::InvalidCoordinates(coordinates));
+        } else {
+            // Nothing to do.
+        }
+
+        // Unusual angles are computed as given, only those that can't be
+        // are refused.
+        let unusable_angle = |angle: f64| !(-90.0..=90.0).contains(&angle);
+        let unusable = parameters.validate().into_iter().find(|issue| match issue {
+            ParameterIssue::InvalidFajrAngle(angle) | ParameterIssue::InvalidIshaaAngle(angle) => {
+                unusable_angle(*angle)
+            }
+            ParameterIssue::AdjustmentOutOfRange(..)
+            | ParameterIssue::DhuhrOffsetOutOfRange(_)
+            | ParameterIssue::InvalidAsrFactor(_)
+            | ParameterIssue::InvalidElevation(_) => true,
+            _ => false,
+        });
+
+        match unusable {
+            Some(issue) => Err(CalculationError::InvalidParameters(issue)),
+            None => Ok(()),
+        }
+    }
+
+    /// Like [`try_computed`](PrayerTimes::try_computed), but also refuses
+    /// longitudes outside of [-180°, 180°] and parameters with any invalid
+    /// value, see [`ParameterIssue::is_error`]. Meant for input that
+    /// can't be trusted, such as requests to a server; never panics.
+    ///
+    /// ```
+    /// use miqat::prelude::*;
+    ///
+    /// let date = NaiveDate::from_ymd_opt(2015, 7, 12).unwrap();
+    /// let params = Method::NorthAmerica.parameters();
+    ///
+    /// assert!(PrayerTimes::try_new(date, Coordinates::new(35.7750, -78.6336), params).is_ok());
+    /// assert!(PrayerTimes::try_new(date, Coordinates::new(135.0, -78.6336), params).is_err());
+    /// ```
+    pub fn try_new(
+        date: NaiveDate,
+        coordinates: Coordinates,
+        parameters: Parameters,
+    ) -> Result<PrayerTimes, CalculationError> {
+        if !(-90.0..=90.0).contains(&coordinates.latitude)
+            || !(-180.0..=180.0).contains(&coordinates.longitude)
+        {
+            return Err(CalculationError::InvalidCoordinates(coordinates));
+        } else {
+            // Nothing to do.
+        }
+
+        match parameters
+            .validate()
+            .into_iter()
+            .find(ParameterIssue::is_error)
+        {
+            Some(issue) => Err(CalculationError::InvalidParameters(issue)),
+            None => PrayerTimes::try_computed(date, coordinates, parameters),
+        }
+    }
+
+    /// Computes the prayer times of `date` along with the Asr times of
+    /// both mazhabs and both conventions for midnight, from the same
+    /// solar calculations. Apps offering these as settings can switch
+    /// between them without computing the day again.
+    ///
+    /// Fails like [`try_computed`](PrayerTimes::try_computed).
+    ///
+    /// ```
+    /// use miqat::prelude::*;
+    ///
+    /// let date = NaiveDate::from_ymd_opt(2015, 7, 12).unwrap();
+    /// let raleigh = Coordinates::new(35.7750, -78.6336);
+    /// let variants =
+    ///     PrayerTimes::with_variants(date, raleigh, Method::NorthAmerica.parameters()).unwrap();
+    ///
+    /// assert_eq!(variants.times.time(Prayer::Asr), variants.asr_shafi);
+    /// assert!(variants.asr_shafi < variants.asr_hanafi);
+    /// ```
+    pub fn with_variants(
+        date: NaiveDate,
+        coordinates: Coordinates,
+        parameters: Parameters,
+    ) -> Result<PrayerVariants, CalculationError> {
+        PrayerTimes::try_computed(date, coordinates, parameters)?;
+
+        let (times, solar_time, solar_time_tomorrow) = PrayerTimes::calculate_with_solar_times(
+            date,
+            Observer::new(coordinates),
+            parameters,
+            None,
+        )?;
+        let asr = |mazhab: Mazhab| {
+            solar_time
+                .afternoon(AsrFactor::Mazhab(mazhab))
+                .adjust_time(parameters.time_adjustments(Prayer::Asr))
+                .map(|asr| asr.rounded_minute(parameters.rounding))
+                .ok_or(CalculationError::DateOutOfRange(date))
+        };
+        let sunset = solar_time.sunset;
+        let halfway = |end: DateTime<Utc>| {
+            (sunset + end.signed_duration_since(sunset) / 2).rounded_minute(parameters.rounding)
+        };
+
+        Ok(PrayerVariants {
+            asr_shafi: asr(Mazhab::Shafi)?,
+            asr_hanafi: asr(Mazhab::Hanafi)?,
+            midnight_standard: halfway(solar_time_tomorrow.sunrise),
+            midnight_jafari: halfway(times.fajr_tomorrow),
+            times,
+        })
+    }
+
+    fn calculate(
+        date: NaiveDate,
+        coordinates: Coordinates,
+        parameters: Parameters,
+    ) -> Result<PrayerTimes, CalculationError> {
+        PrayerTimes::calculate_with_solar_times(date, Observer::new(coordinates), parameters, None)
+            .map(|(times, _, _)| times)
+    }
+
+    /// The prayer times of `date` along with the solar times of its
+    /// prayer day and of the next one, taken from `known` when it has
+    /// them.
+    fn calculate_with_solar_times(
+        date: NaiveDate,
+        observer: Observer,
+        parameters: Parameters,
+        known: Option<&SolarDays>,
+    ) -> Result<(PrayerTimes, SolarTime, SolarTime), CalculationError> {
+        let coordinates = observer.coordinates;
+        let solar_time_of = |day: DateTime<Utc>| {
+            known
+                .and_then(|known| known.get(day))
+                .or_else(|| PrayerTimes::solar_time_for(day, observer, parameters))
+        };
+        let prayer_date = PrayerTimes::solar_day(date, coordinates, parameters)
+            .ok_or(CalculationError::DateOutOfRange(date))?;
+        let solar_time =
+            solar_time_of(prayer_date).ok_or(CalculationError::NoSunriseOrSunset(date))?;
+        let tomorrow = prayer_date
+            .tomorrow()
+            .ok_or(CalculationError::DateOutOfRange(date))?;
+        let solar_time_tomorrow =
+            solar_time_of(tomorrow).ok_or(CalculationError::NoSunriseOrSunset(date))?;
+        let day_after = tomorrow.tomorrow();
+        let solar_time_day_after = day_after.and_then(solar_time_of);
+        let mut times = PrayerTimes::from_solar_times(
+            date,
+            coordinates,
+            parameters,
+            prayer_date,
+            solar_time,
+            solar_time_tomorrow,
+            solar_time_day_after,
+        )
+        .ok_or(CalculationError::DateOutOfRange(date))?;
+
+        times.solar_days = Some(Arc::new(SolarDays([
+            Some((prayer_date, solar_time)),
+            Some((tomorrow, solar_time_tomorrow)),
+            day_after.zip(solar_time_day_after),
+        ])));
+
+        Ok((times, solar_time, solar_time_tomorrow))
+    }
+
+    fn from_solar_times(
+        date: NaiveDate,
+        coordinates: Coordinates,
+        parameters: Parameters,
+        prayer_date: DateTime<Utc>,
+        solar_time: SolarTime,
+        solar_time_tomorrow: SolarTime,
+        solar_time_day_after: Option<SolarTime>,
+    ) -> Option<PrayerTimes> {
+        let tomorrow = prayer_date.tomorrow()?;
         let night = solar_time_tomorrow
             .sunrise
             .signed_duration_since(solar_time.sunset);
 
-        let final_fajr =
-            PrayerTimes::calculate_fajr(parameters, solar_time, night, coordinates, prayer_date)
-                .rounded_minute(parameters.rounding);
-        let final_sunrise = solar_time
-            .sunrise
-            .adjust_time(parameters.time_adjustments(Prayer::Sunrise))
-            .rounded_minute(parameters.rounding);
-        let final_dhuhr = solar_time
-            .transit
-            .adjust_time(parameters.time_adjustments(Prayer::Dhuhr))
-            .rounded_minute(parameters.rounding);
-        let final_asr = asr
-            .adjust_time(parameters.time_adjustments(Prayer::Asr))
-            .rounded_minute(parameters.rounding);
-        let final_maghrib = ops::adjust_time(
-            &solar_time.sunset,
-            parameters.time_adjustments(Prayer::Maghrib),
-        )
-        .rounded_minute(parameters.rounding);
-        let final_isha =
-            PrayerTimes::calculate_isha(parameters, solar_time, night, coordinates, prayer_date)
-                .rounded_minute(parameters.rounding);
-
-        let day_after_tomorrow = tomorrow.tomorrow();
-        let solar_time_day_after = SolarTime::new(day_after_tomorrow, coordinates);
-        let tomorrow_night = solar_time_day_after
-            .sunrise
-            .signed_duration_since(solar_time_tomorrow.sunset);
-        let final_fajr_tomorrow =
-            PrayerTimes::calculate_fajr(parameters, solar_time_tomorrow, tomorrow_night, coordinates, tomorrow);
+        let (fajr, fajr_source, fajr_clamped) =
+            PrayerTimes::calculate_fajr(parameters, solar_time, night, coordinates, prayer_date)?;
+        let final_fajr = fajr.rounded_minute(parameters.rounding);
+        let final_sunrise = PrayerTimes::daytime(Prayer::Sunrise, parameters, &solar_time)?;
+        let final_dhuhr = PrayerTimes::daytime(Prayer::Dhuhr, parameters, &solar_time)?;
+        let final_asr = PrayerTimes::daytime(Prayer::Asr, parameters, &solar_time)?;
+        let mut final_maghrib = PrayerTimes::daytime(Prayer::Maghrib, parameters, &solar_time)?;
+        let (mut ishaa, mut ishaa_source, mut ishaa_clamped) = PrayerTimes::calculate_isha(
+            parameters,
+            solar_time,
+            final_maghrib,
+            night,
+            coordinates,
+            prayer_date,
+        )?;
+
+        let final_fajr_tomorrow = match (parameters.next_day_fajr, solar_time_day_after) {
+            (NextDayFajr::Computed, Some(solar_time_day_after)) => {
+                let tomorrow_night = solar_time_day_after
+                    .sunrise
+                    .signed_duration_since(solar_time_tomorrow.sunset);
+                let (fajr_tomorrow, _, _) = PrayerTimes::calculate_fajr(
+                    parameters,
+                    solar_time_tomorrow,
+                    tomorrow_night,
+                    coordinates,
+                    tomorrow,
+                )?;
+
+                fajr_tomorrow
+            }
+            _ => final_fajr.checked_add_signed(Duration::days(1))?,
+        };
+
+        // With short nights Ishaa can run into the next Fajr, in which
+        // case the night is shared by the high latitude rule instead. As
+        // the rule measures a different night than the next Fajr, it can
+        // still collide, and Ishaa is then put halfway through the night
+        // that is left. The same goes when the rule leaves no time after
+        // Maghrib, as the twilight angle rule does for an Ishaa interval,
+        // which has no angle to take a portion of the night from.
+        let mut ruled = false;
+
+        if ishaa >= final_fajr_tomorrow {
+            ishaa =
+                PrayerTimes::safe_isha(parameters, solar_time, night, coordinates, prayer_date)?
+                    .adjust_time(parameters.time_adjustments(Prayer::Ishaa))?;
+            ishaa_source = PrayerTimes::safe_time_source(parameters, Prayer::Ishaa);
+            ishaa_clamped = false;
+            ruled = true;
+        } else {
+            // Nothing to do.
+        }
+        let rounded = ishaa.rounded_minute(parameters.rounding);
+
+        if rounded >= final_fajr_tomorrow || (ruled && rounded <= final_maghrib) {
+            ishaa = solar_time.sunset
+                + final_fajr_tomorrow.signed_duration_since(solar_time.sunset) / 2;
+            ishaa_source = TimeSource::HighLatitudeRule(HighLatitudeRule::MiddleOfTheNight);
+            ishaa_clamped = false;
+        } else {
+            // Nothing to do.
+        }
+        let final_isha = ishaa.rounded_minute(parameters.rounding);
+
+        // An angle based Maghrib can't wait past Ishaa, which happens with
+        // an interval Ishaa or when Ishaa is moved by the high latitude
+        // rule: Maghrib is then at sunset.
+        if final_maghrib >= final_isha {
+            final_maghrib = PrayerTimes::daytime(
+                Prayer::Maghrib,
+                parameters.maghrib(MaghribParameter::Sunset),
+                &solar_time,
+            )?;
+        } else {
+            // Nothing to do.
+        }
 
-        PrayerTimes {
+        Some(PrayerTimes {
+            date,
+            origin: Origin::Computed(coordinates, parameters),
             fajr: final_fajr,
             sunrise: final_sunrise,
             dhuhr: final_dhuhr,
@@ -85,10 +634,101 @@ impl PrayerTimes {
             maghrib: final_maghrib,
             ishaa: final_isha,
             fajr_tomorrow: final_fajr_tomorrow,
+            fajr_source,
+            ishaa_source,
+            fajr_clamped,
+            ishaa_clamped,
+            solar_days: None,
+        })
+    }
+
+    /// The adjusted and rounded time of a prayer that only depends on the
+    /// sun's position on the prayer day: sunrise, Dhuhr, Asr or Maghrib.
+    /// `None` for the others, or when out of range.
+    fn daytime(
+        prayer: Prayer,
+        parameters: Parameters,
+        solar_time: &SolarTime,
+    ) -> Option<DateTime<Utc>> {
+        let time = match prayer {
+            Prayer::Sunrise => solar_time.sunrise,
+            Prayer::Dhuhr => solar_time
+                .transit
+                .adjust_time(parameters.dhuhr_offset_after_transit)?,
+            Prayer::Asr => solar_time.afternoon(parameters.shadow_factor()),
+            Prayer::Maghrib => match parameters.maghrib_parameter {
+                MaghribParameter::Sunset => solar_time.sunset,
+                MaghribParameter::Angle(angle) => solar_time
+                    .checked_time_for_solar_angle(Angle::new(-angle), true)
+                    .filter(|maghrib| *maghrib > solar_time.sunset)
+                    .unwrap_or(solar_time.sunset),
+            },
+            Prayer::Fajr | Prayer::Ishaa | Prayer::FajrTomorrow => return None,
+        };
+
+        Some(
+            time.adjust_time(parameters.time_adjustments(prayer))?
+                .rounded_minute(parameters.rounding),
+        )
+    }
+
+    /// Like [`computed`](PrayerTimes::computed), but refuses parameters
+    /// with invalid values. When refused, all issues found are returned,
+    /// including warnings; see [`Parameters::validate`]. Otherwise the
+    /// warnings are among the [`warnings`](PrayerTimes::warnings) of the
+    /// schedule, as [`Warning::IgnoredSetting`].
+    pub fn validated(
+        date: NaiveDate,
+        coordinates: Coordinates,
+        parameters: Parameters,
+    ) -> Result<PrayerTimes, Vec<ParameterIssue>> {
+        let issues = parameters.validate();
+
+        if issues.iter().any(ParameterIssue::is_error) {
+            Err(issues)
+        } else {
+            Ok(PrayerTimes::computed(date, coordinates, parameters))
         }
     }
 
-    pub fn precomputed(date: NaiveDate, provider: Provider) -> PrayerTimes {
+    /// Computes the prayer times of the civil date of `datetime` in its
+    /// own timezone, rather than of its UTC date. The day boundary is the
+    /// local civil day at the offset of `datetime`.
+    pub fn at<Tz: TimeZone>(
+        datetime: &DateTime<Tz>,
+        coordinates: Coordinates,
+        mut parameters: Parameters,
+    ) -> PrayerTimes {
+        parameters.day_boundary = DayBoundary::LocalCivil(datetime.offset().fix());
+        PrayerTimes::computed(datetime.date_naive(), coordinates, parameters)
+    }
+
+    /// The schedule read in `zone`.
+    pub fn localized<Tz: TimeZone>(self, zone: Tz) -> LocalizedPrayerTimes<Tz> {
+        LocalizedPrayerTimes { times: self, zone }
+    }
+
+    /// Computes the prayer times of every date from `start` to `end`,
+    /// both inclusive. Fails on the first date that can't be computed.
+    pub fn on_range(
+        start: NaiveDate,
+        end: NaiveDate,
+        coordinates: Coordinates,
+        parameters: Parameters,
+    ) -> Result<Vec<PrayerTimes>, CalculationError> {
+        start
+            .iter_days()
+            .take_while(|date| *date <= end)
+            .map(|date| PrayerTimes::try_computed(date, coordinates, parameters))
+            .collect()
+    }
+
+    /// The times of `date` published by `provider`. Fails when the day
+    /// after `date` can't be represented.
+    pub fn precomputed(
+        date: NaiveDate,
+        provider: Provider,
+    ) -> Result<PrayerTimes, CalculationError> {
         let data = match provider {
             Provider::DarElFatwa(_) => &dar_el_fatwa_beirut::DATA,
         };
@@ -99,18 +739,19 @@ impl PrayerTimes {
 
         let tomorrow_date = date
             .checked_add_days(Days::new(1))
-            .expect("failed to get tomorrow's date");
+            .ok_or(CalculationError::DateOutOfRange(date))?;
         let tomorrow_day = tomorrow_date.day0() as usize;
         let tomorrow_month = tomorrow_date.month0() as usize;
         let tomorrow_times = data[tomorrow_month][tomorrow_day];
 
         let make_time = |d: NaiveDate, h: u8, m: u8| -> DateTime<Utc> {
-            d.and_hms_opt(h.into(), m.into(), 0)
-                .expect("invalid prayer time")
-                .and_utc()
+            d.and_time(NaiveTime::MIN).and_utc()
+                + Duration::minutes(60 * i64::from(h) + i64::from(m))
         };
 
-        PrayerTimes {
+        Ok(PrayerTimes {
+            date,
+            origin: Origin::Precomputed(provider),
             fajr: make_time(date, times[0].0, times[0].1),
             sunrise: make_time(date, times[1].0, times[1].1),
             dhuhr: make_time(date, times[2].0, times[2].1),
@@ -118,6 +759,155 @@ impl PrayerTimes {
             maghrib: make_time(date, times[4].0, times[4].1),
             ishaa: make_time(date, times[5].0, times[5].1),
             fajr_tomorrow: make_time(tomorrow_date, tomorrow_times[0].0, tomorrow_times[0].1),
+            fajr_source: TimeSource::Astronomical,
+            ishaa_source: TimeSource::Astronomical,
+            fajr_clamped: false,
+            ishaa_clamped: false,
+            solar_days: None,
+        })
+    }
+
+    /// The date this schedule is for.
+    pub fn date(&self) -> NaiveDate {
+        self.date
+    }
+
+    /// The schedule of the following day, computed the same way. Fails
+    /// like [`try_computed`](PrayerTimes::try_computed) at the end of
+    /// the supported range.
+    pub fn tomorrow(&self) -> Result<PrayerTimes, CalculationError> {
+        let date = self
+            .date
+            .succ_opt()
+            .ok_or(CalculationError::DateOutOfRange(self.date))?;
+
+        self.with_date(date)
+    }
+
+    /// The schedule of the previous day, computed the same way. Fails
+    /// like [`try_computed`](PrayerTimes::try_computed) at the start of
+    /// the supported range.
+    pub fn yesterday(&self) -> Result<PrayerTimes, CalculationError> {
+        let date = self
+            .date
+            .pred_opt()
+            .ok_or(CalculationError::DateOutOfRange(self.date))?;
+
+        self.with_date(date)
+    }
+
+    fn with_date(&self, date: NaiveDate) -> Result<PrayerTimes, CalculationError> {
+        match &self.origin {
+            Origin::Computed(coordinates, parameters) => {
+                PrayerTimes::check_computable(date, *coordinates, *parameters)?;
+                PrayerTimes::calculate_with_solar_times(
+                    date,
+                    Observer::new(*coordinates),
+                    *parameters,
+                    self.solar_days.as_deref(),
+                )
+                .map(|(times, _, _)| times)
+            }
+            Origin::Precomputed(provider) => PrayerTimes::precomputed(date, *provider),
+            Origin::Table(table) => PrayerTimes::from_table(date, table),
+            Origin::Restored(_) => Err(CalculationError::DateOutOfRange(date)),
+        }
+    }
+
+    pub fn fajr(&self) -> DateTime<Utc> {
+        self.fajr
+    }
+
+    pub fn sunrise(&self) -> DateTime<Utc> {
+        self.sunrise
+    }
+
+    pub fn dhuhr(&self) -> DateTime<Utc> {
+        self.dhuhr
+    }
+
+    pub fn asr(&self) -> DateTime<Utc> {
+        self.asr
+    }
+
+    pub fn maghrib(&self) -> DateTime<Utc> {
+        self.maghrib
+    }
+
+    pub fn ishaa(&self) -> DateTime<Utc> {
+        self.ishaa
+    }
+
+    pub fn fajr_tomorrow(&self) -> DateTime<Utc> {
+        self.fajr_tomorrow
+    }
+
+    /// A hash of what the schedule depends on: its date, where it comes
+    /// from, with the exact coordinates, and the
+    /// [version of the calculation](crate::CALC_VERSION) for computed
+    /// schedules, of the crate and its data otherwise. Official tables,
+    /// which can be reloaded with other entries, are hashed by their
+    /// times. Stable across platforms and runs, for use as an ETag or a
+    /// cache key.
+    ///
+    /// ```
+    /// use miqat::prelude::*;
+    ///
+    /// let date = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+    /// let beirut = Coordinates::new(33.8938, 35.5018);
+    /// let params = Method::MuslimWorldLeague.parameters();
+    /// let hash = |params| PrayerTimes::computed(date, beirut, params).content_hash();
+    ///
+    /// assert_eq!(hash(params), hash(Method::MuslimWorldLeague.parameters()));
+    /// assert_ne!(hash(params), hash(params.mazhab(Mazhab::Hanafi)));
+    /// ```
+    pub fn content_hash(&self) -> u64 {
+        let origin = match &self.origin {
+            // Every field of the parameters and the exact coordinates, as
+            // anything derived from the schedule, e.g. a response echoing
+            // the coordinates, may show them in full.
+            Origin::Computed(coordinates, parameters) => format!(
+                "calc/{:016x}",
+                fingerprint(self.date.year(), *coordinates, *parameters)
+            ),
+            Origin::Precomputed(provider) => {
+                format!("{}/{provider:?}", env!("CARGO_PKG_VERSION"))
+            }
+            Origin::Table(_) | Origin::Restored(_) => {
+                let times = [
+                    self.fajr,
+                    self.sunrise,
+                    self.dhuhr,
+                    self.asr,
+                    self.maghrib,
+                    self.ishaa,
+                    self.fajr_tomorrow,
+                ]
+                .map(|time| time.timestamp().to_string());
+
+                format!("{}/{}", env!("CARGO_PKG_VERSION"), times.join(","))
+            }
+        };
+
+        fnv1a(&format!("{}/{origin}", self.date))
+    }
+
+    /// The location of the schedule: of the provider's city for
+    /// precomputed schedules, and of the fallback for official tables.
+    pub fn coordinates(&self) -> Coordinates {
+        match &self.origin {
+            Origin::Computed(coordinates, _) | Origin::Restored(coordinates) => *coordinates,
+            Origin::Precomputed(provider) => provider.coordinates(),
+            Origin::Table(table) => table.coordinates(),
+        }
+    }
+
+    /// The parameters the schedule was computed with, `None` for
+    /// precomputed schedules, official tables and deserialized schedules.
+    pub fn parameters(&self) -> Option<&Parameters> {
+        match &self.origin {
+            Origin::Computed(_, parameters) => Some(parameters),
+            Origin::Precomputed(_) | Origin::Table(_) | Origin::Restored(_) => None,
         }
     }
 
@@ -133,381 +923,2031 @@ impl PrayerTimes {
         }
     }
 
-    pub fn current(&self) -> Prayer {
-        self.current_time(Utc::now()).expect("Out of bounds")
+    /// The altitude of the sun in degrees at the time of the given prayer,
+    /// e.g. about -18 at Fajr with the Muslim World League method, and
+    /// `None` for precomputed schedules and official tables as they are
+    /// not tied to coordinates. The altitude is geometric: at sunrise and
+    /// Maghrib it is about -0.83 as refraction lifts the sun's image.
+    pub fn solar_altitude_at(&self, prayer: Prayer) -> Option<f64> {
+        match &self.origin {
+            Origin::Computed(coordinates, _) | Origin::Restored(coordinates) => {
+                Some(SolarPosition::new(self.time(prayer), *coordinates).altitude)
+            }
+            Origin::Precomputed(_) | Origin::Table(_) => None,
+        }
     }
 
-    pub fn next(&self) -> Prayer {
-        match self.current() {
-            Prayer::Fajr => Prayer::Sunrise,
-            Prayer::Sunrise => Prayer::Dhuhr,
-            Prayer::Dhuhr => Prayer::Asr,
-            Prayer::Asr => Prayer::Maghrib,
-            Prayer::Maghrib => Prayer::Ishaa,
-            Prayer::Ishaa | Prayer::FajrTomorrow => Prayer::FajrTomorrow,
+    /// The iqamah of the given prayer under `config`, with its clock
+    /// times read at `offset`; see [`IqamahConfig::time`].
+    pub fn iqamah(
+        &self,
+        prayer: Prayer,
+        config: &IqamahConfig,
+        offset: FixedOffset,
+    ) -> Option<DateTime<Utc>> {
+        config.time(self, prayer, offset)
+    }
+
+    /// Whether the given prayer was moved away from its twilight angle
+    /// to a safe time for the day (see [`Parameters::disable_safe_time_clamp`]).
+    /// Only Fajr and Ishaa can be clamped; precomputed schedules never are.
+    /// A time the sun never reaches is estimated but not clamped, see
+    /// [`source`](Self::source).
+    pub fn is_clamped(&self, prayer: Prayer) -> bool {
+        match prayer {
+            Prayer::Fajr => self.fajr_clamped,
+            Prayer::Ishaa => self.ishaa_clamped,
+            _ => false,
         }
     }
 
-    pub fn time_remaining(&self) -> (u32, u32) {
-        let next_time = self.time(self.next());
-        let now = Utc::now();
-        let now_to_next = next_time.signed_duration_since(now).num_seconds() as f64;
-        let whole: f64 = now_to_next / 60.0 / 60.0;
-        let fract = whole.fract();
-        let hours = whole.trunc() as u32;
-        let minutes = (fract * 60.0).round() as u32;
-
-        (hours, minutes)
-    }
-
-    fn current_time(&self, time: DateTime<Utc>) -> Option<Prayer> {
-        let current_prayer: Option<Prayer>;
-
-        if self.fajr_tomorrow.signed_duration_since(time).num_seconds() <= 0 {
-            current_prayer = Some(Prayer::FajrTomorrow)
-        } else if self.ishaa.signed_duration_since(time).num_seconds() <= 0 {
-            current_prayer = Some(Prayer::Ishaa);
-        } else if self.maghrib.signed_duration_since(time).num_seconds() <= 0 {
-            current_prayer = Some(Prayer::Maghrib);
-        } else if self.asr.signed_duration_since(time).num_seconds() <= 0 {
-            current_prayer = Some(Prayer::Asr);
-        } else if self.dhuhr.signed_duration_since(time).num_seconds() <= 0 {
-            current_prayer = Some(Prayer::Dhuhr);
-        } else if self.sunrise.signed_duration_since(time).num_seconds() <= 0 {
-            current_prayer = Some(Prayer::Sunrise);
-        } else if self.fajr.signed_duration_since(time).num_seconds() <= 0 {
-            current_prayer = Some(Prayer::Fajr);
-        } else {
-            current_prayer = None;
+    /// How the time of the given prayer was obtained.
+    pub fn source(&self, prayer: Prayer) -> TimeSource {
+        match prayer {
+            Prayer::Fajr => self.fajr_source,
+            Prayer::Ishaa => self.ishaa_source,
+            _ => TimeSource::Astronomical,
+        }
+    }
+
+    /// The time of the given prayer along with how it was obtained.
+    pub fn timed(&self, prayer: Prayer) -> TimedValue {
+        TimedValue {
+            time: self.time(prayer),
+            source: self.source(prayer),
         }
+    }
 
-        current_prayer
+    /// The consecutive prayers that are not in chronological order, e.g.
+    /// an Asr before sunrise when the sun barely clears the horizon in
+    /// polar regions. Empty for any schedule that can be used as is.
+    pub fn ordering_violations(&self) -> Vec<(Prayer, Prayer)> {
+        Prayer::ALL
+            .into_iter()
+            .map(|prayer| (prayer, PrayerTimes::following(prayer)))
+            .filter(|(prayer, following)| self.time(*prayer) >= self.time(*following))
+            .collect()
     }
 
-    fn calculate_fajr(
-        parameters: Parameters,
-        solar_time: SolarTime,
-        night: Duration,
-        coordinates: Coordinates,
-        prayer_date: DateTime<Utc>,
-    ) -> DateTime<Utc> {
-        let mut fajr = solar_time.time_for_solar_angle(Angle::new(-parameters.fajr_angle), false);
-
-        // special case for moonsighting committee above latitude 55
-        if parameters.is_moonsighting_committee && coordinates.latitude >= 55.0 {
-            let night_fraction = night.num_seconds() / 7;
-            fajr = solar_time
-                .sunrise
-                .checked_add_signed(Duration::seconds(-night_fraction))
-                .unwrap();
+    /// The conditions apps may want to point out about the schedule, see
+    /// [`Warning`]. Settings are only checked for computed schedules.
+    ///
+    /// ```
+    /// use miqat::prelude::*;
+    ///
+    /// let date = NaiveDate::from_ymd_opt(2024, 6, 21).unwrap();
+    /// let oslo = Coordinates::new(59.9139, 10.7522);
+    /// let times = PrayerTimes::computed(date, oslo, Method::MuslimWorldLeague.parameters());
+    ///
+    /// for warning in times.warnings() {
+    ///     println!("{warning}");
+    /// }
+    /// assert!(times.warnings().contains(&Warning::Estimated(
+    ///     Prayer::Fajr,
+    ///     TimeSource::HighLatitudeRule(HighLatitudeRule::MiddleOfTheNight),
+    /// )));
+    /// ```
+    pub fn warnings(&self) -> Warnings {
+        let mut warnings = Warnings::default();
+
+        for prayer in [Prayer::Fajr, Prayer::Ishaa] {
+            let source = self.source(prayer);
+
+            if source.is_estimated() {
+                warnings.push(Warning::Estimated(prayer, source));
+            } else {
+                // Nothing to do.
+            }
+        }
+
+        for (before, after) in self.ordering_violations() {
+            warnings.push(Warning::OutOfOrder(before, after));
+        }
+
+        if let Some(parameters) = self.parameters() {
+            for issue in parameters.validate() {
+                if issue.is_error() {
+                    // Refused by `validated`, and out of range adjustments
+                    // are reported as large below.
+                } else {
+                    warnings.push(Warning::IgnoredSetting(issue));
+                }
+            }
+
+            for prayer in Prayer::ALL {
+                let minutes = parameters.time_adjustments(prayer);
+
+                if minutes.unsigned_abs() > Warnings::LARGE_ADJUSTMENT_MINUTES.unsigned_abs() {
+                    warnings.push(Warning::LargeAdjustment(prayer, minutes));
+                } else {
+                    // Nothing to do.
+                }
+            }
         } else {
             // Nothing to do.
         }
 
-        let safe_fajr = if parameters.is_moonsighting_committee {
-            let day_of_year = prayer_date.ordinal();
-            ops::season_adjusted_morning_twilight(
-                coordinates.latitude,
-                day_of_year,
-                prayer_date.year() as u32,
-                solar_time.sunrise,
-            )
+        let year = self.date.year();
+        if year < PrayerTimes::SUPPORTED_YEARS.start() + Warnings::NEAR_LIMIT_YEARS
+            || year > PrayerTimes::SUPPORTED_YEARS.end() - Warnings::NEAR_LIMIT_YEARS
+        {
+            warnings.push(Warning::NearSupportedLimit(self.date));
         } else {
-            let portion = parameters.night_portions().0;
-            let night_fraction = portion * (night.num_seconds() as f64);
+            // Nothing to do.
+        }
 
-            solar_time
-                .sunrise
-                .checked_add_signed(Duration::seconds(-night_fraction as i64))
-                .unwrap()
-        };
+        warnings
+    }
 
-        if fajr < safe_fajr {
-            fajr = safe_fajr;
+    /// Whether `instant` falls within the window of the given prayer, from
+    /// its time up to (excluding) the time of the following one. The
+    /// window of `FajrTomorrow` has no end in this schedule.
+    pub fn is_within(&self, prayer: Prayer, instant: DateTime<Utc>) -> bool {
+        self.window(prayer)
+            .is_some_and(|window| window.contains(instant))
+    }
+
+    /// How far `instant` is through the window of the given prayer, from
+    /// 0.0 at its start towards 1.0 at its end, or `None` when outside it.
+    pub fn progress(&self, prayer: Prayer, instant: DateTime<Utc>) -> Option<f32> {
+        if self.is_within(prayer, instant) {
+            let Window { start, end } = self.window(prayer)?;
+            let elapsed = instant.signed_duration_since(start).num_milliseconds() as f64;
+            let total = end.signed_duration_since(start).num_milliseconds() as f64;
+
+            Some((elapsed / total) as f32)
         } else {
-            // Nothing to do.
+            None
         }
+    }
 
-        fajr.adjust_time(parameters.time_adjustments(Prayer::Fajr))
+    /// The window of `prayer`, up to the [following](PrayerTimes::following)
+    /// prayer. `None` for `FajrTomorrow`, whose window ends in the next
+    /// schedule.
+    fn window(&self, prayer: Prayer) -> Option<Window> {
+        match prayer {
+            Prayer::FajrTomorrow => None,
+            _ => Some(Window {
+                start: self.time(prayer),
+                end: self.time(PrayerTimes::following(prayer)),
+            }),
+        }
     }
 
-    fn calculate_isha(
-        parameters: Parameters,
+    /// The obligatory prayers of this schedule whose window ended by
+    /// `now` without a check-in within it, see
+    /// [`is_within`](PrayerTimes::is_within), in order. Prayers whose time
+    /// hasn't come or can still be prayed are not missed. A check-in for
+    /// `FajrTomorrow` counts as Fajr, and check-ins for Sunrise are ignored.
+    #[cfg(feature = "qada")]
+    pub fn missed(&self, check_ins: &[CheckIn], now: DateTime<Utc>) -> Vec<Prayer> {
+        let ended = |prayer: Prayer| self.window(prayer).is_some_and(|window| window.end <= now);
+        let prayed = |prayer: Prayer| {
+            check_ins.iter().any(|check_in| {
+                let same = match check_in.prayer {
+                    Prayer::FajrTomorrow => Prayer::Fajr,
+                    other => other,
+                } == prayer;
+
+                same && self.is_within(prayer, check_in.time)
+            })
+        };
+
+        Prayer::OBLIGATORY
+            .into_iter()
+            .filter(|prayer| ended(*prayer) && !prayed(*prayer))
+            .collect()
+    }
+
+    /// The occurrence of `prayer` closest to `instant`, whether past or
+    /// future, on any day. The schedule of the day `instant` falls on is
+    /// computed the same way as this one, and a neighbouring day's only
+    /// when `instant` is more than [`NEAREST_SPAN`](Self::NEAREST_SPAN)
+    /// from that day's `prayer`. `FajrTomorrow` is the same as Fajr. At
+    /// equal distance, the past occurrence is returned. Fails if a
+    /// schedule that is needed can't be computed.
+    ///
+    /// ```
+    /// use miqat::prelude::*;
+    ///
+    /// let date = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+    /// let beirut = Coordinates::new(33.8938, 35.5018);
+    /// let times = PrayerTimes::computed(date, beirut, Method::MuslimWorldLeague.parameters());
+    /// let later = times.time(Prayer::Asr) + Duration::minutes(12);
+    /// let missed = later - times.nearest(Prayer::Asr, later).unwrap();
+    ///
+    /// assert_eq!(missed, Duration::minutes(12));
+    /// assert_eq!(
+    ///     times.nearest(Prayer::Fajr, times.time(Prayer::Ishaa)).unwrap(),
+    ///     times.time(Prayer::FajrTomorrow)
+    /// );
+    /// ```
+    pub fn nearest(
+        &self,
+        prayer: Prayer,
+        instant: DateTime<Utc>,
+    ) -> Result<DateTime<Utc>, CalculationError> {
+        let prayer = match prayer {
+            Prayer::FajrTomorrow => Prayer::Fajr,
+            _ => prayer,
+        };
+        let days = ((instant - self.time(prayer)).as_seconds_f64() / 86_400.0).round();
+        let day = match days as i64 {
+            0 => self.clone(),
+            days => {
+                let date = Duration::try_days(days)
+                    .and_then(|days| self.date.checked_add_signed(days))
+                    .ok_or(CalculationError::DateOutOfRange(self.date))?;
+
+                self.with_date(date)?
+            }
+        };
+        let time = day.time(prayer);
+        let distance = |time: DateTime<Utc>| (time - instant).abs();
+
+        if distance(time) <= PrayerTimes::NEAREST_SPAN {
+            return Ok(time);
+        } else {
+            // Nothing to do.
+        }
+
+        let neighbour = if instant < time {
+            day.yesterday()?.time(prayer)
+        } else {
+            day.tomorrow()?.time(prayer)
+        };
+
+        match distance(neighbour).cmp(&distance(time)) {
+            std::cmp::Ordering::Less => Ok(neighbour),
+            std::cmp::Ordering::Greater => Ok(time),
+            std::cmp::Ordering::Equal => Ok(neighbour.min(time)),
+        }
+    }
+
+    /// The prayer whose time it currently is, or `None` before this
+    /// schedule's Fajr.
+    pub fn current(&self) -> Option<Prayer> {
+        self.current_with(&SystemClock)
+    }
+
+    pub fn next(&self) -> Prayer {
+        self.next_with(&SystemClock)
+    }
+
+    /// The hours and minutes until the [next](PrayerTimes::next) prayer,
+    /// to the nearest minute.
+    pub fn time_remaining(&self) -> (u32, u32) {
+        self.time_remaining_with(&SystemClock)
+    }
+
+    /// How long until the [next](PrayerTimes::next) prayer, written for
+    /// a notification in `language`; see [`format_duration_until`].
+    pub fn countdown(&self, language: Language) -> String {
+        self.countdown_with(&SystemClock, language)
+    }
+
+    /// Like [`current`](PrayerTimes::current), reading the time from `clock`.
+    pub fn current_with(&self, clock: &impl Clock) -> Option<Prayer> {
+        self.current_time(clock.now())
+    }
+
+    /// Like [`next`](PrayerTimes::next), reading the time from `clock`.
+    pub fn next_with(&self, clock: &impl Clock) -> Prayer {
+        match self.current_with(clock) {
+            None => Prayer::Fajr,
+            Some(current) => PrayerTimes::following(current),
+        }
+    }
+
+    /// Like [`time_remaining`](PrayerTimes::time_remaining), reading the
+    /// time from `clock`.
+    pub fn time_remaining_with(&self, clock: &impl Clock) -> (u32, u32) {
+        let next_time = self.time(self.next_with(clock));
+
+        hours_and_minutes(next_time.signed_duration_since(clock.now()))
+    }
+
+    /// Like [`countdown`](PrayerTimes::countdown), reading the time from
+    /// `clock`.
+    pub fn countdown_with(&self, clock: &impl Clock, language: Language) -> String {
+        let next = self.next_with(clock);
+
+        format_duration_until(
+            next,
+            &FixedClock(self.time(next)),
+            self.time(next).signed_duration_since(clock.now()),
+            language,
+        )
+    }
+
+    /// The prayer whose time it is at `instant`, with the bounds of its
+    /// window: from its time until the time of the prayer that follows,
+    /// Ishaa lasting until the next Fajr. `None` before Fajr and from the
+    /// next Fajr on, which fall in the windows of the adjacent days.
+    ///
+    /// ```
+    /// use miqat::prelude::*;
+    ///
+    /// let date = NaiveDate::from_ymd_opt(2015, 7, 12).unwrap();
+    /// let raleigh = Coordinates::new(35.7750, -78.6336);
+    /// let times = PrayerTimes::computed(date, raleigh, Method::NorthAmerica.parameters());
+    /// let afternoon = Utc.with_ymd_and_hms(2015, 7, 12, 22, 0, 0).unwrap();
+    ///
+    /// assert_eq!(
+    ///     times.prayer_at(afternoon),
+    ///     Some((Prayer::Asr, times.time(Prayer::Asr), times.time(Prayer::Maghrib)))
+    /// );
+    /// assert_eq!(times.prayer_at(times.time(Prayer::FajrTomorrow)), None);
+    /// ```
+    pub fn prayer_at(
+        &self,
+        instant: DateTime<Utc>,
+    ) -> Option<(Prayer, DateTime<Utc>, DateTime<Utc>)> {
+        if self.has_started(Prayer::FajrTomorrow, instant) {
+            return None;
+        } else {
+            // Nothing to do.
+        }
+
+        let prayer = Prayer::ALL
+            .into_iter()
+            .rev()
+            .find(|prayer| self.has_started(*prayer, instant))?;
+        let window = self.window(prayer)?;
+
+        Some((prayer, window.start, window.end))
+    }
+
+    pub(crate) fn current_time(&self, time: DateTime<Utc>) -> Option<Prayer> {
+        if self.has_started(Prayer::FajrTomorrow, time) {
+            Some(Prayer::FajrTomorrow)
+        } else {
+            self.prayer_at(time).map(|(prayer, ..)| prayer)
+        }
+    }
+
+    /// The prayer after `prayer`, the next Fajr after Ishaa.
+    pub(crate) fn following(prayer: Prayer) -> Prayer {
+        match prayer {
+            Prayer::Fajr => Prayer::Sunrise,
+            Prayer::Sunrise => Prayer::Dhuhr,
+            Prayer::Dhuhr => Prayer::Asr,
+            Prayer::Asr => Prayer::Maghrib,
+            Prayer::Maghrib => Prayer::Ishaa,
+            Prayer::Ishaa | Prayer::FajrTomorrow => Prayer::FajrTomorrow,
+        }
+    }
+
+    // Within a second of its time, a prayer counts as started.
+    fn has_started(&self, prayer: Prayer, instant: DateTime<Utc>) -> bool {
+        self.time(prayer)
+            .signed_duration_since(instant)
+            .num_seconds()
+            <= 0
+    }
+
+    /// The solar events of the UTC day of `date`, with the sunrise and
+    /// sunset seen from the elevation of `parameters`, so that the night
+    /// and everything measured from it follow them too.
+    pub(crate) fn solar_time(
+        date: DateTime<Utc>,
+        coordinates: Coordinates,
+        parameters: Parameters,
+    ) -> Option<SolarTime> {
+        PrayerTimes::solar_time_for(date, Observer::new(coordinates), parameters)
+    }
+
+    fn solar_time_for(
+        date: DateTime<Utc>,
+        observer: Observer,
+        parameters: Parameters,
+    ) -> Option<SolarTime> {
+        SolarTime::checked_for(date, observer, parameters.delta_t)
+            .map(|solar_time| solar_time.with_horizon(parameters.horizon()))
+    }
+
+    /// The UTC day whose solar events make up the schedule of `date`.
+    fn solar_day(
+        date: NaiveDate,
+        coordinates: Coordinates,
+        parameters: Parameters,
+    ) -> Option<DateTime<Utc>> {
+        let utc_day = date.and_time(NaiveTime::MIN).and_utc();
+
+        match parameters.day_boundary {
+            DayBoundary::Utc => Some(utc_day),
+            DayBoundary::LocalCivil(offset) => {
+                // Without a sunrise or sunset, the mean solar noon is close
+                // enough to tell the date of the transit.
+                let longitude = coordinates
+                    .longitude_angle()
+                    .quadrant_shifted()
+                    .degrees
+                    .clamp(-180.0, 180.0);
+                let transit =
+                    SolarTime::checked_with_delta_t(utc_day, coordinates, parameters.delta_t)
+                        .map(|solar_time| solar_time.transit)
+                        .unwrap_or_else(|| {
+                            utc_day + Duration::minutes((720.0 - 4.0 * longitude) as i64)
+                        });
+                let shift = date.signed_duration_since(transit.with_timezone(&offset).date_naive());
+
+                utc_day.checked_add_signed(shift)
+            }
+        }
+    }
+
+    fn calculate_fajr(
+        parameters: Parameters,
+        solar_time: SolarTime,
+        night: Duration,
+        coordinates: Coordinates,
+        prayer_date: DateTime<Utc>,
+    ) -> Option<(DateTime<Utc>, TimeSource, bool)> {
+        let safe_fajr =
+            PrayerTimes::safe_fajr(parameters, solar_time, night, coordinates, prayer_date)?;
+        let astronomical = solar_time
+            .checked_time_for_solar_angle(Angle::new(-parameters.fajr_angle), false)
+            .filter(|fajr| *fajr < solar_time.sunrise);
+        let (mut fajr, mut source) = match astronomical {
+            Some(fajr) => (fajr, TimeSource::Astronomical),
+            // The sun doesn't get that far below the horizon: fall back to
+            // the high latitude rule, even if the clamp is disabled.
+            None => (
+                safe_fajr,
+                PrayerTimes::safe_time_source(parameters, Prayer::Fajr),
+            ),
+        };
+
+        if let Some(portion) = parameters.behavior.fixed_night_portion(coordinates) {
+            fajr = shifted(solar_time.sunrise, -portion * (night.num_seconds() as f64))?;
+            source = TimeSource::HighLatitudeRule(HighLatitudeRule::SeventhOfTheNight);
+        } else {
+            // Nothing to do.
+        }
+
+        let clamped = !parameters.disable_safe_time_clamp && fajr < safe_fajr;
+
+        if clamped {
+            fajr = safe_fajr;
+            source = PrayerTimes::safe_time_source(parameters, Prayer::Fajr);
+        } else {
+            // Nothing to do.
+        }
+
+        Some((
+            fajr.adjust_time(parameters.time_adjustments(Prayer::Fajr))?,
+            source,
+            clamped,
+        ))
+    }
+
+    fn calculate_isha(
+        parameters: Parameters,
         solar_time: SolarTime,
+        maghrib: DateTime<Utc>,
         night: Duration,
         coordinates: Coordinates,
         prayer_date: DateTime<Utc>,
-    ) -> DateTime<Utc> {
+    ) -> Option<(DateTime<Utc>, TimeSource, bool)> {
         let mut ishaa: DateTime<Utc>;
+        let mut source = TimeSource::Astronomical;
+        let mut clamped = false;
 
         match parameters.ishaa_parameter {
             IshaaParameter::Interval(interval) => {
-                ishaa = solar_time
-                    .sunset
-                    .checked_add_signed(Duration::seconds((interval * 60) as i64))
-                    .unwrap();
+                let start = match parameters.ishaa_interval_start {
+                    IshaaIntervalStart::Sunset => solar_time.sunset,
+                    IshaaIntervalStart::Maghrib => maghrib,
+                };
+
+                ishaa = start.checked_add_signed(Duration::minutes(interval.into()))?;
             }
             IshaaParameter::Angle(angle) => {
-                ishaa = solar_time.time_for_solar_angle(Angle::new(-angle), true);
-
-                // special case for moonsighting committee above latitude 55
-                if parameters.is_moonsighting_committee && coordinates.latitude >= 55.0 {
-                    let night_fraction = night.num_seconds() / 7;
-                    ishaa = solar_time
-                        .sunset
-                        .checked_add_signed(Duration::seconds(night_fraction))
-                        .unwrap();
+                let safe_isha = PrayerTimes::safe_isha(
+                    parameters,
+                    solar_time,
+                    night,
+                    coordinates,
+                    prayer_date,
+                )?;
+                let astronomical = solar_time
+                    .checked_time_for_solar_angle(Angle::new(-angle), true)
+                    .filter(|ishaa| *ishaa > solar_time.sunset);
+
+                match astronomical {
+                    Some(time) => ishaa = time,
+                    // The sun doesn't get that far below the horizon: fall
+                    // back to the high latitude rule, even if the clamp is
+                    // disabled.
+                    None => {
+                        ishaa = safe_isha;
+                        source = PrayerTimes::safe_time_source(parameters, Prayer::Ishaa);
+                    }
+                }
+
+                if let Some(portion) = parameters.behavior.fixed_night_portion(coordinates) {
+                    ishaa = shifted(solar_time.sunset, portion * (night.num_seconds() as f64))?;
+                    source = TimeSource::HighLatitudeRule(HighLatitudeRule::SeventhOfTheNight);
+                } else {
+                    // Nothing to do.
+                }
+
+                if !parameters.disable_safe_time_clamp && ishaa > safe_isha {
+                    ishaa = safe_isha;
+                    source = PrayerTimes::safe_time_source(parameters, Prayer::Ishaa);
+                    clamped = true;
                 } else {
                     // Nothing to do.
                 }
+            }
+        }
+
+        Some((
+            ishaa.adjust_time(parameters.time_adjustments(Prayer::Ishaa))?,
+            source,
+            clamped,
+        ))
+    }
+
+    /// The earliest Fajr allowed by the high latitude rule, or by the
+    /// seasonal estimate.
+    fn safe_fajr(
+        parameters: Parameters,
+        solar_time: SolarTime,
+        night: Duration,
+        coordinates: Coordinates,
+        prayer_date: DateTime<Utc>,
+    ) -> Option<DateTime<Utc>> {
+        if parameters.uses_seasonal_twilight() {
+            let day_of_year = prayer_date.ordinal();
+            seasonal::morning_twilight(
+                coordinates.latitude,
+                day_of_year,
+                prayer_date.year() as u32,
+                solar_time.sunrise,
+            )
+        } else {
+            let portion = parameters.night_portions().0;
+
+            shifted(solar_time.sunrise, -portion * (night.num_seconds() as f64))
+        }
+    }
+
+    /// The latest Ishaa allowed by the high latitude rule, or by the
+    /// seasonal estimate.
+    fn safe_isha(
+        parameters: Parameters,
+        solar_time: SolarTime,
+        night: Duration,
+        coordinates: Coordinates,
+        prayer_date: DateTime<Utc>,
+    ) -> Option<DateTime<Utc>> {
+        if parameters.uses_seasonal_twilight() {
+            let day_of_year = prayer_date.ordinal();
+
+            seasonal::evening_twilight(
+                coordinates.latitude,
+                day_of_year,
+                prayer_date.year() as u32,
+                solar_time.sunset,
+                parameters.twilight,
+            )
+        } else {
+            let portion = parameters.night_portions().1;
+
+            shifted(solar_time.sunset, portion * (night.num_seconds() as f64))
+        }
+    }
+
+    fn safe_time_source(parameters: Parameters, prayer: Prayer) -> TimeSource {
+        if parameters.uses_seasonal_twilight() {
+            TimeSource::SeasonalEstimate
+        } else {
+            TimeSource::HighLatitudeRule(parameters.high_latitude_rule_for(prayer))
+        }
+    }
+}
+
+/// `time` moved by the given number of seconds, or `None` when the
+/// result is out of range.
+fn shifted(time: DateTime<Utc>, seconds: f64) -> Option<DateTime<Utc>> {
+    time.checked_add_signed(Duration::try_seconds(seconds as i64)?)
+}
+
+/// The serialized form of [`PrayerTimes`], see
+/// [`SERIALIZATION_VERSION`](PrayerTimes::SERIALIZATION_VERSION).
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct Versioned {
+    version: u32,
+    date: NaiveDate,
+    latitude: f64,
+    longitude: f64,
+    fajr: DateTime<Utc>,
+    sunrise: DateTime<Utc>,
+    dhuhr: DateTime<Utc>,
+    asr: DateTime<Utc>,
+    maghrib: DateTime<Utc>,
+    ishaa: DateTime<Utc>,
+    fajr_tomorrow: DateTime<Utc>,
+    fajr_source: TimeSource,
+    ishaa_source: TimeSource,
+    #[serde(default)]
+    fajr_clamped: bool,
+    #[serde(default)]
+    ishaa_clamped: bool,
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for PrayerTimes {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let coordinates = self.coordinates();
+
+        Versioned {
+            version: PrayerTimes::SERIALIZATION_VERSION,
+            date: self.date,
+            latitude: coordinates.latitude,
+            longitude: coordinates.longitude,
+            fajr: self.fajr,
+            sunrise: self.sunrise,
+            dhuhr: self.dhuhr,
+            asr: self.asr,
+            maghrib: self.maghrib,
+            ishaa: self.ishaa,
+            fajr_tomorrow: self.fajr_tomorrow,
+            fajr_source: self.fajr_source,
+            ishaa_source: self.ishaa_source,
+            fajr_clamped: self.fajr_clamped,
+            ishaa_clamped: self.ishaa_clamped,
+        }
+        .serialize(serializer)
+    }
+}
+
+/// Reads back a serialized schedule. It keeps its times, sources and
+/// coordinates, but not how it was computed: [`tomorrow`](PrayerTimes::tomorrow)
+/// and [`yesterday`](PrayerTimes::yesterday) fail with
+/// [`CalculationError::DateOutOfRange`], and
+/// [`parameters`](PrayerTimes::parameters) is `None`.
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for PrayerTimes {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let versioned = Versioned::deserialize(deserializer)?;
+        if versioned.version != PrayerTimes::SERIALIZATION_VERSION {
+            return Err(serde::de::Error::custom(format!(
+                "unsupported version {}, expected {}",
+                versioned.version,
+                PrayerTimes::SERIALIZATION_VERSION
+            )));
+        } else {
+            // Nothing to do.
+        }
+
+        Ok(PrayerTimes {
+            date: versioned.date,
+            origin: Origin::Restored(Coordinates::new(versioned.latitude, versioned.longitude)),
+            fajr: versioned.fajr,
+            sunrise: versioned.sunrise,
+            dhuhr: versioned.dhuhr,
+            asr: versioned.asr,
+            maghrib: versioned.maghrib,
+            ishaa: versioned.ishaa,
+            fajr_tomorrow: versioned.fajr_tomorrow,
+            fajr_source: versioned.fajr_source,
+            ishaa_source: versioned.ishaa_source,
+            fajr_clamped: versioned.fajr_clamped,
+            ishaa_clamped: versioned.ishaa_clamped,
+            solar_days: None,
+        })
+    }
+}
+
+/// `duration` in whole hours and minutes, to the nearest minute, or
+/// zero if it is negative.
+pub(crate) fn hours_and_minutes(duration: Duration) -> (u32, u32) {
+    let minutes = (duration.num_seconds().max(0) + 30) / 60;
+    let hours = u32::try_from(minutes / 60).unwrap_or(u32::MAX);
+
+    (hours, (minutes % 60) as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::FixedClock;
+    use crate::precomputed::provider::ProviderCity;
+    use crate::{DeltaT, HighLatitudeRule, Mazhab, Method, Rounding, Twilight};
+    use chrono::{FixedOffset, NaiveDate, TimeZone, Utc};
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serializes_with_a_version() {
+        let date = NaiveDate::from_ymd_opt(2015, 7, 12).unwrap();
+        let coordinates = Coordinates::new(35.7750, -78.6336);
+        let times = PrayerTimes::computed(date, coordinates, Method::NorthAmerica.parameters());
+        let json = serde_json::to_value(&times).unwrap();
+
+        assert_eq!(json["version"], PrayerTimes::SERIALIZATION_VERSION);
+        assert_eq!(json["date"], "2015-07-12");
+        assert_eq!(json["dhuhr"], "2015-07-12T17:21:00Z");
+        assert_eq!(json["fajr_source"], "astronomical");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserializes_what_it_serializes() {
+        let date = NaiveDate::from_ymd_opt(2024, 6, 21).unwrap();
+        let oslo = Coordinates::new(59.9139, 10.7522);
+        let times = PrayerTimes::computed(date, oslo, Method::MuslimWorldLeague.parameters());
+        let json = serde_json::to_string(&times).unwrap();
+        let restored: PrayerTimes = serde_json::from_str(&json).unwrap();
 
-                let safe_isha = if parameters.is_moonsighting_committee {
-                    let day_of_year = prayer_date.ordinal();
+        assert_eq!(serde_json::to_string(&restored).unwrap(), json);
+        assert_eq!(restored.ishaa(), times.ishaa());
+        assert_eq!(restored.source(Prayer::Ishaa), times.source(Prayer::Ishaa));
+        assert_eq!(
+            restored.is_clamped(Prayer::Ishaa),
+            times.is_clamped(Prayer::Ishaa)
+        );
+        assert_eq!(
+            restored.solar_altitude_at(Prayer::Maghrib),
+            times.solar_altitude_at(Prayer::Maghrib)
+        );
+        assert_eq!(restored.parameters(), None);
+        assert_eq!(
+            restored.tomorrow(),
+            Err(CalculationError::DateOutOfRange(date.succ_opt().unwrap()))
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn rejects_other_serialization_versions() {
+        let date = NaiveDate::from_ymd_opt(2024, 6, 21).unwrap();
+        let times = PrayerTimes::computed(
+            date,
+            Coordinates::new(33.8938, 35.5018),
+            Method::MuslimWorldLeague.parameters(),
+        );
+        let mut json = serde_json::to_value(&times).unwrap();
+        json["version"] = (PrayerTimes::SERIALIZATION_VERSION + 1).into();
+
+        let error = serde_json::from_value::<PrayerTimes>(json).unwrap_err();
+        assert!(error.to_string().contains("unsupported version 2"));
+    }
+
+    #[test]
+    fn accessors_match_time() {
+        let date = NaiveDate::from_ymd_opt(2015, 7, 12).unwrap();
+        let params = Method::NorthAmerica.parameters();
+        let times = PrayerTimes::computed(date, Coordinates::new(35.7750, -78.6336), params);
+
+        assert_eq!(times.fajr(), times.time(Prayer::Fajr));
+        assert_eq!(times.ishaa(), times.time(Prayer::Ishaa));
+        assert_eq!(times.fajr_tomorrow(), times.time(Prayer::FajrTomorrow));
+        assert_eq!(times.parameters(), Some(&params));
+    }
+
+    #[test]
+    fn variants_match_separate_calculations() {
+        let date = NaiveDate::from_ymd_opt(2015, 7, 12).unwrap();
+        let coordinates = Coordinates::new(35.7750, -78.6336);
+        let params = Method::NorthAmerica.parameters();
+        let variants = PrayerTimes::with_variants(date, coordinates, params).unwrap();
+        let hanafi = PrayerTimes::computed(date, coordinates, params.mazhab(Mazhab::Hanafi));
+
+        assert_eq!(
+            variants.times,
+            PrayerTimes::computed(date, coordinates, params)
+        );
+        assert_eq!(variants.asr_shafi, variants.times.time(Prayer::Asr));
+        assert_eq!(variants.asr_hanafi, hanafi.time(Prayer::Asr));
+        assert!(variants.midnight_jafari < variants.midnight_standard);
+    }
+
+    #[test]
+    fn current_prayer_should_be_fajr() {
+        // Given the above DateTime, the Fajr prayer is at 2015-07-12T08:42:00Z
+        let local_date = NaiveDate::from_ymd_opt(2015, 7, 12).expect("Invalid date provided");
+        let params = Method::NorthAmerica.parameters();
+        let coordinates = Coordinates::new(35.7750, -78.6336);
+        let times = PrayerTimes::computed(local_date, coordinates, params);
+        let current_prayer_time = local_date.and_hms_opt(9, 0, 0).unwrap().and_utc();
+
+        assert_eq!(times.current_time(current_prayer_time), Some(Prayer::Fajr));
+    }
+
+    #[test]
+    fn current_prayer_should_be_sunrise() {
+        // Given the below DateTime, sunrise is at 2015-07-12T10:08:00Z
+        let local_date = NaiveDate::from_ymd_opt(2015, 7, 12).expect("Invalid date provided");
+        let params = Method::NorthAmerica.parameters();
+        let coordinates = Coordinates::new(35.7750, -78.6336);
+        let times = PrayerTimes::computed(local_date, coordinates, params);
+        let current_prayer_time = local_date.and_hms_opt(11, 0, 0).unwrap().and_utc();
+
+        assert_eq!(
+            times.current_time(current_prayer_time),
+            Some(Prayer::Sunrise)
+        );
+    }
+
+    #[test]
+    fn current_prayer_should_be_dhuhr() {
+        // Given the above DateTime, dhuhr prayer is at 2015-07-12T17:21:00Z
+        let local_date = NaiveDate::from_ymd_opt(2015, 7, 12).expect("Invalid date provided");
+        let params = Method::NorthAmerica.parameters();
+        let coordinates = Coordinates::new(35.7750, -78.6336);
+        let times = PrayerTimes::computed(local_date, coordinates, params);
+        let current_prayer_time = local_date.and_hms_opt(19, 0, 0).unwrap().and_utc();
+
+        assert_eq!(times.current_time(current_prayer_time), Some(Prayer::Dhuhr));
+    }
+
+    #[test]
+    fn current_prayer_should_be_asr() {
+        // Given the below DateTime, asr is at 2015-07-12T22:22:00Z
+        let local_date = NaiveDate::from_ymd_opt(2015, 7, 12).expect("Invalid date provided");
+        let params = Method::NorthAmerica.parameters();
+        let coordinates = Coordinates::new(35.7750, -78.6336);
+        let times = PrayerTimes::computed(local_date, coordinates, params);
+        let current_prayer_time = local_date.and_hms_opt(22, 26, 0).unwrap().and_utc();
+
+        assert_eq!(times.current_time(current_prayer_time), Some(Prayer::Asr));
+    }
+
+    #[test]
+    fn current_prayer_should_be_maghrib() {
+        // Given the below DateTime, maghrib is at 2015-07-13T00:32:00Z
+        let local_date = NaiveDate::from_ymd_opt(2015, 7, 12).expect("Invalid data provided");
+        let params = Method::NorthAmerica.parameters();
+        let coordinates = Coordinates::new(35.7750, -78.6336);
+        let times = PrayerTimes::computed(local_date, coordinates, params);
+        let current_prayer_time = Utc.with_ymd_and_hms(2015, 7, 13, 1, 0, 0).unwrap();
+
+        assert_eq!(
+            times.current_time(current_prayer_time),
+            Some(Prayer::Maghrib)
+        );
+    }
+
+    #[test]
+    fn current_prayer_should_be_ishaa() {
+        // Given the below DateTime, ishaa is at 2015-07-13T01:57:00Z
+        let local_date = NaiveDate::from_ymd_opt(2015, 7, 12).expect("Invalid date provided");
+        let params = Method::NorthAmerica.parameters();
+        let coordinates = Coordinates::new(35.7750, -78.6336);
+        let times = PrayerTimes::computed(local_date, coordinates, params);
+        let current_prayer_time = Utc.with_ymd_and_hms(2015, 7, 13, 2, 0, 0).unwrap();
+
+        assert_eq!(times.current_time(current_prayer_time), Some(Prayer::Ishaa));
+    }
+
+    #[test]
+    fn current_prayer_should_be_none() {
+        let local_date = NaiveDate::from_ymd_opt(2015, 7, 12).expect("Invalid data provided");
+        let params = Method::NorthAmerica.parameters();
+        let coordinates = Coordinates::new(35.7750, -78.6336);
+        let times = PrayerTimes::computed(local_date, coordinates, params);
+        let current_prayer_time = local_date.and_hms_opt(8, 0, 0).unwrap().and_utc();
+
+        assert_eq!(times.current_time(current_prayer_time), None);
+    }
+
+    #[test]
+    fn calculate_times_for_moonsighting_method() {
+        let date = NaiveDate::from_ymd_opt(2016, 1, 31).expect("Invalid date provided");
+        let params = Method::MoonsightingCommittee.parameters();
+        let coordinates = Coordinates::new(35.7750, -78.6336);
+        let prayer_times = PrayerTimes::computed(date, coordinates, params);
+
+        // fajr    = 2016-01-31 10:48:00 UTC
+        // sunrise = 2016-01-31 12:16:00 UTC
+        // dhuhr   = 2016-01-31 17:33:00 UTC
+        // asr     = 2016-01-31 20:20:00 UTC
+        // maghrib = 2016-01-31 22:43:00 UTC
+        // ishaa    = 2016-02-01 00:05:00 UTC
+        assert_eq!(
+            prayer_times
+                .time(Prayer::Fajr)
+                .format("%-l:%M %p")
+                .to_string(),
+            "10:48 AM"
+        );
+        assert_eq!(
+            prayer_times
+                .time(Prayer::Sunrise)
+                .format("%-l:%M %p")
+                .to_string(),
+            "12:16 PM"
+        );
+        assert_eq!(
+            prayer_times
+                .time(Prayer::Dhuhr)
+                .format("%-l:%M %p")
+                .to_string(),
+            "5:33 PM"
+        );
+        assert_eq!(
+            prayer_times
+                .time(Prayer::Asr)
+                .format("%-l:%M %p")
+                .to_string(),
+            "8:20 PM"
+        );
+        assert_eq!(
+            prayer_times
+                .time(Prayer::Maghrib)
+                .format("%-l:%M %p")
+                .to_string(),
+            "10:43 PM"
+        );
+        assert_eq!(
+            prayer_times
+                .time(Prayer::Ishaa)
+                .format("%-l:%M %p")
+                .to_string(),
+            "12:05 AM"
+        );
+    }
+
+    #[test]
+    fn calculate_times_for_moonsighting_method_in_the_southern_hemisphere() {
+        let params = Method::MoonsightingCommittee.parameters();
+        let sydney = Coordinates::new(-33.8688, 151.2093);
+        let johannesburg = Coordinates::new(-26.2041, 28.0473);
+        let local = |times: &PrayerTimes, prayer: Prayer, hours: i32| {
+            let offset = FixedOffset::east_opt(hours * 3600).unwrap();
+            times
+                .time(prayer)
+                .with_timezone(&offset)
+                .format("%H:%M")
+                .to_string()
+        };
+        let date = |month, day| NaiveDate::from_ymd_opt(2024, month, day).unwrap();
+
+        let summer = PrayerTimes::computed(date(1, 15), sydney, params);
+        assert_eq!(local(&summer, Prayer::Fajr, 11), "04:20");
+        assert_eq!(local(&summer, Prayer::Ishaa, 11), "21:23");
+        assert_eq!(summer.source(Prayer::Fajr), TimeSource::SeasonalEstimate);
+
+        let winter = PrayerTimes::computed(date(7, 15), sydney, params);
+        assert_eq!(local(&winter, Prayer::Fajr, 10), "05:30");
+        assert_eq!(local(&winter, Prayer::Ishaa, 10), "18:32");
+
+        let summer = PrayerTimes::computed(date(1, 15), johannesburg, params);
+        assert_eq!(local(&summer, Prayer::Fajr, 2), "04:00");
+        assert_eq!(local(&summer, Prayer::Ishaa, 2), "20:19");
+
+        let winter = PrayerTimes::computed(date(7, 15), johannesburg, params);
+        assert_eq!(local(&winter, Prayer::Fajr, 2), "05:33");
+        assert_eq!(local(&winter, Prayer::Ishaa, 2), "18:55");
+
+        for coordinates in [sydney, johannesburg] {
+            for times in
+                PrayerTimes::on_range(date(1, 1), date(12, 31), coordinates, params).unwrap()
+            {
+                assert!(times.time(Prayer::Fajr) < times.time(Prayer::Sunrise));
+                assert!(times.time(Prayer::Maghrib) < times.time(Prayer::Ishaa));
+            }
+        }
+    }
+
+    #[test]
+    fn shafaq_for_moonsighting_method_in_the_southern_hemisphere() {
+        let sydney = Coordinates::new(-33.8688, 151.2093);
+        let twilight_minutes = |month, day, twilight| {
+            let params = Parameters {
+                twilight,
+                ..Method::MoonsightingCommittee.parameters()
+            };
+            let date = NaiveDate::from_ymd_opt(2024, month, day).unwrap();
+            let times = PrayerTimes::computed(date, sydney, params);
+            let sunset = PrayerTimes::solar_time(times.time(Prayer::Dhuhr), sydney, params)
+                .unwrap()
+                .sunset;
+
+            (times.time(Prayer::Ishaa) - sunset).num_minutes()
+        };
+
+        // The durations of Shaukat's tables at the solstices, worked by
+        // hand in the tests of the seasonal module, unless the sun reaches
+        // 18° below the horizon first: 89 minutes after sunset in June,
+        // and 104 in December.
+        assert_eq!(twilight_minutes(6, 21, Twilight::Red), 73);
+        assert_eq!(twilight_minutes(6, 21, Twilight::General), 89);
+        assert_eq!(twilight_minutes(6, 21, Twilight::White), 89);
+        assert_eq!(twilight_minutes(12, 21, Twilight::Red), 74);
+        assert_eq!(twilight_minutes(12, 21, Twilight::General), 79);
+        assert_eq!(twilight_minutes(12, 21, Twilight::White), 104);
+    }
+
+    #[test]
+    fn calculate_times_for_moonsighting_method_with_high_latitude() {
+        let date = NaiveDate::from_ymd_opt(2016, 1, 1).expect("Invalid date provided");
+        let mut params = Method::MoonsightingCommittee.parameters();
+        params.mazhab = Mazhab::Hanafi;
+        let coordinates = Coordinates::new(59.9094, 10.7349);
+        let prayer_times = PrayerTimes::computed(date, coordinates, params);
+
+        // fajr    = 2016-01-01 06:34:00 UTC
+        // sunrise = 2016-01-01 08:19:00 UTC
+        // dhuhr   = 2016-01-01 11:25:00 UTC
+        // asr     = 2016-01-01 12:36:00 UTC
+        // maghrib = 2016-01-01 14:25:00 UTC
+        // ishaa    = 2016-01-01 16:02:00 UTC
+        assert_eq!(
+            prayer_times
+                .time(Prayer::Fajr)
+                .format("%-l:%M %p")
+                .to_string(),
+            "6:34 AM"
+        );
+        assert_eq!(
+            prayer_times
+                .time(Prayer::Sunrise)
+                .format("%-l:%M %p")
+                .to_string(),
+            "8:19 AM"
+        );
+        assert_eq!(
+            prayer_times
+                .time(Prayer::Dhuhr)
+                .format("%-l:%M %p")
+                .to_string(),
+            "11:25 AM"
+        );
+        assert_eq!(
+            prayer_times
+                .time(Prayer::Asr)
+                .format("%-l:%M %p")
+                .to_string(),
+            "12:36 PM"
+        );
+        assert_eq!(
+            prayer_times
+                .time(Prayer::Maghrib)
+                .format("%-l:%M %p")
+                .to_string(),
+            "2:25 PM"
+        );
+        assert_eq!(
+            prayer_times
+                .time(Prayer::Ishaa)
+                .format("%-l:%M %p")
+                .to_string(),
+            "4:02 PM"
+        );
+    }
+
+    #[test]
+    fn calculate_times_with_high_latitude_rule_per_prayer() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 15).expect("Invalid date provided");
+        let coordinates = Coordinates::new(59.9139, 10.7522);
+        let with_rule = |rule: HighLatitudeRule| {
+            let mut params = Method::MuslimWorldLeague.parameters();
+            params.high_latitude_rule = rule;
+            PrayerTimes::computed(date, coordinates, params)
+        };
+        let seventh = with_rule(HighLatitudeRule::SeventhOfTheNight);
+        let twilight = with_rule(HighLatitudeRule::TwilightAngle);
+
+        let mut params = Method::MuslimWorldLeague.parameters();
+        params.fajr_high_latitude_rule = Some(HighLatitudeRule::SeventhOfTheNight);
+        params.ishaa_high_latitude_rule = Some(HighLatitudeRule::TwilightAngle);
+        let mixed = PrayerTimes::computed(date, coordinates, params);
+
+        assert_ne!(seventh.time(Prayer::Fajr), twilight.time(Prayer::Fajr));
+        assert_ne!(seventh.time(Prayer::Ishaa), twilight.time(Prayer::Ishaa));
+        assert_eq!(mixed.time(Prayer::Fajr), seventh.time(Prayer::Fajr));
+        assert_eq!(mixed.time(Prayer::Ishaa), twilight.time(Prayer::Ishaa));
+    }
+
+    #[test]
+    fn annotate_times_estimated_at_high_latitude() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 15).expect("Invalid date provided");
+        let coordinates = Coordinates::new(59.9139, 10.7522);
+        let mut params = Method::MuslimWorldLeague.parameters();
+        params.high_latitude_rule = HighLatitudeRule::SeventhOfTheNight;
+        let times = PrayerTimes::computed(date, coordinates, params);
+
+        assert_eq!(
+            times.timed(Prayer::Fajr),
+            TimedValue {
+                time: times.time(Prayer::Fajr),
+                source: TimeSource::HighLatitudeRule(HighLatitudeRule::SeventhOfTheNight),
+            }
+        );
+        assert_eq!(times.source(Prayer::Dhuhr), TimeSource::Astronomical);
+
+        params.seasonal_twilight = true;
+        let seasonal = PrayerTimes::computed(date, coordinates, params);
+
+        assert_eq!(seasonal.source(Prayer::Fajr), TimeSource::SeasonalEstimate);
+    }
+
+    #[test]
+    fn calculate_times_without_safe_time_clamp() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 15).expect("Invalid date provided");
+        let coordinates = Coordinates::new(59.9139, 10.7522);
+        let mut params = Method::MuslimWorldLeague.parameters();
+        params.high_latitude_rule = HighLatitudeRule::SeventhOfTheNight;
+        let clamped = PrayerTimes::computed(date, coordinates, params);
+
+        params.disable_safe_time_clamp = true;
+        let raw = PrayerTimes::computed(date, coordinates, params);
+
+        assert!(clamped.is_clamped(Prayer::Fajr));
+        assert!(clamped.is_clamped(Prayer::Ishaa));
+        assert!(!raw.is_clamped(Prayer::Fajr));
+        assert!(!raw.is_clamped(Prayer::Ishaa));
+        assert!(raw.time(Prayer::Fajr) < clamped.time(Prayer::Fajr));
+        assert!(raw.time(Prayer::Ishaa) > clamped.time(Prayer::Ishaa));
+        assert_eq!(raw.time(Prayer::Dhuhr), clamped.time(Prayer::Dhuhr));
+    }
+
+    #[test]
+    fn calculate_times_at_local_civil_date() {
+        let coordinates = Coordinates::new(-36.8485, 174.7633);
+        let params = Method::MuslimWorldLeague.parameters();
+        let auckland = FixedOffset::east_opt(13 * 3600).unwrap();
+        // Still the 14th in UTC, but already the 15th in Auckland.
+        let evening = auckland.with_ymd_and_hms(2024, 1, 15, 8, 0, 0).unwrap();
+
+        let local_day = PrayerTimes::at(&evening, coordinates, params);
+
+        assert_eq!(
+            local_day
+                .time(Prayer::Dhuhr)
+                .with_timezone(&auckland)
+                .date_naive(),
+            NaiveDate::from_ymd_opt(2024, 1, 15).unwrap()
+        );
+    }
+
+    #[test]
+    fn calculate_times_for_local_civil_day_in_the_pacific() {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let kiritimati = Coordinates::new(1.8721, -157.4278);
+        let offset = FixedOffset::east_opt(14 * 3600).unwrap();
+        let mut params = Method::MuslimWorldLeague.parameters();
+        let utc_day = PrayerTimes::computed(date, kiritimati, params);
+
+        params.day_boundary = DayBoundary::LocalCivil(offset);
+        let local_day = PrayerTimes::computed(date, kiritimati, params);
+        let local_date =
+            |times: &PrayerTimes, prayer| times.time(prayer).with_timezone(&offset).date_naive();
+
+        // Anchored at UTC midnight the schedule lands on the next local day.
+        assert_eq!(
+            local_date(&utc_day, Prayer::Dhuhr),
+            date.succ_opt().unwrap()
+        );
+        for prayer in Prayer::ALL {
+            assert_eq!(local_date(&local_day, prayer), date);
+        }
+    }
+
+    #[test]
+    fn local_civil_day_matches_utc_day_near_the_meridian() {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let coordinates = Coordinates::new(33.8938, 35.5018);
+        let mut params = Method::MuslimWorldLeague.parameters();
+        let utc_day = PrayerTimes::computed(date, coordinates, params);
+
+        params.day_boundary = DayBoundary::LocalCivil(FixedOffset::east_opt(2 * 3600).unwrap());
+        let local_day = PrayerTimes::computed(date, coordinates, params);
+
+        assert_eq!(local_day.time(Prayer::Fajr), utc_day.time(Prayer::Fajr));
+        assert_eq!(local_day.time(Prayer::Dhuhr), utc_day.time(Prayer::Dhuhr));
+        assert_eq!(local_day.time(Prayer::Ishaa), utc_day.time(Prayer::Ishaa));
+    }
+
+    #[test]
+    fn neighbouring_days_share_their_solar_times() {
+        let date = NaiveDate::from_ymd_opt(2024, 12, 31).unwrap();
+        let coordinates = Coordinates::new(35.7750, -78.6336);
+        let times = PrayerTimes::computed(date, coordinates, Method::NorthAmerica.parameters());
+        let tomorrow = times.tomorrow().unwrap();
+        let yesterday = times.yesterday().unwrap();
+        let days = |times: &PrayerTimes| {
+            times.solar_days.as_ref().unwrap().0.map(|day| {
+                let (day, solar_time) = day.unwrap();
+
+                (day, solar_time.transit, solar_time.sunset)
+            })
+        };
+
+        assert_eq!(days(&tomorrow)[..2], days(&times)[1..]);
+        assert_eq!(days(&yesterday)[1..], days(&times)[..2]);
+    }
+
+    #[test]
+    fn step_to_neighbouring_days() {
+        let date = NaiveDate::from_ymd_opt(2024, 12, 31).unwrap();
+        let coordinates = Coordinates::new(35.7750, -78.6336);
+        let params = Method::NorthAmerica.parameters();
+        let times = PrayerTimes::computed(date, coordinates, params);
+        let tomorrow = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+
+        assert_eq!(times.date(), date);
+        assert_eq!(
+            times.tomorrow(),
+            PrayerTimes::try_computed(tomorrow, coordinates, params)
+        );
+        assert_eq!(
+            times.tomorrow().and_then(|times| times.yesterday()),
+            Ok(times.clone())
+        );
+        assert_eq!(
+            times.time(Prayer::FajrTomorrow),
+            times.tomorrow().unwrap().time(Prayer::Fajr)
+        );
+
+        let beirut = beirut(date);
+        assert_eq!(beirut.tomorrow(), Ok(self::beirut(tomorrow)));
+
+        let last = NaiveDate::from_ymd_opt(2100, 12, 31).unwrap();
+        assert_eq!(
+            PrayerTimes::computed(last, coordinates, params).tomorrow(),
+            Err(CalculationError::DateOutOfRange(
+                NaiveDate::from_ymd_opt(2101, 1, 1).unwrap()
+            ))
+        );
+    }
+
+    #[test]
+    fn prayer_window_and_progress() {
+        let date = NaiveDate::from_ymd_opt(2015, 7, 12).unwrap();
+        let coordinates = Coordinates::new(35.7750, -78.6336);
+        let times = PrayerTimes::computed(date, coordinates, Method::NorthAmerica.parameters());
+        let dhuhr = times.time(Prayer::Dhuhr);
+        let asr = times.time(Prayer::Asr);
+        let midway = dhuhr + asr.signed_duration_since(dhuhr) / 2;
+
+        assert!(times.is_within(Prayer::Dhuhr, dhuhr));
+        assert!(times.is_within(Prayer::Dhuhr, midway));
+        assert!(!times.is_within(Prayer::Dhuhr, asr));
+        assert!(times.is_within(Prayer::Asr, asr));
+        assert!(!times.is_within(Prayer::FajrTomorrow, times.time(Prayer::FajrTomorrow)));
+
+        assert_eq!(times.progress(Prayer::Dhuhr, dhuhr), Some(0.0));
+        assert_eq!(times.progress(Prayer::Dhuhr, midway), Some(0.5));
+        assert_eq!(times.progress(Prayer::Dhuhr, asr), None);
+    }
+
+    #[test]
+    fn maghrib_at_an_angle_below_the_horizon() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 20).unwrap();
+        let tehran = Coordinates::new(35.6892, 51.3890);
+        let params = Method::Tehran.parameters();
+        let times = PrayerTimes::computed(date, tehran, params);
+        let sunset = PrayerTimes::computed(date, tehran, params.maghrib(MaghribParameter::Sunset));
+        let altitude = |time: DateTime<Utc>| SolarPosition::new(time, tehran).altitude;
+
+        // Times are rounded to the minute, in which the sun sinks by about
+        // a fifth of a degree at this latitude.
+        assert!((altitude(times.time(Prayer::Maghrib)) + 4.5).abs() < 0.15);
+        assert!((altitude(times.time(Prayer::Fajr)) + 17.7).abs() < 0.15);
+        assert!((altitude(times.time(Prayer::Ishaa)) + 14.0).abs() < 0.15);
+        assert!(times.time(Prayer::Maghrib) > sunset.time(Prayer::Maghrib));
+        assert_eq!(times.time(Prayer::Ishaa), sunset.time(Prayer::Ishaa));
+        assert_eq!(
+            PrayerTimes::only(&[Prayer::Maghrib], date, tehran, params)
+                .unwrap()
+                .time(Prayer::Maghrib),
+            Some(times.time(Prayer::Maghrib))
+        );
+    }
+
+    #[test]
+    fn maghrib_angle_falls_back_to_sunset() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 20).unwrap();
+        let tehran = Coordinates::new(35.6892, 51.3890);
+        let sunset = Method::UmmAlQura
+            .parameters()
+            .ishaa_interval_start(IshaaIntervalStart::Sunset);
+        let params = sunset.maghrib(MaghribParameter::Angle(4.5));
+        let late =
+            PrayerTimes::computed(date, tehran, params.maghrib(MaghribParameter::Angle(30.0)));
+
+        // With Ishaa 90 minutes after sunset, 4.5° is still before Ishaa.
+        assert!(
+            PrayerTimes::computed(date, tehran, params).time(Prayer::Maghrib)
+                > PrayerTimes::computed(date, tehran, sunset).time(Prayer::Maghrib)
+        );
+        assert_eq!(
+            late.time(Prayer::Maghrib),
+            PrayerTimes::computed(date, tehran, sunset).time(Prayer::Maghrib)
+        );
+        assert!(late.time(Prayer::Maghrib) < late.time(Prayer::Ishaa));
+    }
+
+    #[test]
+    fn elevation_moves_sunrise_sunset_and_the_night() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 20).unwrap();
+        let makkah = Coordinates::new(21.4225, 39.8262);
+        let params = Method::UmmAlQura
+            .parameters()
+            .ishaa_interval_start(IshaaIntervalStart::Sunset);
+        let sea_level = PrayerTimes::computed(date, makkah, params);
+        let elevated = PrayerTimes::computed(date, makkah, params.elevation(900.0));
+
+        assert!(elevated.time(Prayer::Sunrise) < sea_level.time(Prayer::Sunrise));
+        assert!(elevated.time(Prayer::Maghrib) > sea_level.time(Prayer::Maghrib));
+        assert_eq!(elevated.time(Prayer::Dhuhr), sea_level.time(Prayer::Dhuhr));
+        // The interval Ishaa follows the later sunset.
+        assert_eq!(
+            elevated.time(Prayer::Ishaa) - elevated.time(Prayer::Maghrib),
+            Duration::minutes(90)
+        );
+
+        // So does the Ishaa of the high latitude rule, taken from the
+        // shorter night.
+        let june = NaiveDate::from_ymd_opt(2024, 6, 21).unwrap();
+        let london = Coordinates::new(51.5074, -0.1278);
+        let mut params = Method::MuslimWorldLeague.parameters();
+        params.high_latitude_rule = HighLatitudeRule::SeventhOfTheNight;
+        let sea_level = PrayerTimes::computed(june, london, params);
+        let elevated = PrayerTimes::computed(june, london, params.elevation(900.0));
+
+        assert!(elevated.time(Prayer::Ishaa) > sea_level.time(Prayer::Ishaa));
+        assert!(elevated.time(Prayer::Fajr) < sea_level.time(Prayer::Fajr));
+    }
+
+    #[test]
+    fn ishaa_interval_from_maghrib() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 20).unwrap();
+        let makkah = Coordinates::new(21.4225, 39.8262);
+        let mut params = Method::UmmAlQura.parameters();
+        params.adjustments.maghrib = 5;
+        let from_maghrib = PrayerTimes::computed(date, makkah, params);
+        let from_sunset = PrayerTimes::computed(
+            date,
+            makkah,
+            params.ishaa_interval_start(IshaaIntervalStart::Sunset),
+        );
+
+        assert_eq!(
+            from_maghrib.time(Prayer::Ishaa) - from_maghrib.time(Prayer::Maghrib),
+            Duration::minutes(90)
+        );
+        assert_eq!(
+            from_maghrib.time(Prayer::Ishaa) - from_sunset.time(Prayer::Ishaa),
+            Duration::minutes(5)
+        );
+
+        let angle = params.maghrib(MaghribParameter::Angle(4.0));
+        let times = PrayerTimes::computed(date, makkah, angle);
+
+        assert!(times.time(Prayer::Maghrib) > from_maghrib.time(Prayer::Maghrib));
+        assert_eq!(
+            times.time(Prayer::Ishaa) - times.time(Prayer::Maghrib),
+            Duration::minutes(90)
+        );
+    }
+
+    #[test]
+    fn warnings_of_a_schedule() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+        let coordinates = Coordinates::new(33.8938, 35.5018);
+        let mut params = Method::MuslimWorldLeague.parameters();
+
+        assert!(
+            PrayerTimes::computed(date, coordinates, params)
+                .warnings()
+                .is_empty()
+        );
+
+        params.adjustments.ishaa = 45;
+        params.twilight = Twilight::Red;
+        let late = NaiveDate::from_ymd_opt(2095, 3, 15).unwrap();
+        let warnings: Vec<Warning> = PrayerTimes::computed(late, coordinates, params)
+            .warnings()
+            .into_iter()
+            .collect();
+
+        assert_eq!(
+            warnings,
+            vec![
+                Warning::IgnoredSetting(ParameterIssue::TwilightIgnored),
+                Warning::LargeAdjustment(Prayer::Ishaa, 45),
+                Warning::NearSupportedLimit(late),
+            ]
+        );
+        assert!(
+            beirut(NaiveDate::from_ymd_opt(2026, 1, 1).unwrap())
+                .warnings()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn nearest_occurrence_across_days() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+        let coordinates = Coordinates::new(33.8938, 35.5018);
+        let params = Method::MuslimWorldLeague.parameters();
+        let today = PrayerTimes::computed(date, coordinates, params);
+        let yesterday = today.yesterday().unwrap();
+        let tomorrow = today.tomorrow().unwrap();
+
+        assert_eq!(
+            today.nearest(Prayer::Ishaa, today.time(Prayer::Fajr)),
+            Ok(yesterday.time(Prayer::Ishaa))
+        );
+        assert_eq!(
+            today.nearest(Prayer::Ishaa, today.time(Prayer::Dhuhr)),
+            Ok(today.time(Prayer::Ishaa))
+        );
+        assert_eq!(
+            today.nearest(Prayer::Dhuhr, today.time(Prayer::Dhuhr)),
+            Ok(today.time(Prayer::Dhuhr))
+        );
+        assert_eq!(
+            today.nearest(Prayer::FajrTomorrow, today.time(Prayer::Maghrib)),
+            Ok(tomorrow.time(Prayer::Fajr))
+        );
+
+        // Halfway between two Dhuhrs, the past one wins.
+        let dhuhr = today.time(Prayer::Dhuhr);
+        let halfway = dhuhr + (tomorrow.time(Prayer::Dhuhr) - dhuhr) / 2;
+        assert_eq!(today.nearest(Prayer::Dhuhr, halfway), Ok(dhuhr));
+    }
+
+    #[test]
+    fn nearest_needs_the_adjacent_schedule() {
+        let first = NaiveDate::from_ymd_opt(1900, 1, 1).unwrap();
+        let coordinates = Coordinates::new(33.8938, 35.5018);
+        let times =
+            PrayerTimes::computed(first, coordinates, Method::MuslimWorldLeague.parameters());
+
+        assert_eq!(
+            times.nearest(Prayer::Dhuhr, times.time(Prayer::Fajr)),
+            Ok(times.time(Prayer::Dhuhr))
+        );
+        assert_eq!(
+            times.nearest(Prayer::Dhuhr, times.time(Prayer::Asr)),
+            Ok(times.time(Prayer::Dhuhr))
+        );
+        assert_eq!(
+            times.nearest(Prayer::Ishaa, times.time(Prayer::Fajr)),
+            Err(CalculationError::DateOutOfRange(first.pred_opt().unwrap()))
+        );
+    }
+
+    #[test]
+    fn nearest_on_any_day() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+        let coordinates = Coordinates::new(33.8938, 35.5018);
+        let params = Method::MuslimWorldLeague.parameters();
+        let times = PrayerTimes::computed(date, coordinates, params);
+        let on =
+            |days: u64| PrayerTimes::computed(date + chrono::Days::new(days), coordinates, params);
+
+        assert_eq!(
+            times.nearest(Prayer::Dhuhr, on(5).time(Prayer::Asr)),
+            Ok(on(5).time(Prayer::Dhuhr))
+        );
+        assert_eq!(
+            times.nearest(Prayer::Ishaa, on(5).time(Prayer::Fajr)),
+            Ok(on(4).time(Prayer::Ishaa))
+        );
+        assert_eq!(
+            times.nearest(Prayer::Fajr, times.time(Prayer::Fajr) - Duration::days(40)),
+            Ok(times
+                .with_date(date - chrono::Days::new(40))
+                .unwrap()
+                .time(Prayer::Fajr))
+        );
+    }
+
+    #[test]
+    fn refuse_invalid_parameters() {
+        let date = NaiveDate::from_ymd_opt(2015, 7, 12).unwrap();
+        let coordinates = Coordinates::new(35.7750, -78.6336);
+        let mut params = Method::NorthAmerica.parameters();
+
+        assert_eq!(
+            PrayerTimes::validated(date, coordinates, params),
+            Ok(PrayerTimes::computed(date, coordinates, params))
+        );
+
+        params.maghrib_parameter = MaghribParameter::Angle(4.0);
+        assert!(PrayerTimes::validated(date, coordinates, params).is_ok());
+
+        let ignored = params.ishaa_interval_start(IshaaIntervalStart::Maghrib);
+        assert!(
+            PrayerTimes::validated(date, coordinates, ignored)
+                .unwrap()
+                .warnings()
+                .contains(&Warning::IgnoredSetting(
+                    ParameterIssue::IshaaIntervalStartIgnored
+                ))
+        );
+
+        params.fajr_angle = -15.0;
+        params.maghrib_parameter = MaghribParameter::Angle(-4.0);
+        assert_eq!(
+            PrayerTimes::validated(date, coordinates, params),
+            Err(vec![
+                ParameterIssue::InvalidFajrAngle(-15.0),
+                ParameterIssue::InvalidMaghribAngle(-4.0),
+            ])
+        );
+    }
+
+    #[test]
+    fn estimate_fajr_tomorrow() {
+        let date = NaiveDate::from_ymd_opt(2015, 7, 12).unwrap();
+        let coordinates = Coordinates::new(35.7750, -78.6336);
+        let mut params = Method::NorthAmerica.parameters();
+        let computed = PrayerTimes::computed(date, coordinates, params);
+
+        params.next_day_fajr = NextDayFajr::Estimated;
+        let estimated = PrayerTimes::computed(date, coordinates, params);
+
+        assert_eq!(
+            estimated.time(Prayer::FajrTomorrow),
+            estimated.time(Prayer::Fajr) + Duration::days(1)
+        );
+        assert!(
+            (estimated.time(Prayer::FajrTomorrow) - computed.time(Prayer::FajrTomorrow))
+                .num_minutes()
+                .abs()
+                <= 2
+        );
+        assert_eq!(estimated.time(Prayer::Ishaa), computed.time(Prayer::Ishaa));
+    }
+
+    #[test]
+    fn calculate_times_within_supported_years() {
+        let coordinates = Coordinates::new(35.7750, -78.6336);
+        let params = Method::NorthAmerica.parameters();
+
+        for date in [
+            NaiveDate::from_ymd_opt(1900, 1, 1).unwrap(),
+            NaiveDate::from_ymd_opt(1950, 7, 12).unwrap(),
+            NaiveDate::from_ymd_opt(2100, 12, 31).unwrap(),
+        ] {
+            let times = PrayerTimes::try_computed(date, coordinates, params).unwrap();
+
+            assert!(times.time(Prayer::Fajr) < times.time(Prayer::Sunrise));
+            assert!(times.time(Prayer::Sunrise) < times.time(Prayer::Dhuhr));
+            assert!(times.time(Prayer::Dhuhr) < times.time(Prayer::Asr));
+            assert!(times.time(Prayer::Asr) < times.time(Prayer::Maghrib));
+            assert!(times.time(Prayer::Maghrib) < times.time(Prayer::Ishaa));
+        }
+    }
+
+    #[test]
+    fn refuse_dates_outside_supported_years() {
+        let coordinates = Coordinates::new(35.7750, -78.6336);
+        let params = Method::NorthAmerica.parameters();
+        let before = NaiveDate::from_ymd_opt(1899, 12, 31).unwrap();
+        let after = NaiveDate::from_ymd_opt(2101, 1, 1).unwrap();
+
+        assert_eq!(
+            PrayerTimes::try_computed(before, coordinates, params),
+            Err(CalculationError::DateOutOfRange(before))
+        );
+        assert_eq!(
+            PrayerTimes::try_computed(after, coordinates, params),
+            Err(CalculationError::DateOutOfRange(after))
+        );
+    }
+
+    #[test]
+    fn try_new_refuses_untrusted_input() {
+        let date = NaiveDate::from_ymd_opt(2015, 7, 12).unwrap();
+        let raleigh = Coordinates::new(35.7750, -78.6336);
+        let params = Method::NorthAmerica.parameters();
+
+        assert_eq!(
+            PrayerTimes::try_new(date, raleigh, params),
+            Ok(PrayerTimes::computed(date, raleigh, params))
+        );
+        for coordinates in [
+            Coordinates::new(95.0, -78.6336),
+            Coordinates::new(35.7750, 200.0),
+        ] {
+            assert_eq!(
+                PrayerTimes::try_new(date, coordinates, params),
+                Err(CalculationError::InvalidCoordinates(coordinates))
+            );
+        }
+        assert!(matches!(
+            PrayerTimes::try_new(date, Coordinates::new(f64::NAN, -78.6336), params),
+            Err(CalculationError::InvalidCoordinates(_))
+        ));
+
+        // Unusual angles are computed leniently, but refused as untrusted
+        // input.
+        let negative_fajr = Parameters {
+            fajr_angle: -15.0,
+            ..params
+        };
+
+        assert!(PrayerTimes::try_computed(date, raleigh, negative_fajr).is_ok());
+        assert_eq!(
+            PrayerTimes::try_new(date, raleigh, negative_fajr),
+            Err(CalculationError::InvalidParameters(
+                ParameterIssue::InvalidFajrAngle(-15.0)
+            ))
+        );
+    }
+
+    #[test]
+    fn try_computed_refuses_what_it_cannot_compute() {
+        let date = NaiveDate::from_ymd_opt(2015, 7, 12).unwrap();
+        let raleigh = Coordinates::new(35.7750, -78.6336);
+        let params = Method::NorthAmerica.parameters();
+        let mut extreme = params;
+        extreme.adjustments.maghrib = i64::MAX;
+
+        assert_eq!(
+            PrayerTimes::try_computed(date, raleigh, extreme),
+            Err(CalculationError::InvalidParameters(
+                ParameterIssue::AdjustmentOutOfRange(Prayer::Maghrib, i64::MAX)
+            ))
+        );
+
+        let infinite_fajr = Parameters {
+            fajr_angle: f64::INFINITY,
+            ..params
+        };
+
+        assert_eq!(
+            PrayerTimes::try_computed(date, raleigh, infinite_fajr),
+            Err(CalculationError::InvalidParameters(
+                ParameterIssue::InvalidFajrAngle(f64::INFINITY)
+            ))
+        );
+        for elevation in [-5.0, f64::NAN] {
+            assert!(matches!(
+                PrayerTimes::try_computed(date, raleigh, params.elevation(elevation)),
+                Err(CalculationError::InvalidParameters(
+                    ParameterIssue::InvalidElevation(_)
+                ))
+            ));
+        }
+        assert_eq!(
+            PrayerTimes::try_computed(date, Coordinates::new(1e300, 0.0), params),
+            Err(CalculationError::InvalidCoordinates(Coordinates::new(
+                1e300, 0.0
+            )))
+        );
+    }
 
-                    ops::season_adjusted_evening_twilight(
-                        coordinates.latitude,
-                        day_of_year,
-                        prayer_date.year() as u32,
-                        solar_time.sunset,
-                        parameters.twilight,
-                    )
-                } else {
-                    let portion = parameters.night_portions().1;
-                    let night_fraction = portion * (night.num_seconds() as f64);
+    // A deterministic sweep over the kind of values the fuzz targets
+    // found panics with, so that regressions show up without a fuzzer.
+    #[test]
+    fn untrusted_input_never_panics() {
+        let floats = [
+            0.0,
+            -90.0,
+            90.0,
+            180.0,
+            1e300,
+            f64::NAN,
+            f64::INFINITY,
+            f64::MIN_POSITIVE,
+        ];
+        let minutes = [0, i64::MAX, i64::MIN, 1 << 40];
+        let dates = [
+            NaiveDate::from_ymd_opt(1900, 1, 1).unwrap(),
+            NaiveDate::from_ymd_opt(2024, 3, 20).unwrap(),
+            NaiveDate::from_ymd_opt(2100, 12, 31).unwrap(),
+        ];
+        let eastern = FixedOffset::east_opt(14 * 3600).unwrap();
 
-                    solar_time
-                        .sunset
-                        .checked_add_signed(Duration::seconds(night_fraction as i64))
-                        .unwrap()
-                };
+        for (index, value) in floats.into_iter().enumerate() {
+            let other = floats[(index * 3 + 1) % floats.len()];
+            let mut params = Method::ALL[index % Method::ALL.len()].parameters();
+            params.fajr_angle = other;
+            params.asr_factor = Some(AsrFactor::Custom(value));
+            params.adjustments.fajr = minutes[index % minutes.len()];
+            params.dhuhr_offset_after_transit = minutes[(index + 1) % minutes.len()];
+            params.delta_t = DeltaT::Fixed(other);
+            params.day_boundary = DayBoundary::LocalCivil(eastern);
 
-                if ishaa > safe_isha {
-                    ishaa = safe_isha;
-                } else {
-                    // Nothing to do.
+            for date in dates {
+                for coordinates in [
+                    Coordinates::new(value, other),
+                    Coordinates::new(other, value),
+                ] {
+                    let _ = PrayerTimes::try_new(date, coordinates, params);
+                    let _ = PrayerTimes::try_computed(date, coordinates, params);
                 }
             }
         }
-
-        ishaa.adjust_time(parameters.time_adjustments(Prayer::Ishaa))
     }
 
-}
+    #[test]
+    fn ordering_near_the_arctic_circle() {
+        let coordinates = Coordinates::new(68.0, 20.0);
+        let date = |month, day| NaiveDate::from_ymd_opt(2024, month, day).unwrap();
+        let params = Method::MuslimWorldLeague.parameters();
+        let unclamped = Parameters {
+            disable_safe_time_clamp: true,
+            ..params
+        };
 
+        // Midnight sun in June, polar night in December.
+        for solstice in [date(6, 21), date(12, 21)] {
+            assert_eq!(
+                PrayerTimes::try_computed(solstice, coordinates, params),
+                Err(CalculationError::NoSunriseOrSunset(solstice))
+            );
+        }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::precomputed::provider::ProviderCity;
-    use crate::{Mazhab, Method};
-    use chrono::{NaiveDate, TimeZone, Utc};
+        // The sun never gets 18 degrees below the horizon, so the high
+        // latitude rule applies even without the clamp.
+        for params in [params, unclamped] {
+            let times = PrayerTimes::computed(date(5, 24), coordinates, params);
 
-    #[test]
-    fn current_prayer_should_be_fajr() {
-        // Given the above DateTime, the Fajr prayer is at 2015-07-12T08:42:00Z
-        let local_date = NaiveDate::from_ymd_opt(2015, 7, 12).expect("Invalid date provided");
-        let params = Method::NorthAmerica.parameters();
-        let coordinates = Coordinates::new(35.7750, -78.6336);
-        let times = PrayerTimes::computed(local_date, coordinates, params);
-        let current_prayer_time = local_date.and_hms_opt(9, 0, 0).unwrap().and_utc();
+            assert_eq!(times.ordering_violations(), vec![]);
+            assert_eq!(
+                times.source(Prayer::Ishaa),
+                TimeSource::HighLatitudeRule(HighLatitudeRule::MiddleOfTheNight)
+            );
+        }
 
-        assert_eq!(times.current_time(current_prayer_time), Some(Prayer::Fajr));
+        // Tonight's middle of the night falls after tomorrow's Fajr.
+        let july = PrayerTimes::computed(date(7, 20), coordinates, params);
+
+        assert_eq!(july.ordering_violations(), vec![]);
+        assert!(july.time(Prayer::Maghrib) < july.time(Prayer::Ishaa));
+        assert!(july.time(Prayer::Ishaa) < july.time(Prayer::FajrTomorrow));
+
+        // The sun barely clears the horizon: Fajr and Ishaa are fine, but
+        // Asr has no meaning and is reported.
+        let december = PrayerTimes::computed(date(12, 1), coordinates, params);
+
+        assert_eq!(
+            december.ordering_violations(),
+            vec![(Prayer::Dhuhr, Prayer::Asr)]
+        );
+        assert_eq!(december.source(Prayer::Fajr), TimeSource::Astronomical);
     }
 
     #[test]
-    fn current_prayer_should_be_sunrise() {
-        // Given the below DateTime, sunrise is at 2015-07-12T10:08:00Z
-        let local_date = NaiveDate::from_ymd_opt(2015, 7, 12).expect("Invalid date provided");
-        let params = Method::NorthAmerica.parameters();
+    fn time_remaining_rounds_to_whole_minutes() {
+        let date = NaiveDate::from_ymd_opt(2015, 7, 12).unwrap();
         let coordinates = Coordinates::new(35.7750, -78.6336);
-        let times = PrayerTimes::computed(local_date, coordinates, params);
-        let current_prayer_time = local_date.and_hms_opt(11, 0, 0).unwrap().and_utc();
+        let times = PrayerTimes::computed(date, coordinates, Method::NorthAmerica.parameters());
+        let asr = times.time(Prayer::Asr);
 
         assert_eq!(
-            times.current_time(current_prayer_time),
-            Some(Prayer::Sunrise)
+            times.time_remaining_with(&FixedClock(asr - Duration::seconds(7180))),
+            (2, 0)
+        );
+        assert_eq!(
+            times.time_remaining_with(&FixedClock(asr - Duration::seconds(89))),
+            (0, 1)
+        );
+        assert_eq!(
+            times.countdown_with(&FixedClock(asr - Duration::minutes(83)), Language::English),
+            "1h 23m until Asr"
         );
     }
 
     #[test]
-    fn current_prayer_should_be_dhuhr() {
-        // Given the above DateTime, dhuhr prayer is at 2015-07-12T17:21:00Z
-        let local_date = NaiveDate::from_ymd_opt(2015, 7, 12).expect("Invalid date provided");
-        let params = Method::NorthAmerica.parameters();
+    fn current_and_next_prayer_with_clock() {
+        let date = NaiveDate::from_ymd_opt(2015, 7, 12).unwrap();
         let coordinates = Coordinates::new(35.7750, -78.6336);
-        let times = PrayerTimes::computed(local_date, coordinates, params);
-        let current_prayer_time = local_date.and_hms_opt(19, 0, 0).unwrap().and_utc();
+        let times = PrayerTimes::computed(date, coordinates, Method::NorthAmerica.parameters());
+        let clock = FixedClock(times.time(Prayer::Asr) - Duration::minutes(90));
 
-        assert_eq!(times.current_time(current_prayer_time), Some(Prayer::Dhuhr));
+        assert_eq!(times.current_with(&clock), Some(Prayer::Dhuhr));
+        assert_eq!(times.next_with(&clock), Prayer::Asr);
+        assert_eq!(times.time_remaining_with(&clock), (1, 30));
     }
 
     #[test]
-    fn current_prayer_should_be_asr() {
-        // Given the below DateTime, asr is at 2015-07-12T22:22:00Z
-        let local_date = NaiveDate::from_ymd_opt(2015, 7, 12).expect("Invalid date provided");
-        let params = Method::NorthAmerica.parameters();
+    fn calculate_times_on_range() {
         let coordinates = Coordinates::new(35.7750, -78.6336);
-        let times = PrayerTimes::computed(local_date, coordinates, params);
-        let current_prayer_time = local_date.and_hms_opt(22, 26, 0).unwrap().and_utc();
+        let params = Method::MoonsightingCommittee.parameters();
+        let start = NaiveDate::from_ymd_opt(2016, 1, 30).unwrap();
+        let end = NaiveDate::from_ymd_opt(2016, 2, 1).unwrap();
+        let days = PrayerTimes::on_range(start, end, coordinates, params).unwrap();
+        let clock = |prayer: Prayer| days[1].time(prayer).format("%H:%M").to_string();
 
-        assert_eq!(times.current_time(current_prayer_time), Some(Prayer::Asr));
+        assert_eq!(days.len(), 3);
+        assert_eq!(days[0].date(), start);
+        assert_eq!(days[2].date(), end);
+
+        // Same day as `calculate_times_for_moonsighting_method`.
+        assert_eq!(clock(Prayer::Fajr), "10:48");
+        assert_eq!(clock(Prayer::Sunrise), "12:16");
+        assert_eq!(clock(Prayer::Dhuhr), "17:33");
+        assert_eq!(clock(Prayer::Asr), "20:20");
+        assert_eq!(clock(Prayer::Maghrib), "22:43");
+        assert_eq!(clock(Prayer::Ishaa), "00:05");
+
+        assert_eq!(
+            PrayerTimes::on_range(end, start, coordinates, params),
+            Ok(Vec::new())
+        );
     }
 
     #[test]
-    fn current_prayer_should_be_maghrib() {
-        // Given the below DateTime, maghrib is at 2015-07-13T00:32:00Z
-        let local_date = NaiveDate::from_ymd_opt(2015, 7, 12).expect("Invalid data provided");
-        let params = Method::NorthAmerica.parameters();
+    fn on_range_fails_on_what_it_cannot_compute() {
         let coordinates = Coordinates::new(35.7750, -78.6336);
-        let times = PrayerTimes::computed(local_date, coordinates, params);
-        let current_prayer_time = Utc.with_ymd_and_hms(2015, 7, 13, 01, 0, 0).unwrap();
+        let mut params = Method::NorthAmerica.parameters();
+        params.adjustments.maghrib = i64::MAX;
+        let start = NaiveDate::from_ymd_opt(2015, 7, 12).unwrap();
+        let end = NaiveDate::from_ymd_opt(2015, 7, 14).unwrap();
 
         assert_eq!(
-            times.current_time(current_prayer_time),
-            Some(Prayer::Maghrib)
+            PrayerTimes::on_range(start, end, coordinates, params),
+            Err(CalculationError::InvalidParameters(
+                ParameterIssue::AdjustmentOutOfRange(Prayer::Maghrib, i64::MAX)
+            ))
         );
     }
 
     #[test]
-    fn current_prayer_should_be_ishaa() {
-        // Given the below DateTime, ishaa is at 2015-07-13T01:57:00Z
-        let local_date = NaiveDate::from_ymd_opt(2015, 7, 12).expect("Invalid date provided");
-        let params = Method::NorthAmerica.parameters();
+    fn calculate_dhuhr_with_offset_after_transit() {
+        let date = NaiveDate::from_ymd_opt(2015, 7, 12).expect("Invalid date provided");
         let coordinates = Coordinates::new(35.7750, -78.6336);
-        let times = PrayerTimes::computed(local_date, coordinates, params);
-        let current_prayer_time = Utc.with_ymd_and_hms(2015, 7, 13, 02, 0, 0).unwrap();
+        let mut params = Method::NorthAmerica.parameters();
+        let default = PrayerTimes::computed(date, coordinates, params);
 
-        assert_eq!(times.current_time(current_prayer_time), Some(Prayer::Ishaa));
+        params.dhuhr_offset_after_transit = 4;
+        let offset = PrayerTimes::computed(date, coordinates, params);
+
+        assert_eq!(
+            offset.time(Prayer::Dhuhr),
+            default.time(Prayer::Dhuhr) + Duration::minutes(4)
+        );
+        assert_eq!(offset.time(Prayer::Asr), default.time(Prayer::Asr));
     }
 
     #[test]
-    fn current_prayer_should_be_none() {
-        let local_date = NaiveDate::from_ymd_opt(2015, 7, 12).expect("Invalid data provided");
+    fn unreached_angles_are_estimated_but_not_clamped() {
+        let date = NaiveDate::from_ymd_opt(2024, 6, 21).expect("Invalid date provided");
+        let coordinates = Coordinates::new(59.9139, 10.7522);
+        let mut params = Method::MuslimWorldLeague.parameters();
+        params.high_latitude_rule = HighLatitudeRule::SeventhOfTheNight;
+
+        for disable_safe_time_clamp in [false, true] {
+            params.disable_safe_time_clamp = disable_safe_time_clamp;
+            let times = PrayerTimes::computed(date, coordinates, params);
+
+            assert!(times.source(Prayer::Fajr).is_estimated());
+            assert!(times.source(Prayer::Ishaa).is_estimated());
+            assert!(!times.is_clamped(Prayer::Fajr));
+            assert!(!times.is_clamped(Prayer::Ishaa));
+        }
+    }
+
+    #[test]
+    fn safe_time_clamp_not_needed_at_low_latitude() {
+        let date = NaiveDate::from_ymd_opt(2015, 7, 12).expect("Invalid date provided");
         let params = Method::NorthAmerica.parameters();
         let coordinates = Coordinates::new(35.7750, -78.6336);
-        let times = PrayerTimes::computed(local_date, coordinates, params);
-        let current_prayer_time = local_date.and_hms_opt(8, 0, 0).unwrap().and_utc();
+        let times = PrayerTimes::computed(date, coordinates, params);
 
-        assert_eq!(times.current_time(current_prayer_time), None);
+        assert!(!times.is_clamped(Prayer::Fajr));
+        assert!(!times.is_clamped(Prayer::Ishaa));
     }
 
     #[test]
-    fn calculate_times_for_moonsighting_method() {
-        let date = NaiveDate::from_ymd_opt(2016, 1, 31).expect("Invalid date provided");
-        let params = Method::MoonsightingCommittee.parameters();
-        let coordinates = Coordinates::new(35.7750, -78.6336);
-        let prayer_times = PrayerTimes::computed(date, coordinates, params);
+    fn calculate_times_with_seasonal_twilight_on_any_method() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 15).expect("Invalid date provided");
+        let coordinates = Coordinates::new(51.5074, -0.1278);
+        let mut params = Method::MuslimWorldLeague.parameters();
+        let plain = PrayerTimes::computed(date, coordinates, params);
 
-        // fajr    = 2016-01-31 10:48:00 UTC
-        // sunrise = 2016-01-31 12:16:00 UTC
-        // dhuhr   = 2016-01-31 17:33:00 UTC
-        // asr     = 2016-01-31 20:20:00 UTC
-        // maghrib = 2016-01-31 22:43:00 UTC
-        // ishaa    = 2016-02-01 00:05:00 UTC
-        assert_eq!(
-            prayer_times
-                .time(Prayer::Fajr)
-                .format("%-l:%M %p")
-                .to_string(),
-            "10:48 AM"
-        );
-        assert_eq!(
-            prayer_times
-                .time(Prayer::Sunrise)
-                .format("%-l:%M %p")
-                .to_string(),
-            "12:16 PM"
-        );
-        assert_eq!(
-            prayer_times
-                .time(Prayer::Dhuhr)
-                .format("%-l:%M %p")
-                .to_string(),
-            "5:33 PM"
-        );
-        assert_eq!(
-            prayer_times
-                .time(Prayer::Asr)
-                .format("%-l:%M %p")
-                .to_string(),
-            "8:20 PM"
-        );
-        assert_eq!(
-            prayer_times
-                .time(Prayer::Maghrib)
-                .format("%-l:%M %p")
-                .to_string(),
-            "10:43 PM"
-        );
-        assert_eq!(
-            prayer_times
-                .time(Prayer::Ishaa)
-                .format("%-l:%M %p")
-                .to_string(),
-            "12:05 AM"
-        );
+        params.seasonal_twilight = true;
+        let seasonal = PrayerTimes::computed(date, coordinates, params);
+
+        let solar_time = SolarTime::new(date.and_hms_opt(0, 0, 0).unwrap().and_utc(), coordinates);
+        let safe_fajr = seasonal::morning_twilight(51.5074, 75, 2024, solar_time.sunrise)
+            .and_then(|fajr| fajr.adjust_time(params.time_adjustments(Prayer::Fajr)))
+            .unwrap()
+            .rounded_minute(params.rounding);
+
+        assert!(seasonal.is_clamped(Prayer::Fajr));
+        assert!(!plain.is_clamped(Prayer::Fajr));
+        assert_eq!(seasonal.time(Prayer::Fajr), safe_fajr);
+        assert_eq!(seasonal.time(Prayer::Dhuhr), plain.time(Prayer::Dhuhr));
     }
 
     #[test]
-    fn calculate_times_for_moonsighting_method_with_high_latitude() {
-        let date = NaiveDate::from_ymd_opt(2016, 1, 1).expect("Invalid date provided");
-        let mut params = Method::MoonsightingCommittee.parameters();
-        params.mazhab = Mazhab::Hanafi;
-        let coordinates = Coordinates::new(59.9094, 10.7349);
-        let prayer_times = PrayerTimes::computed(date, coordinates, params);
+    fn solar_altitude_at_each_prayer() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 20).unwrap();
+        let params = Method::MuslimWorldLeague.parameters();
+        let times = PrayerTimes::computed(date, Coordinates::new(35.7750, -78.6336), params);
+        let altitude = |prayer| times.solar_altitude_at(prayer).unwrap();
 
-        // fajr    = 2016-01-01 06:34:00 UTC
-        // sunrise = 2016-01-01 08:19:00 UTC
-        // dhuhr   = 2016-01-01 11:25:00 UTC
-        // asr     = 2016-01-01 12:36:00 UTC
-        // maghrib = 2016-01-01 14:25:00 UTC
-        // ishaa    = 2016-01-01 16:02:00 UTC
-        assert_eq!(
-            prayer_times
-                .time(Prayer::Fajr)
-                .format("%-l:%M %p")
-                .to_string(),
-            "6:34 AM"
-        );
-        assert_eq!(
-            prayer_times
-                .time(Prayer::Sunrise)
-                .format("%-l:%M %p")
-                .to_string(),
-            "8:19 AM"
-        );
-        assert_eq!(
-            prayer_times
-                .time(Prayer::Dhuhr)
-                .format("%-l:%M %p")
-                .to_string(),
-            "11:25 AM"
-        );
-        assert_eq!(
-            prayer_times
-                .time(Prayer::Asr)
-                .format("%-l:%M %p")
-                .to_string(),
-            "12:36 PM"
+        assert!((altitude(Prayer::Fajr) + 18.0).abs() < 0.3);
+        assert!((altitude(Prayer::Sunrise) + 0.833).abs() < 0.3);
+        assert!(altitude(Prayer::Dhuhr) > 50.0);
+        assert!((altitude(Prayer::Maghrib) + 0.833).abs() < 0.3);
+        assert!((altitude(Prayer::Ishaa) + 17.0).abs() < 0.3);
+        assert_eq!(beirut(date).solar_altitude_at(Prayer::Fajr), None);
+    }
+
+    #[test]
+    fn same_instants_in_every_zone() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 20).unwrap();
+        let params = Method::NorthAmerica.parameters();
+        let times = PrayerTimes::computed(date, Coordinates::new(35.7750, -78.6336), params);
+        let eastern = FixedOffset::west_opt(4 * 3600).unwrap();
+        let makkah = FixedOffset::east_opt(3 * 3600).unwrap();
+        let local = times.clone().localized(eastern);
+        let zones = local.in_zones(&[eastern, makkah]);
+        let clock = |times: &LocalizedPrayerTimes<FixedOffset>, prayer| {
+            times.time(prayer).format("%H:%M").to_string()
+        };
+
+        assert_eq!(zones.len(), 2);
+        assert_eq!(zones[0], local);
+        assert_eq!(zones[1].zone(), &makkah);
+        assert_eq!(clock(&zones[0], Prayer::Dhuhr), "13:23");
+        assert_eq!(clock(&zones[1], Prayer::Dhuhr), "20:23");
+        for prayer in [Prayer::Fajr, Prayer::Maghrib, Prayer::FajrTomorrow] {
+            assert_eq!(zones[0].time(prayer), zones[1].time(prayer));
+            assert_eq!(zones[1].time(prayer), times.time(prayer));
+        }
+    }
+
+    fn beirut(date: NaiveDate) -> PrayerTimes {
+        PrayerTimes::precomputed(date, Provider::DarElFatwa(ProviderCity::Beirut)).unwrap()
+    }
+
+    #[test]
+    fn content_hash_follows_the_date_and_the_source() {
+        let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+        let precomputed = beirut(date);
+        let computed = PrayerTimes::computed(
+            date,
+            Coordinates::new(33.8938, 35.5018),
+            Method::MuslimWorldLeague.parameters(),
         );
-        assert_eq!(
-            prayer_times
-                .time(Prayer::Maghrib)
-                .format("%-l:%M %p")
-                .to_string(),
-            "2:25 PM"
+
+        assert_eq!(precomputed.content_hash(), beirut(date).content_hash());
+        assert_ne!(
+            precomputed.content_hash(),
+            precomputed.tomorrow().unwrap().content_hash()
         );
-        assert_eq!(
-            prayer_times
-                .time(Prayer::Ishaa)
-                .format("%-l:%M %p")
-                .to_string(),
-            "4:02 PM"
+        assert_ne!(precomputed.content_hash(), computed.content_hash());
+        assert_ne!(
+            computed.content_hash(),
+            computed.tomorrow().unwrap().content_hash()
         );
     }
 
-    fn beirut(date: NaiveDate) -> PrayerTimes {
-        PrayerTimes::precomputed(date, Provider::DarElFatwa(ProviderCity::Beirut))
+    #[test]
+    fn content_hash_covers_every_input() {
+        let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+        let coordinates = Coordinates::new(33.8938, 35.5018);
+        let params = Method::MuslimWorldLeague.parameters();
+        let hash =
+            |coordinates, params| PrayerTimes::computed(date, coordinates, params).content_hash();
+        let base = hash(coordinates, params);
+        let mut elevated = params;
+        elevated.elevation = 100.0;
+        let mut unrounded = params;
+        unrounded.rounding = Rounding::None;
+        let mut angled = params;
+        angled.maghrib_parameter = MaghribParameter::Angle(4.0);
+
+        assert_ne!(base, hash(Coordinates::new(33.8938, 35.501_800_1), params));
+        assert_ne!(base, hash(coordinates, elevated));
+        assert_ne!(base, hash(coordinates, unrounded));
+        assert_ne!(base, hash(coordinates, angled));
+        assert_ne!(base, hash(coordinates, params.mazhab(Mazhab::Hanafi)));
+
+        let table = |fajr| {
+            let mut table =
+                OfficialTable::new(FixedOffset::east_opt(0).unwrap(), coordinates, params);
+            let time = |hour, minute| NaiveTime::from_hms_opt(hour, minute, 0).unwrap();
+            table.insert(
+                date,
+                [
+                    fajr,
+                    time(6, 43),
+                    time(11, 41),
+                    time(14, 21),
+                    time(16, 45),
+                    time(18, 7),
+                ],
+            );
+            Arc::new(table)
+        };
+        let fajr = |minute| NaiveTime::from_hms_opt(5, minute, 0).unwrap();
+
+        assert_ne!(
+            PrayerTimes::from_table(date, &table(fajr(7)))
+                .unwrap()
+                .content_hash(),
+            PrayerTimes::from_table(date, &table(fajr(8)))
+                .unwrap()
+                .content_hash()
+        );
     }
 
     #[test]
@@ -594,4 +3034,102 @@ mod tests {
         let t = Utc.with_ymd_and_hms(2026, 1, 1, 20, 0, 0).unwrap();
         assert_eq!(pt.current_time(t), Some(Prayer::Ishaa));
     }
+
+    #[test]
+    fn prayer_at_bounds_each_window() {
+        let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+        let pt = beirut(date);
+        let window = |prayer, next| Some((prayer, pt.time(prayer), pt.time(next)));
+
+        assert_eq!(
+            pt.prayer_at(pt.time(Prayer::Fajr)),
+            window(Prayer::Fajr, Prayer::Sunrise)
+        );
+        assert_eq!(
+            pt.prayer_at(pt.time(Prayer::Dhuhr) - Duration::seconds(1)),
+            window(Prayer::Sunrise, Prayer::Dhuhr)
+        );
+        assert_eq!(
+            pt.prayer_at(Utc.with_ymd_and_hms(2026, 1, 1, 20, 0, 0).unwrap()),
+            window(Prayer::Ishaa, Prayer::FajrTomorrow)
+        );
+        assert_eq!(
+            pt.prayer_at(pt.time(Prayer::Fajr) - Duration::minutes(1)),
+            None
+        );
+        assert_eq!(pt.prayer_at(pt.time(Prayer::FajrTomorrow)), None);
+        assert_eq!(
+            pt.current_time(pt.time(Prayer::FajrTomorrow)),
+            Some(Prayer::FajrTomorrow)
+        );
+    }
+
+    #[test]
+    fn only_matches_computed() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 11).unwrap();
+        let subsets: [&[Prayer]; 4] = [
+            &[Prayer::Maghrib],
+            &[Prayer::Fajr, Prayer::Sunrise, Prayer::Dhuhr, Prayer::Asr],
+            &[Prayer::Ishaa, Prayer::Maghrib],
+            &[Prayer::FajrTomorrow],
+        ];
+
+        for (method, latitude) in Method::ALL
+            .iter()
+            .zip([-40.0, 0.0, 33.9, 51.5, 60.0].iter().cycle())
+        {
+            let coordinates = Coordinates::new(*latitude, 35.5);
+            let params = method.parameters();
+            let times = PrayerTimes::computed(date, coordinates, params);
+
+            for prayers in subsets {
+                let only = PrayerTimes::only(prayers, date, coordinates, params).unwrap();
+
+                assert_eq!(only.date(), date);
+                for prayer in [
+                    Prayer::Fajr,
+                    Prayer::Sunrise,
+                    Prayer::Dhuhr,
+                    Prayer::Asr,
+                    Prayer::Maghrib,
+                    Prayer::Ishaa,
+                    Prayer::FajrTomorrow,
+                ] {
+                    let expected = prayers.contains(&prayer).then(|| times.time(prayer));
+
+                    assert_eq!(only.time(prayer), expected, "{method:?} {prayer:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn only_computes_what_it_needs() {
+        // The sun sets on the 27th of November 2024 in Tromsø, and
+        // doesn't rise the next day.
+        let date = NaiveDate::from_ymd_opt(2024, 11, 27).unwrap();
+        let tromso = Coordinates::new(69.6496, 18.9560);
+        let params = Method::MuslimWorldLeague.parameters();
+
+        assert_eq!(
+            PrayerTimes::try_computed(date, tromso, params),
+            Err(CalculationError::NoSunriseOrSunset(date))
+        );
+        assert!(PrayerTimes::only(&[Prayer::Maghrib], date, tromso, params).is_ok());
+        assert_eq!(
+            PrayerTimes::only(&[Prayer::Fajr], date, tromso, params),
+            Err(CalculationError::NoSunriseOrSunset(date))
+        );
+        assert_eq!(
+            PrayerTimes::only(
+                &[Prayer::Maghrib],
+                date.with_year(2500).unwrap(),
+                tromso,
+                params
+            ),
+            Err(CalculationError::DateOutOfRange(
+                date.with_year(2500).unwrap()
+            ))
+        );
+    }
 }
@@ -1,2 +1,3 @@
 pub(crate) mod data;
 pub mod provider;
+pub mod table;
@@ -1,7 +1,20 @@
+use crate::astronomy::unit::Coordinates;
+
+#[derive(PartialEq, Debug, Copy, Clone)]
 pub enum ProviderCity {
     Beirut,
 }
 
+#[derive(PartialEq, Debug, Copy, Clone)]
 pub enum Provider {
     DarElFatwa(ProviderCity),
 }
+
+impl Provider {
+    /// The location the provider's times are published for.
+    pub fn coordinates(&self) -> Coordinates {
+        match self {
+            Provider::DarElFatwa(ProviderCity::Beirut) => Coordinates::new(33.8938, 35.5018),
+        }
+    }
+}
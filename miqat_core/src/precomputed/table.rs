@@ -0,0 +1,518 @@
+//! # Official Tables
+//!
+//! Some authorities publish timetables rather than a method to compute
+//! them. An [`OfficialTable`] holds such a timetable for one location,
+//! read from CSV or JSON, and serves it as [`PrayerTimes`]:
+//!
+//! - days listed in the table use the official times,
+//! - days missing between two listed days are interpolated linearly,
+//! - days before the first or after the last listed day are computed
+//!   with the fallback coordinates and parameters.
+//!
+//! Times in a table are local times at a fixed UTC offset. An Ishaa
+//! earlier than Maghrib is taken to be after midnight.
+//!
+//! ##### Example
+//!
+//! ```
+//! use miqat::precomputed::table::{OfficialTable, TableSource};
+//! use miqat::prelude::*;
+//! use std::sync::Arc;
+//!
+//! let csv = "\
+//! date,fajr,sunrise,dhuhr,asr,maghrib,ishaa
+//! 2024-03-01,05:10,06:32,12:18,15:36,18:04,19:22
+//! 2024-03-03,05:08,06:30,12:18,15:37,18:06,19:24
+//! ";
+//! let beirut = Coordinates::new(33.8938, 35.5018);
+//! let offset = chrono::FixedOffset::east_opt(2 * 3600).unwrap();
+//! let table = Arc::new(
+//!     OfficialTable::from_csv(csv, offset, beirut, Method::MuslimWorldLeague.parameters())
+//!         .unwrap(),
+//! );
+//! let date = NaiveDate::from_ymd_opt(2024, 3, 2).unwrap();
+//! let times = PrayerTimes::from_table(date, &table).unwrap();
+//!
+//! assert_eq!(table.source(date), TableSource::Interpolated);
+//! assert_eq!(
+//!     times.time(Prayer::Fajr).with_timezone(&offset).format("%H:%M").to_string(),
+//!     "05:09"
+//! );
+//! ```
+
+use crate::astronomy::unit::Coordinates;
+use crate::error::CalculationError;
+use crate::models::parameters::Parameters;
+use crate::models::time_source::TimeSource;
+use crate::prayer_times::Origin;
+use crate::prayer_times::PrayerTimes;
+use chrono::Duration;
+use chrono::FixedOffset;
+use chrono::NaiveDate;
+use chrono::NaiveTime;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+
+type Entry<'a> = (&'a NaiveDate, &'a [i64; 6]);
+
+/// The official times of one location, see the [module](self)
+/// documentation.
+#[derive(PartialEq, Debug, Clone)]
+pub struct OfficialTable {
+    offset: FixedOffset,
+    coordinates: Coordinates,
+    parameters: Parameters,
+    /// Minutes since local midnight of Fajr, Sunrise, Dhuhr, Asr,
+    /// Maghrib and Ishaa.
+    entries: BTreeMap<NaiveDate, [i64; 6]>,
+}
+
+/// Where the times of a day come from.
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub enum TableSource {
+    /// Listed in the table.
+    Official,
+    /// Interpolated between the closest listed days.
+    Interpolated,
+    /// Computed with the fallback parameters.
+    Computed,
+}
+
+impl OfficialTable {
+    /// An empty table, where every day is computed with `parameters`
+    /// until times are [inserted](OfficialTable::insert).
+    pub fn new(offset: FixedOffset, coordinates: Coordinates, parameters: Parameters) -> Self {
+        OfficialTable {
+            offset,
+            coordinates,
+            parameters,
+            entries: BTreeMap::new(),
+        }
+    }
+
+    /// Reads a table with a `date,fajr,sunrise,dhuhr,asr,maghrib,ishaa`
+    /// line per day, dates as `YYYY-MM-DD` and times as `HH:MM`. A header
+    /// line, blank lines and lines starting with `#` are skipped.
+    pub fn from_csv(
+        csv: &str,
+        offset: FixedOffset,
+        coordinates: Coordinates,
+        parameters: Parameters,
+    ) -> Result<Self, TableError> {
+        let mut table = OfficialTable::new(offset, coordinates, parameters);
+
+        for (index, line) in csv.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') || line.starts_with("date") {
+                continue;
+            } else {
+                // Nothing to do.
+            }
+
+            let columns: Vec<&str> = line.split(',').map(str::trim).collect();
+            let Some((date, times)) = columns.split_first() else {
+                return Err(TableError::ColumnCount(line_number));
+            };
+            let times: [&str; 6] = times
+                .try_into()
+                .map_err(|_| TableError::ColumnCount(line_number))?;
+
+            table.insert_parsed(line_number, date, times)?;
+        }
+
+        Ok(table)
+    }
+
+    /// Reads a table from a JSON array of objects with a `date` and the
+    /// time of each prayer, in the same formats as
+    /// [`from_csv`](OfficialTable::from_csv).
+    ///
+    /// Only available with the `parser` feature.
+    #[cfg(feature = "parser")]
+    pub fn from_json(
+        json: &str,
+        offset: FixedOffset,
+        coordinates: Coordinates,
+        parameters: Parameters,
+    ) -> Result<Self, TableError> {
+        #[derive(serde::Deserialize)]
+        #[serde(deny_unknown_fields)]
+        struct JsonEntry {
+            date: String,
+            fajr: String,
+            sunrise: String,
+            dhuhr: String,
+            asr: String,
+            maghrib: String,
+            #[serde(alias = "isha")]
+            ishaa: String,
+        }
+
+        let entries: Vec<JsonEntry> =
+            serde_json::from_str(json).map_err(|error| TableError::Syntax(error.to_string()))?;
+        let mut table = OfficialTable::new(offset, coordinates, parameters);
+
+        for (index, entry) in entries.iter().enumerate() {
+            table.insert_parsed(
+                index + 1,
+                &entry.date,
+                [
+                    &entry.fajr,
+                    &entry.sunrise,
+                    &entry.dhuhr,
+                    &entry.asr,
+                    &entry.maghrib,
+                    &entry.ishaa,
+                ],
+            )?;
+        }
+
+        Ok(table)
+    }
+
+    /// Sets the official local times of Fajr, Sunrise, Dhuhr, Asr,
+    /// Maghrib and Ishaa on `date`.
+    pub fn insert(&mut self, date: NaiveDate, times: [NaiveTime; 6]) {
+        let mut minutes =
+            times.map(|time| time.signed_duration_since(NaiveTime::MIN).num_minutes());
+
+        for index in 1..minutes.len() {
+            if minutes[index] < minutes[index - 1] {
+                minutes[index] += Duration::days(1).num_minutes();
+            } else {
+                // Nothing to do.
+            }
+        }
+
+        self.entries.insert(date, minutes);
+    }
+
+    /// The location of the table, also used to compute missing days.
+    pub fn coordinates(&self) -> Coordinates {
+        self.coordinates
+    }
+
+    /// Where the times of `date` come from.
+    pub fn source(&self, date: NaiveDate) -> TableSource {
+        if self.entries.contains_key(&date) {
+            TableSource::Official
+        } else if self.neighbours(date).is_some() {
+            TableSource::Interpolated
+        } else {
+            TableSource::Computed
+        }
+    }
+
+    fn insert_parsed(
+        &mut self,
+        line: usize,
+        date: &str,
+        times: [&str; 6],
+    ) -> Result<(), TableError> {
+        let date = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .map_err(|_| TableError::InvalidDate(line, date.to_string()))?;
+        let mut parsed = [NaiveTime::MIN; 6];
+
+        for (slot, time) in parsed.iter_mut().zip(times) {
+            *slot = NaiveTime::parse_from_str(time, "%H:%M")
+                .map_err(|_| TableError::InvalidTime(line, time.to_string()))?;
+        }
+
+        self.insert(date, parsed);
+
+        Ok(())
+    }
+
+    /// The closest listed days before and after `date`.
+    fn neighbours(&self, date: NaiveDate) -> Option<(Entry<'_>, Entry<'_>)> {
+        let before = self.entries.range(..date).next_back()?;
+        let after = self.entries.range(date..).next()?;
+
+        Some((before, after))
+    }
+
+    /// Minutes since local midnight of each prayer, when `date` is listed
+    /// or can be interpolated.
+    fn minutes(&self, date: NaiveDate) -> Option<[i64; 6]> {
+        if let Some(minutes) = self.entries.get(&date) {
+            return Some(*minutes);
+        } else {
+            // Nothing to do.
+        }
+
+        let ((first, before), (last, after)) = self.neighbours(date)?;
+        let span = last.signed_duration_since(*first).num_days() as f64;
+        let elapsed = date.signed_duration_since(*first).num_days() as f64;
+
+        Some(std::array::from_fn(|index| {
+            let change = (after[index] - before[index]) as f64;
+
+            before[index] + (change * elapsed / span).round() as i64
+        }))
+    }
+
+    /// The times of `date`, from the table or computed.
+    fn day(table: &Arc<Self>, date: NaiveDate) -> Result<PrayerTimes, CalculationError> {
+        let Some(minutes) = table.minutes(date) else {
+            return PrayerTimes::try_computed(date, table.coordinates, table.parameters);
+        };
+        let midnight = date.and_time(NaiveTime::MIN).and_utc()
+            - Duration::seconds(table.offset.local_minus_utc().into());
+        let [fajr, sunrise, dhuhr, asr, maghrib, ishaa] =
+            minutes.map(|minutes| midnight + Duration::minutes(minutes));
+        let source = match table.source(date) {
+            TableSource::Interpolated => TimeSource::Interpolated,
+            TableSource::Official | TableSource::Computed => TimeSource::Astronomical,
+        };
+
+        Ok(PrayerTimes {
+            date,
+            origin: Origin::Table(Arc::clone(table)),
+            fajr,
+            sunrise,
+            dhuhr,
+            asr,
+            maghrib,
+            ishaa,
+            fajr_tomorrow: fajr,
+            fajr_source: source,
+            ishaa_source: source,
+            fajr_clamped: false,
+            ishaa_clamped: false,
+            solar_days: None,
+        })
+    }
+}
+
+impl PrayerTimes {
+    /// The times of `date` from an official table, see
+    /// [`OfficialTable`].
+    ///
+    /// Days that are computed fail like
+    /// [`try_computed`](PrayerTimes::try_computed), and keep the
+    /// [sources](PrayerTimes::source) of their Fajr and Ishaa; those of
+    /// interpolated days are [`TimeSource::Interpolated`].
+    pub fn from_table(
+        date: NaiveDate,
+        table: &Arc<OfficialTable>,
+    ) -> Result<PrayerTimes, CalculationError> {
+        let tomorrow = date
+            .succ_opt()
+            .ok_or(CalculationError::DateOutOfRange(date))?;
+
+        Ok(PrayerTimes {
+            origin: Origin::Table(Arc::clone(table)),
+            fajr_tomorrow: OfficialTable::day(table, tomorrow)?.fajr,
+            solar_days: None,
+            ..OfficialTable::day(table, date)?
+        })
+    }
+}
+
+/// Why a table could not be read. Line numbers start at 1; for JSON
+/// they are the position of the entry in the array.
+#[derive(PartialEq, Debug, Clone)]
+pub enum TableError {
+    /// A line doesn't have a date and six times.
+    ColumnCount(usize),
+    InvalidDate(usize, String),
+    InvalidTime(usize, String),
+    /// The JSON is malformed, or doesn't match the format.
+    Syntax(String),
+}
+
+impl fmt::Display for TableError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TableError::ColumnCount(line) => {
+                write!(f, "line {line}: expected a date and six times")
+            }
+            TableError::InvalidDate(line, date) => write!(f, "line {line}: invalid date `{date}`"),
+            TableError::InvalidTime(line, time) => write!(f, "line {line}: invalid time `{time}`"),
+            TableError::Syntax(message) => write!(f, "invalid table: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for TableError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Method;
+    use crate::models::prayer::Prayer;
+    use chrono::DateTime;
+    use chrono::Utc;
+
+    fn beirut() -> OfficialTable {
+        let csv = "\
+# Dar El-Fatwa, March 2024
+date,fajr,sunrise,dhuhr,asr,maghrib,ishaa
+
+2024-03-01,05:10,06:32,12:18,15:36,18:04,19:22
+2024-03-05,05:06,06:28,12:17,15:38,18:08,19:26
+";
+        OfficialTable::from_csv(
+            csv,
+            FixedOffset::east_opt(2 * 3600).unwrap(),
+            Coordinates::new(33.8938, 35.5018),
+            Method::MuslimWorldLeague.parameters(),
+        )
+        .unwrap()
+    }
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, day).unwrap()
+    }
+
+    #[test]
+    fn listed_days_use_the_official_times() {
+        let table = Arc::new(beirut());
+        let times = PrayerTimes::from_table(date(1), &table).unwrap();
+
+        assert_eq!(table.source(date(1)), TableSource::Official);
+        assert_eq!(
+            times.time(Prayer::Fajr),
+            "2024-03-01T03:10:00Z".parse::<DateTime<Utc>>().unwrap()
+        );
+        assert_eq!(
+            times.time(Prayer::Ishaa),
+            "2024-03-01T17:22:00Z".parse::<DateTime<Utc>>().unwrap()
+        );
+    }
+
+    #[test]
+    fn missing_days_are_interpolated() {
+        let table = Arc::new(beirut());
+        let times = PrayerTimes::from_table(date(3), &table).unwrap();
+
+        assert_eq!(table.source(date(3)), TableSource::Interpolated);
+        assert_eq!(
+            times.time(Prayer::Fajr),
+            "2024-03-03T03:08:00Z".parse::<DateTime<Utc>>().unwrap()
+        );
+        assert_eq!(
+            times.time(Prayer::Maghrib),
+            "2024-03-03T16:06:00Z".parse::<DateTime<Utc>>().unwrap()
+        );
+        assert_eq!(times.tomorrow(), PrayerTimes::from_table(date(4), &table));
+        assert_eq!(times.source(Prayer::Fajr), TimeSource::Interpolated);
+        assert_eq!(times.source(Prayer::Ishaa), TimeSource::Interpolated);
+
+        let listed = PrayerTimes::from_table(date(1), &table).unwrap();
+
+        assert_eq!(listed.source(Prayer::Fajr), TimeSource::Astronomical);
+    }
+
+    #[test]
+    fn days_outside_of_the_table_are_computed() {
+        let table = Arc::new(beirut());
+        let times = PrayerTimes::from_table(date(10), &table).unwrap();
+        let computed = PrayerTimes::computed(
+            date(10),
+            Coordinates::new(33.8938, 35.5018),
+            Method::MuslimWorldLeague.parameters(),
+        );
+
+        assert_eq!(table.source(date(10)), TableSource::Computed);
+        assert_eq!(times.time(Prayer::Dhuhr), computed.time(Prayer::Dhuhr));
+        assert_eq!(
+            PrayerTimes::from_table(date(9), &table)
+                .unwrap()
+                .time(Prayer::FajrTomorrow),
+            times.time(Prayer::Fajr)
+        );
+    }
+
+    #[test]
+    fn computed_days_keep_their_sources() {
+        let oslo = Coordinates::new(59.9139, 10.7522);
+        let params = Method::MuslimWorldLeague.parameters();
+        let table = Arc::new(OfficialTable::new(
+            FixedOffset::east_opt(2 * 3600).unwrap(),
+            oslo,
+            params,
+        ));
+        let june = NaiveDate::from_ymd_opt(2024, 6, 21).unwrap();
+        let times = PrayerTimes::from_table(june, &table).unwrap();
+        let computed = PrayerTimes::computed(june, oslo, params);
+
+        assert!(times.source(Prayer::Fajr).is_estimated());
+        assert_eq!(times.source(Prayer::Fajr), computed.source(Prayer::Fajr));
+        assert_eq!(times.source(Prayer::Ishaa), computed.source(Prayer::Ishaa));
+    }
+
+    #[test]
+    fn ishaa_after_midnight() {
+        let mut table = beirut();
+        let time = |hour, minute| NaiveTime::from_hms_opt(hour, minute, 0).unwrap();
+        table.insert(
+            date(20),
+            [
+                time(2, 30),
+                time(4, 40),
+                time(13, 10),
+                time(17, 30),
+                time(21, 40),
+                time(0, 20),
+            ],
+        );
+        let times = PrayerTimes::from_table(date(20), &Arc::new(table)).unwrap();
+
+        assert!(times.time(Prayer::Ishaa) > times.time(Prayer::Maghrib));
+    }
+
+    #[test]
+    fn rejects_malformed_tables() {
+        let from_csv = |csv: &str| {
+            OfficialTable::from_csv(
+                csv,
+                FixedOffset::east_opt(0).unwrap(),
+                Coordinates::new(0.0, 0.0),
+                Parameters::default(),
+            )
+        };
+
+        assert_eq!(
+            from_csv("2024-03-01,05:10,06:32"),
+            Err(TableError::ColumnCount(1))
+        );
+        assert_eq!(
+            from_csv("\n2024-02-30,05:10,06:32,12:18,15:36,18:04,19:22"),
+            Err(TableError::InvalidDate(2, "2024-02-30".to_string()))
+        );
+        assert_eq!(
+            from_csv("2024-03-01,5h10,06:32,12:18,15:36,18:04,19:22"),
+            Err(TableError::InvalidTime(1, "5h10".to_string()))
+        );
+    }
+
+    #[cfg(feature = "parser")]
+    #[test]
+    fn reads_json_tables() {
+        let json = r#"[
+            {
+                "date": "2024-03-01",
+                "fajr": "05:10",
+                "sunrise": "06:32",
+                "dhuhr": "12:18",
+                "asr": "15:36",
+                "maghrib": "18:04",
+                "isha": "19:22"
+            }
+        ]"#;
+        let table = OfficialTable::from_json(
+            json,
+            FixedOffset::east_opt(2 * 3600).unwrap(),
+            Coordinates::new(33.8938, 35.5018),
+            Method::MuslimWorldLeague.parameters(),
+        )
+        .unwrap();
+
+        assert_eq!(table.entries.get(&date(1)), beirut().entries.get(&date(1)));
+        assert_eq!(table.entries.len(), 1);
+    }
+}
@@ -0,0 +1,242 @@
+//! # Missed Prayers
+//!
+//! A small tracker of missed prayers to make up (qada). Apps log a
+//! [`CheckIn`] whenever a prayer is prayed, and the log is compared with
+//! the schedules of the days it covers: an obligatory prayer whose
+//! window, from its time up to the next prayer, is over without a
+//! check-in within it is missed. Missed prayers are then counted per
+//! prayer, and the counts go down as they are made up.
+//!
+//! Only available with the `qada` feature.
+//!
+//! ##### Example
+//!
+//! ```
+//! use miqat::prelude::*;
+//! use miqat::qada::{self, CheckIn};
+//!
+//! let date = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+//! let beirut = Coordinates::new(33.8938, 35.5018);
+//! let times = PrayerTimes::computed(date, beirut, Method::MuslimWorldLeague.parameters());
+//! let log: Vec<CheckIn> = [Prayer::Fajr, Prayer::Dhuhr, Prayer::Maghrib, Prayer::Ishaa]
+//!     .into_iter()
+//!     .map(|prayer| CheckIn::new(prayer, times.time(prayer) + Duration::minutes(5)))
+//!     .collect();
+//!
+//! let now = times.time(Prayer::FajrTomorrow);
+//! let days = qada::missed_days(&[times], &log, now);
+//! assert_eq!(days[0].missed, vec![Prayer::Asr]);
+//!
+//! let mut counts = qada::QadaCounts::from_days(&days);
+//! assert_eq!(counts.total(), 1);
+//! counts.make_up(Prayer::Asr, 1);
+//! assert_eq!(counts.total(), 0);
+//! ```
+
+use crate::models::prayer::Prayer;
+use crate::prayer_times::PrayerTimes;
+use chrono::DateTime;
+use chrono::NaiveDate;
+use chrono::Utc;
+
+/// A prayer the user reported as prayed, and when.
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct CheckIn {
+    pub prayer: Prayer,
+    pub time: DateTime<Utc>,
+}
+
+impl CheckIn {
+    pub fn new(prayer: Prayer, time: DateTime<Utc>) -> Self {
+        CheckIn { prayer, time }
+    }
+}
+
+/// The prayers missed on a day, see [`PrayerTimes::missed`].
+#[derive(PartialEq, Debug, Clone)]
+pub struct MissedDay {
+    pub date: NaiveDate,
+    pub missed: Vec<Prayer>,
+}
+
+/// The missed prayers of each schedule by `now` against the same log, in
+/// the order of `schedules`. The log needs not be sorted.
+pub fn missed_days(
+    schedules: &[PrayerTimes],
+    check_ins: &[CheckIn],
+    now: DateTime<Utc>,
+) -> Vec<MissedDay> {
+    schedules
+        .iter()
+        .map(|times| MissedDay {
+            date: times.date(),
+            missed: times.missed(check_ins, now),
+        })
+        .collect()
+}
+
+/// How many of each obligatory prayer are left to make up.
+#[derive(PartialEq, Debug, Copy, Clone, Default)]
+pub struct QadaCounts([u32; 5]);
+
+impl QadaCounts {
+    /// The counts of the prayers missed on `days`.
+    pub fn from_days(days: &[MissedDay]) -> Self {
+        let mut counts = QadaCounts::default();
+
+        for prayer in days.iter().flat_map(|day| &day.missed) {
+            counts.add(*prayer, 1);
+        }
+
+        counts
+    }
+
+    /// How many of `prayer` are left, always 0 for Sunrise.
+    /// `FajrTomorrow` is the same as Fajr.
+    pub fn get(&self, prayer: Prayer) -> u32 {
+        QadaCounts::index(prayer).map_or(0, |index| self.0[index])
+    }
+
+    /// How many prayers are left in total.
+    pub fn total(&self) -> u32 {
+        self.0.iter().sum()
+    }
+
+    /// Records `count` more missed prayers, e.g. from before the app was
+    /// used. Sunrise is ignored.
+    pub fn add(&mut self, prayer: Prayer, count: u32) {
+        if let Some(index) = QadaCounts::index(prayer) {
+            self.0[index] = self.0[index].saturating_add(count);
+        } else {
+            // Nothing to do.
+        }
+    }
+
+    /// Records `count` prayers as made up, never going below 0.
+    pub fn make_up(&mut self, prayer: Prayer, count: u32) {
+        if let Some(index) = QadaCounts::index(prayer) {
+            self.0[index] = self.0[index].saturating_sub(count);
+        } else {
+            // Nothing to do.
+        }
+    }
+
+    /// The counts of the obligatory prayers, in order.
+    pub fn iter(&self) -> impl Iterator<Item = (Prayer, u32)> + '_ {
+        Prayer::OBLIGATORY.into_iter().zip(self.0.iter().copied())
+    }
+
+    fn index(prayer: Prayer) -> Option<usize> {
+        let prayer = match prayer {
+            Prayer::FajrTomorrow => Prayer::Fajr,
+            _ => prayer,
+        };
+
+        Prayer::OBLIGATORY
+            .iter()
+            .position(|obligatory| *obligatory == prayer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Coordinates;
+    use crate::Method;
+    use chrono::Duration;
+
+    fn schedules() -> Vec<PrayerTimes> {
+        let first = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+        let beirut = Coordinates::new(33.8938, 35.5018);
+
+        first
+            .iter_days()
+            .take(2)
+            .map(|date| PrayerTimes::computed(date, beirut, Method::MuslimWorldLeague.parameters()))
+            .collect()
+    }
+
+    #[test]
+    fn check_ins_count_within_their_window() {
+        let days = schedules();
+        let (today, tomorrow) = (&days[0], &days[1]);
+        let log = [
+            // Fajr after sunrise is missed.
+            CheckIn::new(Prayer::Fajr, today.time(Prayer::Sunrise)),
+            CheckIn::new(Prayer::Dhuhr, today.time(Prayer::Dhuhr)),
+            // Asr logged as Dhuhr doesn't count as Asr.
+            CheckIn::new(Prayer::Dhuhr, today.time(Prayer::Asr)),
+            CheckIn::new(
+                Prayer::Maghrib,
+                today.time(Prayer::Ishaa) - Duration::minutes(1),
+            ),
+            // Ishaa after midnight, before the next Fajr.
+            CheckIn::new(
+                Prayer::Ishaa,
+                tomorrow.time(Prayer::Fajr) - Duration::minutes(1),
+            ),
+            CheckIn::new(Prayer::FajrTomorrow, tomorrow.time(Prayer::Fajr)),
+        ];
+
+        let missed = missed_days(&days, &log, tomorrow.time(Prayer::FajrTomorrow));
+        assert_eq!(missed[0].date, today.date());
+        assert_eq!(missed[0].missed, vec![Prayer::Fajr, Prayer::Asr]);
+        assert_eq!(
+            missed[1].missed,
+            vec![Prayer::Dhuhr, Prayer::Asr, Prayer::Maghrib, Prayer::Ishaa]
+        );
+    }
+
+    #[test]
+    fn prayers_are_missed_once_their_window_ends() {
+        let days = schedules();
+        let today = &days[0];
+        let log = [CheckIn::new(Prayer::Fajr, today.time(Prayer::Fajr))];
+        let missed = |now| today.missed(&log, now);
+
+        assert_eq!(missed(today.time(Prayer::Fajr)), vec![]);
+        assert_eq!(missed(today.time(Prayer::Dhuhr)), vec![]);
+        assert_eq!(
+            missed(today.time(Prayer::Asr) - Duration::minutes(1)),
+            vec![]
+        );
+        assert_eq!(missed(today.time(Prayer::Asr)), vec![Prayer::Dhuhr]);
+        assert_eq!(
+            missed(today.time(Prayer::Ishaa)),
+            vec![Prayer::Dhuhr, Prayer::Asr, Prayer::Maghrib]
+        );
+        assert_eq!(
+            missed(today.time(Prayer::FajrTomorrow)),
+            vec![Prayer::Dhuhr, Prayer::Asr, Prayer::Maghrib, Prayer::Ishaa]
+        );
+        assert_eq!(
+            missed_days(&days, &log, today.time(Prayer::Ishaa))[1].missed,
+            vec![]
+        );
+    }
+
+    #[test]
+    fn counts_add_up_and_go_down() {
+        let days = schedules();
+        let now = days[1].time(Prayer::FajrTomorrow);
+        let mut counts = QadaCounts::from_days(&missed_days(&days, &[], now));
+
+        assert_eq!(counts.total(), 10);
+        assert_eq!(counts.get(Prayer::FajrTomorrow), 2);
+        assert_eq!(counts.get(Prayer::Sunrise), 0);
+
+        counts.add(Prayer::Sunrise, 3);
+        counts.add(Prayer::Ishaa, 3);
+        counts.make_up(Prayer::Fajr, 5);
+        assert_eq!(
+            counts.iter().collect::<Vec<_>>(),
+            vec![
+                (Prayer::Fajr, 0),
+                (Prayer::Dhuhr, 2),
+                (Prayer::Asr, 2),
+                (Prayer::Maghrib, 2),
+                (Prayer::Ishaa, 5)
+            ]
+        );
+    }
+}
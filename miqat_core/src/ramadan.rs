@@ -0,0 +1,284 @@
+//! # Ramadan
+//!
+//! The day of Ramadan and the countdown to it, and the timetable of a
+//! whole Ramadan with the end of suhoor (Fajr) and iftar (Maghrib) of
+//! each day, so that apps don't need to derive them from Hijri dates.
+//! The [night of Eid](RamadanSchedule::eid_night) runs from the Maghrib of the last day
+//! of Ramadan to the Eid prayer, the deadline to give Zakat al-Fitr.
+//!
+//! Dates follow the calendar of [`HijriDate::from_gregorian`], including
+//! its [offset](crate::hijri::set_hijri_offset_days).
+//!
+//! ##### Example
+//!
+//! ```
+//! use miqat::prelude::*;
+//! use miqat::ramadan::{self, RamadanSchedule};
+//!
+//! let date = NaiveDate::from_ymd_opt(2024, 3, 9).unwrap();
+//! assert_eq!(ramadan::days_until_ramadan(date), Some(2));
+//! assert_eq!(ramadan::ramadan_day(date), None);
+//!
+//! let beirut = Coordinates::new(33.8938, 35.5018);
+//! let params = Method::MuslimWorldLeague.parameters();
+//! let schedule = RamadanSchedule::upcoming(date, beirut, params)
+//!     .unwrap()
+//!     .unwrap();
+//!
+//! assert_eq!(schedule.hijri_year(), 1445);
+//! assert!(schedule.iftar(1).unwrap() > schedule.suhoor_end(1).unwrap());
+//! ```
+
+use crate::astronomy::unit::Coordinates;
+use crate::error::CalculationError;
+use crate::hijri::HijriDate;
+use crate::models::parameters::Parameters;
+use crate::models::prayer::Prayer;
+use crate::models::window::Window;
+use crate::month::MonthDay;
+use crate::month::MonthlySchedule;
+use crate::sunnah_times::DuhaDefinition;
+use crate::sunnah_times::DuhaStart;
+use chrono::DateTime;
+use chrono::Duration;
+use chrono::NaiveDate;
+use chrono::Utc;
+
+/// The Hijri month of Ramadan.
+const RAMADAN: u8 = 9;
+
+/// The day of Ramadan `date` falls on, from 1 to 30, or `None` outside
+/// of Ramadan.
+pub fn ramadan_day(date: NaiveDate) -> Option<u8> {
+    let hijri = HijriDate::from_gregorian(date);
+
+    (hijri.month == RAMADAN).then_some(hijri.day)
+}
+
+/// How many days from `date` to the 1st of the next Ramadan, counting
+/// to the following year's during Ramadan. `None` out of the range of
+/// [`NaiveDate`].
+pub fn days_until_ramadan(date: NaiveDate) -> Option<i64> {
+    let hijri = HijriDate::from_gregorian(date);
+    let year = if hijri.month < RAMADAN {
+        hijri.year
+    } else {
+        hijri.year + 1
+    };
+    let first = first_day(year)?;
+
+    Some(first.signed_duration_since(date).num_days())
+}
+
+fn first_day(hijri_year: i32) -> Option<NaiveDate> {
+    let first = HijriDate {
+        year: hijri_year,
+        month: RAMADAN,
+        day: 1,
+    };
+
+    Some(first.to_gregorian()?.date_naive())
+}
+
+/// The schedules of every day of a Ramadan, by day of the month.
+#[derive(PartialEq, Debug, Clone)]
+pub struct RamadanSchedule {
+    hijri_year: i32,
+    coordinates: Coordinates,
+    month: MonthlySchedule,
+}
+
+impl RamadanSchedule {
+    /// The Ramadan of the Hijri year `hijri_year`. `None` if it is out of
+    /// the range of Gregorian dates, and an error if one of its days
+    /// can't be computed, as in polar regions around the solstices.
+    pub fn new(
+        hijri_year: i32,
+        coordinates: Coordinates,
+        parameters: Parameters,
+    ) -> Result<Option<Self>, CalculationError> {
+        let month = MonthlySchedule::for_hijri_month(hijri_year, RAMADAN, coordinates, parameters)?;
+
+        Ok(month.map(|month| RamadanSchedule {
+            hijri_year,
+            coordinates,
+            month,
+        }))
+    }
+
+    /// The Ramadan `date` falls in, or the next one outside of Ramadan,
+    /// like [`new`](RamadanSchedule::new).
+    pub fn upcoming(
+        date: NaiveDate,
+        coordinates: Coordinates,
+        parameters: Parameters,
+    ) -> Result<Option<Self>, CalculationError> {
+        let hijri = HijriDate::from_gregorian(date);
+        let year = if hijri.month <= RAMADAN {
+            hijri.year
+        } else {
+            hijri.year + 1
+        };
+
+        RamadanSchedule::new(year, coordinates, parameters)
+    }
+
+    pub fn hijri_year(&self) -> i32 {
+        self.hijri_year
+    }
+
+    /// Every day of the month, from the 1st to the 29th or 30th.
+    pub fn days(&self) -> &[MonthDay] {
+        &self.month.days
+    }
+
+    /// The `day`th day of the month, if the month has one.
+    pub fn day(&self, day: u8) -> Option<&MonthDay> {
+        self.month.day(day)
+    }
+
+    /// The day on the Gregorian `date`, if it is in this Ramadan.
+    pub fn on(&self, date: NaiveDate) -> Option<&MonthDay> {
+        self.days().iter().find(|day| day.date() == date)
+    }
+
+    /// The end of suhoor, at Fajr, on the `day`th day.
+    pub fn suhoor_end(&self, day: u8) -> Option<DateTime<Utc>> {
+        Some(self.day(day)?.times.time(Prayer::Fajr))
+    }
+
+    /// Iftar, at Maghrib, on the `day`th day.
+    pub fn iftar(&self, day: u8) -> Option<DateTime<Utc>> {
+        Some(self.day(day)?.times.time(Prayer::Maghrib))
+    }
+
+    /// How long the fast of the `day`th day lasts, from Fajr to Maghrib.
+    pub fn fasting_duration(&self, day: u8) -> Option<Duration> {
+        Some(self.iftar(day)? - self.suhoor_end(day)?)
+    }
+
+    /// The night of Eid al-Fitr following this Ramadan, from the Maghrib
+    /// of its last day, when the month ends, to the Eid prayer at
+    /// `eid_prayer` on the 1st of Shawwal, like the start of Duha. `None`
+    /// when the sun doesn't reach the altitude of `eid_prayer`, or at the
+    /// end of the supported range of dates.
+    pub fn eid_night(&self, eid_prayer: DuhaStart) -> Option<Window> {
+        let last = self.days().last()?;
+        let eid = last.times.tomorrow().ok()?;
+        let definition = DuhaDefinition {
+            start: eid_prayer,
+            ..Default::default()
+        };
+        let duha = Window::duha(&eid, self.coordinates, definition)?;
+
+        Some(Window {
+            start: last.times.time(Prayer::Maghrib),
+            end: duha.start,
+        })
+    }
+
+    /// The deadline to give Zakat al-Fitr, the Eid prayer, see
+    /// [`eid_night`](RamadanSchedule::eid_night).
+    pub fn zakat_al_fitr_deadline(&self, eid_prayer: DuhaStart) -> Option<DateTime<Utc>> {
+        Some(self.eid_night(eid_prayer)?.end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Method;
+    use crate::Rounding;
+    use crate::prayer_times::PrayerTimes;
+    use crate::time_ext::DateTimeExt;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn day_and_countdown() {
+        assert_eq!(ramadan_day(date(2024, 3, 10)), None);
+        assert_eq!(ramadan_day(date(2024, 3, 11)), Some(1));
+        assert_eq!(ramadan_day(date(2024, 4, 9)), Some(30));
+        assert_eq!(ramadan_day(date(2024, 4, 10)), None);
+
+        assert_eq!(days_until_ramadan(date(2024, 3, 10)), Some(1));
+        assert_eq!(days_until_ramadan(date(2024, 3, 11)), Some(355));
+        assert_eq!(days_until_ramadan(date(2024, 4, 10)), Some(325));
+        assert_eq!(days_until_ramadan(NaiveDate::MAX), None);
+    }
+
+    #[test]
+    fn schedule_of_the_current_or_next_ramadan() {
+        let beirut = Coordinates::new(33.8938, 35.5018);
+        let params = Method::MuslimWorldLeague.parameters();
+        let during = RamadanSchedule::upcoming(date(2024, 3, 20), beirut, params)
+            .unwrap()
+            .unwrap();
+        let after = RamadanSchedule::upcoming(date(2024, 4, 10), beirut, params)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(during.hijri_year(), 1445);
+        assert_eq!(during.days().len(), 30);
+        assert_eq!(during.on(date(2024, 3, 20)).unwrap().hijri.day, 10);
+        assert_eq!(during.on(date(2024, 4, 10)), None);
+        assert_eq!(after.hijri_year(), 1446);
+        assert_eq!(after.days()[0].date(), date(2025, 3, 1));
+
+        let first = during.day(1).unwrap();
+        assert_eq!(during.suhoor_end(1), Some(first.times.time(Prayer::Fajr)));
+        assert_eq!(
+            during.fasting_duration(1),
+            Some(first.times.time(Prayer::Maghrib) - first.times.time(Prayer::Fajr))
+        );
+        // The days lengthen towards the spring equinox and after it.
+        assert!(during.fasting_duration(30) > during.fasting_duration(1));
+        assert_eq!(during.iftar(31), None);
+    }
+
+    #[test]
+    fn eid_night_ends_at_the_eid_prayer() {
+        let beirut = Coordinates::new(33.8938, 35.5018);
+        let params = Method::MuslimWorldLeague.parameters();
+        let ramadan = RamadanSchedule::new(1446, beirut, params).unwrap().unwrap();
+        let eid = PrayerTimes::computed(date(2025, 3, 30), beirut, params);
+        let night = ramadan.eid_night(DuhaStart::AfterSunrise(20)).unwrap();
+
+        assert_eq!(ramadan.days().len(), 29);
+        assert_eq!(night.start, ramadan.iftar(29).unwrap());
+        assert_eq!(
+            night.end,
+            (eid.time(Prayer::Sunrise) + Duration::minutes(20)).rounded_minute(Rounding::Nearest)
+        );
+        assert!(night.contains(eid.time(Prayer::Fajr)));
+        assert!(!night.contains(night.end));
+        assert_eq!(
+            ramadan.zakat_al_fitr_deadline(DuhaStart::AfterSunrise(20)),
+            Some(night.end)
+        );
+
+        // The sun stays below 60° in Beirut at the end of March.
+        assert_eq!(ramadan.eid_night(DuhaStart::SolarAltitude(60.0)), None);
+    }
+
+    #[test]
+    fn polar_ramadan_is_an_error() {
+        let params = Method::MuslimWorldLeague.parameters();
+        let tromso = Coordinates::new(69.65, 18.96);
+        let longyearbyen = Coordinates::new(78.2232, 15.6267);
+
+        // Ramadan 1436 ran from the 18th of June to the 16th of July 2015,
+        // under the midnight sun.
+        assert_eq!(
+            RamadanSchedule::new(1436, tromso, params),
+            Err(CalculationError::NoSunriseOrSunset(date(2015, 6, 18)))
+        );
+        assert!(matches!(
+            RamadanSchedule::new(1436, longyearbyen, params),
+            Err(CalculationError::NoSunriseOrSunset(_))
+        ));
+        assert!(RamadanSchedule::new(1446, tromso, params).is_ok());
+    }
+}
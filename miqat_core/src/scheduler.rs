@@ -0,0 +1,290 @@
+//! # Scheduler
+//!
+//! A chronological feed of the moments to notify about: each obligatory
+//! prayer, and optionally reminders some time before it. The feed spans
+//! as many days as are read from it.
+//!
+//! ##### Example
+//!
+//! ```
+//! use miqat::prelude::*;
+//! use miqat::scheduler::{EventKind, Scheduler};
+//!
+//! let raleigh = Coordinates::new(35.7750, -78.6336);
+//! let scheduler = Scheduler::new(raleigh, Method::NorthAmerica.parameters())
+//!     .reminder(Duration::minutes(10));
+//! let noon = Utc.with_ymd_and_hms(2015, 7, 12, 16, 0, 0).unwrap();
+//! let mut events = scheduler.events_after(noon);
+//!
+//! let reminder = events.next().unwrap();
+//! assert_eq!(reminder.prayer, Prayer::Dhuhr);
+//! assert_eq!(reminder.kind, EventKind::Reminder(Duration::minutes(10)));
+//! assert_eq!(events.next().unwrap().kind, EventKind::Adhan);
+//! ```
+//!
+//! Applications that play the adhan implement [`AdhanTrigger`], e.g. with
+//! an audio library or a system command, and hand it to
+//! [`Scheduler::run`], which blocks and calls it as each event comes due.
+
+use crate::astronomy::unit::Coordinates;
+use crate::clock::Clock;
+use crate::error::CalculationError;
+use crate::models::day_boundary::DayBoundary;
+use crate::models::parameters::Parameters;
+use crate::models::prayer::Prayer;
+use crate::prayer_times::PrayerTimes;
+use chrono::DateTime;
+use chrono::Duration;
+use chrono::Utc;
+use std::convert::Infallible;
+
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub enum EventKind {
+    /// The time of the prayer has come.
+    Adhan,
+
+    /// The prayer is due after the given duration.
+    Reminder(Duration),
+}
+
+/// A moment to notify about.
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct Event {
+    pub prayer: Prayer,
+    /// The time of the prayer itself, even for a reminder.
+    pub prayer_time: DateTime<Utc>,
+    pub kind: EventKind,
+}
+
+impl Event {
+    /// When the event is due.
+    pub fn time(&self) -> DateTime<Utc> {
+        match self.kind {
+            EventKind::Adhan => self.prayer_time,
+            EventKind::Reminder(before) => self.prayer_time - before,
+        }
+    }
+}
+
+/// Called by [`Scheduler::run`] as each event comes due.
+pub trait AdhanTrigger {
+    fn on_event(&mut self, event: &Event);
+}
+
+impl<F: FnMut(&Event)> AdhanTrigger for F {
+    fn on_event(&mut self, event: &Event) {
+        self(event)
+    }
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct Scheduler {
+    coordinates: Coordinates,
+    parameters: Parameters,
+    reminders: Vec<Duration>,
+}
+
+impl Scheduler {
+    pub fn new(coordinates: Coordinates, parameters: Parameters) -> Self {
+        Scheduler {
+            coordinates,
+            parameters,
+            reminders: Vec::new(),
+        }
+    }
+
+    /// Adds a reminder `before` every prayer.
+    pub fn reminder(mut self, before: Duration) -> Self {
+        self.reminders.push(before);
+        self
+    }
+
+    /// The day boundary the schedules roll over at.
+    pub fn day_boundary(&self) -> DayBoundary {
+        self.parameters.day_boundary
+    }
+
+    /// The events due strictly after `instant`, in chronological order.
+    /// The feed ends at the first day that can't be computed, see
+    /// [`try_computed`](PrayerTimes::try_computed).
+    pub fn events_after(&self, instant: DateTime<Utc>) -> impl Iterator<Item = Event> + '_ {
+        // Start a day early, as the previous day's Ishaa or a reminder for
+        // it can still be ahead of an instant early in the day.
+        let today = self.parameters.day_boundary.date_of(instant);
+        let start = today.pred_opt().unwrap_or(today);
+
+        start
+            .iter_days()
+            .map_while(move |date| {
+                PrayerTimes::try_computed(date, self.coordinates, self.parameters).ok()
+            })
+            .flat_map(move |times| self.events_of(&times))
+            .filter(move |event| event.time() > instant)
+    }
+
+    /// Blocks, calling `trigger` as each event comes due, until the
+    /// schedule of a day can't be computed, e.g. at a polar location
+    /// around the solstices, and returns why.
+    pub fn run(
+        &self,
+        clock: &impl Clock,
+        trigger: &mut impl AdhanTrigger,
+    ) -> Result<Infallible, CalculationError> {
+        loop {
+            let now = clock.now();
+            let today = self.parameters.day_boundary.date_of(now);
+            // The events of the next day come from the schedules of the
+            // day before to the day after, and the feed quietly ends at
+            // the first one that can't be computed.
+            let yesterday = today.pred_opt().unwrap_or(today);
+            let tomorrow = today
+                .succ_opt()
+                .ok_or(CalculationError::DateOutOfRange(today))?;
+
+            for date in [yesterday, today, tomorrow] {
+                PrayerTimes::try_computed(date, self.coordinates, self.parameters)?;
+            }
+
+            self.run_until(clock, trigger, now + Duration::days(1));
+        }
+    }
+
+    /// Blocks until `end`, calling `trigger` as each event due by then
+    /// comes due. An event whose time passed while the thread was not
+    /// scheduled, e.g. during a system suspend, is triggered late rather
+    /// than skipped.
+    pub fn run_until(
+        &self,
+        clock: &impl Clock,
+        trigger: &mut impl AdhanTrigger,
+        end: DateTime<Utc>,
+    ) {
+        let events = self
+            .events_after(clock.now())
+            .take_while(|event| event.time() <= end);
+
+        for event in events {
+            if let Ok(remaining) = event.time().signed_duration_since(clock.now()).to_std() {
+                clock.sleep(remaining);
+            } else {
+                // Nothing to do.
+            }
+            trigger.on_event(&event);
+        }
+
+        if let Ok(remaining) = end.signed_duration_since(clock.now()).to_std() {
+            clock.sleep(remaining);
+        } else {
+            // Nothing to do.
+        }
+    }
+
+    fn events_of(&self, times: &PrayerTimes) -> Vec<Event> {
+        let mut events: Vec<Event> = Prayer::OBLIGATORY
+            .iter()
+            .flat_map(|prayer| {
+                let prayer_time = times.time(*prayer);
+                let event = move |kind| Event {
+                    prayer: *prayer,
+                    prayer_time,
+                    kind,
+                };
+
+                self.reminders
+                    .iter()
+                    .map(move |before| event(EventKind::Reminder(*before)))
+                    .chain([event(EventKind::Adhan)])
+            })
+            .collect();
+        events.sort_by_key(Event::time);
+
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Method;
+    use chrono::TimeZone;
+    use std::cell::Cell;
+    use std::time;
+
+    struct SimulatedClock(Cell<DateTime<Utc>>);
+
+    impl Clock for SimulatedClock {
+        fn now(&self) -> DateTime<Utc> {
+            self.0.get()
+        }
+
+        fn sleep(&self, duration: time::Duration) {
+            self.0
+                .set(self.0.get() + Duration::from_std(duration).unwrap());
+        }
+    }
+
+    #[test]
+    fn events_span_days_in_order() {
+        let raleigh = Coordinates::new(35.7750, -78.6336);
+        let scheduler = Scheduler::new(raleigh, Method::NorthAmerica.parameters())
+            .reminder(Duration::minutes(15))
+            .reminder(Duration::minutes(5));
+        let instant = Utc.with_ymd_and_hms(2015, 7, 12, 0, 30, 0).unwrap();
+        let events: Vec<Event> = scheduler.events_after(instant).take(30).collect();
+
+        // The previous day's Maghrib is still ahead at 8:30 PM EDT.
+        assert_eq!(events[0].prayer, Prayer::Maghrib);
+        assert_eq!(events[0].kind, EventKind::Adhan);
+        assert!(
+            events
+                .windows(2)
+                .all(|pair| pair[0].time() <= pair[1].time())
+        );
+        assert!(events.iter().all(|event| event.time() > instant));
+        assert_eq!(
+            events
+                .iter()
+                .filter(|event| event.kind == EventKind::Adhan)
+                .count(),
+            10
+        );
+    }
+
+    #[test]
+    fn run_triggers_each_event_when_due() {
+        let raleigh = Coordinates::new(35.7750, -78.6336);
+        let scheduler = Scheduler::new(raleigh, Method::NorthAmerica.parameters())
+            .reminder(Duration::minutes(10));
+        let start = Utc.with_ymd_and_hms(2015, 7, 12, 12, 0, 0).unwrap();
+        let clock = SimulatedClock(Cell::new(start));
+        let mut triggered = Vec::new();
+
+        scheduler.run_until(
+            &clock,
+            &mut |event: &Event| triggered.push((*event, clock.now())),
+            start + Duration::days(1),
+        );
+
+        assert_eq!(triggered.len(), 10);
+        assert!(triggered.iter().all(|(event, at)| event.time() == *at));
+        assert_eq!(triggered[1].0.prayer, Prayer::Dhuhr);
+        assert_eq!(triggered[1].0.kind, EventKind::Adhan);
+        assert_eq!(clock.now(), start + Duration::days(1));
+    }
+
+    #[test]
+    fn run_stops_at_the_first_day_without_a_schedule() {
+        let tromso = Coordinates::new(69.6496, 18.9560);
+        let scheduler = Scheduler::new(tromso, Method::MuslimWorldLeague.parameters());
+        let start = Utc.with_ymd_and_hms(2024, 5, 10, 12, 0, 0).unwrap();
+        let clock = SimulatedClock(Cell::new(start));
+        let mut adhans = 0;
+
+        let Err(error) = scheduler.run(&clock, &mut |_: &Event| adhans += 1);
+
+        assert!(matches!(error, CalculationError::NoSunriseOrSunset(_)));
+        assert!(adhans > 0);
+        assert!(clock.now() > start);
+        assert!(clock.now() < start + Duration::days(30));
+    }
+}
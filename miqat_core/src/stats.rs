@@ -0,0 +1,308 @@
+//! # Statistics
+//!
+//! Statistics over the schedules of a range of dates, for apps that
+//! chart a year of prayer times: the mean and standard deviation of each
+//! prayer's clock time, the length of the fasting days and its trend,
+//! and the dates on which a prayer crosses a given clock time.
+//!
+//! Clock times are read at a fixed UTC offset, like the rest of the
+//! rendering helpers.
+//!
+//! ##### Example
+//!
+//! ```
+//! use chrono::FixedOffset;
+//! use chrono::NaiveTime;
+//! use miqat::prelude::*;
+//! use miqat::stats::{Crossing, RangeStats};
+//!
+//! let raleigh = Coordinates::new(35.7750, -78.6336);
+//! let eastern = FixedOffset::west_opt(5 * 3600).unwrap();
+//! let stats = RangeStats::new(
+//!     NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+//!     NaiveDate::from_ymd_opt(2024, 12, 31).unwrap(),
+//!     raleigh,
+//!     Method::NorthAmerica.parameters(),
+//! )
+//! .unwrap();
+//!
+//! // When does Fajr move before 5:00?
+//! let five = NaiveTime::from_hms_opt(5, 0, 0).unwrap();
+//! let crossings = stats.crossings(Prayer::Fajr, five, eastern);
+//!
+//! assert_eq!(crossings[0].direction, Crossing::Earlier);
+//! ```
+
+use crate::astronomy::unit::Coordinates;
+use crate::error::CalculationError;
+use crate::models::parameters::Parameters;
+use crate::models::prayer::Prayer;
+use crate::prayer_times::PrayerTimes;
+use chrono::Duration;
+use chrono::FixedOffset;
+use chrono::NaiveDate;
+use chrono::NaiveTime;
+use chrono::Timelike;
+use std::f64::consts::TAU;
+
+/// Seconds in a day.
+const DAY: f64 = 86_400.0;
+
+/// The direction in which a prayer crosses a clock time.
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub enum Crossing {
+    /// The prayer moves from at or after the clock time to before it.
+    Earlier,
+    /// The prayer moves from before the clock time to at or after it.
+    Later,
+}
+
+/// A day on which a prayer is on the other side of a clock time than
+/// the day before.
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct ThresholdCrossing {
+    pub date: NaiveDate,
+    pub direction: Crossing,
+}
+
+/// The distribution of a prayer's clock time.
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct TimeStats {
+    pub mean: NaiveTime,
+    pub std_dev: Duration,
+}
+
+/// The schedules of a range of dates.
+#[derive(PartialEq, Debug, Clone)]
+pub struct RangeStats {
+    pub days: Vec<PrayerTimes>,
+}
+
+impl RangeStats {
+    /// The schedules from `start` to `end`, both included. Fails when
+    /// any of them can't be computed.
+    pub fn new(
+        start: NaiveDate,
+        end: NaiveDate,
+        coordinates: Coordinates,
+        parameters: Parameters,
+    ) -> Result<Self, CalculationError> {
+        Ok(RangeStats {
+            days: PrayerTimes::on_range(start, end, coordinates, parameters)?,
+        })
+    }
+
+    /// The mean and standard deviation of the clock time of `prayer` at
+    /// `offset`. `None` for an empty range.
+    ///
+    /// Clock times are taken around their circular mean, so that a prayer
+    /// on either side of midnight averages to around midnight rather than
+    /// to noon.
+    pub fn time_stats(&self, prayer: Prayer, offset: FixedOffset) -> Option<TimeStats> {
+        let seconds: Vec<f64> = self
+            .days
+            .iter()
+            .map(|times| clock_time(times, prayer, offset).num_seconds_from_midnight() as f64)
+            .collect();
+        let reference = circular_mean(&seconds)?;
+        let unwrapped: Vec<f64> = seconds
+            .iter()
+            .map(|value| reference + (value - reference + DAY / 2.0).rem_euclid(DAY) - DAY / 2.0)
+            .collect();
+        let (mean, variance) = mean_and_variance(&unwrapped)?;
+
+        Some(TimeStats {
+            mean: NaiveTime::from_num_seconds_from_midnight_opt(
+                mean.rem_euclid(DAY).round() as u32 % DAY as u32,
+                0,
+            )?,
+            std_dev: Duration::seconds(variance.sqrt().round() as i64),
+        })
+    }
+
+    /// The length of each fasting day, from Fajr to Maghrib.
+    pub fn fasting_durations(&self) -> Vec<(NaiveDate, Duration)> {
+        self.days
+            .iter()
+            .map(|times| {
+                let duration = times
+                    .time(Prayer::Maghrib)
+                    .signed_duration_since(times.time(Prayer::Fajr));
+                (times.date(), duration)
+            })
+            .collect()
+    }
+
+    /// The change of the fasting day per day, as the slope of a least
+    /// squares fit. `None` for fewer than two days.
+    pub fn fasting_trend(&self) -> Option<Duration> {
+        let durations = self.fasting_durations();
+        if durations.len() < 2 {
+            return None;
+        }
+
+        let first = durations[0].0;
+        let xs: Vec<f64> = durations
+            .iter()
+            .map(|(date, _)| (*date - first).num_days() as f64)
+            .collect();
+        let ys: Vec<f64> = durations
+            .iter()
+            .map(|(_, duration)| duration.num_seconds() as f64)
+            .collect();
+        let (mean_x, variance_x) = mean_and_variance(&xs)?;
+        let (mean_y, _) = mean_and_variance(&ys)?;
+        let covariance = xs
+            .iter()
+            .zip(&ys)
+            .map(|(x, y)| (x - mean_x) * (y - mean_y))
+            .sum::<f64>()
+            / xs.len() as f64;
+
+        Some(Duration::milliseconds(
+            (covariance / variance_x * 1000.0).round() as i64,
+        ))
+    }
+
+    /// The days on which the clock time of `prayer` at `offset` crosses
+    /// `threshold`, compared to the day before.
+    pub fn crossings(
+        &self,
+        prayer: Prayer,
+        threshold: NaiveTime,
+        offset: FixedOffset,
+    ) -> Vec<ThresholdCrossing> {
+        self.days
+            .windows(2)
+            .filter_map(|pair| {
+                let before = clock_time(&pair[0], prayer, offset) < threshold;
+                let after = clock_time(&pair[1], prayer, offset) < threshold;
+
+                let direction = match (before, after) {
+                    (false, true) => Crossing::Earlier,
+                    (true, false) => Crossing::Later,
+                    _ => return None,
+                };
+                Some(ThresholdCrossing {
+                    date: pair[1].date(),
+                    direction,
+                })
+            })
+            .collect()
+    }
+}
+
+fn clock_time(times: &PrayerTimes, prayer: Prayer, offset: FixedOffset) -> NaiveTime {
+    times.time(prayer).with_timezone(&offset).time()
+}
+
+/// The mean of seconds from midnight as angles around the clock.
+fn circular_mean(seconds: &[f64]) -> Option<f64> {
+    if seconds.is_empty() {
+        return None;
+    }
+
+    let (sin, cos) = seconds.iter().fold((0.0, 0.0), |(sin, cos), value| {
+        let angle = value / DAY * TAU;
+        (sin + angle.sin(), cos + angle.cos())
+    });
+
+    Some((sin.atan2(cos) / TAU * DAY).rem_euclid(DAY))
+}
+
+fn mean_and_variance(values: &[f64]) -> Option<(f64, f64)> {
+    if values.is_empty() {
+        return None;
+    }
+
+    let count = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / count;
+    let variance = values
+        .iter()
+        .map(|value| (value - mean).powi(2))
+        .sum::<f64>()
+        / count;
+
+    Some((mean, variance))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Method;
+    use chrono::Datelike;
+
+    fn raleigh_2015() -> RangeStats {
+        RangeStats::new(
+            NaiveDate::from_ymd_opt(2015, 1, 1).unwrap(),
+            NaiveDate::from_ymd_opt(2015, 12, 31).unwrap(),
+            Coordinates::new(35.7750, -78.6336),
+            Method::NorthAmerica.parameters(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn fajr_crosses_five_in_spring_and_autumn() {
+        let stats = raleigh_2015();
+        let eastern = FixedOffset::west_opt(5 * 3600).unwrap();
+        let five = NaiveTime::from_hms_opt(5, 0, 0).unwrap();
+        let crossings = stats.crossings(Prayer::Fajr, five, eastern);
+
+        assert_eq!(crossings.len(), 2);
+        assert_eq!(crossings[0].direction, Crossing::Earlier);
+        assert_eq!(crossings[1].direction, Crossing::Later);
+        assert!(crossings[0].date.month0() < 6 && crossings[1].date.month0() > 6);
+    }
+
+    #[test]
+    fn time_stats_of_a_year() {
+        let stats = raleigh_2015();
+        let eastern = FixedOffset::west_opt(5 * 3600).unwrap();
+        let dhuhr = stats.time_stats(Prayer::Dhuhr, eastern).unwrap();
+        let fajr = stats.time_stats(Prayer::Fajr, eastern).unwrap();
+
+        assert_eq!(dhuhr.mean.hour(), 12);
+        assert!(dhuhr.std_dev < Duration::minutes(10));
+        assert!(fajr.std_dev > Duration::minutes(30));
+
+        let empty = RangeStats { days: vec![] };
+
+        assert_eq!(empty.time_stats(Prayer::Fajr, eastern), None);
+        assert_eq!(empty.fasting_trend(), None);
+    }
+
+    #[test]
+    fn time_stats_across_midnight() {
+        // Ishaa in London in June and July is between 23:00 and 01:00 BST.
+        let stats = RangeStats::new(
+            NaiveDate::from_ymd_opt(2024, 6, 1).unwrap(),
+            NaiveDate::from_ymd_opt(2024, 7, 31).unwrap(),
+            Coordinates::new(51.5074, -0.1278),
+            Method::MuslimWorldLeague.parameters(),
+        )
+        .unwrap();
+        let bst = FixedOffset::east_opt(3600).unwrap();
+        let ishaa = stats.time_stats(Prayer::Ishaa, bst).unwrap();
+        let eleven = NaiveTime::from_hms_opt(23, 0, 0).unwrap();
+        let one = NaiveTime::from_hms_opt(1, 0, 0).unwrap();
+
+        assert!(ishaa.mean >= eleven || ishaa.mean <= one);
+        assert!(ishaa.std_dev < Duration::hours(1));
+    }
+
+    #[test]
+    fn fasting_days_lengthen_in_spring() {
+        let spring = RangeStats::new(
+            NaiveDate::from_ymd_opt(2015, 3, 1).unwrap(),
+            NaiveDate::from_ymd_opt(2015, 3, 31).unwrap(),
+            Coordinates::new(35.7750, -78.6336),
+            Method::NorthAmerica.parameters(),
+        )
+        .unwrap();
+        let trend = spring.fasting_trend().unwrap();
+
+        assert_eq!(spring.fasting_durations().len(), 31);
+        assert!(trend > Duration::minutes(1) && trend < Duration::minutes(3));
+    }
+}
@@ -0,0 +1,387 @@
+//! # Sunnah Times
+//!
+//! Times of the night derived from a schedule, for Qiyam and Tahajjud.
+//! The night runs from tonight's Maghrib to the next Fajr.
+//!
+//! The [Duha window](Window::duha), from after sunrise until just before
+//! zawal, is derived from the same schedule, with a
+//! [definition](DuhaDefinition) of its start to match the local custom.
+//! So are the windows of [Awwabin](Window::awwabin), after Maghrib, and
+//! of [Tahajjud](Window::tahajjud), before Fajr, with offsets from the
+//! prayers that bound them.
+//!
+//! ##### Example
+//!
+//! ```
+//! use miqat::prelude::*;
+//! use miqat::sunnah_times::SunnahTimes;
+//!
+//! let date = NaiveDate::from_ymd_opt(2015, 7, 12).unwrap();
+//! let raleigh = Coordinates::new(35.7750, -78.6336);
+//! let times = PrayerTimes::computed(date, raleigh, Method::NorthAmerica.parameters());
+//! let sunnah = SunnahTimes::from_prayer_times(&times);
+//!
+//! assert!(sunnah.middle_of_the_night < sunnah.last_third_of_the_night);
+//! ```
+
+use crate::astronomy::solar::SolarTime;
+use crate::astronomy::unit::Angle;
+use crate::astronomy::unit::Coordinates;
+use crate::models::delta_t::DeltaT;
+use crate::models::prayer::Prayer;
+use crate::models::rounding::Rounding;
+use crate::models::window::Window;
+use crate::prayer_times::PrayerTimes;
+use crate::time_ext::DateTimeExt;
+use chrono::DateTime;
+use chrono::Duration;
+use chrono::Utc;
+
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct SunnahTimes {
+    /// Halfway between Maghrib and the next Fajr.
+    pub middle_of_the_night: DateTime<Utc>,
+    /// Two thirds of the way from Maghrib to the next Fajr.
+    pub last_third_of_the_night: DateTime<Utc>,
+}
+
+impl SunnahTimes {
+    /// The sunnah times of the night from `maghrib` to `next_fajr`,
+    /// rounded to the nearest minute.
+    pub fn new(maghrib: DateTime<Utc>, next_fajr: DateTime<Utc>) -> Self {
+        let night = next_fajr.signed_duration_since(maghrib).num_seconds() as f64;
+        let after_maghrib = |portion: f64| maghrib + Duration::seconds((night * portion) as i64);
+
+        SunnahTimes {
+            middle_of_the_night: after_maghrib(1.0 / 2.0).rounded_minute(Rounding::Nearest),
+            last_third_of_the_night: after_maghrib(2.0 / 3.0).rounded_minute(Rounding::Nearest),
+        }
+    }
+
+    /// The sunnah times of the night following the day of `times`.
+    pub fn from_prayer_times(times: &PrayerTimes) -> Self {
+        SunnahTimes::new(
+            times.time(Prayer::Maghrib),
+            times.time(Prayer::FajrTomorrow),
+        )
+    }
+}
+
+/// When Duha starts.
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub enum DuhaStart {
+    /// The given number of minutes after sunrise.
+    AfterSunrise(i64),
+    /// When the sun has risen to the given altitude, in degrees above
+    /// the horizon.
+    SolarAltitude(f64),
+}
+
+impl Default for DuhaStart {
+    fn default() -> Self {
+        DuhaStart::AfterSunrise(15)
+    }
+}
+
+/// How the Duha window is derived from a schedule.
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct DuhaDefinition {
+    pub start: DuhaStart,
+    /// Minutes before solar transit (zawal) at which Duha ends.
+    pub minutes_before_zawal: i64,
+}
+
+impl Default for DuhaDefinition {
+    fn default() -> Self {
+        DuhaDefinition {
+            start: DuhaStart::default(),
+            minutes_before_zawal: 5,
+        }
+    }
+}
+
+/// How the Awwabin window is derived from a schedule.
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct AwwabinDefinition {
+    /// Minutes after Maghrib at which Awwabin starts, leaving time for
+    /// Maghrib and its sunnah.
+    pub minutes_after_maghrib: i64,
+    /// Minutes before Ishaa at which Awwabin ends.
+    pub minutes_before_ishaa: i64,
+}
+
+impl Default for AwwabinDefinition {
+    fn default() -> Self {
+        AwwabinDefinition {
+            minutes_after_maghrib: 15,
+            minutes_before_ishaa: 0,
+        }
+    }
+}
+
+/// When Tahajjud starts.
+#[derive(PartialEq, Debug, Copy, Clone, Default)]
+pub enum TahajjudStart {
+    /// At Ishaa.
+    Ishaa,
+    /// Halfway between Maghrib and the next Fajr.
+    MiddleOfTheNight,
+    /// Two thirds of the way from Maghrib to the next Fajr.
+    #[default]
+    LastThirdOfTheNight,
+}
+
+/// How the Tahajjud window is derived from a schedule.
+#[derive(PartialEq, Debug, Copy, Clone, Default)]
+pub struct TahajjudDefinition {
+    pub start: TahajjudStart,
+    /// Minutes before the next Fajr at which Tahajjud ends, e.g. to
+    /// leave time for suhoor.
+    pub minutes_before_fajr: i64,
+}
+
+impl Window {
+    /// The window of the Duha prayer on the day of `times` at
+    /// `coordinates`, rounded to the nearest minute. The sun is followed
+    /// with the delta T and elevation of a computed schedule. `None` when
+    /// the sun doesn't rise and set that day, or doesn't reach the
+    /// altitude of the definition before transit.
+    ///
+    /// ```
+    /// use miqat::prelude::*;
+    /// use miqat::sunnah_times::{DuhaDefinition, DuhaStart};
+    ///
+    /// let date = NaiveDate::from_ymd_opt(2015, 7, 12).unwrap();
+    /// let raleigh = Coordinates::new(35.7750, -78.6336);
+    /// let times = PrayerTimes::computed(date, raleigh, Method::NorthAmerica.parameters());
+    /// let definition = DuhaDefinition {
+    ///     start: DuhaStart::SolarAltitude(4.0),
+    ///     ..Default::default()
+    /// };
+    /// let duha = Window::duha(&times, raleigh, definition).unwrap();
+    ///
+    /// assert!(times.time(Prayer::Sunrise) < duha.start);
+    /// assert!(duha.end < times.time(Prayer::Dhuhr));
+    /// ```
+    pub fn duha(
+        times: &PrayerTimes,
+        coordinates: Coordinates,
+        definition: DuhaDefinition,
+    ) -> Option<Window> {
+        let dhuhr = times.time(Prayer::Dhuhr);
+        let solar_time = match times.parameters() {
+            Some(parameters) => PrayerTimes::solar_time(dhuhr, coordinates, *parameters),
+            None => SolarTime::checked_with_delta_t(dhuhr, coordinates, DeltaT::Estimated),
+        }?;
+
+        let start = match definition.start {
+            DuhaStart::AfterSunrise(minutes) => {
+                times.time(Prayer::Sunrise) + Duration::minutes(minutes)
+            }
+            DuhaStart::SolarAltitude(altitude) => {
+                solar_time.checked_time_for_solar_angle(Angle::new(altitude), false)?
+            }
+        };
+        let end = solar_time.transit - Duration::minutes(definition.minutes_before_zawal);
+
+        Window::rounded(start, end)
+    }
+
+    /// The window of the Awwabin prayer, between Maghrib and Ishaa, on
+    /// the day of `times`, rounded to the nearest minute. `None` when
+    /// the offsets leave no time between Maghrib and Ishaa.
+    ///
+    /// ```
+    /// use miqat::prelude::*;
+    /// use miqat::sunnah_times::AwwabinDefinition;
+    ///
+    /// let date = NaiveDate::from_ymd_opt(2015, 7, 12).unwrap();
+    /// let raleigh = Coordinates::new(35.7750, -78.6336);
+    /// let times = PrayerTimes::computed(date, raleigh, Method::NorthAmerica.parameters());
+    /// let awwabin = Window::awwabin(&times, AwwabinDefinition::default()).unwrap();
+    ///
+    /// assert_eq!(awwabin.start, times.time(Prayer::Maghrib) + Duration::minutes(15));
+    /// assert_eq!(awwabin.end, times.time(Prayer::Ishaa));
+    /// ```
+    pub fn awwabin(times: &PrayerTimes, definition: AwwabinDefinition) -> Option<Window> {
+        let start =
+            times.time(Prayer::Maghrib) + Duration::minutes(definition.minutes_after_maghrib);
+        let end = times.time(Prayer::Ishaa) - Duration::minutes(definition.minutes_before_ishaa);
+
+        Window::rounded(start, end)
+    }
+
+    /// The window of the Tahajjud prayer in the night following the day
+    /// of `times`, rounded to the nearest minute. `None` when the offset
+    /// leaves no time after the start.
+    ///
+    /// ```
+    /// use miqat::prelude::*;
+    /// use miqat::sunnah_times::{SunnahTimes, TahajjudDefinition};
+    ///
+    /// let date = NaiveDate::from_ymd_opt(2015, 7, 12).unwrap();
+    /// let raleigh = Coordinates::new(35.7750, -78.6336);
+    /// let times = PrayerTimes::computed(date, raleigh, Method::NorthAmerica.parameters());
+    /// let definition = TahajjudDefinition {
+    ///     minutes_before_fajr: 10,
+    ///     ..Default::default()
+    /// };
+    /// let tahajjud = Window::tahajjud(&times, definition).unwrap();
+    ///
+    /// assert_eq!(tahajjud.start, SunnahTimes::from_prayer_times(&times).last_third_of_the_night);
+    /// assert_eq!(tahajjud.end, times.time(Prayer::FajrTomorrow) - Duration::minutes(10));
+    /// ```
+    pub fn tahajjud(times: &PrayerTimes, definition: TahajjudDefinition) -> Option<Window> {
+        let night = SunnahTimes::from_prayer_times(times);
+        let start = match definition.start {
+            TahajjudStart::Ishaa => times.time(Prayer::Ishaa),
+            TahajjudStart::MiddleOfTheNight => night.middle_of_the_night,
+            TahajjudStart::LastThirdOfTheNight => night.last_third_of_the_night,
+        };
+        let end =
+            times.time(Prayer::FajrTomorrow) - Duration::minutes(definition.minutes_before_fajr);
+
+        Window::rounded(start, end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Method;
+    use chrono::FixedOffset;
+    use chrono::NaiveDate;
+    use chrono::TimeZone;
+
+    #[test]
+    fn sunnah_times_in_raleigh() {
+        let date = NaiveDate::from_ymd_opt(2015, 7, 12).unwrap();
+        let coordinates = Coordinates::new(35.7750, -78.6336);
+        let times = PrayerTimes::computed(date, coordinates, Method::NorthAmerica.parameters());
+        let sunnah = SunnahTimes::from_prayer_times(&times);
+        let eastern = FixedOffset::west_opt(4 * 3600).unwrap();
+        let local =
+            |time: DateTime<Utc>| time.with_timezone(&eastern).format("%-l:%M %p").to_string();
+
+        assert_eq!(local(sunnah.middle_of_the_night), "12:38 AM");
+        assert_eq!(local(sunnah.last_third_of_the_night), "1:59 AM");
+    }
+
+    #[test]
+    fn duha_in_raleigh() {
+        let date = NaiveDate::from_ymd_opt(2015, 7, 12).unwrap();
+        let coordinates = Coordinates::new(35.7750, -78.6336);
+        let times = PrayerTimes::computed(date, coordinates, Method::NorthAmerica.parameters());
+        let duha = Window::duha(&times, coordinates, DuhaDefinition::default()).unwrap();
+
+        assert_eq!(
+            duha.start,
+            times.time(Prayer::Sunrise) + Duration::minutes(15)
+        );
+        assert_eq!(
+            duha.end,
+            Utc.with_ymd_and_hms(2015, 7, 12, 17, 15, 0).unwrap()
+        );
+        assert!(duha.contains(duha.start));
+        assert!(!duha.contains(duha.end));
+
+        let by_altitude = DuhaDefinition {
+            start: DuhaStart::SolarAltitude(12.0),
+            ..Default::default()
+        };
+        let later = Window::duha(&times, coordinates, by_altitude).unwrap();
+
+        assert!(later.start > duha.start);
+        assert_eq!(later.end, duha.end);
+
+        let unreachable = DuhaDefinition {
+            start: DuhaStart::SolarAltitude(85.0),
+            ..Default::default()
+        };
+        assert_eq!(Window::duha(&times, coordinates, unreachable), None);
+    }
+
+    #[test]
+    fn duha_follows_the_sun_of_the_schedule() {
+        let date = NaiveDate::from_ymd_opt(2015, 7, 12).unwrap();
+        let coordinates = Coordinates::new(35.7750, -78.6336);
+        let mut parameters = Method::NorthAmerica.parameters().elevation(2000.0);
+        parameters.delta_t = DeltaT::Fixed(3600.0);
+        let times = PrayerTimes::computed(date, coordinates, parameters);
+        let solar_time =
+            PrayerTimes::solar_time(times.time(Prayer::Dhuhr), coordinates, parameters).unwrap();
+        let definition = DuhaDefinition {
+            start: DuhaStart::SolarAltitude(4.0),
+            ..Default::default()
+        };
+        let duha = Window::duha(&times, coordinates, definition).unwrap();
+
+        assert_eq!(
+            duha.start,
+            solar_time
+                .checked_time_for_solar_angle(Angle::new(4.0), false)
+                .unwrap()
+                .rounded_minute(Rounding::Nearest)
+        );
+        assert_eq!(
+            duha.end,
+            (solar_time.transit - Duration::minutes(5)).rounded_minute(Rounding::Nearest)
+        );
+    }
+
+    #[test]
+    fn awwabin_and_tahajjud_in_raleigh() {
+        let date = NaiveDate::from_ymd_opt(2015, 7, 12).unwrap();
+        let coordinates = Coordinates::new(35.7750, -78.6336);
+        let times = PrayerTimes::computed(date, coordinates, Method::NorthAmerica.parameters());
+        let at = |hour, minute| Utc.with_ymd_and_hms(2015, 7, 13, hour, minute, 0).unwrap();
+
+        let awwabin = Window::awwabin(&times, AwwabinDefinition::default()).unwrap();
+        assert_eq!(awwabin.start, at(0, 47));
+        assert_eq!(awwabin.end, at(1, 57));
+        assert!(awwabin.contains(at(1, 0)));
+        let squeezed = AwwabinDefinition {
+            minutes_after_maghrib: 60,
+            minutes_before_ishaa: 60,
+        };
+        assert_eq!(Window::awwabin(&times, squeezed), None);
+
+        let after_ishaa = TahajjudDefinition {
+            start: TahajjudStart::Ishaa,
+            minutes_before_fajr: 15,
+        };
+        let tahajjud = Window::tahajjud(&times, after_ishaa).unwrap();
+        assert_eq!(tahajjud.start, times.time(Prayer::Ishaa));
+        assert_eq!(tahajjud.end, at(8, 28));
+        assert!(!tahajjud.contains(at(8, 30)));
+        let middle = TahajjudDefinition {
+            start: TahajjudStart::MiddleOfTheNight,
+            minutes_before_fajr: 0,
+        };
+        assert_eq!(
+            Window::tahajjud(&times, middle).unwrap().start,
+            SunnahTimes::from_prayer_times(&times).middle_of_the_night
+        );
+        let too_late = TahajjudDefinition {
+            minutes_before_fajr: 180,
+            ..Default::default()
+        };
+        assert_eq!(Window::tahajjud(&times, too_late), None);
+    }
+
+    #[test]
+    fn night_uses_the_next_fajr() {
+        let maghrib = Utc.with_ymd_and_hms(2024, 3, 15, 16, 0, 0).unwrap();
+        let next_fajr = Utc.with_ymd_and_hms(2024, 3, 16, 4, 0, 0).unwrap();
+        let sunnah = SunnahTimes::new(maghrib, next_fajr);
+
+        assert_eq!(
+            sunnah.middle_of_the_night,
+            Utc.with_ymd_and_hms(2024, 3, 15, 22, 0, 0).unwrap()
+        );
+        assert_eq!(
+            sunnah.last_third_of_the_night,
+            Utc.with_ymd_and_hms(2024, 3, 16, 0, 0, 0).unwrap()
+        );
+    }
+}
@@ -0,0 +1,152 @@
+//! # Ticker
+//!
+//! A pull-based source of wakeups for reactive UIs. Rather than polling
+//! the schedule every second, an app asks the [`Ticker`] when the next
+//! moment that changes what it displays is, sleeps or schedules a timer
+//! until then, and asks again.
+//!
+//! The moments are those of a [`Scheduler`]: each prayer and its
+//! reminders, plus the rollover to the next day's schedule.
+//!
+//! ##### Example
+//!
+//! ```
+//! use miqat::prelude::*;
+//! use miqat::scheduler::Scheduler;
+//! use miqat::ticker::{TickReason, Ticker};
+//!
+//! let raleigh = Coordinates::new(35.7750, -78.6336);
+//! let scheduler = Scheduler::new(raleigh, Method::NorthAmerica.parameters())
+//!     .reminder(Duration::minutes(10));
+//! let ticker = Ticker::new(scheduler);
+//!
+//! let noon = Utc.with_ymd_and_hms(2015, 7, 12, 16, 0, 0).unwrap();
+//! let tick = ticker.next_tick(noon).unwrap();
+//!
+//! assert!(matches!(tick.reason, TickReason::Event(_)));
+//! assert_eq!(tick.at, noon + tick.remaining);
+//! ```
+
+use crate::scheduler::Event;
+use crate::scheduler::Scheduler;
+use chrono::DateTime;
+use chrono::Duration;
+use chrono::NaiveDate;
+use chrono::Utc;
+
+/// Why a tick is due.
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub enum TickReason {
+    /// A prayer, or a reminder of one, is due.
+    Event(Event),
+    /// The schedule of the given date takes over.
+    Rollover(NaiveDate),
+}
+
+/// The next moment a UI should update at.
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct Tick {
+    pub at: DateTime<Utc>,
+    /// The time left until the tick from the instant it was asked for.
+    pub remaining: Duration,
+    pub reason: TickReason,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct Ticker {
+    scheduler: Scheduler,
+}
+
+impl Ticker {
+    pub fn new(scheduler: Scheduler) -> Self {
+        Ticker { scheduler }
+    }
+
+    /// The first tick strictly after `now`. A prayer due at the same
+    /// instant as the rollover is reported rather than the rollover.
+    /// `None` past the last representable date.
+    pub fn next_tick(&self, now: DateTime<Utc>) -> Option<Tick> {
+        let boundary = self.scheduler.day_boundary();
+        let tomorrow = boundary.date_of(now).succ_opt()?;
+        let rollover = boundary.start_of(tomorrow);
+
+        let (at, reason) = match self.scheduler.events_after(now).next() {
+            Some(event) if event.time() <= rollover => (event.time(), TickReason::Event(event)),
+            _ => (rollover, TickReason::Rollover(tomorrow)),
+        };
+
+        Some(Tick {
+            at,
+            remaining: at.signed_duration_since(now),
+            reason,
+        })
+    }
+
+    /// The ticks after `now`, in order.
+    pub fn ticks_after(&self, now: DateTime<Utc>) -> impl Iterator<Item = Tick> + '_ {
+        std::iter::successors(self.next_tick(now), move |tick| self.next_tick(tick.at))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Method;
+    use crate::astronomy::unit::Coordinates;
+    use crate::models::day_boundary::DayBoundary;
+    use crate::models::prayer::Prayer;
+    use crate::scheduler::EventKind;
+    use chrono::FixedOffset;
+    use chrono::TimeZone;
+
+    #[test]
+    fn ticks_cover_events_and_rollover() {
+        let raleigh = Coordinates::new(35.7750, -78.6336);
+        let scheduler = Scheduler::new(raleigh, Method::NorthAmerica.parameters())
+            .reminder(Duration::minutes(10));
+        let ticker = Ticker::new(scheduler);
+        let start = Utc.with_ymd_and_hms(2015, 7, 12, 16, 0, 0).unwrap();
+        let ticks: Vec<Tick> = ticker.ticks_after(start).take(12).collect();
+
+        match ticks[0].reason {
+            TickReason::Event(event) => {
+                assert_eq!(event.prayer, Prayer::Dhuhr);
+                assert_eq!(event.kind, EventKind::Reminder(Duration::minutes(10)));
+            }
+            TickReason::Rollover(_) => panic!("Expected the Dhuhr reminder"),
+        }
+        assert!(ticks.windows(2).all(|pair| pair[0].at < pair[1].at));
+
+        let rollovers: Vec<Tick> = ticks
+            .iter()
+            .filter(|tick| matches!(tick.reason, TickReason::Rollover(_)))
+            .copied()
+            .collect();
+
+        assert_eq!(rollovers.len(), 1);
+        assert_eq!(
+            rollovers[0].at,
+            Utc.with_ymd_and_hms(2015, 7, 13, 0, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn rollover_follows_the_day_boundary() {
+        let raleigh = Coordinates::new(35.7750, -78.6336);
+        let eastern = FixedOffset::west_opt(4 * 3600).unwrap();
+        let params = crate::Parameters {
+            day_boundary: DayBoundary::LocalCivil(eastern),
+            ..Method::NorthAmerica.parameters()
+        };
+        let ticker = Ticker::new(Scheduler::new(raleigh, params));
+        let late = Utc.with_ymd_and_hms(2015, 7, 13, 3, 0, 0).unwrap();
+        let tick = ticker.next_tick(late).unwrap();
+
+        assert_eq!(
+            tick.reason,
+            TickReason::Rollover(NaiveDate::from_ymd_opt(2015, 7, 13).unwrap())
+        );
+        assert_eq!(tick.at, Utc.with_ymd_and_hms(2015, 7, 13, 4, 0, 0).unwrap());
+        assert_eq!(tick.remaining, Duration::hours(1));
+    }
+}
@@ -0,0 +1,163 @@
+//! # Time Extensions
+//!
+//! The rounding and minute offsets applied to every computed prayer time,
+//! as an extension trait on [`DateTime`], so that bindings and
+//! post-processing of times from other sources behave exactly like the
+//! library.
+//!
+//! ##### Example
+//!
+//! ```
+//! use miqat::time_ext::DateTimeExt;
+//! use miqat::prelude::*;
+//!
+//! let time = Utc.with_ymd_and_hms(2015, 7, 13, 4, 37, 30).unwrap();
+//!
+//! assert_eq!(
+//!     time.rounded_minute(Rounding::Nearest),
+//!     Utc.with_ymd_and_hms(2015, 7, 13, 4, 38, 0).unwrap()
+//! );
+//! assert_eq!(
+//!     time.rounded_minute(Rounding::Floor).adjust_time(-2),
+//!     Utc.with_ymd_and_hms(2015, 7, 13, 4, 35, 0).single()
+//! );
+//! ```
+
+use crate::models::rounding::Rounding;
+use chrono::DateTime;
+use chrono::Duration;
+use chrono::TimeZone;
+use chrono::Timelike;
+
+/// Minute rounding and offsetting of date/times.
+pub trait DateTimeExt {
+    /// Rounds to a whole minute, see [`Rounding`]. Sub-second precision
+    /// is kept, as computed times never have any.
+    fn rounded_minute(&self, rounding: Rounding) -> Self;
+
+    /// Moves the time by the given number of minutes, which can be
+    /// negative, or `None` if the result is out of the range of
+    /// [`DateTime`].
+    fn adjust_time(&self, minutes: i64) -> Option<Self>
+    where
+        Self: Sized;
+}
+
+impl<Tz: TimeZone> DateTimeExt for DateTime<Tz> {
+    fn rounded_minute(&self, rounding: Rounding) -> Self {
+        let adjusted = self.clone();
+        let seconds = adjusted.second();
+
+        match rounding {
+            Rounding::Nearest => {
+                let rounded = ((seconds as f64) / 60.0).round() as i64;
+                let adjusted_seconds = seconds as i64;
+
+                if rounded == 1 {
+                    adjusted + Duration::seconds(60 - adjusted_seconds)
+                } else {
+                    adjusted + Duration::seconds(-adjusted_seconds)
+                }
+            }
+            Rounding::Ceil if seconds == 0 && adjusted.nanosecond() == 0 => adjusted,
+            Rounding::Ceil => {
+                let adjusted_seconds = seconds as i64;
+
+                adjusted + Duration::seconds(60 - adjusted_seconds)
+            }
+            Rounding::Floor => adjusted - Duration::seconds(seconds.into()),
+            Rounding::None => adjusted,
+        }
+    }
+
+    fn adjust_time(&self, minutes: i64) -> Option<Self> {
+        self.clone()
+            .checked_add_signed(Duration::try_minutes(minutes)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    #[test]
+    fn calculate_rounding_nearest() {
+        let time_1 = Utc
+            .with_ymd_and_hms(2015, 7, 13, 4, 37, 30)
+            .single()
+            .expect("Invalid date and time.");
+
+        assert_eq!(
+            time_1.rounded_minute(Rounding::Nearest),
+            Utc.with_ymd_and_hms(2015, 7, 13, 4, 38, 0)
+                .single()
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn calculate_rounding_up() {
+        let time_1 = Utc
+            .with_ymd_and_hms(2015, 7, 13, 5, 59, 20)
+            .single()
+            .expect("Invalid date and time.");
+
+        assert_eq!(
+            time_1.rounded_minute(Rounding::Ceil),
+            Utc.with_ymd_and_hms(2015, 7, 13, 6, 0, 0).single().unwrap()
+        );
+    }
+
+    #[test]
+    fn rounding_up_keeps_whole_minutes() {
+        let time = Utc.with_ymd_and_hms(2015, 7, 13, 5, 59, 0).unwrap();
+
+        assert_eq!(time.rounded_minute(Rounding::Ceil), time);
+    }
+
+    #[test]
+    fn calculate_rounding_down() {
+        let time_1 = Utc
+            .with_ymd_and_hms(2015, 7, 13, 5, 59, 50)
+            .single()
+            .expect("Invalid date and time.");
+
+        assert_eq!(
+            time_1.rounded_minute(Rounding::Floor),
+            Utc.with_ymd_and_hms(2015, 7, 13, 5, 59, 0)
+                .single()
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn calculate_rounding_none() {
+        let time_1 = Utc
+            .with_ymd_and_hms(2015, 7, 13, 5, 59, 20)
+            .single()
+            .expect("Invalid date and time.");
+
+        assert_eq!(
+            time_1.rounded_minute(Rounding::None),
+            Utc.with_ymd_and_hms(2015, 7, 13, 5, 59, 20)
+                .single()
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn adjust_time_by_minutes() {
+        let time = Utc.with_ymd_and_hms(2015, 7, 13, 23, 58, 0).unwrap();
+
+        assert_eq!(
+            time.adjust_time(3),
+            Utc.with_ymd_and_hms(2015, 7, 14, 0, 1, 0).single()
+        );
+        assert_eq!(
+            time.adjust_time(-58),
+            Utc.with_ymd_and_hms(2015, 7, 13, 23, 0, 0).single()
+        );
+        assert_eq!(time.adjust_time(i64::MAX), None);
+    }
+}
@@ -0,0 +1,128 @@
+//! # Travel
+//!
+//! A traveler (musafir) may shorten (qasr) and combine prayers once they
+//! are far enough from home. This module reports whether that applies and
+//! the combined prayer windows, as an opt-in on top of the regular schedule.
+//!
+//! ##### Example
+//!
+//! ```
+//! use miqat::prelude::*;
+//! use miqat::travel::Travel;
+//!
+//! let beirut = Coordinates::new(33.8938, 35.5018);
+//! let damascus = Coordinates::new(33.5138, 36.2765);
+//! let travel = Travel::new(beirut);
+//!
+//! assert!(travel.is_traveler(damascus));
+//! ```
+
+use crate::astronomy::unit::Coordinates;
+use crate::models::prayer::Prayer;
+use crate::prayer_times::PrayerTimes;
+use chrono::DateTime;
+use chrono::Utc;
+
+/// The distance from home, in kilometers, beyond which the majority
+/// of scholars consider a person to be traveling (roughly 48 miles).
+pub const QASR_DISTANCE_KM: f64 = 81.0;
+
+/// Two prayers that may be performed together, and the window in which
+/// they can be.
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct CombinedWindow {
+    pub first: Prayer,
+    pub second: Prayer,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+/// Traveler status relative to a home location.
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct Travel {
+    pub home: Coordinates,
+    pub distance_threshold: f64,
+}
+
+impl Travel {
+    pub fn new(home: Coordinates) -> Self {
+        Travel {
+            home,
+            distance_threshold: QASR_DISTANCE_KM,
+        }
+    }
+
+    /// Overrides the default distance threshold, in kilometers.
+    pub fn distance_threshold(mut self, kilometers: f64) -> Self {
+        self.distance_threshold = kilometers;
+        self
+    }
+
+    /// Whether being at `current` makes one a traveler.
+    pub fn is_traveler(&self, current: Coordinates) -> bool {
+        self.home.distance_to(current) >= self.distance_threshold
+    }
+
+    /// The Dhuhr with Asr and Maghrib with Ishaa windows of `times`, or
+    /// `None` when being at `current` does not make one a traveler.
+    pub fn combined_windows(
+        &self,
+        current: Coordinates,
+        times: &PrayerTimes,
+    ) -> Option<[CombinedWindow; 2]> {
+        if self.is_traveler(current) {
+            Some([
+                CombinedWindow {
+                    first: Prayer::Dhuhr,
+                    second: Prayer::Asr,
+                    start: times.time(Prayer::Dhuhr),
+                    end: times.time(Prayer::Maghrib),
+                },
+                CombinedWindow {
+                    first: Prayer::Maghrib,
+                    second: Prayer::Ishaa,
+                    start: times.time(Prayer::Maghrib),
+                    end: times.time(Prayer::FajrTomorrow),
+                },
+            ])
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Method;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn traveler_beyond_threshold() {
+        let beirut = Coordinates::new(33.8938, 35.5018);
+        let jounieh = Coordinates::new(33.9808, 35.6178);
+        let damascus = Coordinates::new(33.5138, 36.2765);
+        let travel = Travel::new(beirut);
+
+        assert!(!travel.is_traveler(jounieh));
+        assert!(travel.is_traveler(damascus));
+        assert!(!travel.distance_threshold(100.0).is_traveler(damascus));
+    }
+
+    #[test]
+    fn combined_windows_only_when_traveling() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+        let beirut = Coordinates::new(33.8938, 35.5018);
+        let damascus = Coordinates::new(33.5138, 36.2765);
+        let times = PrayerTimes::computed(date, damascus, Method::MuslimWorldLeague.parameters());
+        let travel = Travel::new(beirut);
+
+        assert_eq!(travel.combined_windows(beirut, &times), None);
+
+        let [noon, evening] = travel.combined_windows(damascus, &times).unwrap();
+        assert_eq!(noon.start, times.time(Prayer::Dhuhr));
+        assert_eq!(noon.end, times.time(Prayer::Maghrib));
+        assert_eq!(evening.start, times.time(Prayer::Maghrib));
+        assert_eq!(evening.end, times.time(Prayer::FajrTomorrow));
+    }
+}
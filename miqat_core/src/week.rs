@@ -0,0 +1,104 @@
+//! # Weekly Schedule
+//!
+//! Seven consecutive schedules, Saturday to Friday, along with what a
+//! mosque needs to plan Jumua.
+//!
+//! ##### Example
+//!
+//! ```
+//! use miqat::prelude::*;
+//! use miqat::week::week_of;
+//!
+//! let date = NaiveDate::from_ymd_opt(2024, 3, 13).unwrap();
+//! let beirut = Coordinates::new(33.8938, 35.5018);
+//! let params = Method::MuslimWorldLeague.parameters();
+//! let week = week_of(date, beirut, params, Duration::minutes(15)).unwrap();
+//!
+//! assert_eq!(week.days.len(), 7);
+//! assert_eq!(week.jumua.date, NaiveDate::from_ymd_opt(2024, 3, 15).unwrap());
+//! ```
+
+use crate::astronomy::unit::Coordinates;
+use crate::error::CalculationError;
+use crate::models::parameters::Parameters;
+use crate::models::prayer::Prayer;
+use crate::prayer_times::PrayerTimes;
+use chrono::DateTime;
+use chrono::Duration;
+use chrono::NaiveDate;
+use chrono::Utc;
+use chrono::Weekday;
+
+/// Friday prayer details for a week.
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct Jumua {
+    pub date: NaiveDate,
+    /// Dhuhr on Friday, the earliest time for Jumua.
+    pub dhuhr: DateTime<Utc>,
+    /// Dhuhr delayed by the khutbah offset; a suggested start time.
+    pub suggested_start: DateTime<Utc>,
+}
+
+/// The schedules of a week, from Saturday to Friday.
+#[derive(PartialEq, Debug, Clone)]
+pub struct WeekSchedule {
+    pub days: Vec<PrayerTimes>,
+    pub jumua: Jumua,
+}
+
+/// The schedules of the Saturday to Friday week that contains `date`.
+/// `khutbah_offset` is added to Friday's Dhuhr for the suggested
+/// Jumua start. Fails when any day of the week can't be computed.
+pub fn week_of(
+    date: NaiveDate,
+    coordinates: Coordinates,
+    parameters: Parameters,
+    khutbah_offset: Duration,
+) -> Result<WeekSchedule, CalculationError> {
+    let week = date.week(Weekday::Sat);
+    let days = PrayerTimes::on_range(week.first_day(), week.last_day(), coordinates, parameters)?;
+    let friday = &days[6];
+    let jumua = Jumua {
+        date: friday.date(),
+        dhuhr: friday.time(Prayer::Dhuhr),
+        suggested_start: friday.time(Prayer::Dhuhr) + khutbah_offset,
+    };
+
+    Ok(WeekSchedule { days, jumua })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Method;
+    use chrono::Datelike;
+
+    #[test]
+    fn week_runs_from_saturday_to_friday() {
+        let beirut = Coordinates::new(33.8938, 35.5018);
+        let params = Method::MuslimWorldLeague.parameters();
+
+        for day in 9..=15 {
+            let date = NaiveDate::from_ymd_opt(2024, 3, day).unwrap();
+            let week = week_of(date, beirut, params, Duration::zero()).unwrap();
+
+            assert_eq!(week.days[0].date().weekday(), Weekday::Sat);
+            assert_eq!(week.days[0].date().day(), 9);
+            assert_eq!(week.days[6].date().weekday(), Weekday::Fri);
+        }
+    }
+
+    #[test]
+    fn jumua_start_follows_khutbah_offset() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 13).unwrap();
+        let beirut = Coordinates::new(33.8938, 35.5018);
+        let params = Method::MuslimWorldLeague.parameters();
+        let week = week_of(date, beirut, params, Duration::minutes(20)).unwrap();
+        let friday = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+        let dhuhr = PrayerTimes::computed(friday, beirut, params).time(Prayer::Dhuhr);
+
+        assert_eq!(week.jumua.date, friday);
+        assert_eq!(week.jumua.dhuhr, dhuhr);
+        assert_eq!(week.jumua.suggested_start, dhuhr + Duration::minutes(20));
+    }
+}
@@ -0,0 +1,182 @@
+//! # Widget Model
+//!
+//! A flat summary of the upcoming prayers for watch faces and home
+//! screen widgets, which render from a snapshot and can't afford to
+//! compute times themselves. Times are Unix timestamps in seconds.
+//!
+//! ##### Example
+//!
+//! ```
+//! use miqat::prelude::*;
+//! use miqat::widget::WidgetModel;
+//!
+//! let raleigh = Coordinates::new(35.7750, -78.6336);
+//! let params = Method::NorthAmerica.parameters();
+//! let now = Utc.with_ymd_and_hms(2015, 7, 12, 18, 0, 0).unwrap();
+//! let widget = WidgetModel::build(raleigh, params, now, 3);
+//!
+//! assert_eq!(widget.upcoming.len(), 3);
+//! assert_eq!(widget.upcoming[0].name, "Asr");
+//! assert_eq!(widget.countdown_secs, 11_340);
+//! ```
+
+use crate::astronomy::unit::Coordinates;
+use crate::clock::FixedClock;
+use crate::error::CalculationError;
+use crate::models::parameters::Parameters;
+use crate::models::prayer::Prayer;
+use crate::prayer_times::PrayerTimes;
+use chrono::DateTime;
+use chrono::NaiveDate;
+use chrono::Utc;
+
+#[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct WidgetEntry {
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub prayer: Prayer,
+    /// The display name, Jumua in place of Dhuhr on Fridays.
+    pub name: &'static str,
+    pub timestamp: i64,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct WidgetModel {
+    /// The date of the schedule in progress, from its Fajr to the next.
+    pub date: NaiveDate,
+    /// The prayers after now, today's first and then the following days'.
+    pub upcoming: Vec<WidgetEntry>,
+    pub fajr_tomorrow: i64,
+    /// Seconds left until the first upcoming prayer.
+    pub countdown_secs: i64,
+}
+
+impl WidgetModel {
+    /// The widget's state at `now`, listing the next `count` prayers.
+    ///
+    /// Panics if the schedule of `now` can't be computed, see
+    /// [`try_build`](WidgetModel::try_build).
+    pub fn build(
+        coordinates: Coordinates,
+        parameters: Parameters,
+        now: DateTime<Utc>,
+        count: usize,
+    ) -> Self {
+        WidgetModel::try_build(coordinates, parameters, now, count)
+            .unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    /// Like [`build`](WidgetModel::build), but returns an error when the
+    /// schedule of `now` can't be computed, see
+    /// [`PrayerTimes::try_computed`].
+    pub fn try_build(
+        coordinates: Coordinates,
+        parameters: Parameters,
+        now: DateTime<Utc>,
+        count: usize,
+    ) -> Result<Self, CalculationError> {
+        // Start a day early, as the previous day's Ishaa can still be
+        // ahead of an instant early in the day, and move on to the
+        // schedule whose Fajr came last.
+        let date = parameters.day_boundary.date_of(now);
+        let yesterday = date
+            .pred_opt()
+            .and_then(|date| PrayerTimes::try_computed(date, coordinates, parameters).ok());
+        let mut today = match yesterday {
+            Some(yesterday) => yesterday,
+            None => PrayerTimes::try_computed(date, coordinates, parameters)?,
+        };
+        while let Ok(tomorrow) = today.tomorrow()
+            && tomorrow.time(Prayer::Fajr) <= now
+        {
+            today = tomorrow;
+        }
+        let upcoming: Vec<WidgetEntry> =
+            std::iter::successors(Some(today.clone()), |times| times.tomorrow().ok())
+                .flat_map(|times| {
+                    Prayer::ALL.map(|prayer| {
+                        let time = times.time(prayer);
+                        WidgetEntry {
+                            prayer,
+                            name: prayer.name_with(&FixedClock(time)),
+                            timestamp: time.timestamp(),
+                        }
+                    })
+                })
+                .filter(|entry| entry.timestamp > now.timestamp())
+                .take(count)
+                .collect();
+        let countdown_secs = upcoming
+            .first()
+            .map_or(0, |entry| entry.timestamp - now.timestamp());
+
+        Ok(WidgetModel {
+            date: today.date(),
+            upcoming,
+            fajr_tomorrow: today.time(Prayer::FajrTomorrow).timestamp(),
+            countdown_secs,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Method;
+    use chrono::TimeZone;
+
+    #[test]
+    fn upcoming_prayers_continue_into_tomorrow() {
+        let raleigh = Coordinates::new(35.7750, -78.6336);
+        let params = Method::NorthAmerica.parameters();
+        let now = Utc.with_ymd_and_hms(2015, 7, 12, 23, 0, 0).unwrap();
+        let widget = WidgetModel::build(raleigh, params, now, 4);
+        let names: Vec<&str> = widget.upcoming.iter().map(|entry| entry.name).collect();
+
+        assert_eq!(names, ["Maghrib", "Ishaa", "Fajr", "Sunrise"]);
+        assert_eq!(widget.upcoming[2].timestamp, widget.fajr_tomorrow);
+    }
+
+    #[test]
+    fn the_evening_continues_past_midnight_utc() {
+        let raleigh = Coordinates::new(35.7750, -78.6336);
+        let params = Method::NorthAmerica.parameters();
+        let before = Utc.with_ymd_and_hms(2015, 7, 12, 23, 50, 0).unwrap();
+        let after = Utc.with_ymd_and_hms(2015, 7, 13, 0, 10, 0).unwrap();
+
+        for now in [before, after] {
+            let widget = WidgetModel::build(raleigh, params, now, 3);
+            let names: Vec<&str> = widget.upcoming.iter().map(|entry| entry.name).collect();
+
+            assert_eq!(widget.date, NaiveDate::from_ymd_opt(2015, 7, 12).unwrap());
+            assert_eq!(names, ["Maghrib", "Ishaa", "Fajr"]);
+            assert_eq!(widget.upcoming[2].timestamp, widget.fajr_tomorrow);
+        }
+    }
+
+    #[test]
+    fn polar_days_are_errors() {
+        let tromso = Coordinates::new(69.6496, 18.9560);
+        let params = Method::MuslimWorldLeague.parameters();
+        let now = Utc.with_ymd_and_hms(2015, 6, 21, 12, 0, 0).unwrap();
+
+        assert!(matches!(
+            WidgetModel::try_build(tromso, params, now, 3),
+            Err(CalculationError::NoSunriseOrSunset(_))
+        ));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serializes_flat() {
+        let raleigh = Coordinates::new(35.7750, -78.6336);
+        let params = Method::NorthAmerica.parameters();
+        let now = Utc.with_ymd_and_hms(2015, 7, 12, 18, 0, 0).unwrap();
+        let json = serde_json::to_value(WidgetModel::build(raleigh, params, now, 1)).unwrap();
+
+        assert_eq!(json["date"], "2015-07-12");
+        assert_eq!(json["upcoming"][0]["name"], "Asr");
+        assert_eq!(json["countdown_secs"], 11_340);
+    }
+}
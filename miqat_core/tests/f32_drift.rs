@@ -0,0 +1,61 @@
+//! Prayer times with the `f32-trigonometry` feature against those of the
+//! default, double precision build, every day of a year at several
+//! latitudes.
+//!
+//! `data/double_precision.txt` holds the times of the default build, a
+//! line per location and day, in minutes since midnight UTC. The default
+//! build must reproduce them exactly, and the single precision build to
+//! within a minute, the most a rounded time can move by.
+
+use miqat::prelude::*;
+
+const CITIES: [(&str, f64, f64); 4] = [
+    ("Quito", -0.1807, -78.4678),
+    ("Makkah", 21.4225, 39.8262),
+    ("London", 51.5074, -0.1278),
+    ("Sydney", -33.8688, 151.2093),
+];
+
+const REFERENCE: &str = include_str!("data/double_precision.txt");
+
+fn minutes(times: &PrayerTimes, prayer: Prayer) -> i64 {
+    let time = times.time(prayer);
+
+    time.signed_duration_since(times.date().and_hms_opt(0, 0, 0).unwrap().and_utc())
+        .num_minutes()
+}
+
+#[test]
+fn single_precision_stays_within_a_minute() {
+    let start = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+    let params = Method::MuslimWorldLeague.parameters();
+    let mut lines = REFERENCE.lines();
+    let tolerance = if cfg!(feature = "f32-trigonometry") {
+        1
+    } else {
+        0
+    };
+
+    for (city, latitude, longitude) in CITIES {
+        let coordinates = Coordinates::new(latitude, longitude);
+
+        for date in start.iter_days().take_while(|date| date.year() == 2024) {
+            let times = PrayerTimes::computed(date, coordinates, params);
+            let line = lines.next().expect("a reference line per day");
+            let mut fields = line.split(' ');
+
+            assert_eq!(fields.next(), Some(city));
+            assert_eq!(fields.next(), Some(date.to_string().as_str()));
+            for (prayer, expected) in Prayer::ALL.into_iter().zip(fields) {
+                let expected: i64 = expected.parse().unwrap();
+                let actual = minutes(&times, prayer);
+
+                assert!(
+                    (actual - expected).abs() <= tolerance,
+                    "{prayer:?} in {city} on {date}: {actual} instead of {expected}"
+                );
+            }
+        }
+    }
+    assert_eq!(lines.next(), None);
+}
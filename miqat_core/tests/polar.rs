@@ -0,0 +1,174 @@
+//! Prayer times north of the Arctic Circle and just below it, across the
+//! year, for every high latitude rule.
+//!
+//! The behavior these tests pin down:
+//!
+//! | Situation                               | Result                                                    |
+//! |-----------------------------------------|-----------------------------------------------------------|
+//! | Midnight sun or polar night             | `Err(NoSunriseOrSunset)`                                  |
+//! | The sun doesn't reach the Fajr or Ishaa | The rule's portion of the night, annotated with the rule  |
+//! | angle                                   | (or with the seasonal estimate, for methods that use it)  |
+//! | Ishaa would run into the next Fajr      | The rule's portion of the night, or else halfway through  |
+//! |                                         | the night, annotated with `MiddleOfTheNight`              |
+//! | The sun doesn't get high enough for Asr | Asr is out of order, reported by `ordering_violations`    |
+//!
+//! Fajr, sunrise, Maghrib, Ishaa and the next Fajr are always in order.
+
+use miqat::prelude::*;
+
+const CITIES: [(&str, f64, f64); 4] = [
+    ("Longyearbyen", 78.2232, 15.6267),
+    ("Tromsø", 69.6492, 18.9553),
+    ("Reykjavik", 64.1466, -21.9426),
+    ("Murmansk", 68.9585, 33.0827),
+];
+
+const RULES: [HighLatitudeRule; 3] = [
+    HighLatitudeRule::MiddleOfTheNight,
+    HighLatitudeRule::SeventhOfTheNight,
+    HighLatitudeRule::TwilightAngle,
+];
+
+fn date(month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2024, month, day).unwrap()
+}
+
+fn parameters(method: Method, rule: HighLatitudeRule) -> Parameters {
+    Parameters {
+        high_latitude_rule: rule,
+        ..method.parameters()
+    }
+}
+
+#[test]
+fn every_day_is_computed_or_refused() {
+    let year: Vec<NaiveDate> = date(1, 1).iter_days().take(366).collect();
+
+    for (city, latitude, longitude) in CITIES {
+        let coordinates = Coordinates::new(latitude, longitude);
+
+        for rule in RULES {
+            for &method in Method::ALL {
+                for date in &year {
+                    let context = format!("{city}, {method:?}, {rule:?}, {date}");
+
+                    match PrayerTimes::try_computed(*date, coordinates, parameters(method, rule)) {
+                        Ok(times) => {
+                            let order = [
+                                Prayer::Fajr,
+                                Prayer::Sunrise,
+                                Prayer::Maghrib,
+                                Prayer::Ishaa,
+                                Prayer::FajrTomorrow,
+                            ];
+
+                            for pair in order.windows(2) {
+                                assert!(
+                                    times.time(pair[0]) < times.time(pair[1]),
+                                    "{context}: {:?} is not before {:?}",
+                                    pair[0],
+                                    pair[1]
+                                );
+                            }
+                            for violation in times.ordering_violations() {
+                                assert!(
+                                    matches!(
+                                        violation,
+                                        (Prayer::Dhuhr, Prayer::Asr)
+                                            | (Prayer::Asr, Prayer::Maghrib)
+                                    ),
+                                    "{context}: {violation:?}"
+                                );
+                            }
+                        }
+                        Err(error) => assert_eq!(
+                            error,
+                            CalculationError::NoSunriseOrSunset(*date),
+                            "{context}"
+                        ),
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn solstices() {
+    let params = Method::MuslimWorldLeague.parameters();
+
+    for (city, latitude, longitude) in CITIES {
+        let coordinates = Coordinates::new(latitude, longitude);
+
+        for solstice in [date(6, 21), date(12, 21)] {
+            let result = PrayerTimes::try_computed(solstice, coordinates, params);
+
+            if city == "Reykjavik" {
+                assert!(result.is_ok(), "{city}, {solstice}");
+            } else {
+                assert_eq!(
+                    result,
+                    Err(CalculationError::NoSunriseOrSunset(solstice)),
+                    "{city}, {solstice}"
+                );
+            }
+        }
+    }
+
+    // Below the Arctic Circle the sun still sets in June, but never gets
+    // far enough below the horizon; in December the night is long enough.
+    let reykjavik = Coordinates::new(CITIES[2].1, CITIES[2].2);
+    let june = PrayerTimes::computed(date(6, 21), reykjavik, params);
+    let december = PrayerTimes::computed(date(12, 21), reykjavik, params);
+
+    assert!(june.source(Prayer::Fajr).is_estimated());
+    assert!(june.source(Prayer::Ishaa).is_estimated());
+    assert!(!december.source(Prayer::Fajr).is_estimated());
+    assert!(!december.source(Prayer::Ishaa).is_estimated());
+}
+
+#[test]
+fn estimates_are_annotated_with_the_rule() {
+    let longyearbyen = Coordinates::new(CITIES[0].1, CITIES[0].2);
+
+    for equinox in [date(3, 20), date(9, 22)] {
+        for rule in RULES {
+            let params = parameters(Method::MuslimWorldLeague, rule);
+            let times = PrayerTimes::computed(equinox, longyearbyen, params);
+
+            assert_eq!(
+                times.source(Prayer::Fajr),
+                TimeSource::HighLatitudeRule(rule)
+            );
+            assert_eq!(
+                times.source(Prayer::Ishaa),
+                TimeSource::HighLatitudeRule(rule)
+            );
+
+            // The Moonsighting Committee always uses a seventh of the
+            // night this far north, and estimates Ishaa seasonally.
+            let params = parameters(Method::MoonsightingCommittee, rule);
+            let times = PrayerTimes::computed(equinox, longyearbyen, params);
+
+            assert_eq!(
+                times.source(Prayer::Fajr),
+                TimeSource::HighLatitudeRule(HighLatitudeRule::SeventhOfTheNight)
+            );
+            assert_eq!(times.source(Prayer::Ishaa), TimeSource::SeasonalEstimate);
+        }
+    }
+}
+
+#[test]
+fn interval_ishaa_stays_after_maghrib() {
+    let tromso = Coordinates::new(CITIES[1].1, CITIES[1].2);
+    let params = parameters(Method::UmmAlQura, HighLatitudeRule::TwilightAngle);
+    let times = PrayerTimes::computed(date(5, 14), tromso, params);
+
+    assert!(times.time(Prayer::Maghrib) < times.time(Prayer::Ishaa));
+    assert!(times.time(Prayer::Ishaa) < times.time(Prayer::FajrTomorrow));
+    assert_eq!(
+        times.source(Prayer::Ishaa),
+        TimeSource::HighLatitudeRule(HighLatitudeRule::MiddleOfTheNight)
+    );
+}
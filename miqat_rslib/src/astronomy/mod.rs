@@ -1 +1,39 @@
 pub mod unit;
+
+use crate::error;
+use crate::error::MiqatError;
+use miqat_core::Coordinates;
+use miqat_core::SolarPosition;
+use miqat_core::prelude::Qiblah;
+
+/// The direction of the Kaaba from the location, in degrees clockwise
+/// from north.
+#[uniffi::export]
+pub fn qiblah(latitude: f64, longitude: f64) -> f64 {
+    Qiblah::new(Coordinates::new(latitude, longitude)).value()
+}
+
+/// Where the sun is in the sky, for compass screens.
+#[derive(uniffi::Record)]
+pub struct SunPosition {
+    /// Degrees clockwise from north.
+    pub azimuth: f64,
+    /// Degrees above the horizon, negative below it.
+    pub altitude: f64,
+}
+
+/// The position of the sun at `timestamp_secs` as seen from the location.
+#[uniffi::export]
+pub fn sun_position(
+    timestamp_secs: i64,
+    latitude: f64,
+    longitude: f64,
+) -> Result<SunPosition, MiqatError> {
+    let instant = error::datetime(timestamp_secs)?;
+    let position = SolarPosition::new(instant, Coordinates::new(latitude, longitude));
+
+    Ok(SunPosition {
+        azimuth: position.azimuth,
+        altitude: position.altitude,
+    })
+}
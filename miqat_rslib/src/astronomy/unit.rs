@@ -1,4 +1,4 @@
-pub type Coordinates = miqat::Coordinates;
+pub type Coordinates = miqat_core::Coordinates;
 
 #[uniffi::remote(Record)]
 pub struct Coordinates {
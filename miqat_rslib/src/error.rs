@@ -0,0 +1,104 @@
+use chrono::DateTime;
+use chrono::NaiveDate;
+use chrono::NaiveTime;
+use chrono::Utc;
+use std::fmt;
+
+/// Why a call could not be completed. Returned across the FFI boundary
+/// instead of crashing the host app on bad input.
+#[derive(Debug, uniffi::Error)]
+pub enum MiqatError {
+    /// The timestamp can't be represented as a date.
+    InvalidTimestamp { timestamp_secs: i64 },
+    /// Prayer times can't be calculated for the date.
+    DateOutOfRange { timestamp_secs: i64 },
+    /// Prayer times can't be calculated for the year.
+    YearOutOfRange { year: i32 },
+    /// There's no such month in the Gregorian calendar.
+    InvalidMonth { year: i32, month: u32 },
+    /// The sun does not rise or set around the date.
+    NoSunriseOrSunset { timestamp_secs: i64 },
+    /// The coordinates are outside of the globe.
+    InvalidCoordinates { latitude: f64, longitude: f64 },
+    /// A calculation parameter has an invalid value.
+    InvalidParameters { reason: String },
+}
+
+impl fmt::Display for MiqatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MiqatError::InvalidTimestamp { timestamp_secs } => {
+                write!(f, "{timestamp_secs} is not a valid timestamp")
+            }
+            MiqatError::DateOutOfRange { timestamp_secs } => {
+                write!(
+                    f,
+                    "prayer times can't be calculated for timestamp {timestamp_secs}"
+                )
+            }
+            MiqatError::YearOutOfRange { year } => {
+                write!(f, "prayer times can't be calculated for {year}")
+            }
+            MiqatError::InvalidMonth { year, month } => {
+                write!(f, "{year}-{month:02} is not a valid month")
+            }
+            MiqatError::NoSunriseOrSunset { timestamp_secs } => {
+                write!(
+                    f,
+                    "the sun does not rise or set around timestamp {timestamp_secs}"
+                )
+            }
+            MiqatError::InvalidCoordinates {
+                latitude,
+                longitude,
+            } => {
+                write!(f, "{latitude}, {longitude} are not valid coordinates")
+            }
+            MiqatError::InvalidParameters { reason } => write!(f, "invalid parameters: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for MiqatError {}
+
+impl From<miqat_core::CalculationError> for MiqatError {
+    fn from(error: miqat_core::CalculationError) -> Self {
+        match error {
+            miqat_core::CalculationError::DateOutOfRange(date) => MiqatError::DateOutOfRange {
+                timestamp_secs: midnight(date).timestamp(),
+            },
+            miqat_core::CalculationError::YearOutOfRange(year) => {
+                MiqatError::YearOutOfRange { year }
+            }
+            miqat_core::CalculationError::NoSunriseOrSunset(date) => {
+                MiqatError::NoSunriseOrSunset {
+                    timestamp_secs: midnight(date).timestamp(),
+                }
+            }
+            miqat_core::CalculationError::InvalidCoordinates(coordinates) => {
+                MiqatError::InvalidCoordinates {
+                    latitude: coordinates.latitude,
+                    longitude: coordinates.longitude,
+                }
+            }
+            miqat_core::CalculationError::InvalidParameters(issue) => {
+                MiqatError::InvalidParameters {
+                    reason: issue.to_string(),
+                }
+            }
+        }
+    }
+}
+
+pub(crate) fn datetime(timestamp_secs: i64) -> Result<DateTime<Utc>, MiqatError> {
+    DateTime::from_timestamp_secs(timestamp_secs)
+        .ok_or(MiqatError::InvalidTimestamp { timestamp_secs })
+}
+
+pub(crate) fn date(timestamp_secs: i64) -> Result<NaiveDate, MiqatError> {
+    datetime(timestamp_secs).map(|datetime| datetime.date_naive())
+}
+
+fn midnight(date: NaiveDate) -> DateTime<Utc> {
+    date.and_time(NaiveTime::MIN).and_utc()
+}
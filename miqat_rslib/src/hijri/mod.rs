@@ -1,7 +1,8 @@
-use chrono::DateTime;
-use miqat::HijriDate as CoreHijriDate;
+use crate::error;
+use crate::error::MiqatError;
+use miqat_core::HijriDate as CoreHijriDate;
 
-pub type IslamicEvent = miqat::IslamicEvent;
+pub type IslamicEvent = miqat_core::IslamicEvent;
 
 #[uniffi::remote(Enum)]
 pub enum IslamicEvent {
@@ -23,7 +24,7 @@ pub enum IslamicEvent {
     EidAlAdha,
 }
 
-pub type HijriDate = miqat::HijriDate;
+pub type HijriDate = miqat_core::HijriDate;
 
 #[uniffi::remote(Record)]
 pub struct HijriDate {
@@ -44,7 +45,7 @@ pub struct IslamicEventOccurrence {
 /// sorted chronologically by Gregorian date.
 #[uniffi::export]
 pub fn events_for_gregorian_year(gregorian_year: i32) -> Vec<IslamicEventOccurrence> {
-    miqat::hijri::events::events_for_gregorian_year(gregorian_year)
+    miqat_core::hijri::events::events_for_gregorian_year(gregorian_year)
         .into_iter()
         .map(|o| IslamicEventOccurrence {
             event: o.event,
@@ -62,13 +63,11 @@ pub struct HijriDateInfo {
 #[uniffi::export]
 impl HijriDateInfo {
     #[uniffi::constructor]
-    pub fn from_timestamp(timestamp_secs: i64) -> Self {
-        let date = DateTime::from_timestamp_secs(timestamp_secs)
-            .unwrap()
-            .date_naive();
-        Self {
+    pub fn from_timestamp(timestamp_secs: i64) -> Result<Self, MiqatError> {
+        let date = error::date(timestamp_secs)?;
+        Ok(Self {
             date: CoreHijriDate::from_gregorian(date),
-        }
+        })
     }
 
     pub fn date(&self) -> HijriDate {
@@ -1,6 +1,8 @@
 pub mod astronomy;
+pub mod error;
 pub mod hijri;
 pub mod models;
 pub mod prayer_times;
+pub mod widget;
 
 uniffi::setup_scaffolding!();
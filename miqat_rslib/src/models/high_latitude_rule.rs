@@ -0,0 +1,8 @@
+pub type HighLatitudeRule = miqat_core::HighLatitudeRule;
+
+#[uniffi::remote(Enum)]
+pub enum HighLatitudeRule {
+    MiddleOfTheNight,
+    SeventhOfTheNight,
+    TwilightAngle,
+}
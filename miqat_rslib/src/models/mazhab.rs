@@ -1,4 +1,4 @@
-pub type Mazhab = miqat::Mazhab;
+pub type Mazhab = miqat_core::Mazhab;
 
 #[uniffi::remote(Enum)]
 pub enum Mazhab {
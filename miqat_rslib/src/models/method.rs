@@ -1,4 +1,4 @@
-pub type Method = miqat::Method;
+pub type Method = miqat_core::Method;
 
 #[uniffi::remote(Enum)]
 pub enum Method {
@@ -8,4 +8,25 @@ pub enum Method {
     MoonsightingCommittee,
     NorthAmerica,
     Singapore,
+    Tehran,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::UniFfiTag;
+    use uniffi::Lift;
+    use uniffi::Lower;
+
+    #[test]
+    fn every_method_crosses_the_ffi() {
+        for &method in Method::ALL {
+            let lowered = <Method as Lower<UniFfiTag>>::lower(method);
+
+            assert_eq!(
+                <Method as Lift<UniFfiTag>>::try_lift(lowered).unwrap(),
+                method
+            );
+        }
+    }
 }
@@ -1,4 +1,6 @@
+pub mod high_latitude_rule;
 pub mod mazhab;
 pub mod method;
 pub mod prayer;
 pub mod provider;
+pub mod rounding;
@@ -1,4 +1,4 @@
-pub type Prayer = miqat::Prayer;
+pub type Prayer = miqat_core::Prayer;
 
 #[uniffi::remote(Enum)]
 pub enum Prayer {
@@ -1,5 +1,5 @@
-pub type ProviderCity = miqat::ProviderCity;
-pub type Provider = miqat::Provider;
+pub type ProviderCity = miqat_core::ProviderCity;
+pub type Provider = miqat_core::Provider;
 
 #[uniffi::remote(Enum)]
 pub enum ProviderCity {
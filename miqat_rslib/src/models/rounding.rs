@@ -0,0 +1,10 @@
+pub type Rounding = miqat_core::Rounding;
+
+#[uniffi::remote(Enum)]
+#[non_exhaustive]
+pub enum Rounding {
+    Nearest,
+    Ceil,
+    Floor,
+    None,
+}
@@ -1,9 +1,61 @@
+use crate::error;
+use crate::error::MiqatError;
 use crate::hijri::HijriDate;
-use chrono::DateTime;
-use miqat::Coordinates;
-use miqat::Method;
-use miqat::Prayer;
-use miqat::Provider;
+use chrono::Datelike;
+use chrono::NaiveDate;
+use chrono::NaiveTime;
+use miqat_core::Coordinates;
+use miqat_core::HighLatitudeRule;
+use miqat_core::Mazhab;
+use miqat_core::Method;
+use miqat_core::Prayer;
+use miqat_core::Provider;
+use miqat_core::Rounding;
+
+/// The prayer times of one day, as timestamps in seconds.
+#[derive(uniffi::Record)]
+pub struct DailyPrayerTimes {
+    pub date_utc_timestamp_secs: i64,
+    pub fajr: i64,
+    pub sunrise: i64,
+    pub dhuhr: i64,
+    pub asr: i64,
+    pub maghrib: i64,
+    pub ishaa: i64,
+}
+
+/// The prayer times of every day of a Gregorian month, in one call.
+#[uniffi::export]
+pub fn prayer_times_for_month(
+    year: i32,
+    month: u32,
+    coordinates: Coordinates,
+    method: Method,
+    mazhab: Mazhab,
+) -> Result<Vec<DailyPrayerTimes>, MiqatError> {
+    let invalid_month = MiqatError::InvalidMonth { year, month };
+    let start = NaiveDate::from_ymd_opt(year, month, 1).ok_or(invalid_month)?;
+    let parameters = method.parameters().mazhab(mazhab);
+
+    start
+        .iter_days()
+        .take_while(|date| date.month() == month)
+        .map(|date| {
+            let times = miqat_core::PrayerTimes::try_computed(date, coordinates, parameters)?;
+            let midnight = date.and_time(NaiveTime::MIN).and_utc();
+
+            Ok(DailyPrayerTimes {
+                date_utc_timestamp_secs: midnight.timestamp(),
+                fajr: times.time(Prayer::Fajr).timestamp(),
+                sunrise: times.time(Prayer::Sunrise).timestamp(),
+                dhuhr: times.time(Prayer::Dhuhr).timestamp(),
+                asr: times.time(Prayer::Asr).timestamp(),
+                maghrib: times.time(Prayer::Maghrib).timestamp(),
+                ishaa: times.time(Prayer::Ishaa).timestamp(),
+            })
+        })
+        .collect()
+}
 
 #[derive(uniffi::Object)]
 pub struct PrayerTimes {
@@ -15,7 +67,7 @@ pub struct PrayerTimes {
     ishaa: i64,
     fajr_tomorrow: i64,
     hijri_date: HijriDate,
-    inner: miqat::PrayerTimes,
+    inner: miqat_core::PrayerTimes,
 }
 
 #[uniffi::export]
@@ -25,21 +77,39 @@ impl PrayerTimes {
         date_utc_timestamp_secs: i64,
         coordinates: Coordinates,
         method: Method,
-    ) -> Self {
-        let date = DateTime::from_timestamp_secs(date_utc_timestamp_secs)
-            .unwrap()
-            .date_naive();
-        let inner = miqat::PrayerTimes::computed(date, coordinates, method.parameters());
-        Self::from_inner(inner, date)
+    ) -> Result<Self, MiqatError> {
+        let date = error::date(date_utc_timestamp_secs)?;
+        let inner = miqat_core::PrayerTimes::try_computed(date, coordinates, method.parameters())?;
+        Ok(Self::from_inner(inner, date))
     }
 
+    /// Like `from_method`, overriding the method's mazhab, high latitude
+    /// rule and rounding.
     #[uniffi::constructor]
-    pub fn from_precomputed(date_utc_timestamp_secs: i64, provider: Provider) -> Self {
-        let date = DateTime::from_timestamp_secs(date_utc_timestamp_secs)
-            .unwrap()
-            .date_naive();
-        let inner = miqat::PrayerTimes::precomputed(date, provider);
-        Self::from_inner(inner, date)
+    pub fn from_options(
+        date_utc_timestamp_secs: i64,
+        coordinates: Coordinates,
+        method: Method,
+        mazhab: Mazhab,
+        high_latitude_rule: HighLatitudeRule,
+        rounding: Rounding,
+    ) -> Result<Self, MiqatError> {
+        let date = error::date(date_utc_timestamp_secs)?;
+        let mut parameters = method.parameters().mazhab(mazhab);
+        parameters.high_latitude_rule = high_latitude_rule;
+        parameters.rounding = rounding;
+        let inner = miqat_core::PrayerTimes::try_computed(date, coordinates, parameters)?;
+        Ok(Self::from_inner(inner, date))
+    }
+
+    #[uniffi::constructor]
+    pub fn from_precomputed(
+        date_utc_timestamp_secs: i64,
+        provider: Provider,
+    ) -> Result<Self, MiqatError> {
+        let date = error::date(date_utc_timestamp_secs)?;
+        let inner = miqat_core::PrayerTimes::precomputed(date, provider)?;
+        Ok(Self::from_inner(inner, date))
     }
 
     pub fn fajr(&self) -> i64 {
@@ -70,7 +140,8 @@ impl PrayerTimes {
         self.fajr_tomorrow
     }
 
-    pub fn current_prayer(&self) -> Prayer {
+    /// The current prayer, or `None` before this day's Fajr.
+    pub fn current_prayer(&self) -> Option<Prayer> {
         self.inner.current()
     }
 
@@ -84,7 +155,7 @@ impl PrayerTimes {
 }
 
 impl PrayerTimes {
-    fn from_inner(inner: miqat::PrayerTimes, date: chrono::NaiveDate) -> Self {
+    fn from_inner(inner: miqat_core::PrayerTimes, date: chrono::NaiveDate) -> Self {
         PrayerTimes {
             fajr: inner.time(Prayer::Fajr).timestamp(),
             sunrise: inner.time(Prayer::Sunrise).timestamp(),
@@ -93,7 +164,7 @@ impl PrayerTimes {
             maghrib: inner.time(Prayer::Maghrib).timestamp(),
             ishaa: inner.time(Prayer::Ishaa).timestamp(),
             fajr_tomorrow: inner.time(Prayer::FajrTomorrow).timestamp(),
-            hijri_date: miqat::HijriDate::from_gregorian(date),
+            hijri_date: miqat_core::HijriDate::from_gregorian(date),
             inner,
         }
     }
@@ -0,0 +1,51 @@
+use crate::error;
+use crate::error::MiqatError;
+use crate::models::prayer::Prayer;
+use miqat_core::Coordinates;
+use miqat_core::Method;
+
+#[derive(uniffi::Record)]
+pub struct WidgetEntry {
+    pub prayer: Prayer,
+    pub name: String,
+    pub timestamp: i64,
+}
+
+#[derive(uniffi::Record)]
+pub struct WidgetModel {
+    pub upcoming: Vec<WidgetEntry>,
+    pub fajr_tomorrow: i64,
+    pub countdown_secs: i64,
+}
+
+/// The state of a prayer widget at `now_utc_timestamp_secs`, listing the
+/// next `count` prayers.
+#[uniffi::export]
+pub fn widget_model(
+    now_utc_timestamp_secs: i64,
+    coordinates: Coordinates,
+    method: Method,
+    count: u32,
+) -> Result<WidgetModel, MiqatError> {
+    let now = error::datetime(now_utc_timestamp_secs)?;
+    let model = miqat_core::widget::WidgetModel::try_build(
+        coordinates,
+        method.parameters(),
+        now,
+        count as usize,
+    )?;
+
+    Ok(WidgetModel {
+        upcoming: model
+            .upcoming
+            .into_iter()
+            .map(|entry| WidgetEntry {
+                prayer: entry.prayer,
+                name: entry.name.to_string(),
+                timestamp: entry.timestamp,
+            })
+            .collect(),
+        fajr_tomorrow: model.fajr_tomorrow,
+        countdown_secs: model.countdown_secs,
+    })
+}
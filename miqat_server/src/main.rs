@@ -0,0 +1,68 @@
+//! A small HTTP service answering the `/timings` and `/qibla` endpoints
+//! with responses shaped like the AlAdhan API's, so that its clients can
+//! be pointed at a self-hosted instance.
+//!
+//! ```text
+//! miqat_server [address]
+//! ```
+//!
+//! The address defaults to `127.0.0.1:8080`. Places can be given by name
+//! with a `city` parameter instead of coordinates, looked up among the
+//! built-in [capitals](miqat::geocoder::Capitals). Timings carry an
+//! `ETag`, and are answered with `304 Not Modified` when it matches
+//! `If-None-Match`, one of its listed tags, weak or not, or `*`.
+
+mod routes;
+
+use miqat::geocoder::Capitals;
+use routes::Request;
+use std::env;
+use std::process::ExitCode;
+use tiny_http::Header;
+use tiny_http::Response;
+use tiny_http::Server;
+
+const DEFAULT_ADDRESS: &str = "127.0.0.1:8080";
+
+fn main() -> ExitCode {
+    let address = env::args().nth(1).unwrap_or_else(|| DEFAULT_ADDRESS.into());
+    let server = match Server::http(&address) {
+        Ok(server) => server,
+        Err(error) => {
+            eprintln!("failed to listen on {address}: {error}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let content_type =
+        Header::from_bytes("Content-Type", "application/json").expect("valid header");
+
+    for request in server.incoming_requests() {
+        let routed = Request::new(request.url(), &Capitals);
+        let etag = routed.etag();
+        let cached = request.headers().iter().any(|header| {
+            header.field.equiv("If-None-Match") && routed.not_modified(header.value.as_str())
+        });
+        let mut response = if cached {
+            Response::from_string(String::new()).with_status_code(304)
+        } else {
+            let (status, body) = routes::respond(&routed);
+
+            Response::from_string(body.to_string())
+                .with_status_code(status)
+                .with_header(content_type.clone())
+        };
+
+        if let Some(etag) = etag {
+            response.add_header(Header::from_bytes("ETag", etag).expect("valid header"));
+        } else {
+            // Nothing to do.
+        }
+
+        if let Err(error) = request.respond(response) {
+            eprintln!("failed to respond: {error}");
+        }
+    }
+
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,319 @@
+use chrono::FixedOffset;
+use miqat::aladhan;
+use miqat::aladhan::TimingsData;
+use miqat::geocoder::GeocodeError;
+use miqat::geocoder::Geocoder;
+use miqat::prelude::*;
+use serde_json::Value;
+use serde_json::json;
+use std::collections::HashMap;
+
+/// A request for `url`, which holds the path and query string, resolved
+/// to what it asks for. The schedule of a timings request is computed
+/// here once, for both its entity tag and its body.
+pub struct Request {
+    route: Result<Route, (u16, String)>,
+}
+
+enum Route {
+    Timings(Box<PrayerTimes>, FixedOffset),
+    Qibla(Coordinates),
+}
+
+impl Request {
+    /// Resolves `url`. Places given by name, with a `city` parameter, are
+    /// looked up with `geocoder`.
+    pub fn new(url: &str, geocoder: &dyn Geocoder) -> Self {
+        let (path, query) = url.split_once('?').unwrap_or((url, ""));
+        let query = parse_query(query);
+
+        let route = match path.trim_end_matches('/') {
+            "/timings" => timings_request(&query, geocoder)
+                .map(|(times, offset)| Route::Timings(Box::new(times), offset)),
+            "/qibla" => coordinates(&query, geocoder).map(Route::Qibla),
+            _ => Err((404, "Not found.".to_string())),
+        };
+
+        Request { route }
+    }
+
+    /// The entity tag of the response, for conditional requests. Only
+    /// timings have one: they change with the schedule's
+    /// [content hash](PrayerTimes::content_hash) and the requested offset.
+    pub fn etag(&self) -> Option<String> {
+        match &self.route {
+            Ok(Route::Timings(times, offset)) => {
+                Some(format!("\"{:016x}{offset}\"", times.content_hash()))
+            }
+            Ok(Route::Qibla(_)) | Err(_) => None,
+        }
+    }
+
+    /// Whether an `If-None-Match` header of `value` matches the entity
+    /// tag, so that the response can be `304 Not Modified`. The value is
+    /// `*` or a list of tags, compared weakly, i.e. ignoring `W/`.
+    pub fn not_modified(&self, value: &str) -> bool {
+        let Some(etag) = self.etag() else {
+            return false;
+        };
+
+        value.trim() == "*"
+            || value
+                .split(',')
+                .map(|tag| tag.trim())
+                .any(|tag| tag.strip_prefix("W/").unwrap_or(tag) == etag)
+    }
+}
+
+/// Answers `request` with a status code and a JSON body.
+pub fn respond(request: &Request) -> (u16, Value) {
+    let result = match &request.route {
+        Ok(Route::Timings(times, offset)) => Ok(timings(times, *offset)),
+        Ok(Route::Qibla(coordinates)) => Ok(qibla(*coordinates)),
+        Err(error) => Err(error.clone()),
+    };
+
+    match result {
+        Ok(data) => (200, json!({ "code": 200, "status": "OK", "data": data })),
+        Err((code, message)) => (
+            code,
+            json!({ "code": code, "status": status_text(code), "data": message }),
+        ),
+    }
+}
+
+/// The parameters of a query string, decoded as an HTML form, with `+`
+/// for a space and percent-escapes for everything else.
+fn parse_query(query: &str) -> HashMap<String, String> {
+    form_urlencoded::parse(query.as_bytes())
+        .into_owned()
+        .collect()
+}
+
+fn timings(times: &PrayerTimes, offset: FixedOffset) -> Value {
+    serde_json::to_value(TimingsData::new(times, offset)).expect("serializable timings")
+}
+
+/// The schedule and the offset asked for by a timings request.
+fn timings_request(
+    query: &HashMap<String, String>,
+    geocoder: &dyn Geocoder,
+) -> Result<(PrayerTimes, FixedOffset), (u16, String)> {
+    let coordinates = coordinates(query, geocoder)?;
+    let method = match query.get("method") {
+        Some(value) => method(value).ok_or((400, format!("Unknown method `{value}`.")))?,
+        None => Method::MuslimWorldLeague,
+    };
+    let date = match query.get("date") {
+        Some(value) => date(value).ok_or((400, format!("Invalid date `{value}`.")))?,
+        None => Utc::now().date_naive(),
+    };
+    let offset = match query.get("tz") {
+        Some(value) => value
+            .parse::<FixedOffset>()
+            .map_err(|_| (400, format!("Invalid timezone offset `{value}`.")))?,
+        None => FixedOffset::east_opt(0).expect("valid offset"),
+    };
+
+    let times = PrayerTimes::try_computed(date, coordinates, method.parameters())
+        .map_err(|error| (400, error.to_string()))?;
+
+    Ok((times, offset))
+}
+
+fn qibla(coordinates: Coordinates) -> Value {
+    json!({
+        "latitude": coordinates.latitude,
+        "longitude": coordinates.longitude,
+        "direction": Qiblah::new(coordinates).value(),
+    })
+}
+
+/// The coordinates given by `latitude` and `longitude`, or else by the
+/// name of a `city`.
+fn coordinates(
+    query: &HashMap<String, String>,
+    geocoder: &dyn Geocoder,
+) -> Result<Coordinates, (u16, String)> {
+    if let Some(city) = query.get("city") {
+        return geocoder.lookup(city).map_err(|error| match error {
+            GeocodeError::NotFound(_) => (400, format!("{error}.")),
+            GeocodeError::Unavailable(_) => (503, format!("{error}.")),
+        });
+    } else {
+        // Nothing to do.
+    }
+
+    let number = |keys: [&str; 2]| {
+        keys.iter()
+            .find_map(|key| query.get(*key))
+            .and_then(|value| value.parse::<f64>().ok())
+    };
+    let latitude = number(["latitude", "lat"]).filter(|v| (-90.0..=90.0).contains(v));
+    let longitude = number(["longitude", "lon"]).filter(|v| (-180.0..=180.0).contains(v));
+
+    match (latitude, longitude) {
+        (Some(latitude), Some(longitude)) => Ok(Coordinates::new(latitude, longitude)),
+        _ => Err((
+            400,
+            "Please specify a valid latitude and longitude.".to_string(),
+        )),
+    }
+}
+
+/// A method by its AlAdhan id or by its slug, e.g. `umm_al_qura`.
+fn method(value: &str) -> Option<Method> {
+    match value.parse::<u8>() {
+        Ok(id) => aladhan::method_for_id(id),
+        Err(_) => Method::from_slug(value),
+    }
+}
+
+/// A date in AlAdhan's `DD-MM-YYYY` format, or in ISO 8601.
+fn date(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value, "%d-%m-%Y")
+        .or_else(|_| NaiveDate::parse_from_str(value, "%Y-%m-%d"))
+        .ok()
+}
+
+fn status_text(code: u16) -> &'static str {
+    match code {
+        400 => "BAD_REQUEST",
+        404 => "NOT_FOUND",
+        503 => "SERVICE_UNAVAILABLE",
+        _ => "ERROR",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use miqat::geocoder::Capitals;
+
+    fn respond(url: &str, geocoder: &dyn Geocoder) -> (u16, Value) {
+        super::respond(&Request::new(url, geocoder))
+    }
+
+    fn etag(url: &str, geocoder: &dyn Geocoder) -> Option<String> {
+        Request::new(url, geocoder).etag()
+    }
+
+    #[test]
+    fn timings_in_the_aladhan_shape() {
+        let (status, body) = respond(
+            "/timings?lat=35.7750&lon=-78.6336&method=2&date=12-07-2015&tz=-04:00",
+            &Capitals,
+        );
+
+        assert_eq!(status, 200);
+        assert_eq!(body["status"], "OK");
+        assert_eq!(body["data"]["timings"]["Fajr"], "04:42");
+        assert_eq!(body["data"]["timings"]["Isha"], "21:57");
+        assert_eq!(body["data"]["date"]["gregorian"]["date"], "12-07-2015");
+        assert_eq!(body["data"]["meta"]["method"]["id"], 2);
+    }
+
+    #[test]
+    fn qibla_direction() {
+        let (status, body) = respond("/qibla?latitude=40.7128&longitude=-74.0059", &Capitals);
+
+        assert_eq!(status, 200);
+        assert_eq!(body["data"]["direction"].as_f64().unwrap().round(), 58.0);
+    }
+
+    #[test]
+    fn timings_have_an_etag() {
+        let url = "/timings?lat=35.7750&lon=-78.6336&method=2&date=12-07-2015&tz=-04:00";
+        let tag = etag(url, &Capitals).unwrap();
+
+        assert_eq!(Some(tag.clone()), etag(url, &Capitals));
+        assert_ne!(
+            Some(tag.clone()),
+            etag(&url.replace("-04:00", "-05:00"), &Capitals)
+        );
+        assert_ne!(
+            Some(tag.clone()),
+            etag(&url.replace("12-07", "13-07"), &Capitals)
+        );
+        // The response echoes the coordinates in full.
+        assert_ne!(
+            Some(tag),
+            etag(&url.replace("35.7750", "35.77501"), &Capitals)
+        );
+        assert_eq!(
+            etag(&url.replace("-04:00", "%2D04%3A00"), &Capitals),
+            etag(url, &Capitals)
+        );
+        assert_eq!(etag("/timings?lat=95&lon=0", &Capitals), None);
+        assert_eq!(etag("/qibla?lat=0&lon=0", &Capitals), None);
+    }
+
+    #[test]
+    fn if_none_match() {
+        let url = "/timings?lat=35.7750&lon=-78.6336&method=2&date=12-07-2015&tz=-04:00";
+        let request = Request::new(url, &Capitals);
+        let tag = request.etag().unwrap();
+
+        assert!(request.not_modified(&tag));
+        assert!(request.not_modified(&format!("W/{tag}")));
+        assert!(request.not_modified(&format!("\"other\", {tag}")));
+        assert!(request.not_modified(" * "));
+        assert!(!request.not_modified("\"other\""));
+        assert!(!request.not_modified(&tag.replace('"', "")));
+        assert!(!Request::new("/qibla?lat=0&lon=0", &Capitals).not_modified("*"));
+    }
+
+    #[test]
+    fn places_by_name() {
+        let (status, body) = respond("/qibla?city=Kuala+Lumpur", &Capitals);
+
+        assert_eq!(status, 200);
+        assert_eq!(body["data"]["latitude"], 3.139);
+        assert_eq!(
+            respond("/qibla?city=Kuala%20Lumpur", &Capitals),
+            (status, body.clone())
+        );
+        assert_eq!(
+            respond("/qibla?city=riyadh%2C+sa", &Capitals).1["data"]["latitude"],
+            24.7136
+        );
+        assert_eq!(respond("/timings?city=Atlantis", &Capitals).0, 400);
+    }
+
+    #[test]
+    fn geocoders_that_cannot_answer() {
+        struct Offline;
+
+        impl Geocoder for Offline {
+            fn lookup(&self, _: &str) -> Result<Coordinates, GeocodeError> {
+                Err(GeocodeError::Unavailable("no network".to_string()))
+            }
+        }
+
+        let (status, body) = respond("/timings?city=Beirut", &Offline);
+
+        assert_eq!(status, 503);
+        assert_eq!(body["status"], "SERVICE_UNAVAILABLE");
+        assert_eq!(respond("/timings?lat=0&lon=0", &Offline).0, 200);
+    }
+
+    #[test]
+    fn queries_are_form_decoded() {
+        let url = "/timings?lat=35.7750&lon=-78.6336&method=2&date=12-07-2015";
+        let (_, east) = respond(&format!("{url}&tz=%2B03%3A00"), &Capitals);
+        let (status, plain) = respond(&format!("{url}&tz=%2b03:00"), &Capitals);
+
+        assert_eq!(status, 200);
+        assert_eq!(east, plain);
+        assert_eq!(east["data"]["timings"]["Fajr"], "11:42");
+        // A bare `+` is a space, as in any HTML form.
+        assert_eq!(respond(&format!("{url}&tz=+03:00"), &Capitals).0, 400);
+    }
+
+    #[test]
+    fn invalid_requests() {
+        assert_eq!(respond("/timings?lat=95&lon=0", &Capitals).0, 400);
+        assert_eq!(respond("/timings?lat=0&lon=0&method=99", &Capitals).0, 400);
+        assert_eq!(respond("/prayers", &Capitals).0, 404);
+    }
+}
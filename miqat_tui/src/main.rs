@@ -0,0 +1,142 @@
+//! A terminal clock showing today's prayer times, highlighting the
+//! current prayer and counting down to the next one.
+//!
+//! ```text
+//! miqat_tui <latitude> <longitude> [method]
+//! miqat_tui <city> [method]
+//! ```
+//!
+//! Cities are looked up among the built-in
+//! [capitals](miqat::geocoder::Capitals), e.g. `"Kuala Lumpur"` or
+//! `Rabat,MA`. The method is the slug of one of the [`Method`] presets, e.g.
+//! `umm_al_qura`, and defaults to the Muslim World League. The Hijri date
+//! is written in the language of `LANG` when it is one of the built-in
+//! [locales](miqat::locale::Locale), in English otherwise. Press `q` to quit.
+
+mod ui;
+
+use miqat::clock::SystemClock;
+use miqat::geocoder::Capitals;
+use miqat::geocoder::Geocoder;
+use miqat::live::LiveSchedule;
+use miqat::locale::Locale;
+use miqat::prelude::*;
+use ratatui::crossterm::event;
+use ratatui::crossterm::event::Event;
+use ratatui::crossterm::event::KeyCode;
+use std::env;
+use std::io;
+use std::process::ExitCode;
+use std::time::Duration;
+
+const USAGE: &str = "usage: miqat_tui <latitude> <longitude> [method] | <city> [method]";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let Some((coordinates, method)) = parse_args(&args, &Capitals) else {
+        eprintln!("{USAGE}");
+        return ExitCode::FAILURE;
+    };
+
+    let locale = env::var("LANG")
+        .ok()
+        .and_then(|tag| Locale::from_language_tag(&tag))
+        .unwrap_or_default();
+
+    let mut terminal = ratatui::init();
+    let result = run(&mut terminal, coordinates, method.parameters(), locale);
+    ratatui::restore();
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("{error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Draws the schedule every second until `q` is pressed. The schedule
+/// follows the local day, and is started over when the UTC offset of the
+/// displayed date changes, e.g. after a daylight saving change.
+fn run(
+    terminal: &mut ratatui::DefaultTerminal,
+    coordinates: Coordinates,
+    mut parameters: Parameters,
+    locale: Locale,
+) -> io::Result<()> {
+    parameters.day_boundary = day_boundary(Local::now().date_naive());
+    let mut schedule = LiveSchedule::new(coordinates, parameters);
+
+    loop {
+        let boundary = day_boundary(Local::now().date_naive());
+
+        if boundary != parameters.day_boundary {
+            parameters.day_boundary = boundary;
+            schedule = LiveSchedule::new(coordinates, parameters);
+        } else {
+            // Nothing to do.
+        }
+
+        terminal.draw(|frame| ui::draw(frame, &schedule, &SystemClock, &locale))?;
+
+        if event::poll(Duration::from_secs(1))?
+            && let Event::Key(key) = event::read()?
+            && key.code == KeyCode::Char('q')
+        {
+            return Ok(());
+        }
+    }
+}
+
+/// The local day at the UTC offset in effect at noon on `date`, away
+/// from daylight saving changes, which happen in the small hours.
+fn day_boundary(date: NaiveDate) -> DayBoundary {
+    let noon = date.and_hms_opt(12, 0, 0).unwrap();
+
+    DayBoundary::LocalCivil(Local.offset_from_utc_datetime(&noon))
+}
+
+/// The coordinates, given as a latitude and a longitude or as a place
+/// looked up with `geocoder`, and the method that follows them.
+fn parse_args(args: &[String], geocoder: &dyn Geocoder) -> Option<(Coordinates, Method)> {
+    let (coordinates, rest) = match args.first()?.parse() {
+        Ok(latitude) => {
+            let longitude = args.get(1)?.parse().ok()?;
+
+            (Coordinates::new(latitude, longitude), &args[2..])
+        }
+        Err(_) => (geocoder.lookup(&args[0]).ok()?, &args[1..]),
+    };
+    let method = match rest.first() {
+        Some(slug) => Method::from_slug(slug)?,
+        None => Method::MuslimWorldLeague,
+    };
+
+    Some((coordinates, method))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn coordinates_or_a_city() {
+        let rabat = Coordinates::new(34.0209, -6.8416);
+
+        assert_eq!(
+            parse_args(&args(&["34.0209", "-6.8416"]), &Capitals),
+            Some((rabat, Method::MuslimWorldLeague))
+        );
+        assert_eq!(
+            parse_args(&args(&["Rabat,MA", "umm_al_qura"]), &Capitals),
+            Some((rabat, Method::UmmAlQura))
+        );
+        assert_eq!(parse_args(&args(&["Atlantis"]), &Capitals), None);
+        assert_eq!(parse_args(&args(&["34.0209"]), &Capitals), None);
+    }
+}
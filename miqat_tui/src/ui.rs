@@ -0,0 +1,116 @@
+use chrono::Local;
+use miqat::clock::Clock;
+use miqat::live::LiveSchedule;
+use miqat::locale::DEFAULT_HIJRI_FORMAT;
+use miqat::locale::Localizer;
+use miqat::prelude::*;
+use ratatui::Frame;
+use ratatui::layout::Constraint;
+use ratatui::layout::Layout;
+use ratatui::style::Modifier;
+use ratatui::style::Style;
+use ratatui::text::Line;
+use ratatui::widgets::Block;
+use ratatui::widgets::Paragraph;
+use ratatui::widgets::Row;
+use ratatui::widgets::Table;
+
+/// Draws today's table, with the current prayer highlighted and the
+/// Hijri date written by `localizer`, above a countdown to the next
+/// prayer, or why the times could not be calculated.
+pub fn draw<C: Clock>(
+    frame: &mut Frame,
+    schedule: &LiveSchedule<C>,
+    clock: &C,
+    localizer: &dyn Localizer,
+) {
+    let state = schedule.today().and_then(|today| {
+        Ok((
+            today,
+            schedule.current()?,
+            schedule.next()?,
+            schedule.until_next()?,
+        ))
+    });
+    let (today, current, next, remaining) = match state {
+        Ok(state) => state,
+        Err(error) => {
+            frame.render_widget(
+                Paragraph::new(error.to_string()).block(Block::bordered()),
+                frame.area(),
+            );
+            return;
+        }
+    };
+    let [table_area, countdown_area] =
+        Layout::vertical([Constraint::Length(8), Constraint::Length(3)]).areas(frame.area());
+
+    let rows = Prayer::ALL.iter().map(|prayer| {
+        let time = today.time(*prayer).with_timezone(&Local);
+        let row = Row::new(vec![
+            prayer.name_with(clock).to_string(),
+            time.format("%H:%M").to_string(),
+        ]);
+
+        if *prayer == current {
+            row.style(Style::new().add_modifier(Modifier::REVERSED))
+        } else {
+            row
+        }
+    });
+    let title = format!(" {} ", today.date().format("%A %-d %B %Y"));
+    let hijri = format!(
+        " {} ",
+        localizer.hijri_date(today.date(), DEFAULT_HIJRI_FORMAT)
+    );
+    let table = Table::new(rows, [Constraint::Length(10), Constraint::Length(5)])
+        .block(Block::bordered().title(title).title_bottom(hijri));
+    frame.render_widget(table, table_area);
+
+    let countdown = Line::from(format!(
+        "{} in {:02}:{:02}:{:02}",
+        next.name_with(clock),
+        remaining.num_hours(),
+        remaining.num_minutes() % 60,
+        remaining.num_seconds() % 60,
+    ));
+    frame.render_widget(
+        Paragraph::new(countdown).block(Block::bordered()),
+        countdown_area,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use miqat::clock::FixedClock;
+    use miqat::locale::Locale;
+    use ratatui::Terminal;
+    use ratatui::backend::TestBackend;
+
+    #[test]
+    fn highlights_the_current_prayer_and_counts_down() {
+        let clock = FixedClock(Utc.with_ymd_and_hms(2015, 7, 12, 18, 0, 0).unwrap());
+        let raleigh = Coordinates::new(35.7750, -78.6336);
+        let schedule = LiveSchedule::with_clock(raleigh, Method::NorthAmerica.parameters(), clock);
+        let mut terminal = Terminal::new(TestBackend::new(40, 11)).unwrap();
+
+        terminal
+            .draw(|frame| draw(frame, &schedule, &clock, &Locale::Turkish))
+            .unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let line = |y: u16| -> String {
+            (0..buffer.area.width)
+                .map(|x| buffer[(x, y)].symbol())
+                .collect()
+        };
+        let dhuhr_row = (0..buffer.area.height)
+            .find(|y| line(*y).contains("Dhuhr"))
+            .unwrap();
+
+        assert!(buffer[(1, dhuhr_row)].modifier.contains(Modifier::REVERSED));
+        assert!(line(7).contains("25 Ramazan 1436"));
+        assert!(line(9).contains("Asr in 03:09:00"));
+    }
+}